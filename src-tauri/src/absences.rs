@@ -0,0 +1,255 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::attendance_keys::entry_key;
+use crate::audit::record_audit;
+use crate::capacity::{booked_count, is_full};
+use crate::dashboard::{emit_dashboard_update, DashboardEvent};
+use crate::entry_history::record_change;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::waitlist::{emit_waitlist_promotion, promote_from_waitlist, WaitlistPromotion};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, ServiceType};
+
+/// A period the owner has told us their dog won't be coming in, e.g. a family
+/// holiday. Unlike a cancellation it covers every service, stops schedules
+/// booking the dog in, and leaves the affected bookings marked as excused.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Absence {
+    pub id: String,
+    pub dog_id: String,
+    pub start_date: String, // Inclusive, YYYY-MM-DD
+    pub end_date: String,
+    pub reason: String,
+    pub recorded_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Absence {
+    pub fn covers(&self, date: &str) -> bool {
+        self.start_date.as_str() <= date && date <= self.end_date.as_str()
+    }
+}
+
+pub fn is_absent(absences: &[Absence], dog_id: &str, date: &str) -> bool {
+    absences.iter().any(|a| a.dog_id == dog_id && a.covers(date))
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AbsenceResult {
+    pub absence: Absence,
+    pub excused_dates: Vec<String>,
+    pub promotions: Vec<WaitlistPromotion>,
+}
+
+/// Mark the dog's bookings in the absence as excused, except on locked days.
+/// Places freed on days that were full go to the waitlist, as they would for a
+/// cancellation.
+fn excuse_bookings(data: &mut AppData, absence: &Absence) -> (Vec<(String, ServiceType)>, Vec<WaitlistPromotion>) {
+    let mut excused = Vec::new();
+    let mut freed_full = Vec::new();
+    let updated_by = current_staff_id();
+    
+    let locked: Vec<String> = data.locked_days.iter().map(|l| l.date.clone()).collect();
+    for (date, day_data) in data.daily_data.iter_mut().filter(|(date, _)| absence.covers(date) && !locked.contains(date)) {
+        let services: Vec<ServiceType> = day_data.attendance.entries.values()
+            .filter(|e| e.dog_id == absence.dog_id && e.attending)
+            .map(|e| e.service_type.clone())
+            .collect();
+        for service_type in services {
+            if is_full(&data.settings.capacity, &data.dogs, day_data, &service_type) {
+                freed_full.push((date.clone(), service_type.clone()));
+            }
+            if let Some(entry) = day_data.attendance.entries.get_mut(&entry_key(&absence.dog_id, &service_type)) {
+                entry.attending = false;
+                entry.excused = true;
+                entry.awaiting_confirmation = false;
+                entry.updated_by = updated_by.clone();
+            }
+            if service_type == ServiceType::Daycare {
+                day_data.attendance.dogs.insert(absence.dog_id.clone(), false);
+            }
+            record_change(day_data, &absence.dog_id, Some(&service_type), "attending", Some(true.to_string()), Some(false.to_string()));
+            excused.push((date.clone(), service_type));
+        }
+    }
+    excused.sort_by(|a, b| a.0.cmp(&b.0));
+    
+    let promotions = freed_full.iter()
+        .filter_map(|(date, service_type)| promote_from_waitlist(data, date, service_type))
+        .collect();
+    (excused, promotions)
+}
+
+/// Put back the bookings an absence excused, except on locked days. A place
+/// given to someone else in the meantime isn't taken back: where the booking
+/// no longer fits, it stays excused and is reported as a conflict.
+fn reinstate_bookings(data: &mut AppData, absence: &Absence) -> (Vec<(String, ServiceType)>, Vec<String>) {
+    let mut reinstated = Vec::new();
+    let mut conflicts = Vec::new();
+    let updated_by = current_staff_id();
+    let capacity = &data.settings.capacity;
+    let places = capacity.places_for(data.dogs.iter().find(|d| d.id == absence.dog_id));
+    
+    let locked: Vec<String> = data.locked_days.iter().map(|l| l.date.clone()).collect();
+    for (date, day_data) in data.daily_data.iter_mut().filter(|(date, _)| absence.covers(date) && !locked.contains(date)) {
+        let services: Vec<ServiceType> = day_data.attendance.entries.values()
+            .filter(|e| e.dog_id == absence.dog_id && e.excused)
+            .map(|e| e.service_type.clone())
+            .collect();
+        for service_type in services {
+            let limit = capacity.for_service(&service_type);
+            if limit > 0 && booked_count(capacity, &data.dogs, day_data, &service_type) + places > limit {
+                conflicts.push(format!("{} {}: fully booked", date, service_type.key()));
+                continue;
+            }
+            if let Some(entry) = day_data.attendance.entries.get_mut(&entry_key(&absence.dog_id, &service_type)) {
+                entry.attending = true;
+                entry.excused = false;
+                entry.updated_by = updated_by.clone();
+            }
+            if service_type == ServiceType::Daycare {
+                day_data.attendance.dogs.insert(absence.dog_id.clone(), true);
+            }
+            record_change(day_data, &absence.dog_id, Some(&service_type), "attending", Some(false.to_string()), Some(true.to_string()));
+            reinstated.push((date.clone(), service_type));
+        }
+    }
+    reinstated.sort_by(|a, b| a.0.cmp(&b.0));
+    conflicts.sort();
+    (reinstated, conflicts)
+}
+
+#[tauri::command]
+pub fn record_absence(
+    app: tauri::AppHandle,
+    dog_id: String,
+    start_date: String,
+    end_date: String,
+    reason: String,
+) -> Result<AbsenceResult, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let dog_name = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.name.clone())
+        .ok_or("Dog not found")?;
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid start date format".to_string())?;
+    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid end date format".to_string())?;
+    if end < start {
+        return Err("Absence end date is before its start date".to_string());
+    }
+    if let Some(existing) = data.absences.iter().find(|a| a.dog_id == dog_id && a.start_date <= end_date && start_date <= a.end_date) {
+        return Err(format!(
+            "{} already has an absence from {} to {}",
+            dog_name, existing.start_date, existing.end_date
+        ));
+    }
+    
+    let absence = Absence {
+        id: Uuid::new_v4().to_string(),
+        dog_id,
+        start_date,
+        end_date,
+        reason: reason.trim().to_string(),
+        recorded_by: current_staff_id(),
+        created_at: Utc::now(),
+    };
+    
+    let (excused, promotions) = excuse_bookings(&mut data, &absence);
+    data.absences.push(absence.clone());
+    record_audit(&mut data, "record_absence", &format!(
+        "{} absent {} to {} ({} booking(s) excused)",
+        dog_name, absence.start_date, absence.end_date, excused.len()
+    ));
+    
+    save_app_data(&data)?;
+    
+    for (date, service_type) in &excused {
+        emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+            date: date.clone(),
+            dog_id: absence.dog_id.clone(),
+            service_type: Some(service_type.clone()),
+            attending: false,
+        });
+    }
+    for promotion in &promotions {
+        emit_waitlist_promotion(&app, promotion);
+        emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+            date: promotion.date.clone(),
+            dog_id: promotion.dog_id.clone(),
+            service_type: Some(promotion.service_type.clone()),
+            attending: true,
+        });
+    }
+    
+    let mut excused_dates: Vec<String> = excused.into_iter().map(|(date, _)| date).collect();
+    excused_dates.dedup();
+    Ok(AbsenceResult { absence, excused_dates, promotions })
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AbsenceRemoval {
+    pub reinstated_dates: Vec<String>,
+    pub conflicts: Vec<String>, // Bookings left excused because the day has filled up since
+}
+
+/// Remove an absence recorded by mistake. Bookings it excused are reinstated
+/// where there's still room; schedules fill in any days that were never
+/// generated next time they run.
+#[tauri::command]
+pub fn delete_absence(app: tauri::AppHandle, absence_id: String) -> Result<AbsenceRemoval, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let index = data.absences.iter()
+        .position(|a| a.id == absence_id)
+        .ok_or("Absence not found")?;
+    let absence = data.absences.remove(index);
+    
+    let (reinstated, conflicts) = reinstate_bookings(&mut data, &absence);
+    
+    let dog_name = data.dogs.iter()
+        .find(|d| d.id == absence.dog_id)
+        .map(|d| d.name.clone())
+        .unwrap_or_default();
+    record_audit(&mut data, "delete_absence", &format!(
+        "{} absence {} to {} removed ({} booking(s) reinstated, {} left excused as fully booked)",
+        dog_name, absence.start_date, absence.end_date, reinstated.len(), conflicts.len()
+    ));
+    
+    save_app_data(&data)?;
+    
+    for (date, service_type) in &reinstated {
+        emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+            date: date.clone(),
+            dog_id: absence.dog_id.clone(),
+            service_type: Some(service_type.clone()),
+            attending: true,
+        });
+    }
+    
+    let mut reinstated_dates: Vec<String> = reinstated.into_iter().map(|(date, _)| date).collect();
+    reinstated_dates.dedup();
+    Ok(AbsenceRemoval { reinstated_dates, conflicts })
+}
+
+/// Absences for one dog, or everyone's, that haven't finished yet unless
+/// `include_past` is set. Soonest first.
+#[tauri::command]
+pub fn get_absences(dog_id: Option<String>, include_past: Option<bool>) -> Result<Vec<Absence>, String> {
+    let data = load_app_data()?;
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let include_past = include_past.unwrap_or(false);
+    
+    let mut absences: Vec<Absence> = data.absences.into_iter()
+        .filter(|a| dog_id.as_ref().is_none_or(|d| a.dog_id == *d))
+        .filter(|a| include_past || a.end_date >= today)
+        .collect();
+    
+    absences.sort_by(|a, b| a.start_date.cmp(&b.start_date));
+    Ok(absences)
+}
@@ -0,0 +1,252 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::record_audit;
+use crate::billing::Payment;
+use crate::invoices::{period_bounds, Invoice, InvoiceLine, InvoiceStatus};
+use crate::permissions::{require_permission, Permission};
+use crate::{lock_app_data, save_app_data, AppData, ServiceType};
+
+/// Accounts invoices and payments are posted to in the accounting software.
+/// QuickBooks matches accounts by name, Xero by code.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountingSettings {
+    pub daycare_account: String,
+    pub training_account: String,
+    pub boarding_account: String,
+    pub other_income_account: String, // House food and other extras
+    pub discounts_account: String,
+    pub receivables_account: String,
+    pub deposit_account: String, // Where payments received are banked
+    pub tax_type: String, // Xero tax type for invoice lines, e.g. "Tax Exempt"
+    pub payment_terms_days: u32, // Invoices fall due this many days after they're dated
+}
+
+impl Default for AccountingSettings {
+    fn default() -> Self {
+        Self {
+            daycare_account: "4000".to_string(),
+            training_account: "4010".to_string(),
+            boarding_account: "4020".to_string(),
+            other_income_account: "4090".to_string(),
+            discounts_account: "4900".to_string(),
+            receivables_account: "1100".to_string(),
+            deposit_account: "1200".to_string(),
+            tax_type: "Tax Exempt".to_string(),
+            payment_terms_days: 14,
+        }
+    }
+}
+
+impl AccountingSettings {
+    fn account_for(&self, line: &InvoiceLine) -> &str {
+        if line.amount < 0.0 {
+            return &self.discounts_account;
+        }
+        match line.service_type {
+            Some(ServiceType::Daycare) => &self.daycare_account,
+            Some(ServiceType::Training) => &self.training_account,
+            Some(ServiceType::Boarding) => &self.boarding_account,
+            None => &self.other_income_account,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AccountingFormat {
+    #[serde(rename = "quickbooks")]
+    QuickBooks, // One IIF file with invoices and payments
+    #[serde(rename = "xero")]
+    Xero, // A sales invoice import and a bank statement of payments, both CSV
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AccountingFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AccountingExport {
+    pub period: String,
+    pub format: AccountingFormat,
+    pub invoices: usize,
+    pub payments: usize,
+    pub files: Vec<AccountingFile>,
+}
+
+/// Name the household is known by in the accounts: the owner of its first dog.
+pub fn customer(data: &AppData, household_id: &str) -> (String, String) {
+    data.dogs.iter()
+        .filter(|d| d.household_key() == household_id)
+        .min_by_key(|d| d.created_at)
+        .map(|d| (d.owner.clone(), d.email.clone()))
+        .unwrap_or_else(|| (household_id.to_string(), String::new()))
+}
+
+/// Invoices go in dated the day they were issued, or the end of their period.
+fn invoice_date(invoice: &Invoice, period_end: NaiveDate) -> NaiveDate {
+    invoice.issued_at.map(|at| at.date_naive()).unwrap_or(period_end)
+}
+
+fn money(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
+
+fn quickbooks_iif(data: &AppData, invoices: &[&Invoice], payments: &[&Payment], period_end: NaiveDate) -> String {
+    let accounts = &data.settings.accounting;
+    let us_date = |date: NaiveDate| date.format("%m/%d/%Y").to_string();
+    let row = |fields: &[&str]| format!("{}\r\n", fields.join("\t"));
+    // Tabs and line breaks would break the file's layout
+    let clean = |text: &str| text.replace(['\t', '\r', '\n'], " ");
+    
+    let mut iif = String::new();
+    iif.push_str(&row(&["!TRNS", "TRNSTYPE", "DATE", "ACCNT", "NAME", "AMOUNT", "DOCNUM", "MEMO"]));
+    iif.push_str(&row(&["!SPL", "TRNSTYPE", "DATE", "ACCNT", "NAME", "AMOUNT", "DOCNUM", "MEMO"]));
+    iif.push_str(&row(&["!ENDTRNS"]));
+    
+    for invoice in invoices {
+        let (name, _) = customer(data, &invoice.household_id);
+        let name = clean(&name);
+        let date = us_date(invoice_date(invoice, period_end));
+        iif.push_str(&row(&["TRNS", "INVOICE", &date, &accounts.receivables_account, &name,
+            &money(invoice.total), &invoice.number, &format!("Invoice for {}", invoice.period)]));
+        // Split lines carry the opposite sign to the transaction line
+        for line in &invoice.lines {
+            iif.push_str(&row(&["SPL", "INVOICE", &date, accounts.account_for(line), &name,
+                &money(-line.amount), &invoice.number, &clean(&line.description)]));
+        }
+        iif.push_str(&row(&["ENDTRNS"]));
+    }
+    
+    for payment in payments {
+        let name = payment.household_id.as_deref()
+            .map(|h| clean(&customer(data, h).0))
+            .unwrap_or_default();
+        let date = NaiveDate::parse_from_str(&payment.date, "%Y-%m-%d")
+            .map(us_date)
+            .unwrap_or_else(|_| payment.date.clone());
+        let reference = clean(payment.reference.as_deref().unwrap_or(""));
+        iif.push_str(&row(&["TRNS", "PAYMENT", &date, &accounts.deposit_account, &name,
+            &money(payment.amount), &reference, &format!("{:?} payment", payment.method)]));
+        iif.push_str(&row(&["SPL", "PAYMENT", &date, &accounts.receivables_account, &name,
+            &money(-payment.amount), &reference, ""]));
+        iif.push_str(&row(&["ENDTRNS"]));
+    }
+    iif
+}
+
+fn xero_invoices_csv(data: &AppData, invoices: &[&Invoice], period_end: NaiveDate) -> Result<String, String> {
+    let accounts = &data.settings.accounting;
+    let csv_error = |e: csv::Error| format!("Failed to write Xero invoices: {}", e);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "*ContactName", "EmailAddress", "*InvoiceNumber", "Reference", "*InvoiceDate", "*DueDate",
+        "*Description", "*Quantity", "*UnitAmount", "*AccountCode", "*TaxType", "Currency",
+    ]).map_err(csv_error)?;
+    
+    for invoice in invoices {
+        let (name, email) = customer(data, &invoice.household_id);
+        let date = invoice_date(invoice, period_end);
+        let due = date + Duration::days(accounts.payment_terms_days as i64);
+        for line in &invoice.lines {
+            writer.write_record([
+                name.clone(),
+                email.clone(),
+                invoice.number.clone(),
+                invoice.period.clone(),
+                date.format("%Y-%m-%d").to_string(),
+                due.format("%Y-%m-%d").to_string(),
+                line.description.clone(),
+                line.quantity.to_string(),
+                money(line.unit_price),
+                accounts.account_for(line).to_string(),
+                accounts.tax_type.clone(),
+                data.settings.currency.code.clone(),
+            ]).map_err(csv_error)?;
+        }
+    }
+    
+    let bytes = writer.into_inner()
+        .map_err(|e| format!("Failed to write Xero invoices: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write Xero invoices: {}", e))
+}
+
+/// Payments laid out as a Xero bank statement import, to be reconciled against
+/// the invoices in the deposit account.
+fn xero_payments_csv(data: &AppData, payments: &[&Payment]) -> Result<String, String> {
+    let csv_error = |e: csv::Error| format!("Failed to write Xero payments: {}", e);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["*Date", "*Amount", "Payee", "Description", "Reference"]).map_err(csv_error)?;
+    
+    for payment in payments {
+        let payee = payment.household_id.as_deref()
+            .map(|h| customer(data, h).0)
+            .unwrap_or_default();
+        let invoice_number = payment.invoice_id.as_deref()
+            .and_then(|id| data.invoices.iter().find(|i| i.id == id))
+            .map(|i| i.number.clone());
+        writer.write_record([
+            payment.date.clone(),
+            money(payment.amount),
+            payee,
+            format!("{:?} payment", payment.method),
+            invoice_number.or_else(|| payment.reference.clone()).unwrap_or_default(),
+        ]).map_err(csv_error)?;
+    }
+    
+    let bytes = writer.into_inner()
+        .map_err(|e| format!("Failed to write Xero payments: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write Xero payments: {}", e))
+}
+
+/// Issued invoices and payments received for a YYYY-MM period, in a layout
+/// QuickBooks or Xero can import, posted to the accounts in the settings.
+/// Draft and void invoices are left out; tips aren't included in payments.
+#[tauri::command]
+pub fn export_accounting(period: String, format: AccountingFormat) -> Result<AccountingExport, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    let (start, end) = period_bounds(&period)?;
+    let (first, last) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    
+    let mut invoices: Vec<&Invoice> = data.invoices.iter()
+        .filter(|i| i.period == period && !matches!(i.status, InvoiceStatus::Draft | InvoiceStatus::Void))
+        .collect();
+    invoices.sort_by(|a, b| a.number.cmp(&b.number));
+    let mut payments: Vec<&Payment> = data.payments.iter()
+        .filter(|p| first <= p.date && p.date <= last)
+        .collect();
+    payments.sort_by(|a, b| a.date.cmp(&b.date));
+    
+    let files = match format {
+        AccountingFormat::QuickBooks => vec![AccountingFile {
+            file_name: format!("accounts-{}.iif", period),
+            content: quickbooks_iif(&data, &invoices, &payments, end),
+        }],
+        AccountingFormat::Xero => vec![
+            AccountingFile {
+                file_name: format!("invoices-{}.csv", period),
+                content: xero_invoices_csv(&data, &invoices, end)?,
+            },
+            AccountingFile {
+                file_name: format!("payments-{}.csv", period),
+                content: xero_payments_csv(&data, &payments)?,
+            },
+        ],
+    };
+    let export = AccountingExport {
+        period,
+        format,
+        invoices: invoices.len(),
+        payments: payments.len(),
+        files,
+    };
+    
+    record_audit(&mut data, "accounting_export", &format!(
+        "Exported {} invoices and {} payments for {} in {:?} format",
+        export.invoices, export.payments, export.period, export.format
+    ));
+    save_app_data(&data)?;
+    Ok(export)
+}
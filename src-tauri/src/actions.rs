@@ -0,0 +1,175 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::invoices::{previous_period, run_billing_now};
+use crate::permissions::{require_permission, Permission};
+use crate::quarantine::is_quarantined;
+use crate::{check_in_dog, check_out_dog, load_app_data, AppData};
+
+/// Where the command palette was opened from, so it can offer the actions that
+/// make sense there.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ActionContext {
+    pub date: Option<String>, // Day being viewed; today if not given
+    pub dog_id: Option<String>, // Dog selected, to only offer actions for that dog
+    pub query: Option<String>, // What's been typed so far
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuickAction {
+    pub id: String, // Passed back to execute_action, e.g. "check_in:<dog id>"
+    pub label: String,
+    pub group: String, // "Attendance", "Billing" or "Go to"
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum ActionOutcome {
+    #[serde(rename = "navigate")]
+    Navigate { view: String, date: Option<String> }, // The frontend switches to the view
+    #[serde(rename = "done")]
+    Done { message: String, warnings: Vec<String> },
+}
+
+/// Values an action may take in place of its defaults.
+#[derive(Debug, Deserialize, Default)]
+struct ActionArgs {
+    date: Option<String>,
+    time: Option<String>,
+    period: Option<String>, // YYYY-MM, for generating invoices
+}
+
+fn today() -> String {
+    Local::now().date_naive().format("%Y-%m-%d").to_string()
+}
+
+fn allowed(data: &AppData, permission: Permission) -> bool {
+    require_permission(data, permission).is_ok()
+}
+
+fn action(id: impl Into<String>, label: impl Into<String>, group: &str) -> QuickAction {
+    QuickAction { id: id.into(), label: label.into(), group: group.to_string() }
+}
+
+/// Check-ins for dogs booked in on the day and not yet here, and check-outs
+/// for dogs here and not yet collected.
+fn attendance_actions(data: &AppData, date: &str, dog_id: Option<&str>) -> Vec<QuickAction> {
+    let day_data = match data.daily_data.get(date) {
+        Some(day_data) => day_data,
+        None => return Vec::new(),
+    };
+    let mut dogs: Vec<_> = data.dogs.iter()
+        .filter(|d| dog_id.is_none_or(|id| d.id == id))
+        .collect();
+    dogs.sort_by(|a, b| a.name.cmp(&b.name));
+    
+    let mut actions = Vec::new();
+    for dog in dogs {
+        let record = day_data.records.get(&dog.id);
+        let checked_in = record.is_some_and(|r| r.checked_in_at.is_some());
+        let checked_out = record.is_some_and(|r| r.checked_out_at.is_some());
+        let booked = day_data.attendance.entries.values().any(|e| e.dog_id == dog.id && e.attending);
+        if booked && !checked_in && !is_quarantined(dog, date) {
+            actions.push(action(format!("check_in:{}", dog.id), format!("Check in {}", dog.name), "Attendance"));
+        }
+        if checked_in && !checked_out {
+            actions.push(action(format!("check_out:{}", dog.id), format!("Check out {}", dog.name), "Attendance"));
+        }
+    }
+    actions
+}
+
+fn matches_query(action: &QuickAction, query: &str) -> bool {
+    let label = action.label.to_lowercase();
+    query.to_lowercase().split_whitespace().all(|word| label.contains(word))
+}
+
+/// Quick actions the signed-in user is allowed to run, for the command palette.
+/// Actions are filtered by what's been typed, matching every word.
+#[tauri::command]
+pub fn get_actions(context: Option<ActionContext>) -> Result<Vec<QuickAction>, String> {
+    let data = load_app_data()?;
+    let context = context.unwrap_or_default();
+    let date = context.date.unwrap_or_else(today);
+    let on_today = date == today();
+    
+    let mut actions = Vec::new();
+    if allowed(&data, Permission::EditAttendance) {
+        actions.extend(attendance_actions(&data, &date, context.dog_id.as_deref()));
+    }
+    if context.dog_id.is_none() {
+        let roster = if on_today { "Open today's roster".to_string() } else { format!("Open the roster for {}", date) };
+        actions.push(action("open_roster", roster, "Go to"));
+        actions.push(action("open_calendar", "Open the calendar", "Go to"));
+        actions.push(action("open_compliance", "Open vaccination compliance", "Go to"));
+        actions.push(action("open_dogs", "Open dog management", "Go to"));
+        if allowed(&data, Permission::EditSettings) {
+            actions.push(action("open_settings", "Open settings", "Go to"));
+        }
+        if allowed(&data, Permission::ManageBilling) {
+            let period = previous_period(Local::now().date_naive());
+            actions.push(action("run_billing", format!("Generate invoices for {}", period), "Billing"));
+        }
+    }
+    
+    if let Some(query) = context.query.as_deref().filter(|q| !q.trim().is_empty()) {
+        actions.retain(|a| matches_query(a, query));
+    }
+    Ok(actions)
+}
+
+fn navigate(view: &str, date: Option<String>) -> ActionOutcome {
+    ActionOutcome::Navigate { view: view.to_string(), date }
+}
+
+/// Run an action from get_actions. The commands behind it check permissions
+/// as they would if called directly.
+#[tauri::command]
+pub fn execute_action(app: tauri::AppHandle, id: String, args: Option<Value>) -> Result<ActionOutcome, String> {
+    let args: ActionArgs = match args {
+        Some(args) => serde_json::from_value(args).map_err(|e| format!("Invalid action arguments: {}", e))?,
+        None => ActionArgs::default(),
+    };
+    let date = args.date.unwrap_or_else(today);
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", date))?;
+    
+    let (name, target) = id.split_once(':').unwrap_or((id.as_str(), ""));
+    match name {
+        "check_in" => {
+            let result = check_in_dog(app, date, target.to_string(), args.time, None)?;
+            let dog = dog_name(target)?;
+            Ok(ActionOutcome::Done { message: format!("Checked in {}", dog), warnings: result.warnings })
+        }
+        "check_out" => {
+            check_out_dog(app, date, target.to_string(), args.time, None)?;
+            let dog = dog_name(target)?;
+            Ok(ActionOutcome::Done { message: format!("Checked out {}", dog), warnings: Vec::new() })
+        }
+        "run_billing" => {
+            let period = args.period.unwrap_or_else(|| previous_period(Local::now().date_naive()));
+            let summary = run_billing_now(app, period.clone())?;
+            Ok(ActionOutcome::Done {
+                message: format!("Drafted {} invoices for {}", summary.invoices_created, period),
+                warnings: summary.warnings,
+            })
+        }
+        "open_roster" => Ok(navigate("daily", Some(date))),
+        "open_calendar" => Ok(navigate("calendar", Some(date))),
+        "open_compliance" => Ok(navigate("compliance", None)),
+        "open_dogs" => Ok(navigate("management", None)),
+        "open_settings" => {
+            require_permission(&load_app_data()?, Permission::EditSettings)?;
+            Ok(navigate("settings", None))
+        }
+        _ => Err(format!("Unknown action: {}", id)),
+    }
+}
+
+fn dog_name(dog_id: &str) -> Result<String, String> {
+    let data = load_app_data()?;
+    data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.name.clone())
+        .ok_or("Dog not found".to_string())
+}
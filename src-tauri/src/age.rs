@@ -0,0 +1,85 @@
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{Dog, NeuterStatus};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AgeClass {
+    #[serde(rename = "puppy")]
+    Puppy,
+    #[serde(rename = "adult")]
+    Adult,
+    #[serde(rename = "senior")]
+    Senior,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeClassSettings {
+    pub puppy_under_months: u32,
+    pub senior_from_years: u32,
+}
+
+impl Default for AgeClassSettings {
+    fn default() -> Self {
+        Self {
+            puppy_under_months: 12,
+            senior_from_years: 8,
+        }
+    }
+}
+
+/// Age class of a dog on a given date. Dogs without a valid date of birth
+/// aren't classified.
+pub fn age_class_on(dog: &Dog, settings: &AgeClassSettings, date: NaiveDate) -> Option<AgeClass> {
+    let born = NaiveDate::parse_from_str(dog.date_of_birth.as_deref()?, "%Y-%m-%d").ok()?;
+    if born > date {
+        return None;
+    }
+    
+    let puppy_until = born.checked_add_months(Months::new(settings.puppy_under_months))?;
+    let senior_from = born.checked_add_months(Months::new(settings.senior_from_years * 12))?;
+    
+    Some(if date < puppy_until {
+        AgeClass::Puppy
+    } else if date >= senior_from {
+        AgeClass::Senior
+    } else {
+        AgeClass::Adult
+    })
+}
+
+/// Fill in each dog's current age class before returning dogs to the frontend.
+pub fn classify_dogs(dogs: &mut [Dog], settings: &AgeClassSettings, today: NaiveDate) {
+    for dog in dogs.iter_mut() {
+        dog.age_class = age_class_on(dog, settings, today);
+    }
+}
+
+/// Licensing condition that intact dogs past a certain age stay out of group play.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct IntactPolicy {
+    pub group_play_max_age_months: Option<u32>, // None: no restriction
+}
+
+/// Warning when an intact dog is past the policy age on `date`. Intact dogs
+/// without a date of birth are flagged too, since their age can't be checked.
+pub fn intact_warning(dog: &Dog, policy: &IntactPolicy, date: NaiveDate) -> Option<String> {
+    let max_months = policy.group_play_max_age_months?;
+    if dog.neuter_status != NeuterStatus::Intact {
+        return None;
+    }
+    
+    let born = dog.date_of_birth.as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    match born.and_then(|b| b.checked_add_months(Months::new(max_months))) {
+        Some(limit) if date < limit => None,
+        Some(_) => Some(format!(
+            "{} is intact and over {} months old, so not allowed in group play",
+            dog.name, max_months
+        )),
+        None => Some(format!(
+            "{} is intact and has no date of birth; check they're under {} months before group play",
+            dog.name, max_months
+        )),
+    }
+}
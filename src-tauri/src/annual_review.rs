@@ -0,0 +1,183 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::compliance::attended_dogs;
+use crate::pdf::PdfWriter;
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, AppData, Dog};
+
+const MARGIN: f32 = 20.0;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BusiestDay {
+    pub date: String,
+    pub dogs: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TopAttendee {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub days: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnnualReview {
+    pub year: i32, // Fiscal year, named after the calendar year it starts in
+    pub label: String, // e.g. "2024/25" when the fiscal year doesn't start in January
+    pub start_date: String,
+    pub end_date: String,
+    pub dog_days: usize, // One per dog per day on the premises
+    pub days_open: usize,
+    pub busiest_day: Option<BusiestDay>,
+    pub top_attendee: Option<TopAttendee>,
+    pub new_dogs: usize,
+    pub new_customers: usize, // Owners whose first dog joined this year
+    pub incidents: usize, // Notes written up against a dog, as in the compliance pack
+    pub revenue: f64, // Payments received, less refunds and excluding tips
+}
+
+fn in_period(date: &str, (start, end): (NaiveDate, NaiveDate)) -> bool {
+    NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").is_ok_and(|d| start <= d && d <= end)
+}
+
+fn review(data: &AppData, year: i32) -> Result<AnnualReview, String> {
+    let period = data.settings.fiscal.year_bounds(year)?;
+    let joined_in_period = |joined: &DateTime<Utc>| {
+        let day = joined.date_naive();
+        period.0 <= day && day <= period.1
+    };
+    
+    let mut dog_days = 0;
+    let mut days_open = 0;
+    let mut busiest_day: Option<BusiestDay> = None;
+    let mut days_per_dog: HashMap<&String, usize> = HashMap::new();
+    let mut incidents = 0;
+    
+    let mut dates: Vec<&String> = data.daily_data.keys().filter(|d| in_period(d, period)).collect();
+    dates.sort();
+    for date in dates {
+        let day_data = &data.daily_data[date];
+        let dogs = attended_dogs(day_data);
+        incidents += day_data.records.values()
+            .filter(|r| r.notes.as_deref().is_some_and(|n| !n.trim().is_empty()))
+            .count();
+        if dogs.is_empty() {
+            continue;
+        }
+        days_open += 1;
+        dog_days += dogs.len();
+        // Earliest day wins a tie
+        if busiest_day.as_ref().is_none_or(|b| dogs.len() > b.dogs) {
+            busiest_day = Some(BusiestDay { date: date.clone(), dogs: dogs.len() });
+        }
+        for dog_id in dogs {
+            *days_per_dog.entry(dog_id).or_insert(0) += 1;
+        }
+    }
+    
+    let top_attendee = days_per_dog.into_iter()
+        .filter_map(|(dog_id, days)| {
+            let dog = data.dogs.iter().find(|d| d.id == *dog_id)?;
+            Some(TopAttendee { dog_id: dog.id.clone(), dog_name: dog.name.clone(), days })
+        })
+        .max_by(|a, b| a.days.cmp(&b.days).then(b.dog_name.cmp(&a.dog_name)));
+    
+    // Owners are matched by record where linked, otherwise by name
+    let owner_key = |dog: &Dog| dog.owner_id.clone().unwrap_or_else(|| dog.owner.trim().to_lowercase());
+    let mut first_joined: HashMap<String, DateTime<Utc>> = HashMap::new();
+    for dog in &data.dogs {
+        let joined = first_joined.entry(owner_key(dog)).or_insert(dog.created_at);
+        *joined = (*joined).min(dog.created_at);
+    }
+    let new_customers: HashSet<&String> = first_joined.iter()
+        .filter(|(_, joined)| joined_in_period(joined))
+        .map(|(owner, _)| owner)
+        .collect();
+    
+    let revenue: f64 = data.payments.iter()
+        .filter(|p| in_period(&p.date, period))
+        .map(|p| p.amount)
+        .sum();
+    
+    Ok(AnnualReview {
+        year,
+        label: data.settings.fiscal.year_label(year),
+        start_date: period.0.format("%Y-%m-%d").to_string(),
+        end_date: period.1.format("%Y-%m-%d").to_string(),
+        dog_days,
+        days_open,
+        busiest_day,
+        top_attendee,
+        new_dogs: data.dogs.iter().filter(|d| joined_in_period(&d.created_at)).count(),
+        new_customers: new_customers.len(),
+        incidents,
+        revenue: data.settings.currency.round(revenue),
+    })
+}
+
+fn check_year(data: &AppData, year: i32) -> Result<(), String> {
+    if year < 2000 || year > data.settings.fiscal.year_of(Utc::now().date_naive()) {
+        return Err(format!("No review is available for {}", year));
+    }
+    Ok(())
+}
+
+/// The fiscal year's headline numbers: dog-days, the busiest day, the most regular
+/// dog, newcomers, incidents and revenue.
+#[tauri::command]
+pub fn get_annual_review(year: i32) -> Result<AnnualReview, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    check_year(&data, year)?;
+    
+    review(&data, year)
+}
+
+/// The annual review as a one-page PDF for the newsletter or business plan.
+/// Returns the PDF bytes.
+#[tauri::command]
+pub fn export_annual_review(year: i32) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    check_year(&data, year)?;
+    let review = review(&data, year)?;
+    
+    let title = format!("{} - {} in review", data.settings.business_name, review.label);
+    let pdf = PdfWriter::a4(&title, false, &data.settings.branding)?;
+    pdf.heading(&data.settings.business_name, 20.0, MARGIN, 30.0);
+    pdf.heading(&format!("Our {} in review", review.label), 15.0, MARGIN, 42.0);
+    
+    let busiest = review.busiest_day.as_ref()
+        .map(|b| {
+            let date = NaiveDate::parse_from_str(&b.date, "%Y-%m-%d")
+                .map(|d| d.format("%A %-d %B").to_string())
+                .unwrap_or_else(|_| b.date.clone());
+            format!("{} with {} dogs", date, b.dogs)
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let top = review.top_attendee.as_ref()
+        .map(|t| format!("{} ({} days)", t.dog_name, t.days))
+        .unwrap_or_else(|| "-".to_string());
+    let rows = [
+        ("Dog-days of care", review.dog_days.to_string()),
+        ("Days open", review.days_open.to_string()),
+        ("Busiest day", busiest),
+        ("Most frequent attendee", top),
+        ("New dogs", review.new_dogs.to_string()),
+        ("New customers", review.new_customers.to_string()),
+        ("Incidents recorded", review.incidents.to_string()),
+        ("Revenue", data.settings.currency.format(review.revenue)),
+    ];
+    
+    let mut y = 60.0;
+    for (label, value) in rows {
+        pdf.text(label, 12.0, MARGIN, y, true);
+        pdf.text(&value, 12.0, MARGIN + 70.0, y, false);
+        y += 10.0;
+    }
+    pdf.text(&format!("Produced {}", Utc::now().format("%-d %B %Y")), 9.0, MARGIN, y + 6.0, false);
+    
+    pdf.finish()
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::age::{age_class_on, AgeClass};
+use crate::audit::record_audit;
+use crate::billing::PaymentMethod;
+use crate::breeds::{breed_group, BreedGroup};
+use crate::invoices::InvoiceStatus;
+use crate::permissions::{require_permission, Permission};
+use crate::{lock_app_data, save_app_data, AppData, AttendanceType, DogStatus, EntrySource, ServiceType};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnonymizedDog {
+    pub dog_ref: String, // "dog-1", "dog-2"... in the order dogs joined
+    pub household_ref: String,
+    pub breed_group: Option<BreedGroup>,
+    pub age_class: Option<AgeClass>,
+    pub status: DogStatus,
+    pub joined: String, // YYYY-MM
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnonymizedAttendance {
+    pub date: String,
+    pub dog_ref: String,
+    pub service_type: ServiceType,
+    pub attendance_type: Option<AttendanceType>, // Half or full day, for daycare
+    pub attending: bool,
+    pub source: EntrySource,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnonymizedPayment {
+    pub date: String,
+    pub household_ref: Option<String>,
+    pub amount: f64, // Negative for refunds; tips left out
+    pub method: PaymentMethod,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnonymizedInvoice {
+    pub period: String,
+    pub household_ref: String,
+    pub total: f64,
+    pub status: InvoiceStatus,
+}
+
+/// The business's data with everything that identifies a customer taken out:
+/// no names, contact details, notes, photos or original ids. Dogs and
+/// households are replaced by stable made-up references so patterns still
+/// line up across the lists.
+#[derive(Debug, Serialize, Clone)]
+pub struct AnonymizedDataset {
+    pub exported_at: DateTime<Utc>,
+    pub currency: String,
+    pub dogs: Vec<AnonymizedDog>,
+    pub attendance: Vec<AnonymizedAttendance>,
+    pub payments: Vec<AnonymizedPayment>,
+    pub invoices: Vec<AnonymizedInvoice>,
+}
+
+fn anonymize(data: &AppData) -> AnonymizedDataset {
+    let today = Utc::now().date_naive();
+    
+    let mut dogs: Vec<_> = data.dogs.iter().collect();
+    dogs.sort_by_key(|d| d.created_at);
+    let mut dog_refs: HashMap<&str, String> = HashMap::new();
+    let mut household_refs: HashMap<&str, String> = HashMap::new();
+    for dog in &dogs {
+        dog_refs.insert(&dog.id, format!("dog-{}", dog_refs.len() + 1));
+        let next = household_refs.len() + 1;
+        household_refs.entry(dog.household_key()).or_insert_with(|| format!("household-{}", next));
+    }
+    
+    let anonymized_dogs = dogs.iter()
+        .map(|dog| AnonymizedDog {
+            dog_ref: dog_refs[dog.id.as_str()].clone(),
+            household_ref: household_refs[dog.household_key()].clone(),
+            breed_group: breed_group(&dog.breed),
+            age_class: age_class_on(dog, &data.settings.age_classes, today),
+            status: dog.status,
+            joined: dog.created_at.format("%Y-%m").to_string(),
+        })
+        .collect();
+    
+    let mut attendance: Vec<AnonymizedAttendance> = data.daily_data.iter()
+        .flat_map(|(date, day_data)| day_data.attendance.entries.values().filter_map(|entry| {
+            let attendance_type = match entry.service_type {
+                ServiceType::Daycare => day_data.attendance.types.get(&entry.dog_id).cloned(),
+                _ => None,
+            };
+            Some(AnonymizedAttendance {
+                date: date.clone(),
+                dog_ref: dog_refs.get(entry.dog_id.as_str())?.clone(),
+                service_type: entry.service_type.clone(),
+                attendance_type,
+                attending: entry.attending,
+                source: entry.source,
+            })
+        }))
+        .collect();
+    attendance.sort_by(|a, b| a.date.cmp(&b.date).then(a.dog_ref.cmp(&b.dog_ref)));
+    
+    let household_ref = |id: &str| household_refs.get(id).cloned();
+    let mut payments: Vec<AnonymizedPayment> = data.payments.iter()
+        .map(|p| AnonymizedPayment {
+            date: p.date.clone(),
+            household_ref: p.household_id.as_deref().and_then(household_ref),
+            amount: p.amount,
+            method: p.method.clone(),
+        })
+        .collect();
+    payments.sort_by(|a, b| a.date.cmp(&b.date));
+    
+    let mut invoices: Vec<AnonymizedInvoice> = data.invoices.iter()
+        .filter_map(|i| Some(AnonymizedInvoice {
+            period: i.period.clone(),
+            household_ref: household_ref(&i.household_id)?,
+            total: i.total,
+            status: i.status.clone(),
+        }))
+        .collect();
+    invoices.sort_by(|a, b| a.period.cmp(&b.period).then(a.household_ref.cmp(&b.household_ref)));
+    
+    AnonymizedDataset {
+        exported_at: Utc::now(),
+        currency: data.settings.currency.code.clone(),
+        dogs: anonymized_dogs,
+        attendance,
+        payments,
+        invoices,
+    }
+}
+
+/// Attendance patterns, service mix and revenue without any customer details,
+/// for sharing with an accountant or consultant. Returns JSON.
+#[tauri::command]
+pub fn export_anonymized() -> Result<String, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let dataset = anonymize(&data);
+    let json = serde_json::to_string_pretty(&dataset)
+        .map_err(|e| format!("Failed to export data: {}", e))?;
+    
+    record_audit(&mut data, "anonymized_export", &format!(
+        "Exported anonymized data for {} dogs and {} attendance entries",
+        dataset.dogs.len(), dataset.attendance.len()
+    ));
+    save_app_data(&data)?;
+    Ok(json)
+}
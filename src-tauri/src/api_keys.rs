@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::crypto::{random_token, sha256_hex};
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data};
+
+const KEY_PREFIX: &str = "ddc_";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ApiScope {
+    #[serde(rename = "dogs:read")]
+    DogsRead,
+    #[serde(rename = "attendance:read")]
+    AttendanceRead,
+    #[serde(rename = "attendance:write")]
+    AttendanceWrite,
+    #[serde(rename = "webhooks")]
+    Webhooks,
+}
+
+/// An integration's access key. Only the SHA-256 hash of the secret is stored;
+/// the secret itself is shown once when the key is issued.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiKey {
+    pub id: String,
+    pub label: String,
+    pub key_hash: String,
+    pub key_hint: String, // First characters of the secret, to tell keys apart in the UI
+    pub scopes: Vec<ApiScope>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IssuedApiKey {
+    pub key: ApiKey,
+    pub secret: String,
+}
+
+#[tauri::command]
+pub fn list_api_keys() -> Result<Vec<ApiKey>, String> {
+    let data = load_app_data()?;
+    Ok(data.api_keys)
+}
+
+#[tauri::command]
+pub fn issue_api_key(label: String, scopes: Vec<ApiScope>) -> Result<IssuedApiKey, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageApiKeys)?;
+    
+    if label.trim().is_empty() {
+        return Err("A label is required so the key can be identified later".to_string());
+    }
+    if scopes.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    
+    let secret = format!("{}{}", KEY_PREFIX, random_token(32));
+    let key = ApiKey {
+        id: Uuid::new_v4().to_string(),
+        label,
+        key_hash: sha256_hex(secret.as_bytes()),
+        key_hint: secret.chars().take(KEY_PREFIX.len() + 6).collect(),
+        scopes,
+        created_at: Utc::now(),
+        last_used_at: None,
+        revoked_at: None,
+    };
+    
+    data.api_keys.push(key.clone());
+    record_audit(&mut data, "api_key_issued", &format!("Issued API key \"{}\"", key.label));
+    save_app_data(&data)?;
+    
+    Ok(IssuedApiKey { key, secret })
+}
+
+#[tauri::command]
+pub fn revoke_api_key(key_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageApiKeys)?;
+    
+    let key = data.api_keys.iter_mut()
+        .find(|k| k.id == key_id)
+        .ok_or("API key not found")?;
+    
+    if key.revoked_at.is_none() {
+        key.revoked_at = Some(Utc::now());
+        let details = format!("Revoked API key \"{}\"", key.label);
+        record_audit(&mut data, "api_key_revoked", &details);
+        save_app_data(&data)?;
+    }
+    
+    Ok(())
+}
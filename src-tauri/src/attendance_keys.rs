@@ -0,0 +1,144 @@
+use std::collections::hash_map::Entry;
+
+use crate::{AppData, ServiceType};
+
+/// Key a dog's booking for a service is stored under in a day's attendance
+/// entries, e.g. "<dog id>_Daycare". The frontend builds the same keys.
+pub fn entry_key(dog_id: &str, service_type: &ServiceType) -> String {
+    format!("{}_{}", dog_id, service_type.key())
+}
+
+/// Dog id and service from an entry key. Dog ids may themselves contain
+/// underscores, so the service is taken from after the last one.
+pub fn parse_entry_key(key: &str) -> Option<(&str, ServiceType)> {
+    let (dog_id, service) = key.rsplit_once('_')?;
+    if dog_id.is_empty() {
+        return None;
+    }
+    Some((dog_id, ServiceType::from_key(service)?))
+}
+
+/// File every attendance entry under the key its own dog and service give it,
+/// so lookups by key always find it. When two entries claim the same key, the
+/// one already filed there wins. Returns whether anything was moved.
+pub fn rekey_entries(data: &mut AppData) -> bool {
+    let mut changed = false;
+    for (date, day_data) in data.daily_data.iter_mut() {
+        let entries = &mut day_data.attendance.entries;
+        let misfiled: Vec<String> = entries.iter()
+            .filter(|(key, entry)| parse_entry_key(key) != Some((entry.dog_id.as_str(), entry.service_type.clone())))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in misfiled {
+            let entry = match entries.remove(&key) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let correct = entry_key(&entry.dog_id, &entry.service_type);
+            match entries.entry(correct) {
+                Entry::Occupied(_) => println!("Dropping duplicate attendance entry {} on {}", key, date),
+                Entry::Vacant(slot) => {
+                    println!("Moving attendance entry {} to {} on {}", key, slot.key(), date);
+                    slot.insert(entry);
+                }
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AttendanceEntry, EntrySource};
+    
+    fn entry(dog_id: &str, service_type: ServiceType) -> AttendanceEntry {
+        AttendanceEntry {
+            dog_id: dog_id.to_string(),
+            service_type,
+            attending: true,
+            drop_off_time: None,
+            pick_up_time: None,
+            notes: None,
+            handler_id: None,
+            updated_by: None,
+            trial: false,
+            awaiting_confirmation: false,
+            excused: false,
+            generated_from: None,
+            source: EntrySource::Manual,
+            allergies: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn keys_round_trip_for_every_service() {
+        for service_type in ServiceType::ALL {
+            let key = entry_key("dog-1", &service_type);
+            assert_eq!(parse_entry_key(&key), Some(("dog-1", service_type)));
+        }
+    }
+    
+    #[test]
+    fn keys_match_the_format_already_saved() {
+        // Data saved before keys had their own method used the Debug name
+        for service_type in ServiceType::ALL {
+            assert_eq!(entry_key("abc", &service_type), format!("abc_{:?}", service_type));
+        }
+        assert_eq!(entry_key("abc", &ServiceType::Daycare), "abc_Daycare");
+    }
+    
+    #[test]
+    fn service_keys_are_distinct_and_parse_back() {
+        for service_type in ServiceType::ALL {
+            assert_eq!(ServiceType::from_key(service_type.key()), Some(service_type.clone()));
+            let same_key = ServiceType::ALL.iter().filter(|s| s.key() == service_type.key()).count();
+            assert_eq!(same_key, 1);
+        }
+    }
+    
+    #[test]
+    fn dog_ids_with_underscores_parse() {
+        let key = entry_key("imported_42", &ServiceType::Boarding);
+        assert_eq!(parse_entry_key(&key), Some(("imported_42", ServiceType::Boarding)));
+    }
+    
+    #[test]
+    fn unknown_keys_do_not_parse() {
+        assert_eq!(parse_entry_key("dog-1_Grooming"), None);
+        assert_eq!(parse_entry_key("dog-1"), None);
+        assert_eq!(parse_entry_key("_Daycare"), None);
+    }
+    
+    #[test]
+    fn rekey_moves_misfiled_entries() {
+        let mut data = AppData::default();
+        let day = data.daily_data.entry("2024-05-01".to_string()).or_default();
+        day.attendance.entries.insert("dog-1_daycare".to_string(), entry("dog-1", ServiceType::Daycare));
+        day.attendance.entries.insert(entry_key("dog-2", &ServiceType::Training), entry("dog-2", ServiceType::Training));
+        
+        assert!(rekey_entries(&mut data));
+        let entries = &data.daily_data["2024-05-01"].attendance.entries;
+        assert_eq!(entries.len(), 2);
+        for (key, entry) in entries {
+            assert_eq!(*key, entry_key(&entry.dog_id, &entry.service_type));
+        }
+        assert!(!rekey_entries(&mut data));
+    }
+    
+    #[test]
+    fn rekey_keeps_the_correctly_filed_duplicate() {
+        let mut data = AppData::default();
+        let day = data.daily_data.entry("2024-05-01".to_string()).or_default();
+        let mut stray = entry("dog-1", ServiceType::Daycare);
+        stray.attending = false;
+        day.attendance.entries.insert("stray".to_string(), stray);
+        day.attendance.entries.insert(entry_key("dog-1", &ServiceType::Daycare), entry("dog-1", ServiceType::Daycare));
+        
+        assert!(rekey_entries(&mut data));
+        let entries = &data.daily_data["2024-05-01"].attendance.entries;
+        assert_eq!(entries.len(), 1);
+        assert!(entries[&entry_key("dog-1", &ServiceType::Daycare)].attending);
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, AppData};
+
+/// A record of who did what, for sensitive or after-the-fact changes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub staff_id: Option<String>, // None when made without anyone signed in
+    pub action: String,
+    pub details: String,
+}
+
+impl AuditEntry {
+    pub fn new(staff_id: Option<String>, action: &str, details: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            staff_id,
+            action: action.to_string(),
+            details: details.to_string(),
+        }
+    }
+}
+
+/// Append an audit entry attributed to the signed-in staff member. The caller saves.
+pub fn record_audit(data: &mut AppData, action: &str, details: &str) {
+    data.audit_log.push(AuditEntry::new(current_staff_id(), action, details));
+}
+
+#[tauri::command]
+pub fn get_audit_log(start_date: String, end_date: String) -> Result<Vec<AuditEntry>, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageStaff)?;
+    
+    let mut entries: Vec<AuditEntry> = data.audit_log.into_iter()
+        .filter(|e| {
+            let date = e.timestamp.format("%Y-%m-%d").to_string();
+            date >= start_date && date <= end_date
+        })
+        .collect();
+    
+    entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    Ok(entries)
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use native_tls::TlsConnector;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::{cleanup_old_backups, data_encryption, export_json, load_app_data, lock_app_data, save_app_data, save_cloud_backup, AppData};
+
+/// Status id for the backup folder configured before extra destinations existed.
+const PRIMARY_FOLDER_ID: &str = "cloud_directory";
+
+/// How long an S3 upload may stall before it's given up on, so a dead
+/// connection doesn't hang the backup.
+const S3_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum DestinationKind {
+    #[serde(rename = "folder")]
+    Folder { directory: String }, // Local disk, USB drive or a synced cloud folder
+    #[serde(rename = "s3")]
+    S3 {
+        bucket: String,
+        region: String,
+        #[serde(default)]
+        endpoint: Option<String>, // host[:port] of an S3-compatible service; Amazon S3 if not given
+        #[serde(default)]
+        prefix: String, // Key prefix, e.g. "daycare/"
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+/// Somewhere backups are written in addition to the main backup folder, so one
+/// failing sync client doesn't leave the business without a recent copy.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupDestination {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub kind: DestinationKind,
+}
+
+/// Outcome of the latest backup to one destination.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DestinationStatus {
+    pub destination_id: String,
+    pub name: String,
+    pub last_attempt_at: DateTime<Utc>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>, // None when the latest attempt worked
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac(key: &[u8], message: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// PUT the backup to S3, signed with AWS Signature Version 4. Buckets on
+/// Amazon are addressed by virtual host, anything else by path.
+fn put_s3_object(
+    bucket: &str,
+    region: &str,
+    endpoint: Option<&str>,
+    key: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let key_path: Vec<String> = key.split('/').map(|part| urlencoding::encode(part).to_string()).collect();
+    let (host, path) = match endpoint.map(|e| e.trim().trim_start_matches("https://").trim_end_matches('/')) {
+        Some(endpoint) if !endpoint.is_empty() => (endpoint.to_string(), format!("/{}/{}", bucket, key_path.join("/"))),
+        _ => (format!("{}.s3.{}.amazonaws.com", bucket, region), format!("/{}", key_path.join("/"))),
+    };
+    
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let day = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(body));
+    let canonical_request = format!(
+        "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        path, host, payload_hash, amz_date, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", day, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let date_key = hmac(format!("AWS4{}", secret_access_key).as_bytes(), &day);
+    let signing_key = hmac(&hmac(&hmac(&date_key, region), "s3"), "aws4_request");
+    let signature = hex(&hmac(&signing_key, &string_to_sign));
+    
+    let request = format!(
+        "PUT {} HTTP/1.1\r\nHost: {}\r\nx-amz-content-sha256: {}\r\nx-amz-date: {}\r\n\
+        Authorization: AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}\r\n\
+        Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path, host, payload_hash, amz_date, access_key_id, scope, signature, body.len()
+    );
+    
+    let (server, port) = match host.rsplit_once(':') {
+        Some((server, port)) => (server, port.parse().map_err(|_| format!("Invalid port in {}", host))?),
+        None => (host.as_str(), 443),
+    };
+    let connector = TlsConnector::new().map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    let stream = TcpStream::connect((server, port)).map_err(|e| format!("Failed to connect to {}: {}", host, e))?;
+    stream.set_read_timeout(Some(S3_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(S3_TIMEOUT)))
+        .map_err(|e| format!("Failed to set up the connection to {}: {}", host, e))?;
+    let mut stream = connector.connect(server, stream).map_err(|e| format!("TLS error with {}: {}", host, e))?;
+    stream.write_all(request.as_bytes())
+        .and_then(|_| stream.write_all(body))
+        .map_err(|e| format!("Failed to upload to {}: {}", host, e))?;
+    
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|e| format!("No response from {}: {}", host, e))?;
+    let status_line = response.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1).is_some_and(|code| code.starts_with('2')) {
+        Ok(())
+    } else {
+        // S3 explains failures in an XML <Message> element
+        let message = response.split_once("<Message>")
+            .and_then(|(_, rest)| rest.split_once("</Message>"))
+            .map(|(message, _)| message.to_string())
+            .unwrap_or_else(|| status_line.to_string());
+        Err(format!("S3 rejected the backup: {}", message))
+    }
+}
+
+fn write_backup(kind: &DestinationKind, filename: &str, content: &str, max_backups: u32) -> Result<(), String> {
+    match kind {
+        DestinationKind::Folder { directory } => {
+            save_cloud_backup(directory.clone(), filename.to_string(), content.to_string())?;
+            cleanup_old_backups(directory.clone(), max_backups)
+        }
+        DestinationKind::S3 { bucket, region, endpoint, prefix, access_key_id, secret_access_key } => {
+            let key = format!("{}{}", prefix, filename);
+            put_s3_object(bucket, region, endpoint.as_deref(), &key, access_key_id, secret_access_key, content.as_bytes())
+        }
+    }
+}
+
+/// The main backup folder and every enabled extra destination.
+fn destinations(data: &AppData) -> Vec<BackupDestination> {
+    let config = match data.settings.cloud_backup {
+        Some(ref config) if config.enabled => config,
+        _ => return Vec::new(),
+    };
+    let mut destinations = Vec::new();
+    if !config.cloud_directory.trim().is_empty() {
+        destinations.push(BackupDestination {
+            id: PRIMARY_FOLDER_ID.to_string(),
+            name: "Backup folder".to_string(),
+            enabled: true,
+            kind: DestinationKind::Folder { directory: config.cloud_directory.clone() },
+        });
+    }
+    destinations.extend(config.destinations.iter().filter(|d| d.enabled).cloned());
+    destinations
+}
+
+/// Back up to every destination, carrying on past any that fail. Each
+/// destination's outcome is recorded; an error is only returned when there
+/// was nowhere to back up to or every destination failed.
+#[tauri::command]
+pub fn run_backup() -> Result<Vec<DestinationStatus>, String> {
+    let mut data = lock_app_data()?;
+    let destinations = destinations(&data);
+    if destinations.is_empty() {
+        return Err("No backup destinations are set up".to_string());
+    }
+    let max_backups = data.settings.cloud_backup.as_ref().map(|c| c.max_backups).unwrap_or(100);
+    // Encrypted with the data file's key while encryption is on
+    let content = data_encryption::encrypt_data(export_json(&data)?)?;
+    let filename = format!("doggy-daycare-backup-{}.json", Utc::now().format("%Y-%m-%dT%H-%M-%S-%3fZ"));
+    
+    let mut statuses = Vec::new();
+    for destination in &destinations {
+        let result = write_backup(&destination.kind, &filename, &content, max_backups);
+        let now = Utc::now();
+        let previous = data.backup_status.iter().find(|s| s.destination_id == destination.id);
+        let status = DestinationStatus {
+            destination_id: destination.id.clone(),
+            name: destination.name.clone(),
+            last_attempt_at: now,
+            last_success_at: if result.is_ok() { Some(now) } else { previous.and_then(|s| s.last_success_at) },
+            last_error: result.err(),
+        };
+        if let Some(ref error) = status.last_error {
+            println!("Backup to {} failed: {}", destination.name, error);
+        }
+        statuses.push(status);
+    }
+    
+    let all_failed = statuses.iter().all(|s| s.last_error.is_some());
+    data.backup_status.retain(|s| destinations.iter().any(|d| d.id == s.destination_id));
+    for status in &statuses {
+        match data.backup_status.iter_mut().find(|s| s.destination_id == status.destination_id) {
+            Some(existing) => *existing = status.clone(),
+            None => data.backup_status.push(status.clone()),
+        }
+    }
+    save_app_data(&data)?;
+    
+    if all_failed {
+        let errors: Vec<String> = statuses.iter()
+            .filter_map(|s| s.last_error.as_ref().map(|e| format!("{}: {}", s.name, e)))
+            .collect();
+        return Err(format!("Every backup destination failed. {}", errors.join("; ")));
+    }
+    Ok(statuses)
+}
+
+/// Latest outcome for each backup destination.
+#[tauri::command]
+pub fn get_backup_status() -> Result<Vec<DestinationStatus>, String> {
+    let data = load_app_data()?;
+    Ok(data.backup_status)
+}
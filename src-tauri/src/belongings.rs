@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data};
+
+/// Something the owner left with the dog at drop-off, e.g. a lead or harness.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Belonging {
+    pub item: String,
+    pub returned: bool,
+}
+
+pub fn belongings_from(items: Vec<String>) -> Vec<Belonging> {
+    items.into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .map(|item| Belonging { item, returned: false })
+        .collect()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UnreturnedBelonging {
+    pub date: String,
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub phone: String,
+    pub item: String,
+    pub dog_checked_out: bool,
+}
+
+#[tauri::command]
+pub fn set_belongings(date: String, dog_id: String, items: Vec<String>) -> Result<Vec<Belonging>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    
+    let record = data.daily_data.entry(date).or_default()
+        .records.entry(dog_id).or_default();
+    
+    // Keep the returned flag for items that were already on the list
+    let previous = std::mem::take(&mut record.belongings);
+    record.belongings = belongings_from(items).into_iter()
+        .map(|mut b| {
+            b.returned = previous.iter().any(|p| p.item == b.item && p.returned);
+            b
+        })
+        .collect();
+    record.updated_by = current_staff_id();
+    let belongings = record.belongings.clone();
+    
+    save_app_data(&data)?;
+    Ok(belongings)
+}
+
+#[tauri::command]
+pub fn set_belonging_returned(date: String, dog_id: String, item: String, returned: bool) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let record = data.daily_data.get_mut(&date)
+        .and_then(|d| d.records.get_mut(&dog_id))
+        .ok_or("Daily record not found")?;
+    let belonging = record.belongings.iter_mut()
+        .find(|b| b.item == item)
+        .ok_or("Item not found")?;
+    belonging.returned = returned;
+    record.updated_by = current_staff_id();
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Items brought in between the dates that were never ticked off at pick-up.
+#[tauri::command]
+pub fn get_unreturned_belongings(start_date: String, end_date: String) -> Result<Vec<UnreturnedBelonging>, String> {
+    let data = load_app_data()?;
+    
+    let mut unreturned = Vec::new();
+    for (date, day_data) in data.daily_data.iter().filter(|(d, _)| **d >= start_date && **d <= end_date) {
+        for (dog_id, record) in &day_data.records {
+            let dog = data.dogs.iter().find(|d| d.id == *dog_id);
+            for belonging in record.belongings.iter().filter(|b| !b.returned) {
+                unreturned.push(UnreturnedBelonging {
+                    date: date.clone(),
+                    dog_id: dog_id.clone(),
+                    dog_name: dog.map(|d| d.name.clone()).unwrap_or_default(),
+                    owner: dog.map(|d| d.owner.clone()).unwrap_or_default(),
+                    phone: dog.map(|d| d.phone.clone()).unwrap_or_default(),
+                    item: belonging.item.clone(),
+                    dog_checked_out: record.checked_out_at.is_some(),
+                });
+            }
+        }
+    }
+    
+    unreturned.sort_by(|a, b| a.date.cmp(&b.date).then(a.dog_name.cmp(&b.dog_name)));
+    Ok(unreturned)
+}
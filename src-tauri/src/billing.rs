@@ -0,0 +1,367 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::invoices::{amount_due, loyalty_credit, InvoiceStatus};
+use crate::permissions::{require_permission, Permission};
+use crate::staff::{hours_worked_by_staff, parse_period};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum PaymentMethod {
+    #[serde(rename = "cash")]
+    Cash,
+    #[serde(rename = "card")]
+    Card,
+    #[serde(rename = "bank_transfer")]
+    BankTransfer,
+    #[serde(rename = "other")]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", content = "staff_id")]
+pub enum TipRecipient {
+    Staff(String),
+    Pooled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tip {
+    pub amount: f64,
+    pub recipient: TipRecipient,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Payment {
+    pub id: String,
+    pub household_id: Option<String>,
+    pub amount: f64, // Service revenue only, excluding any tip; negative for refunds
+    pub method: PaymentMethod,
+    pub date: String, // YYYY-MM-DD
+    pub reference: Option<String>,
+    pub invoice_id: Option<String>,
+    #[serde(default)]
+    pub tip: Option<Tip>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StaffTipTotal {
+    pub staff_id: String,
+    pub staff_name: String,
+    pub direct_tips: f64,
+    pub pooled_share: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TipReport {
+    pub period_start: String,
+    pub period_end: String,
+    pub direct_total: f64,
+    pub pooled_total: f64,
+    pub per_staff: Vec<StaffTipTotal>,
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn record_payment(
+    household_id: Option<String>,
+    amount: f64,
+    method: PaymentMethod,
+    date: String,
+    reference: Option<String>,
+    invoice_id: Option<String>,
+    tip_amount: Option<f64>,
+    tip_staff_id: Option<String>,
+) -> Result<Payment, String> {
+    let mut data = lock_app_data()?;
+    let currency = data.settings.currency.clone();
+    
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format. Expected YYYY-MM-DD".to_string())?;
+    
+    require_permission(&data, Permission::ManageBilling)?;
+    if amount < 0.0 {
+        require_permission(&data, Permission::IssueRefunds)?;
+    }
+    
+    let tip = match tip_amount.filter(|t| *t > 0.0) {
+        Some(tip_amount) => {
+            let recipient = match tip_staff_id.filter(|id| !id.is_empty()) {
+                Some(staff_id) => {
+                    if !data.staff.iter().any(|s| s.id == staff_id) {
+                        return Err("Staff member not found".to_string());
+                    }
+                    TipRecipient::Staff(staff_id)
+                }
+                None => TipRecipient::Pooled,
+            };
+            Some(Tip { amount: currency.round(tip_amount), recipient })
+        }
+        None => None,
+    };
+    
+    let payment = Payment {
+        id: Uuid::new_v4().to_string(),
+        household_id,
+        amount: currency.round(amount),
+        method,
+        date,
+        reference,
+        invoice_id,
+        tip,
+        created_at: Utc::now(),
+    };
+    
+    data.payments.push(payment.clone());
+    if payment.amount < 0.0 {
+        let details = format!("Refund of {} on {}", currency.format(-payment.amount), payment.date);
+        record_audit(&mut data, "refund_issued", &details);
+    }
+    save_app_data(&data)?;
+    
+    Ok(payment)
+}
+
+#[tauri::command]
+pub fn get_payments(start_date: String, end_date: String) -> Result<Vec<Payment>, String> {
+    let data = load_app_data()?;
+    
+    let mut payments: Vec<Payment> = data.payments.into_iter()
+        .filter(|p| p.date >= start_date && p.date <= end_date)
+        .collect();
+    
+    payments.sort_by(|a, b| a.date.cmp(&b.date).then(a.created_at.cmp(&b.created_at)));
+    Ok(payments)
+}
+
+#[tauri::command]
+pub fn delete_payment(payment_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    if let Some(index) = data.payments.iter().position(|p| p.id == payment_id) {
+        let removed = data.payments.remove(index);
+        let details = format!("Deleted payment of {} from {}", data.settings.currency.format(removed.amount), removed.date);
+        record_audit(&mut data, "payment_deleted", &details);
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Payment not found".to_string())
+    }
+}
+
+/// Tips per staff member for payout. Direct tips go to the named person; pooled
+/// tips are shared in proportion to hours worked in the period (from the time
+/// clock), or equally among active staff if nobody clocked any hours.
+#[tauri::command]
+pub fn get_tip_report(period_start: String, period_end: String) -> Result<TipReport, String> {
+    let data = load_app_data()?;
+    let (start, end) = parse_period(&period_start, &period_end)?;
+    let currency = &data.settings.currency;
+    
+    let mut direct: HashMap<String, f64> = HashMap::new();
+    let mut pooled_total = 0.0;
+    
+    for payment in &data.payments {
+        if payment.date < period_start || payment.date > period_end {
+            continue;
+        }
+        
+        if let Some(ref tip) = payment.tip {
+            match tip.recipient {
+                TipRecipient::Staff(ref staff_id) => *direct.entry(staff_id.clone()).or_insert(0.0) += tip.amount,
+                TipRecipient::Pooled => pooled_total += tip.amount,
+            }
+        }
+    }
+    
+    let hours = hours_worked_by_staff(&data.time_clock, start, end);
+    let total_hours: f64 = hours.values().sum();
+    let active_staff = data.staff.iter().filter(|s| s.active).count();
+    
+    let mut per_staff: Vec<StaffTipTotal> = data.staff.iter()
+        .map(|member| {
+            let direct_tips = direct.get(&member.id).copied().unwrap_or(0.0);
+            let pooled_share = if total_hours > 0.0 {
+                pooled_total * hours.get(&member.id).copied().unwrap_or(0.0) / total_hours
+            } else if member.active && active_staff > 0 {
+                pooled_total / active_staff as f64
+            } else {
+                0.0
+            };
+            let pooled_share = currency.round(pooled_share);
+            
+            StaffTipTotal {
+                staff_id: member.id.clone(),
+                staff_name: member.name.clone(),
+                direct_tips,
+                pooled_share,
+                total: currency.round(direct_tips + pooled_share),
+            }
+        })
+        .filter(|t| t.total > 0.0)
+        .collect();
+    
+    per_staff.sort_by(|a, b| a.staff_name.cmp(&b.staff_name));
+    
+    Ok(TipReport {
+        period_start,
+        period_end,
+        direct_total: currency.round(direct.values().sum()),
+        pooled_total: currency.round(pooled_total),
+        per_staff,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoyaltyRedemption {
+    pub id: String,
+    pub household_id: String,
+    pub invoice_id: String,
+    pub points: u32,
+    pub value: f64,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoyaltyBalance {
+    pub household_id: String,
+    pub attended_days: u32,
+    pub points_earned: u32,
+    pub points_redeemed: u32,
+    pub points_available: u32,
+    pub redeemable_value: f64,
+}
+
+/// Days (per dog, up to today) on which a household's dogs actually attended.
+/// Points are derived from this each time rather than accrued, so corrections to
+/// past attendance are reflected in the balance automatically.
+fn attended_days_for_household(data: &AppData, household_id: &str) -> u32 {
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let dog_ids: Vec<&str> = data.dogs.iter()
+        .filter(|d| d.household_key() == household_id)
+        .map(|d| d.id.as_str())
+        .collect();
+    
+    data.daily_data.iter()
+        .filter(|(date, _)| **date <= today)
+        .map(|(_, day_data)| {
+            dog_ids.iter()
+                .filter(|dog_id| {
+                    day_data.attendance.entries.values().any(|e| e.dog_id == **dog_id && e.attending)
+                        || day_data.attendance.dogs.get(**dog_id).copied().unwrap_or(false)
+                })
+                .count() as u32
+        })
+        .sum()
+}
+
+fn loyalty_balance(data: &AppData, household_id: &str) -> LoyaltyBalance {
+    let rules = &data.settings.loyalty;
+    let attended_days = attended_days_for_household(data, household_id);
+    let points_earned = attended_days * rules.points_per_attended_day;
+    let points_redeemed: u32 = data.loyalty_redemptions.iter()
+        .filter(|r| r.household_id == household_id)
+        .map(|r| r.points)
+        .sum();
+    let points_available = points_earned.saturating_sub(points_redeemed);
+    
+    LoyaltyBalance {
+        household_id: household_id.to_string(),
+        attended_days,
+        points_earned,
+        points_redeemed,
+        points_available,
+        redeemable_value: data.settings.currency.round(points_available as f64 * rules.redemption_value_per_point),
+    }
+}
+
+#[tauri::command]
+pub fn get_loyalty_balance(household_id: String) -> Result<LoyaltyBalance, String> {
+    let data = load_app_data()?;
+    
+    if !data.dogs.iter().any(|d| d.household_key() == household_id) {
+        return Err("Household not found".to_string());
+    }
+    
+    Ok(loyalty_balance(&data, &household_id))
+}
+
+/// Redeem points as a credit against an invoice. Redeems the full available
+/// balance, up to what's left to pay on the invoice, unless `points` is given.
+/// Paid and voided invoices can't take points.
+#[tauri::command]
+pub fn redeem_points(household_id: String, invoice_id: String, points: Option<u32>) -> Result<LoyaltyRedemption, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    let rules = data.settings.loyalty.clone();
+    
+    if !rules.enabled {
+        return Err("Loyalty program is not enabled".to_string());
+    }
+    
+    if !data.dogs.iter().any(|d| d.household_key() == household_id) {
+        return Err("Household not found".to_string());
+    }
+    
+    if data.loyalty_redemptions.iter().any(|r| r.household_id == household_id && r.invoice_id == invoice_id) {
+        return Err("Points have already been redeemed against this invoice".to_string());
+    }
+    
+    let invoice = data.invoices.iter()
+        .find(|i| i.id == invoice_id)
+        .ok_or("Invoice not found")?;
+    if invoice.household_id != household_id {
+        return Err(format!("Invoice {} is for another household", invoice.number));
+    }
+    let remaining = match invoice.status {
+        InvoiceStatus::Void => return Err(format!("Invoice {} has been voided", invoice.number)),
+        InvoiceStatus::Draft => data.settings.currency.round(invoice.total - loyalty_credit(&data, &invoice.id)),
+        _ => amount_due(&data, invoice),
+    };
+    if remaining <= 0.0 {
+        return Err(format!("Invoice {} is already paid", invoice.number));
+    }
+    
+    let balance = loyalty_balance(&data, &household_id);
+    let covers_remaining = if rules.redemption_value_per_point > 0.0 {
+        (remaining / rules.redemption_value_per_point).floor() as u32
+    } else {
+        balance.points_available
+    };
+    let points = points.unwrap_or(balance.points_available.min(covers_remaining));
+    
+    if points > balance.points_available {
+        return Err(format!("Only {} points available", balance.points_available));
+    }
+    if points == 0 || points < rules.minimum_redemption_points {
+        return Err(format!("At least {} points are needed to redeem", rules.minimum_redemption_points));
+    }
+    
+    let value = data.settings.currency.round(points as f64 * rules.redemption_value_per_point);
+    if value > remaining {
+        return Err(format!(
+            "{} points are worth {}, more than the {} left to pay",
+            points, data.settings.currency.format(value), data.settings.currency.format(remaining)
+        ));
+    }
+    
+    let redemption = LoyaltyRedemption {
+        id: Uuid::new_v4().to_string(),
+        household_id,
+        invoice_id,
+        points,
+        value,
+        redeemed_at: Utc::now(),
+    };
+    
+    data.loyalty_redemptions.push(redemption.clone());
+    save_app_data(&data)?;
+    
+    Ok(redemption)
+}
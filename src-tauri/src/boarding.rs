@@ -0,0 +1,461 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::age::{age_class_on, AgeClass};
+use crate::attendance_keys::entry_key;
+use crate::invoices::InvoiceLine;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::pricing::{rate_label, service_label};
+use crate::times::normalize_optional;
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, AttendanceEntry, AttendanceType, EntrySource, ServiceType};
+
+/// One booking of one or more dogs from a household staying with us overnight.
+/// Each night is booked as boarding attendance; the stay is billed as a whole
+/// in the month the dogs go home.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoardingStay {
+    pub id: String,
+    pub dog_ids: Vec<String>, // Dogs sharing the stay get the multi-dog discount
+    pub check_in_date: String,
+    pub check_out_date: String,
+    pub check_out_time: Option<String>, // HH:MM; after the late checkout time costs extra
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A day we charge more for, e.g. Christmas Eve. The surcharge applies to
+/// the night starting on that date, on stays and on boarding nights booked
+/// day by day.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeakDate {
+    pub date: String,
+    pub name: String,
+    pub surcharge_percent: f64,
+    #[serde(default)]
+    pub min_stay_nights: Option<u32>, // Stays including this night must be at least this long
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoardingSettings {
+    pub late_checkout_after: String, // HH:MM
+    pub late_checkout_percent: f64, // Of the nightly rate; 100 charges a full extra night
+    pub peak_dates: Vec<PeakDate>,
+}
+
+impl Default for BoardingSettings {
+    fn default() -> Self {
+        Self {
+            late_checkout_after: "12:00".to_string(),
+            late_checkout_percent: 50.0,
+            peak_dates: Vec::new(),
+        }
+    }
+}
+
+impl BoardingSettings {
+    pub fn peak_date(&self, date: &str) -> Option<&PeakDate> {
+        self.peak_dates.iter().find(|p| p.date == date)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BoardingCharges {
+    pub stay_id: String,
+    pub nights: u32,
+    pub late_checkout: bool,
+    pub lines: Vec<InvoiceLine>,
+    pub total: f64,
+    pub warnings: Vec<String>, // e.g. nights with no boarding rate set
+}
+
+fn parse_date(date: &str, label: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid {} format", label))
+}
+
+/// Nights between check-in and check-out, each as the date the night starts.
+pub fn stay_nights(stay: &BoardingStay) -> Result<Vec<NaiveDate>, String> {
+    let check_in = parse_date(&stay.check_in_date, "check-in date")?;
+    let check_out = parse_date(&stay.check_out_date, "check-out date")?;
+    Ok(check_in.iter_days().take_while(|d| *d < check_out).collect())
+}
+
+pub fn is_late_checkout(settings: &BoardingSettings, check_out_time: Option<&str>) -> bool {
+    let parse = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").ok();
+    match (check_out_time.and_then(parse), parse(&settings.late_checkout_after)) {
+        (Some(time), Some(cutoff)) => time > cutoff,
+        _ => false,
+    }
+}
+
+/// The stay covering a dog's night, if the night was booked as part of one.
+pub fn stay_for_night<'a>(data: &'a AppData, dog_id: &str, date: &str) -> Option<&'a BoardingStay> {
+    data.boarding_stays.iter().find(|s| {
+        s.dog_ids.iter().any(|d| d == dog_id) && s.check_in_date.as_str() <= date && date < s.check_out_date.as_str()
+    })
+}
+
+/// A dog being charged for a stay, with the age class it's in on each night.
+struct StayDog {
+    name: String,
+    dog_id: Option<String>,
+    age_classes: Vec<Option<AgeClass>>, // One per night
+}
+
+/// Price a stay: each dog's nights at the boarding rate for their age class,
+/// peak-date surcharges, a late checkout charge and the multi-dog discount
+/// for dogs sharing the stay. Dogs are charged in the order given.
+fn price_stay(
+    data: &AppData,
+    nights: &[NaiveDate],
+    check_out_time: Option<&str>,
+    dogs: &[StayDog],
+) -> (Vec<InvoiceLine>, Vec<String>) {
+    let prices = &data.settings.pricing;
+    let currency = &data.settings.currency;
+    let settings = &data.settings.boarding;
+    let late_checkout = is_late_checkout(settings, check_out_time);
+    
+    let mut lines = Vec::new();
+    let mut warnings = Vec::new();
+    for (rank, dog) in dogs.iter().enumerate() {
+        // Nights grouped by rate, keeping the order they first appear in
+        let mut rated: Vec<(String, f64, u32)> = Vec::new();
+        let mut surcharges: Vec<(String, f64)> = Vec::new();
+        let mut last_rate = None;
+        
+        for (night, age_class) in nights.iter().zip(&dog.age_classes) {
+            let rate = match prices.rate(&ServiceType::Boarding, &AttendanceType::FullDay, *age_class, *night) {
+                Some(rate) => rate,
+                None => {
+                    let warning = format!("No price set for {}", service_label(&ServiceType::Boarding, &AttendanceType::FullDay).to_lowercase());
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                    continue;
+                }
+            };
+            let label = rate_label(rate);
+            match rated.iter_mut().find(|(l, p, _)| *l == label && *p == rate.price) {
+                Some(group) => group.2 += 1,
+                None => rated.push((label, rate.price, 1)),
+            }
+            last_rate = Some(rate.price);
+            
+            if let Some(peak) = settings.peak_date(&night.format("%Y-%m-%d").to_string()) {
+                surcharges.push((peak_surcharge_label(peak, *night), rate.price * peak.surcharge_percent / 100.0));
+            }
+        }
+        
+        let mut dog_lines = Vec::new();
+        for (label, price, count) in rated {
+            dog_lines.push(InvoiceLine {
+                description: format!("{}: {} ({} nights)", dog.name, label, count),
+                dog_id: dog.dog_id.clone(),
+                quantity: count,
+                unit_price: currency.round(price),
+                amount: currency.round(price * count as f64),
+                service_type: Some(ServiceType::Boarding),
+            });
+        }
+        for (label, amount) in surcharges {
+            dog_lines.push(InvoiceLine {
+                description: format!("{}: {}", dog.name, label),
+                dog_id: dog.dog_id.clone(),
+                quantity: 1,
+                unit_price: currency.round(amount),
+                amount: currency.round(amount),
+                service_type: Some(ServiceType::Boarding),
+            });
+        }
+        if let (true, Some(rate)) = (late_checkout, last_rate) {
+            let amount = currency.round(rate * settings.late_checkout_percent / 100.0);
+            if amount > 0.0 {
+                dog_lines.push(InvoiceLine {
+                    description: format!(
+                        "{}: late checkout ({} after {})",
+                        dog.name,
+                        check_out_time.unwrap_or(""),
+                        settings.late_checkout_after
+                    ),
+                    dog_id: dog.dog_id.clone(),
+                    quantity: 1,
+                    unit_price: amount,
+                    amount,
+                    service_type: Some(ServiceType::Boarding),
+                });
+            }
+        }
+        
+        let percent = prices.multi_dog_percent(rank as u32 + 1);
+        let subtotal: f64 = dog_lines.iter().map(|l| l.amount).sum();
+        lines.append(&mut dog_lines);
+        if percent > 0.0 && subtotal > 0.0 {
+            let discount = currency.round(subtotal * percent / 100.0);
+            lines.push(InvoiceLine {
+                description: format!("{}: multi-dog discount ({}%)", dog.name, percent),
+                dog_id: dog.dog_id.clone(),
+                quantity: 1,
+                unit_price: -discount,
+                amount: -discount,
+                service_type: None,
+            });
+        }
+    }
+    (lines, warnings)
+}
+
+pub fn peak_surcharge_label(peak: &PeakDate, night: NaiveDate) -> String {
+    format!("peak surcharge, {} {} ({}%)", peak.name, night.format("%-d %b"), peak.surcharge_percent)
+}
+
+/// Itemized charges for a booked stay.
+pub fn boarding_charges(data: &AppData, stay: &BoardingStay) -> Result<BoardingCharges, String> {
+    let nights = stay_nights(stay)?;
+    
+    let mut dogs: Vec<&crate::Dog> = stay.dog_ids.iter()
+        .filter_map(|id| data.dogs.iter().find(|d| d.id == *id))
+        .collect();
+    dogs.sort_by_key(|d| d.created_at);
+    let dogs: Vec<StayDog> = dogs.into_iter()
+        .map(|dog| StayDog {
+            name: dog.name.clone(),
+            dog_id: Some(dog.id.clone()),
+            age_classes: nights.iter().map(|n| age_class_on(dog, &data.settings.age_classes, *n)).collect(),
+        })
+        .collect();
+    
+    let (lines, warnings) = price_stay(data, &nights, stay.check_out_time.as_deref(), &dogs);
+    Ok(BoardingCharges {
+        stay_id: stay.id.clone(),
+        nights: nights.len() as u32,
+        late_checkout: is_late_checkout(&data.settings.boarding, stay.check_out_time.as_deref()),
+        total: data.settings.currency.round(lines.iter().map(|l| l.amount).sum()),
+        lines,
+        warnings,
+    })
+}
+
+fn book_nights(data: &mut AppData, stay: &BoardingStay) -> Result<(), String> {
+    let key = |dog_id: &str| entry_key(dog_id, &ServiceType::Boarding);
+    for night in stay_nights(stay)? {
+        let day_data = data.daily_data.entry(night.format("%Y-%m-%d").to_string()).or_default();
+        for dog_id in &stay.dog_ids {
+            day_data.attendance.entries.insert(key(dog_id), AttendanceEntry {
+                dog_id: dog_id.clone(),
+                service_type: ServiceType::Boarding,
+                attending: true,
+                drop_off_time: None,
+                pick_up_time: None,
+                notes: Some("Boarding stay".to_string()),
+                handler_id: None,
+                updated_by: current_staff_id(),
+                trial: false,
+                awaiting_confirmation: false,
+                excused: false,
+                generated_from: None,
+                source: EntrySource::Manual,
+                allergies: Vec::new(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn release_nights(data: &mut AppData, stay: &BoardingStay) -> Result<(), String> {
+    for night in stay_nights(stay)? {
+        if let Some(day_data) = data.daily_data.get_mut(&night.format("%Y-%m-%d").to_string()) {
+            for dog_id in &stay.dog_ids {
+                day_data.attendance.entries.remove(&entry_key(dog_id, &ServiceType::Boarding));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_stay(data: &AppData, stay: &BoardingStay) -> Result<(), String> {
+    if stay.dog_ids.is_empty() {
+        return Err("Choose at least one dog for the stay".to_string());
+    }
+    for dog_id in &stay.dog_ids {
+        let dog = data.dogs.iter()
+            .find(|d| d.id == *dog_id)
+            .ok_or("Dog not found")?;
+        let overlapping = data.boarding_stays.iter().find(|s| {
+            s.id != stay.id && s.dog_ids.contains(dog_id)
+                && s.check_in_date < stay.check_out_date && stay.check_in_date < s.check_out_date
+        });
+        if let Some(other) = overlapping {
+            return Err(format!("{} is already staying from {} to {}", dog.name, other.check_in_date, other.check_out_date));
+        }
+    }
+    let nights = stay_nights(stay)?;
+    if nights.is_empty() {
+        return Err("Check-out must be at least one night after check-in".to_string());
+    }
+    check_minimum_stay(&data.settings.boarding, &nights)
+}
+
+/// Enforce the longest minimum stay of any peak night the stay includes.
+fn check_minimum_stay(settings: &BoardingSettings, nights: &[NaiveDate]) -> Result<(), String> {
+    let strictest = nights.iter()
+        .filter_map(|n| settings.peak_date(&n.format("%Y-%m-%d").to_string()))
+        .filter_map(|p| p.min_stay_nights.map(|min| (min, p)))
+        .max_by_key(|(min, _)| *min);
+    match strictest {
+        Some((min, peak)) if (nights.len() as u32) < min => Err(format!(
+            "Stays over {} ({}) must be at least {} nights",
+            peak.name, peak.date, min
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn create_boarding_stay(
+    dog_ids: Vec<String>,
+    check_in_date: String,
+    check_out_date: String,
+    check_out_time: Option<String>,
+    notes: Option<String>,
+) -> Result<BoardingStay, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let stay = BoardingStay {
+        id: Uuid::new_v4().to_string(),
+        dog_ids,
+        check_in_date,
+        check_out_date,
+        check_out_time: normalize_optional(check_out_time, "check-out time")?,
+        notes,
+        created_by: current_staff_id(),
+        created_at: Utc::now(),
+    };
+    validate_stay(&data, &stay)?;
+    
+    book_nights(&mut data, &stay)?;
+    data.boarding_stays.push(stay.clone());
+    save_app_data(&data)?;
+    
+    Ok(stay)
+}
+
+/// Change when a stay ends, e.g. an early collection or a late checkout
+/// agreed with the owner. Nights are re-booked to match.
+#[tauri::command]
+pub fn update_stay_checkout(
+    stay_id: String,
+    check_out_date: String,
+    check_out_time: Option<String>,
+) -> Result<BoardingStay, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let index = data.boarding_stays.iter()
+        .position(|s| s.id == stay_id)
+        .ok_or("Stay not found")?;
+    let old = data.boarding_stays[index].clone();
+    let stay = BoardingStay {
+        check_out_date,
+        check_out_time: normalize_optional(check_out_time, "check-out time")?,
+        ..old.clone()
+    };
+    validate_stay(&data, &stay)?;
+    
+    release_nights(&mut data, &old)?;
+    book_nights(&mut data, &stay)?;
+    data.boarding_stays[index] = stay.clone();
+    save_app_data(&data)?;
+    
+    Ok(stay)
+}
+
+#[tauri::command]
+pub fn cancel_boarding_stay(stay_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let index = data.boarding_stays.iter()
+        .position(|s| s.id == stay_id)
+        .ok_or("Stay not found")?;
+    let stay = data.boarding_stays.remove(index);
+    release_nights(&mut data, &stay)?;
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Stays overlapping the date range (inclusive, YYYY-MM-DD), by check-in date.
+#[tauri::command]
+pub fn get_boarding_stays(start_date: String, end_date: String) -> Result<Vec<BoardingStay>, String> {
+    let data = load_app_data()?;
+    
+    let mut stays: Vec<BoardingStay> = data.boarding_stays.into_iter()
+        .filter(|s| s.check_in_date <= end_date && s.check_out_date >= start_date)
+        .collect();
+    
+    stays.sort_by(|a, b| a.check_in_date.cmp(&b.check_in_date));
+    Ok(stays)
+}
+
+#[tauri::command]
+pub fn calculate_boarding_charges(stay_id: String) -> Result<BoardingCharges, String> {
+    let data = load_app_data()?;
+    let stay = data.boarding_stays.iter()
+        .find(|s| s.id == stay_id)
+        .ok_or("Stay not found")?;
+    
+    boarding_charges(&data, stay)
+}
+
+/// Price an enquiry for a stay before the dogs are booked (or registered),
+/// with the same rules used to invoice stays.
+#[tauri::command]
+pub fn quote_boarding_stay(
+    dogs: u32,
+    check_in_date: String,
+    check_out_date: String,
+    check_out_time: Option<String>,
+    age_class: Option<AgeClass>,
+) -> Result<BoardingCharges, String> {
+    let data = load_app_data()?;
+    if dogs == 0 {
+        return Err("At least one dog is required".to_string());
+    }
+    
+    let stay = BoardingStay {
+        id: "".to_string(),
+        dog_ids: Vec::new(),
+        check_in_date,
+        check_out_date,
+        check_out_time: normalize_optional(check_out_time, "check-out time")?,
+        notes: None,
+        created_by: None,
+        created_at: Utc::now(),
+    };
+    let nights = stay_nights(&stay)?;
+    if nights.is_empty() {
+        return Err("Check-out must be at least one night after check-in".to_string());
+    }
+    check_minimum_stay(&data.settings.boarding, &nights)?;
+    
+    let dogs: Vec<StayDog> = (1..=dogs)
+        .map(|number| StayDog {
+            name: format!("Dog {}", number),
+            dog_id: None,
+            age_classes: vec![age_class; nights.len()],
+        })
+        .collect();
+    let (lines, warnings) = price_stay(&data, &nights, stay.check_out_time.as_deref(), &dogs);
+    
+    Ok(BoardingCharges {
+        stay_id: stay.id,
+        nights: nights.len() as u32,
+        late_checkout: is_late_checkout(&data.settings.boarding, stay.check_out_time.as_deref()),
+        total: data.settings.currency.round(lines.iter().map(|l| l.amount).sum()),
+        lines,
+        warnings,
+    })
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::contact_preferences::opted_out;
+use crate::mailer::send_email;
+use crate::messages::{record_outbound, render_template, Message, MessageChannel, MessageStatus};
+use crate::{AppData, ServiceType};
+
+/// Telling owners about bookings made or confirmed on their behalf, so it
+/// doesn't depend on staff remembering to.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookingNoticeSettings {
+    pub enabled: bool,
+    pub channel: MessageChannel,
+    // Templates take {ownerName}, {dogName}, {date}, {service} and {businessName}
+    pub waitlist_promotion_subject: String,
+    pub waitlist_promotion: String,
+    pub booking_confirmed_subject: String,
+    pub booking_confirmed: String,
+}
+
+impl Default for BookingNoticeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: MessageChannel::Email,
+            waitlist_promotion_subject: "A place has opened up for {dogName}".to_string(),
+            waitlist_promotion: "Hi {ownerName},\n\nGood news: a {service} place has opened up for {dogName} on {date}, so we've booked them in from the waitlist. Please let us know if you no longer need it.\n\n{businessName}".to_string(),
+            booking_confirmed_subject: "{dogName}'s booking on {date}".to_string(),
+            booking_confirmed: "Hi {ownerName},\n\nThis is to confirm {dogName}'s {service} booking on {date}. See you then!\n\n{businessName}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookingNotice {
+    WaitlistPromotion,
+    BookingConfirmed,
+}
+
+impl BookingNotice {
+    fn template_key(&self) -> &'static str {
+        match self {
+            BookingNotice::WaitlistPromotion => "waitlist_promotion",
+            BookingNotice::BookingConfirmed => "booking_confirmed",
+        }
+    }
+}
+
+fn service_name(service_type: &ServiceType) -> &'static str {
+    match service_type {
+        ServiceType::Daycare => "daycare",
+        ServiceType::Training => "training",
+        ServiceType::Boarding => "boarding",
+    }
+}
+
+/// Send the owner the notice for a booking and log it in the dog's message
+/// history. Email goes out straight away; WhatsApp and SMS can only be sent by
+/// staff, so those are queued in the history for them. Nothing is sent when
+/// notices are off, the channel is turned off, or the owner has opted out of it.
+pub fn notify_owner(
+    data: &mut AppData,
+    notice: BookingNotice,
+    dog_id: &str,
+    date: &str,
+    service_type: &ServiceType,
+) -> Option<Message> {
+    let settings = data.settings.booking_notices.clone();
+    if !settings.enabled || !data.settings.notifications.channel_enabled(&settings.channel) {
+        return None;
+    }
+    let dog = data.dogs.iter().find(|d| d.id == dog_id)?.clone();
+    if opted_out(data, &dog, &settings.channel) {
+        return None;
+    }
+    let recipient = match settings.channel {
+        MessageChannel::Email => dog.email.trim(),
+        MessageChannel::WhatsApp | MessageChannel::Sms => dog.phone.trim(),
+    };
+    if recipient.is_empty() {
+        return None;
+    }
+    
+    let (subject, template) = match notice {
+        BookingNotice::WaitlistPromotion => (&settings.waitlist_promotion_subject, &settings.waitlist_promotion),
+        BookingNotice::BookingConfirmed => (&settings.booking_confirmed_subject, &settings.booking_confirmed),
+    };
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%A %-d %B").to_string())
+        .unwrap_or_else(|_| date.to_string());
+    let fill = |text: &str| render_template(text, &dog)
+        .replace("{date}", &day)
+        .replace("{service}", service_name(service_type))
+        .replace("{businessName}", &data.settings.business_name);
+    let subject = fill(subject);
+    let body = fill(template);
+    
+    let status = match settings.channel {
+        MessageChannel::Email => {
+            match send_email(&data.settings.smtp, &data.settings.business_name, recipient, &subject, &body, None) {
+                Ok(()) => MessageStatus::Sent,
+                Err(e) => {
+                    println!("Failed to send {} notice to {}: {}", notice.template_key(), dog.owner, e);
+                    MessageStatus::Failed
+                }
+            }
+        }
+        MessageChannel::WhatsApp | MessageChannel::Sms => MessageStatus::Queued,
+    };
+    let subject = (settings.channel == MessageChannel::Email).then_some(subject);
+    let recipient = recipient.to_string();
+    Some(record_outbound(
+        data,
+        &dog.id,
+        settings.channel,
+        &recipient,
+        Some(notice.template_key().to_string()),
+        subject,
+        &body,
+        status,
+    ))
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::audit::record_audit;
+use crate::instance::ensure_writable;
+use crate::permissions::{require_permission, Permission};
+use crate::{get_app_data_path, lock_app_data, save_app_data};
+
+/// Logo, colour and footer applied to every generated PDF.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Branding {
+    pub logo_path: Option<String>, // Copy kept in the app data directory
+    pub primary_color: String, // Hex, e.g. "#1f4e79"; used for headings
+    pub footer_text: String, // Printed at the bottom of every page; empty for none
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Self {
+            logo_path: None,
+            primary_color: "#000000".to_string(),
+            footer_text: "".to_string(),
+        }
+    }
+}
+
+/// Parse a "#rrggbb" colour into 0-1 RGB components.
+pub fn parse_color(hex: &str) -> Option<(f32, f32, f32)> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|c| c as f32 / 255.0);
+    Some((component(0)?, component(2)?, component(4)?))
+}
+
+/// Copy a logo image into the app data directory and use it on documents.
+/// Replaces any previous logo.
+#[tauri::command]
+pub fn set_branding_logo(source_path: String) -> Result<Branding, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    ensure_writable()?;
+    
+    let source = Path::new(&source_path);
+    let extension = source.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| ["png", "jpg", "jpeg"].contains(&e.as_str()))
+        .ok_or("Logo must be a PNG or JPEG image")?;
+    printpdf::image_crate::open(source)
+        .map_err(|e| format!("Failed to read image {}: {}", source_path, e))?;
+    
+    let dir = get_app_data_path()?.with_file_name("branding");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create branding directory: {}", e))?;
+    // Copied alongside first, so picking the current logo again doesn't remove it before it's read
+    let target = dir.join(format!("logo.{}", extension));
+    let staged = dir.join(format!("logo.{}.tmp", extension));
+    fs::copy(source, &staged).map_err(|e| format!("Failed to copy logo: {}", e))?;
+    fs::rename(&staged, &target).map_err(|e| format!("Failed to save logo: {}", e))?;
+    
+    let target = target.to_string_lossy().to_string();
+    if let Some(old) = data.settings.branding.logo_path.as_ref().filter(|old| **old != target) {
+        let _ = fs::remove_file(old);
+    }
+    data.settings.branding.logo_path = Some(target);
+    record_audit(&mut data, "branding_logo_updated", "Document logo updated");
+    save_app_data(&data)?;
+    
+    Ok(data.settings.branding.clone())
+}
+
+#[tauri::command]
+pub fn clear_branding_logo() -> Result<Branding, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    ensure_writable()?;
+    
+    if let Some(old) = data.settings.branding.logo_path.take() {
+        let _ = fs::remove_file(old);
+    }
+    record_audit(&mut data, "branding_logo_updated", "Document logo removed");
+    save_app_data(&data)?;
+    
+    Ok(data.settings.branding.clone())
+}
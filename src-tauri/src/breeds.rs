@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use crate::age::{classify_dogs, AgeClass};
+use crate::{load_app_data, AppData, Dog, DogStatus, NeuterStatus};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum BreedGroup {
+    #[serde(rename = "gundog")]
+    Gundog,
+    #[serde(rename = "hound")]
+    Hound,
+    #[serde(rename = "pastoral")]
+    Pastoral,
+    #[serde(rename = "terrier")]
+    Terrier,
+    #[serde(rename = "toy")]
+    Toy,
+    #[serde(rename = "utility")]
+    Utility,
+    #[serde(rename = "working")]
+    Working,
+    #[serde(rename = "crossbreed")]
+    Crossbreed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Breed {
+    pub name: &'static str,
+    pub group: BreedGroup,
+}
+
+const fn breed(name: &'static str, group: BreedGroup) -> Breed {
+    Breed { name, group }
+}
+
+/// Breeds offered in the breed picker, grouped as the Kennel Club does. Any
+/// other breed can still be typed in; it just won't have a group.
+pub const BREEDS: &[Breed] = &[
+    breed("Cocker Spaniel", BreedGroup::Gundog),
+    breed("English Springer Spaniel", BreedGroup::Gundog),
+    breed("German Shorthaired Pointer", BreedGroup::Gundog),
+    breed("Golden Retriever", BreedGroup::Gundog),
+    breed("Hungarian Vizsla", BreedGroup::Gundog),
+    breed("Labrador Retriever", BreedGroup::Gundog),
+    breed("Weimaraner", BreedGroup::Gundog),
+    breed("Basset Hound", BreedGroup::Hound),
+    breed("Beagle", BreedGroup::Hound),
+    breed("Dachshund", BreedGroup::Hound),
+    breed("Greyhound", BreedGroup::Hound),
+    breed("Rhodesian Ridgeback", BreedGroup::Hound),
+    breed("Whippet", BreedGroup::Hound),
+    breed("Australian Shepherd", BreedGroup::Pastoral),
+    breed("Border Collie", BreedGroup::Pastoral),
+    breed("German Shepherd Dog", BreedGroup::Pastoral),
+    breed("Old English Sheepdog", BreedGroup::Pastoral),
+    breed("Pembroke Welsh Corgi", BreedGroup::Pastoral),
+    breed("Shetland Sheepdog", BreedGroup::Pastoral),
+    breed("Samoyed", BreedGroup::Pastoral),
+    breed("Airedale Terrier", BreedGroup::Terrier),
+    breed("Border Terrier", BreedGroup::Terrier),
+    breed("Bull Terrier", BreedGroup::Terrier),
+    breed("Jack Russell Terrier", BreedGroup::Terrier),
+    breed("Staffordshire Bull Terrier", BreedGroup::Terrier),
+    breed("West Highland White Terrier", BreedGroup::Terrier),
+    breed("Cavalier King Charles Spaniel", BreedGroup::Toy),
+    breed("Chihuahua", BreedGroup::Toy),
+    breed("Maltese", BreedGroup::Toy),
+    breed("Pomeranian", BreedGroup::Toy),
+    breed("Pug", BreedGroup::Toy),
+    breed("Yorkshire Terrier", BreedGroup::Toy),
+    breed("Bichon Frise", BreedGroup::Toy),
+    breed("Bulldog", BreedGroup::Utility),
+    breed("Dalmatian", BreedGroup::Utility),
+    breed("French Bulldog", BreedGroup::Utility),
+    breed("Miniature Schnauzer", BreedGroup::Utility),
+    breed("Poodle", BreedGroup::Utility),
+    breed("Shih Tzu", BreedGroup::Utility),
+    breed("Akita", BreedGroup::Utility),
+    breed("Boxer", BreedGroup::Working),
+    breed("Bernese Mountain Dog", BreedGroup::Working),
+    breed("Dobermann", BreedGroup::Working),
+    breed("Great Dane", BreedGroup::Working),
+    breed("Rottweiler", BreedGroup::Working),
+    breed("Siberian Husky", BreedGroup::Working),
+    breed("Cane Corso", BreedGroup::Working),
+    breed("Cockapoo", BreedGroup::Crossbreed),
+    breed("Cavapoo", BreedGroup::Crossbreed),
+    breed("Labradoodle", BreedGroup::Crossbreed),
+    breed("Goldendoodle", BreedGroup::Crossbreed),
+    breed("Crossbreed", BreedGroup::Crossbreed),
+];
+
+/// Common shorthand owners and import files use for breeds in the list.
+const ALIASES: &[(&str, &str)] = &[
+    ("lab", "Labrador Retriever"),
+    ("labrador", "Labrador Retriever"),
+    ("golden", "Golden Retriever"),
+    ("springer", "English Springer Spaniel"),
+    ("cocker", "Cocker Spaniel"),
+    ("vizsla", "Hungarian Vizsla"),
+    ("gsd", "German Shepherd Dog"),
+    ("german shepherd", "German Shepherd Dog"),
+    ("alsatian", "German Shepherd Dog"),
+    ("aussie", "Australian Shepherd"),
+    ("sheltie", "Shetland Sheepdog"),
+    ("corgi", "Pembroke Welsh Corgi"),
+    ("jack russell", "Jack Russell Terrier"),
+    ("jrt", "Jack Russell Terrier"),
+    ("staffie", "Staffordshire Bull Terrier"),
+    ("staffy", "Staffordshire Bull Terrier"),
+    ("westie", "West Highland White Terrier"),
+    ("yorkie", "Yorkshire Terrier"),
+    ("cavalier", "Cavalier King Charles Spaniel"),
+    ("english bulldog", "Bulldog"),
+    ("frenchie", "French Bulldog"),
+    ("doberman", "Dobermann"),
+    ("husky", "Siberian Husky"),
+    ("sausage dog", "Dachshund"),
+    ("mixed", "Crossbreed"),
+    ("mixed breed", "Crossbreed"),
+    ("mongrel", "Crossbreed"),
+    ("cross", "Crossbreed"),
+];
+
+pub fn find_breed(name: &str) -> Option<&'static Breed> {
+    let wanted = name.trim().to_lowercase();
+    let canonical = ALIASES.iter()
+        .find(|(alias, _)| *alias == wanted)
+        .map(|(_, breed)| breed.to_lowercase())
+        .unwrap_or(wanted);
+    
+    BREEDS.iter().find(|b| b.name.to_lowercase() == canonical)
+}
+
+/// Canonical name for a known breed, otherwise the text as entered (trimmed).
+pub fn normalize_breed(name: &str) -> String {
+    match find_breed(name) {
+        Some(breed) => breed.name.to_string(),
+        None => name.trim().to_string(),
+    }
+}
+
+/// Group for a breed; unlisted crosses ("Lab x Poodle") count as crossbreeds.
+pub fn breed_group(name: &str) -> Option<BreedGroup> {
+    if let Some(breed) = find_breed(name) {
+        return Some(breed.group);
+    }
+    let lower = name.to_lowercase();
+    let is_cross = lower.contains(" x ") || lower.contains(" cross") || lower.ends_with("poo") || lower.ends_with("doodle");
+    is_cross.then_some(BreedGroup::Crossbreed)
+}
+
+pub fn is_restricted(data: &AppData, dog: &Dog) -> bool {
+    let breed = normalize_breed(&dog.breed).to_lowercase();
+    !breed.is_empty() && data.settings.restricted_breeds.iter().any(|r| normalize_breed(r).to_lowercase() == breed)
+}
+
+/// Warnings to show when booking a dog in, e.g. breeds our insurance restricts.
+pub fn booking_warnings(data: &AppData, dog: &Dog) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if is_restricted(data, dog) {
+        warnings.push(format!(
+            "{} is a {}, which is on the restricted breed list; check insurance cover before booking",
+            dog.name,
+            normalize_breed(&dog.breed)
+        ));
+    }
+    warnings
+}
+
+/// Fill in each dog's breed group before returning dogs to the frontend.
+pub fn group_dogs(dogs: &mut [Dog]) {
+    for dog in dogs.iter_mut() {
+        dog.breed_group = breed_group(&dog.breed);
+    }
+}
+
+#[tauri::command]
+pub fn get_breeds() -> Vec<Breed> {
+    let mut breeds = BREEDS.to_vec();
+    breeds.sort_by_key(|b| b.name);
+    breeds
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DogQuery {
+    pub breed: Option<String>,
+    pub breed_group: Option<BreedGroup>,
+    pub restricted_only: Option<bool>,
+    pub age_class: Option<AgeClass>,
+    pub neuter_status: Option<NeuterStatus>,
+    pub status: Option<DogStatus>,
+}
+
+/// Dogs matching every filter given; filters left out match everything.
+#[tauri::command]
+pub fn query_dogs(query: DogQuery) -> Result<Vec<Dog>, String> {
+    let mut data = load_app_data()?;
+    let mut dogs = std::mem::take(&mut data.dogs);
+    classify_dogs(&mut dogs, &data.settings.age_classes, chrono::Utc::now().date_naive());
+    group_dogs(&mut dogs);
+    
+    let breed = query.breed.as_deref().map(|b| normalize_breed(b).to_lowercase());
+    dogs.retain(|dog| {
+        breed.as_ref().is_none_or(|b| normalize_breed(&dog.breed).to_lowercase() == *b)
+            && query.breed_group.is_none_or(|g| dog.breed_group == Some(g))
+            && (!query.restricted_only.unwrap_or(false) || is_restricted(&data, dog))
+            && query.age_class.is_none_or(|c| dog.age_class == Some(c))
+            && query.neuter_status.is_none_or(|n| dog.neuter_status == n)
+            && query.status.is_none_or(|s| dog.status == s)
+    });
+    
+    dogs.sort_by_key(|d| d.name.to_lowercase());
+    Ok(dogs)
+}
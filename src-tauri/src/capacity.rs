@@ -0,0 +1,171 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::attendance_keys::entry_key;
+use crate::{load_app_data, schedule_applies_on, AppData, DayData, Dog, ServiceType};
+
+/// Maximum dogs booked per day for each service; 0 means unlimited.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CapacitySettings {
+    pub daycare: u32,
+    pub training: u32,
+    pub boarding: u32,
+    #[serde(default = "default_alert_threshold")]
+    pub alert_threshold_percent: f64, // Utilization at which a day is flagged
+    #[serde(default = "default_solo_care_places")]
+    pub solo_care_places: u32, // Places a solo-care dog takes up, as they tie up a handler
+}
+
+fn default_alert_threshold() -> f64 {
+    90.0
+}
+
+fn default_solo_care_places() -> u32 {
+    2
+}
+
+impl Default for CapacitySettings {
+    fn default() -> Self {
+        Self {
+            daycare: 0,
+            training: 0,
+            boarding: 0,
+            alert_threshold_percent: default_alert_threshold(),
+            solo_care_places: default_solo_care_places(),
+        }
+    }
+}
+
+impl CapacitySettings {
+    pub fn for_service(&self, service_type: &ServiceType) -> u32 {
+        match service_type {
+            ServiceType::Daycare => self.daycare,
+            ServiceType::Training => self.training,
+            ServiceType::Boarding => self.boarding,
+        }
+    }
+    
+    /// Places a dog takes up in a day's capacity.
+    pub fn places_for(&self, dog: Option<&Dog>) -> u32 {
+        if dog.is_some_and(|d| d.solo_care) {
+            self.solo_care_places
+        } else {
+            1
+        }
+    }
+    
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=10).contains(&self.solo_care_places) {
+            return Err("A solo-care dog must take up between 1 and 10 places".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Places booked for a service on a day, with solo-care dogs counting extra.
+pub fn booked_count(capacity: &CapacitySettings, dogs: &[Dog], day_data: &DayData, service_type: &ServiceType) -> u32 {
+    day_data.attendance.entries.values()
+        .filter(|e| e.attending && e.service_type == *service_type)
+        .map(|e| capacity.places_for(dogs.iter().find(|d| d.id == e.dog_id)))
+        .sum()
+}
+
+pub fn is_full(capacity: &CapacitySettings, dogs: &[Dog], day_data: &DayData, service_type: &ServiceType) -> bool {
+    let limit = capacity.for_service(service_type);
+    limit > 0 && booked_count(capacity, dogs, day_data, service_type) >= limit
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DayUtilization {
+    pub date: String,
+    pub booked: u32, // Places taken, so solo-care dogs count more than once
+    pub capacity: u32, // 0 when no capacity is configured
+    pub percent: f64,
+    pub above_threshold: bool,
+    pub absent: Vec<String>, // Names of regulars away on an owner's absence notice
+}
+
+/// Dogs who would normally be in for a service on a date but whose owners have
+/// told us they'll be away: either their booking was excused or their schedule
+/// would have booked them in.
+fn absent_dogs(data: &AppData, date: NaiveDate, service_type: &ServiceType) -> Vec<String> {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let day_data = data.daily_data.get(&date_str);
+    
+    let mut names: Vec<String> = data.dogs.iter()
+        .filter(|dog| crate::absences::is_absent(&data.absences, &dog.id, &date_str))
+        .filter(|dog| {
+            let excused = day_data
+                .and_then(|d| d.attendance.entries.get(&entry_key(&dog.id, service_type)))
+                .is_some_and(|e| e.excused);
+            excused || data.recurring_schedules.iter()
+                .any(|s| s.dog_id == dog.id && s.service_type == *service_type && schedule_applies_on(s, date))
+        })
+        .map(|dog| dog.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WeeklyUtilization {
+    pub week_start: String,
+    pub week_year: i32, // Year the week is numbered in, per the fiscal settings
+    pub week_number: u32,
+    pub service_type: ServiceType,
+    pub alert_threshold_percent: f64,
+    pub days: Vec<DayUtilization>,
+    pub average_percent: f64,
+}
+
+/// Booked places against capacity for the week containing `week_start`, with days
+/// at or above the alert threshold flagged so extra shifts can be arranged early.
+/// Regulars away that day are listed, since their places may be offered to others.
+#[tauri::command]
+pub fn get_weekly_utilization(week_start: String, service_type: Option<ServiceType>) -> Result<WeeklyUtilization, String> {
+    let data = load_app_data()?;
+    let service_type = service_type.unwrap_or(ServiceType::Daycare);
+    let start = NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|_| "Invalid week start date format".to_string())?;
+    // Line the week up with the one reports use
+    let start = data.settings.fiscal.week_start(start);
+    let (week_year, week_number) = data.settings.fiscal.week_number(start);
+    
+    let capacity = data.settings.capacity.for_service(&service_type);
+    let threshold = data.settings.capacity.alert_threshold_percent;
+    
+    let days: Vec<DayUtilization> = (0..7)
+        .map(|offset| {
+            let day = start + Duration::days(offset);
+            let date = day.format("%Y-%m-%d").to_string();
+            let booked = data.daily_data.get(&date)
+                .map(|d| booked_count(&data.settings.capacity, &data.dogs, d, &service_type))
+                .unwrap_or(0);
+            let percent = if capacity > 0 {
+                (booked as f64 / capacity as f64 * 1000.0).round() / 10.0
+            } else {
+                0.0
+            };
+            DayUtilization {
+                date,
+                booked,
+                capacity,
+                percent,
+                above_threshold: capacity > 0 && percent >= threshold,
+                absent: absent_dogs(&data, day, &service_type),
+            }
+        })
+        .collect();
+    
+    let average_percent = (days.iter().map(|d| d.percent).sum::<f64>() / days.len() as f64 * 10.0).round() / 10.0;
+    
+    Ok(WeeklyUtilization {
+        week_start: start.format("%Y-%m-%d").to_string(),
+        week_year,
+        week_number,
+        service_type,
+        alert_threshold_percent: threshold,
+        days,
+        average_percent,
+    })
+}
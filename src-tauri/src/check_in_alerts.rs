@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+
+use crate::consent::consent_expiry;
+use crate::invoices::amount_due;
+use crate::packages::covered_by_package;
+use crate::vaccinations::expiries;
+use crate::{AppData, Dog};
+
+/// Money the household owes on issued invoices, once it's over the alert
+/// threshold in the billing settings.
+fn balance_warning(data: &AppData, dog: &Dog) -> Option<String> {
+    let household_id = dog.household_key();
+    let unpaid: Vec<f64> = data.invoices.iter()
+        .filter(|i| i.household_id == household_id)
+        .map(|i| amount_due(data, i))
+        .filter(|due| *due > 0.0)
+        .collect();
+    let owed = data.settings.currency.round(unpaid.iter().sum());
+    if owed <= data.settings.billing.check_in_alert_balance {
+        return None;
+    }
+    Some(format!(
+        "{} owes {} on {} unpaid invoice{}",
+        dog.owner,
+        data.settings.currency.format(owed),
+        unpaid.len(),
+        if unpaid.len() == 1 { "" } else { "s" }
+    ))
+}
+
+/// Households on packages whose credits have run out: today wasn't covered,
+/// or used the last credit.
+fn package_warnings(data: &AppData, dog: &Dog, date: &str) -> Vec<String> {
+    let household_id = dog.household_key();
+    let services: Vec<_> = data.daily_data.get(date)
+        .map(|d| d.attendance.entries.values()
+            .filter(|e| e.dog_id == dog.id && e.attending)
+            .map(|e| &e.service_type)
+            .collect())
+        .unwrap_or_default();
+    
+    let mut warnings = Vec::new();
+    for service_type in services {
+        let packages: Vec<_> = data.packages.iter()
+            .filter(|p| p.household_id == household_id && p.service_type == *service_type)
+            .collect();
+        if packages.is_empty() {
+            continue;
+        }
+        let remaining: u32 = packages.iter()
+            .filter(|p| p.valid_on(date))
+            .map(|p| p.credits_remaining)
+            .sum();
+        let service = service_type.key().to_lowercase();
+        if !covered_by_package(data, &dog.id, date, service_type) {
+            warnings.push(format!("No {} package credits left for {}; today will be billed", service, dog.name));
+        } else if remaining == 0 {
+            warnings.push(format!("Today used the last {} package credit for {}", service, dog.name));
+        }
+    }
+    warnings
+}
+
+fn vaccination_warnings(dog: &Dog, on: NaiveDate) -> Vec<String> {
+    expiries(dog).into_iter()
+        .filter(|(_, expires, _)| *expires < on)
+        .map(|(vaccine_type, expires, _)| format!(
+            "{}'s {} expired on {}", dog.name, vaccine_type, expires.format("%Y-%m-%d")
+        ))
+        .collect()
+}
+
+fn consent_warning(data: &AppData, dog: &Dog, on: NaiveDate) -> Option<String> {
+    match consent_expiry(dog, data.settings.consent_requests.validity_months) {
+        None => Some(format!("{} has no signed consent form on record", dog.name)),
+        Some(expiry) if expiry <= on => Some(format!(
+            "{}'s consent form ran out on {}", dog.name, expiry.format("%Y-%m-%d")
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Account problems to raise with the owner at the door: money owed, package
+/// credits run out, and lapsed vaccinations or consent. Run after the dog is
+/// checked in, so any package credit for the day has been used.
+pub fn account_warnings(data: &AppData, dog_id: &str, date: &str) -> Vec<String> {
+    let (dog, on) = match (data.dogs.iter().find(|d| d.id == dog_id), NaiveDate::parse_from_str(date, "%Y-%m-%d")) {
+        (Some(dog), Ok(on)) => (dog, on),
+        _ => return Vec::new(),
+    };
+    
+    let mut warnings = Vec::new();
+    warnings.extend(balance_warning(data, dog));
+    warnings.extend(package_warnings(data, dog, date));
+    warnings.extend(vaccination_warnings(dog, on));
+    warnings.extend(consent_warning(data, dog, on));
+    warnings
+}
@@ -0,0 +1,303 @@
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::HashSet;
+
+use crate::consent::consent_due;
+use crate::jobs::{self, JobContext, JobKind};
+use crate::pdf::{fit_text, wrap_text, PdfWriter};
+use crate::reminders::vaccine_due;
+use crate::staff::parse_period;
+use crate::vaccinations::next_expiry;
+use crate::{load_app_data, AppData, DayData, DogStatus, ServiceType};
+
+const MARGIN: f32 = 12.0;
+const TOP: f32 = 22.0;
+const BOTTOM: f32 = 12.0;
+const ROW_HEIGHT: f32 = 5.0;
+const FONT_SIZE: f32 = 8.0;
+
+/// Longest period one pack covers; inspections ask for a year at a time.
+const MAX_PACK_DAYS: i64 = 366;
+
+/// Text flowing down A4 pages, starting a new page whenever the next block
+/// wouldn't fit.
+struct Pack {
+    pdf: PdfWriter,
+    y: f32,
+}
+
+impl Pack {
+    fn ensure_room(&mut self, height: f32) {
+        if self.y + height > self.pdf.height - BOTTOM {
+            self.pdf.new_page();
+            self.y = TOP;
+        }
+    }
+    
+    /// Start a section on a fresh page, as each goes to a different part of the inspection.
+    fn section(&mut self, title: &str, note: &str) {
+        self.pdf.new_page();
+        self.pdf.heading(title, 13.0, MARGIN, TOP - 6.0);
+        self.y = TOP;
+        if !note.is_empty() {
+            self.pdf.text(note, 7.5, MARGIN, self.y, false);
+            self.y += ROW_HEIGHT + 1.0;
+        }
+    }
+    
+    fn subheading(&mut self, text: &str) {
+        self.ensure_room(ROW_HEIGHT * 3.0);
+        self.y += 2.0;
+        self.pdf.text(text, 9.5, MARGIN, self.y, true);
+        self.y += ROW_HEIGHT;
+    }
+    
+    /// A table row; `widths` are column widths in mm from the left margin.
+    fn row(&mut self, cells: &[&str], widths: &[f32], bold: bool) {
+        self.ensure_room(ROW_HEIGHT);
+        let mut x = MARGIN;
+        for (cell, width) in cells.iter().zip(widths) {
+            self.pdf.text(&fit_text(cell, FONT_SIZE, width - 2.0), FONT_SIZE, x, self.y, bold);
+            x += width;
+        }
+        self.y += ROW_HEIGHT;
+    }
+    
+    fn paragraph(&mut self, text: &str, indent: f32) {
+        for line in wrap_text(text, FONT_SIZE, self.pdf.width - 2.0 * MARGIN - indent) {
+            self.ensure_room(ROW_HEIGHT);
+            self.pdf.text(&line, FONT_SIZE, MARGIN + indent, self.y, false);
+            self.y += ROW_HEIGHT;
+        }
+    }
+}
+
+fn service_name(service_type: &ServiceType) -> &'static str {
+    match service_type {
+        ServiceType::Daycare => "Daycare",
+        ServiceType::Training => "Training",
+        ServiceType::Boarding => "Boarding",
+    }
+}
+
+/// Dogs who were on the premises: checked in, or booked in on days nobody was
+/// checked in (before check-in was recorded).
+pub fn attended_dogs(day_data: &DayData) -> Vec<&String> {
+    let checked_in = day_data.records.values().any(|r| r.checked_in_at.is_some());
+    let mut dog_ids: Vec<&String> = if checked_in {
+        day_data.records.iter()
+            .filter(|(_, r)| r.checked_in_at.is_some())
+            .map(|(dog_id, _)| dog_id)
+            .collect()
+    } else {
+        day_data.attendance.entries.values()
+            .filter(|e| e.attending)
+            .map(|e| &e.dog_id)
+            .collect()
+    };
+    dog_ids.sort();
+    dog_ids.dedup();
+    dog_ids
+}
+
+fn days_in(data: &AppData, start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, &DayData)> {
+    start.iter_days()
+        .take_while(|d| *d <= end)
+        .filter_map(|date| {
+            let date_str = date.format("%Y-%m-%d").to_string();
+            data.daily_data.get(&date_str).map(|day_data| (date, day_data))
+        })
+        .collect()
+}
+
+fn write_registers(pack: &mut Pack, data: &AppData, days: &[(NaiveDate, &DayData)]) {
+    pack.section("Daily registers", "Every dog on the premises each day, with arrival and collection times.");
+    let widths = [50.0, 50.0, 30.0, 25.0, 25.0];
+    
+    for (date, day_data) in days {
+        let dog_ids = attended_dogs(day_data);
+        if dog_ids.is_empty() {
+            continue;
+        }
+        pack.subheading(&format!("{} ({} dogs)", date.format("%A %-d %B %Y"), dog_ids.len()));
+        pack.row(&["Dog", "Owner", "Service", "Arrived", "Collected"], &widths, true);
+        
+        let mut rows: Vec<[String; 5]> = dog_ids.into_iter()
+            .filter_map(|id| data.dogs.iter().find(|d| d.id == *id))
+            .map(|dog| {
+                let record = day_data.records.get(&dog.id);
+                let mut services: Vec<&str> = day_data.attendance.entries.values()
+                    .filter(|e| e.dog_id == dog.id && e.attending)
+                    .map(|e| service_name(&e.service_type))
+                    .collect();
+                services.sort();
+                [
+                    dog.name.clone(),
+                    dog.owner.clone(),
+                    services.join(", "),
+                    record.and_then(|r| r.drop_off_time.clone()).unwrap_or_default(),
+                    record.and_then(|r| r.pick_up_time.clone()).unwrap_or_default(),
+                ]
+            })
+            .collect();
+        rows.sort();
+        for row in &rows {
+            let cells: Vec<&str> = row.iter().map(|c| c.as_str()).collect();
+            pack.row(&cells, &widths, false);
+        }
+    }
+}
+
+fn write_temperatures(pack: &mut Pack, days: &[(NaiveDate, &DayData)]) {
+    pack.section("Temperature log", "Morning and afternoon readings as recorded on the daily checklist.");
+    let widths = [60.0, 40.0, 40.0];
+    pack.row(&["Date", "Morning", "Afternoon"], &widths, true);
+    
+    let reading = |t: &Option<String>| t.clone().filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "not recorded".to_string());
+    for (date, day_data) in days.iter().filter(|(_, d)| !attended_dogs(d).is_empty()) {
+        pack.row(
+            &[&date.format("%a %-d %b %Y").to_string(), &reading(&day_data.am_temp), &reading(&day_data.pm_temp)],
+            &widths,
+            false,
+        );
+    }
+}
+
+fn write_incidents(pack: &mut Pack, data: &AppData, days: &[(NaiveDate, &DayData)]) {
+    pack.section("Incident reports and daily notes", "Notes staff recorded against each dog, including any incidents.");
+    
+    let mut any = false;
+    for (date, day_data) in days {
+        let mut notes: Vec<(String, &str)> = day_data.records.iter()
+            .filter_map(|(dog_id, record)| {
+                let note = record.notes.as_deref().map(str::trim).filter(|n| !n.is_empty())?;
+                let name = data.dogs.iter().find(|d| d.id == *dog_id).map(|d| d.name.clone()).unwrap_or_else(|| dog_id.clone());
+                Some((name, note))
+            })
+            .collect();
+        if notes.is_empty() {
+            continue;
+        }
+        any = true;
+        notes.sort();
+        pack.subheading(&date.format("%A %-d %B %Y").to_string());
+        for (name, note) in notes {
+            pack.paragraph(&format!("{}: {}", name, note), 0.0);
+        }
+    }
+    if !any {
+        pack.paragraph("No notes were recorded in this period.", 0.0);
+    }
+}
+
+fn write_vaccinations(pack: &mut Pack, data: &AppData, as_at: NaiveDate) {
+    pack.section(
+        &format!("Vaccination and consent status at {}", as_at.format("%-d %B %Y")),
+        "Vaccinations run until the earliest expiry across vaccine types, or a year from the date given; consent forms are renewed as set in the consent settings.",
+    );
+    let widths = [50.0, 50.0, 45.0, 40.0];
+    pack.row(&["Dog", "Owner", "Vaccinated", "Consent signed"], &widths, true);
+    
+    let mut dogs: Vec<_> = data.dogs.iter().filter(|d| d.status == DogStatus::Active).collect();
+    dogs.sort_by(|a, b| a.name.cmp(&b.name));
+    for dog in dogs {
+        let status = |date: &Option<String>, lapsed: bool| match date {
+            Some(date) if lapsed => format!("{} (expired)", date.get(..10).unwrap_or(date)),
+            Some(date) => date.get(..10).unwrap_or(date).to_string(),
+            None => "missing".to_string(),
+        };
+        pack.row(
+            &[
+                &dog.name,
+                &dog.owner,
+                &match next_expiry(dog) {
+                    Some(expires) if vaccine_due(dog, as_at) => format!("expired {}", expires),
+                    Some(expires) => format!("until {}", expires),
+                    None => status(&dog.vaccine_date, vaccine_due(dog, as_at)),
+                },
+                &status(&dog.consent_last_signed, consent_due(dog, as_at, 0, data.settings.consent_requests.validity_months)),
+            ],
+            &widths,
+            false,
+        );
+    }
+}
+
+fn write_staff_ratios(pack: &mut Pack, data: &AppData, days: &[(NaiveDate, &DayData)]) {
+    pack.section("Staff ratios", "Dogs on the premises against staff who clocked in that day.");
+    let widths = [60.0, 30.0, 30.0, 40.0];
+    pack.row(&["Date", "Dogs", "Staff", "Dogs per staff"], &widths, true);
+    
+    for (date, day_data) in days {
+        let dogs = attended_dogs(day_data).len();
+        if dogs == 0 {
+            continue;
+        }
+        let staff: HashSet<&String> = data.time_clock.iter()
+            .filter(|e| e.clock_in.date_naive() == *date)
+            .map(|e| &e.staff_id)
+            .collect();
+        let ratio = if staff.is_empty() {
+            "no staff clocked in".to_string()
+        } else {
+            format!("{:.1}", dogs as f64 / staff.len() as f64)
+        };
+        pack.row(
+            &[&date.format("%a %-d %b %Y").to_string(), &dogs.to_string(), &staff.len().to_string(), &ratio],
+            &widths,
+            false,
+        );
+    }
+}
+
+/// Everything the licensing inspection asks for over a period in one PDF:
+/// daily registers, temperature log, incident notes, vaccination and consent
+/// status, and staff ratios. Returns the PDF bytes.
+#[tauri::command]
+pub fn export_compliance_pack(start: String, end: String) -> Result<Vec<u8>, String> {
+    compliance_pack(&start, &end, &jobs::untracked(JobKind::Export))
+}
+
+/// Produce the compliance pack in the background. Returns the job id; the PDF
+/// bytes are the job's result.
+#[tauri::command]
+pub fn start_compliance_pack_export(app: tauri::AppHandle, start: String, end: String) -> Result<String, String> {
+    jobs::start_job(app, JobKind::Export, move |job| compliance_pack(&start, &end, job))
+}
+
+fn compliance_pack(start: &str, end: &str, job: &JobContext) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    let (start_date, end_date) = parse_period(start, end)?;
+    if end_date - start_date >= Duration::days(MAX_PACK_DAYS) {
+        return Err("A compliance pack can cover at most a year".to_string());
+    }
+    let days = days_in(&data, start_date, end_date);
+    
+    let title = format!("{} - compliance pack", data.settings.business_name);
+    let pdf = PdfWriter::a4(&title, false, &data.settings.branding)?;
+    pdf.heading(&title, 18.0, MARGIN, 40.0);
+    pdf.text(
+        &format!("{} to {}", start_date.format("%-d %B %Y"), end_date.format("%-d %B %Y")),
+        12.0, MARGIN, 50.0, false,
+    );
+    pdf.text(&format!("Produced {}", Utc::now().format("%-d %B %Y")), 9.0, MARGIN, 58.0, false);
+    let contents = ["Daily registers", "Temperature log", "Incident reports and daily notes", "Vaccination and consent status", "Staff ratios"];
+    for (index, section) in contents.iter().enumerate() {
+        pdf.text(&format!("{}. {}", index + 1, section), 10.0, MARGIN, 74.0 + index as f32 * 7.0, false);
+    }
+    
+    let mut pack = Pack { pdf, y: TOP };
+    let sections = contents.len() + 1;
+    job.progress(0, sections)?;
+    write_registers(&mut pack, &data, &days);
+    job.progress(1, sections)?;
+    write_temperatures(&mut pack, &days);
+    job.progress(2, sections)?;
+    write_incidents(&mut pack, &data, &days);
+    job.progress(3, sections)?;
+    write_vaccinations(&mut pack, &data, end_date.min(Utc::now().date_naive()));
+    job.progress(4, sections)?;
+    write_staff_ratios(&mut pack, &data, &days);
+    job.progress(5, sections)?;
+    
+    pack.pdf.finish()
+}
@@ -0,0 +1,483 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use printpdf::image_crate;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use uuid::Uuid;
+
+use crate::mailer::{send_email, EmailAttachment};
+use crate::messages::{record_outbound, render_template, MessageChannel, MessageDirection, MessageStatus};
+use crate::audit::record_audit;
+use crate::contact_preferences::{contactable_now, opted_out};
+use crate::documents::{document_path, store_document, DocumentKind};
+use crate::instance::ensure_writable;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::printouts::{consent_form_pdf, consent_statement};
+use crate::{load_app_data, lock_app_data, save_app_data, with_app_data, with_app_data_mut, AppData, Dog, DogStatus};
+
+const CONSENT_TEMPLATE: &str = "consent_form";
+
+/// Longest a signed consent form can be accepted for.
+const MAX_VALIDITY_MONTHS: u32 = 24;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsentRequestSettings {
+    pub auto_send: bool, // Email due consent forms once a day in the background
+    pub resend_after_days: u32,
+    #[serde(default = "default_validity_months")]
+    pub validity_months: u32, // How long a signed form lasts before it must be signed again
+}
+
+fn default_validity_months() -> u32 {
+    1
+}
+
+impl Default for ConsentRequestSettings {
+    fn default() -> Self {
+        Self {
+            auto_send: false,
+            resend_after_days: 7,
+            validity_months: default_validity_months(),
+        }
+    }
+}
+
+impl ConsentRequestSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.validity_months == 0 || self.validity_months > MAX_VALIDITY_MONTHS {
+            return Err(format!("Consent must last between 1 and {} months", MAX_VALIDITY_MONTHS));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ConsentMethod {
+    #[serde(rename = "paper")]
+    Paper,
+    #[serde(rename = "digital")]
+    Digital,
+}
+
+/// One signed consent form. A dog's `consent_last_signed` is the latest of these.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsentSigning {
+    pub id: String,
+    pub dog_id: String,
+    pub signed_at: String, // YYYY-MM-DD
+    pub version: String, // Version of the form the owner signed
+    pub method: ConsentMethod,
+    pub attachment_id: Option<String>, // Document with the scanned form or the captured signature
+    pub recorded_by: Option<String>,
+    pub recorded_at: DateTime<Utc>, // When a digital form was signed
+    #[serde(default)]
+    pub signer_name: Option<String>, // Digital forms only, as are the fields below
+    #[serde(default)]
+    pub signed_text: Option<String>, // Wording the owner was shown
+    #[serde(default)]
+    pub text_hash: Option<String>, // SHA-256 of signed_text, hex
+}
+
+/// What was agreed to on a digitally signed form.
+struct SignedText {
+    signer_name: String,
+    text: String,
+    hash: String,
+}
+
+/// A consent form ready to show on a tablet for signing. The hash goes back
+/// with the signature, so a form whose wording changed in between isn't
+/// recorded as signed.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConsentForm {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub version: String,
+    pub text: String,
+    pub text_hash: String,
+}
+
+/// Latest signing on record for a dog, if any have been recorded.
+pub fn last_signed(data: &AppData, dog_id: &str) -> Option<String> {
+    data.consent_signings.iter()
+        .filter(|s| s.dog_id == dog_id)
+        .map(|s| s.signed_at.clone())
+        .max()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConsentRequestSummary {
+    pub sent: Vec<String>,
+    pub recently_requested: Vec<String>,
+    pub no_email: Vec<String>,
+    pub opted_out: Vec<String>, // Owner has opted out of email
+    pub outside_contact_times: Vec<String>, // Left for a later run
+    pub failed: Vec<String>, // "Dog name: reason"
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OverdueConsent {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub last_signed: Option<String>,
+    pub expired_on: Option<String>, // None when the dog has never signed
+    pub days_overdue: Option<i64>,
+}
+
+/// First day a dog's consent no longer covers, `validity_months` after signing.
+pub fn consent_expiry(dog: &Dog, validity_months: u32) -> Option<NaiveDate> {
+    dog.consent_last_signed.as_ref()
+        .and_then(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok())
+        .and_then(|signed| signed.checked_add_months(Months::new(validity_months)))
+}
+
+/// Consent is due when missing or past its validity period, or when it will be
+/// within `advance_days`.
+pub fn consent_due(dog: &Dog, today: NaiveDate, advance_days: u32, validity_months: u32) -> bool {
+    let horizon = today + Duration::days(advance_days as i64);
+    consent_expiry(dog, validity_months).is_none_or(|expiry| expiry < horizon)
+}
+
+fn requested_recently(data: &AppData, dog: &Dog, days: u32) -> bool {
+    let since = Utc::now() - Duration::days(days as i64);
+    data.messages.iter().any(|m| {
+        m.direction == MessageDirection::Outbound
+            && m.template.as_deref() == Some(CONSENT_TEMPLATE)
+            && m.status != MessageStatus::Failed
+            && m.timestamp >= since
+            && m.dog_ids.contains(&dog.id)
+    })
+}
+
+/// Email the consent template and a pre-filled consent form to owners of every
+/// active dog whose consent is due, skipping dogs already asked within the
+/// resend window. Each attempt is logged in the message history.
+fn request_consents(data: &mut AppData) -> Result<ConsentRequestSummary, String> {
+    data.settings.notifications.require_channel(&MessageChannel::Email)?;
+    if !data.settings.smtp.is_configured() {
+        return Err("Set up the outgoing mail server in settings first".to_string());
+    }
+    
+    let today = Utc::now().date_naive();
+    let resend_after_days = data.settings.consent_requests.resend_after_days;
+    let advance_days = data.settings.notifications.reminder_advance_days;
+    let validity_months = data.settings.consent_requests.validity_months;
+    let due: Vec<Dog> = data.dogs.iter()
+        .filter(|d| d.status == DogStatus::Active && consent_due(d, today, advance_days, validity_months))
+        .cloned()
+        .collect();
+    
+    let mut summary = ConsentRequestSummary {
+        sent: Vec::new(),
+        recently_requested: Vec::new(),
+        no_email: Vec::new(),
+        opted_out: Vec::new(),
+        outside_contact_times: Vec::new(),
+        failed: Vec::new(),
+    };
+    
+    for dog in due {
+        if requested_recently(data, &dog, resend_after_days) {
+            summary.recently_requested.push(dog.name.clone());
+            continue;
+        }
+        if dog.email.trim().is_empty() {
+            summary.no_email.push(dog.name.clone());
+            continue;
+        }
+        if opted_out(data, &dog, &MessageChannel::Email) {
+            summary.opted_out.push(dog.name.clone());
+            continue;
+        }
+        if !contactable_now(data, &dog) {
+            summary.outside_contact_times.push(dog.name.clone());
+            continue;
+        }
+        
+        let subject = render_template(&data.settings.email_subjects.consent_form, &dog);
+        let body = render_template(&data.settings.email_templates.consent_form, &dog);
+        let result = consent_form_pdf(data, &dog).and_then(|pdf| {
+            send_email(
+                &data.settings.smtp,
+                &data.settings.business_name,
+                &dog.email,
+                &subject,
+                &body,
+                Some(EmailAttachment {
+                    filename: format!("Consent form - {}.pdf", dog.name),
+                    content_type: "application/pdf".to_string(),
+                    content: pdf,
+                }),
+            )
+        });
+        
+        let status = match result {
+            Ok(()) => {
+                summary.sent.push(dog.name.clone());
+                MessageStatus::Sent
+            }
+            Err(e) => {
+                summary.failed.push(format!("{}: {}", dog.name, e));
+                MessageStatus::Failed
+            }
+        };
+        record_outbound(
+            data,
+            &dog.id,
+            MessageChannel::Email,
+            &dog.email,
+            Some(CONSENT_TEMPLATE.to_string()),
+            Some(subject),
+            &body,
+            status,
+        );
+    }
+    
+    Ok(summary)
+}
+
+/// Add a signing to the history and bring the dog's `consent_last_signed` up to date.
+fn add_signing(
+    data: &mut AppData,
+    dog_id: &str,
+    signed_at: String,
+    version: String,
+    method: ConsentMethod,
+    attachment_id: Option<String>,
+    signed_text: Option<SignedText>,
+) -> Result<ConsentSigning, String> {
+    let signed = NaiveDate::parse_from_str(&signed_at, "%Y-%m-%d")
+        .map_err(|_| "Invalid signing date format".to_string())?;
+    if signed > Utc::now().date_naive() {
+        return Err("Signing date can't be in the future".to_string());
+    }
+    if version.trim().is_empty() {
+        return Err("Consent form version is required".to_string());
+    }
+    let dog_name = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.name.clone())
+        .ok_or("Dog not found")?;
+    
+    let signing = ConsentSigning {
+        id: Uuid::new_v4().to_string(),
+        dog_id: dog_id.to_string(),
+        signed_at,
+        version: version.trim().to_string(),
+        method,
+        attachment_id: attachment_id.filter(|a| !a.trim().is_empty()),
+        recorded_by: current_staff_id(),
+        recorded_at: Utc::now(),
+        signer_name: signed_text.as_ref().map(|s| s.signer_name.clone()),
+        text_hash: signed_text.as_ref().map(|s| s.hash.clone()),
+        signed_text: signed_text.map(|s| s.text),
+    };
+    data.consent_signings.push(signing.clone());
+    
+    let latest = last_signed(data, dog_id);
+    if let Some(dog) = data.dogs.iter_mut().find(|d| d.id == dog_id) {
+        dog.consent_last_signed = latest;
+    }
+    record_audit(data, "consent_signed", &format!(
+        "{} consent form v{} signed {}", dog_name, signing.version, signing.signed_at
+    ));
+    
+    Ok(signing)
+}
+
+#[tauri::command]
+pub fn record_consent_signing(
+    dog_id: String,
+    signed_at: String,
+    version: String,
+    method: ConsentMethod,
+    attachment_id: Option<String>,
+) -> Result<ConsentSigning, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let signing = add_signing(&mut data, &dog_id, signed_at, version, method, attachment_id, None)?;
+    save_app_data(&data)?;
+    Ok(signing)
+}
+
+/// Quick record of a paper form signed on `date`, e.g. from the compliance
+/// screen. The form version carries over from the dog's previous signing.
+#[tauri::command]
+pub fn record_consent_signed(dog_id: String, date: String) -> Result<ConsentSigning, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let version = current_version(&data, &dog_id);
+    let signing = add_signing(&mut data, &dog_id, date, version, ConsentMethod::Paper, None, None)?;
+    save_app_data(&data)?;
+    Ok(signing)
+}
+
+/// Form version the dog last signed, which new signings carry over.
+fn current_version(data: &AppData, dog_id: &str) -> String {
+    data.consent_signings.iter()
+        .filter(|s| s.dog_id == dog_id)
+        .max_by(|a, b| a.signed_at.cmp(&b.signed_at))
+        .map(|s| s.version.clone())
+        .unwrap_or_else(|| "1".to_string())
+}
+
+fn text_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The consent form for a dog, filled in from the same wording as the printed
+/// form, to show for signing on a tablet.
+#[tauri::command]
+pub fn get_consent_form(dog_id: String) -> Result<ConsentForm, String> {
+    let data = load_app_data()?;
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    let text = consent_statement(&data, dog);
+    Ok(ConsentForm {
+        dog_id: dog.id.clone(),
+        dog_name: dog.name.clone(),
+        owner: dog.owner.clone(),
+        version: current_version(&data, &dog.id),
+        text_hash: text_hash(&text),
+        text,
+    })
+}
+
+/// Record a consent form signed on a tablet today. The signature image, base64
+/// with or without a data URL prefix, is kept as one of the dog's documents
+/// and the signing stores the wording agreed to and its hash.
+#[tauri::command]
+pub fn sign_consent_form(
+    dog_id: String,
+    signer_name: String,
+    signature_image: String,
+    text_hash_shown: String,
+) -> Result<ConsentSigning, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    ensure_writable()?;
+    
+    if signer_name.trim().is_empty() {
+        return Err("The name of the person signing is required".to_string());
+    }
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    let text = consent_statement(&data, dog);
+    let hash = text_hash(&text);
+    if hash != text_hash_shown {
+        return Err("The consent form has changed since it was shown; show it again before signing".to_string());
+    }
+    
+    let encoded = signature_image.split_once("base64,").map(|(_, data)| data).unwrap_or(&signature_image);
+    let image = BASE64.decode(encoded.trim()).map_err(|_| "The signature image isn't valid base64".to_string())?;
+    image_crate::load_from_memory(&image).map_err(|e| format!("Failed to read signature image: {}", e))?;
+    
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let filename = format!("consent-signature-{}.png", today);
+    let document = store_document(&mut data, &dog_id, DocumentKind::SignedWaiver, &filename, &image)?;
+    let version = current_version(&data, &dog_id);
+    let signed_text = SignedText { signer_name: signer_name.trim().to_string(), text, hash };
+    let signing = add_signing(&mut data, &dog_id, today, version, ConsentMethod::Digital, Some(document.id.clone()), Some(signed_text))
+        .and_then(|signing| save_app_data(&data).map(|_| signing));
+    if signing.is_err() {
+        let _ = fs::remove_file(document_path(&document)?);
+    }
+    signing
+}
+
+/// A dog's signed consent forms, most recent first.
+#[tauri::command]
+pub fn get_consent_history(dog_id: String) -> Result<Vec<ConsentSigning>, String> {
+    let data = load_app_data()?;
+    
+    let mut history: Vec<ConsentSigning> = data.consent_signings.into_iter()
+        .filter(|s| s.dog_id == dog_id)
+        .collect();
+    
+    history.sort_by(|a, b| b.signed_at.cmp(&a.signed_at).then(b.recorded_at.cmp(&a.recorded_at)));
+    Ok(history)
+}
+
+/// Active dogs whose consent has lapsed or was never signed, longest overdue
+/// first with never-signed dogs at the top.
+#[tauri::command]
+pub fn get_overdue_consents() -> Result<Vec<OverdueConsent>, String> {
+    let data = load_app_data()?;
+    let today = Utc::now().date_naive();
+    let validity_months = data.settings.consent_requests.validity_months;
+    
+    let mut overdue: Vec<OverdueConsent> = data.dogs.iter()
+        .filter(|d| d.status == DogStatus::Active && consent_due(d, today, 0, validity_months))
+        .map(|dog| {
+            let expiry = consent_expiry(dog, validity_months);
+            OverdueConsent {
+                dog_id: dog.id.clone(),
+                dog_name: dog.name.clone(),
+                owner: dog.owner.clone(),
+                last_signed: dog.consent_last_signed.clone(),
+                expired_on: expiry.map(|e| e.format("%Y-%m-%d").to_string()),
+                days_overdue: expiry.map(|e| (today - e).num_days()),
+            }
+        })
+        .collect();
+    
+    overdue.sort_by(|a, b| {
+        b.days_overdue.unwrap_or(i64::MAX).cmp(&a.days_overdue.unwrap_or(i64::MAX)).then(a.dog_name.cmp(&b.dog_name))
+    });
+    Ok(overdue)
+}
+
+#[tauri::command]
+pub fn request_consents_due() -> Result<ConsentRequestSummary, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let summary = request_consents(&mut data)?;
+    save_app_data(&data)?;
+    
+    println!("Sent {} consent requests ({} failed)", summary.sent.len(), summary.failed.len());
+    Ok(summary)
+}
+
+/// Send due consent requests once a day in the background when enabled, waiting
+/// until quiet hours are over. Owners outside their contact times are tried
+/// again on the next hourly pass.
+pub fn spawn_consent_scheduler() {
+    std::thread::spawn(|| {
+        let mut last_run: Option<NaiveDate> = None;
+        loop {
+            let today = Utc::now().date_naive();
+            if last_run != Some(today) {
+                let due = with_app_data(|data| {
+                    data.settings.consent_requests.auto_send && !data.settings.notifications.quiet_now()
+                });
+                match due {
+                    // Sent under the data lock, so the sent markers land on the latest data
+                    Ok(true) => match with_app_data_mut(request_consents) {
+                        Ok(summary) => {
+                            if summary.outside_contact_times.is_empty() {
+                                last_run = Some(today);
+                            }
+                        }
+                        Err(e) => {
+                            last_run = Some(today);
+                            println!("Automatic consent requests failed: {}", e);
+                        }
+                    },
+                    Ok(false) => {}
+                    Err(e) => println!("Failed to load data for consent requests: {}", e),
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+        }
+    });
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::messages::MessageChannel;
+use crate::permissions::{require_permission, Permission};
+use crate::times::normalize_optional;
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, Dog};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ContactMethod {
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "whatsapp")]
+    WhatsApp,
+    #[serde(rename = "sms")]
+    Sms,
+    #[serde(rename = "phone")]
+    Phone,
+}
+
+impl ContactMethod {
+    fn label(&self) -> &'static str {
+        match self {
+            ContactMethod::Email => "email",
+            ContactMethod::WhatsApp => "WhatsApp",
+            ContactMethod::Sms => "text message",
+            ContactMethod::Phone => "a phone call",
+        }
+    }
+}
+
+impl From<&MessageChannel> for ContactMethod {
+    fn from(channel: &MessageChannel) -> Self {
+        match channel {
+            MessageChannel::Email => ContactMethod::Email,
+            MessageChannel::WhatsApp => ContactMethod::WhatsApp,
+            MessageChannel::Sms => ContactMethod::Sms,
+        }
+    }
+}
+
+/// How and when a household likes to be contacted.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContactPreferences {
+    pub household_id: String,
+    pub preferred: Option<ContactMethod>,
+    pub opted_out: Vec<ContactMethod>,
+    pub contact_from: Option<String>, // HH:MM; wraps past midnight when after contact_until
+    pub contact_until: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ContactPreferences {
+    fn within_contact_times(&self, time: NaiveTime) -> bool {
+        let parse = |t: &Option<String>| t.as_deref().and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok());
+        match (parse(&self.contact_from), parse(&self.contact_until)) {
+            (Some(from), Some(until)) if from <= until => time >= from && time < until,
+            (Some(from), Some(until)) => time >= from || time < until,
+            _ => true,
+        }
+    }
+}
+
+pub fn preferences_for<'a>(data: &'a AppData, dog: &Dog) -> Option<&'a ContactPreferences> {
+    data.contact_preferences.iter().find(|p| p.household_id == dog.household_key())
+}
+
+pub fn opted_out(data: &AppData, dog: &Dog, channel: &MessageChannel) -> bool {
+    preferences_for(data, dog).is_some_and(|p| p.opted_out.contains(&channel.into()))
+}
+
+/// Whether it's currently within the household's preferred contact times.
+pub fn contactable_now(data: &AppData, dog: &Dog) -> bool {
+    preferences_for(data, dog).is_none_or(|p| p.within_contact_times(Local::now().time()))
+}
+
+/// Why contacting a dog's owner on `channel` right now goes against their
+/// preferences, if it does.
+pub fn contact_warning(data: &AppData, dog: &Dog, channel: &MessageChannel) -> Option<String> {
+    let preferences = preferences_for(data, dog)?;
+    let method = ContactMethod::from(channel);
+    
+    if preferences.opted_out.contains(&method) {
+        let instead = match preferences.preferred {
+            Some(preferred) if preferred != method => format!("; they prefer {}", preferred.label()),
+            _ => String::new(),
+        };
+        return Some(format!("{} has opted out of {}{}", dog.owner, method.label(), instead));
+    }
+    if !preferences.within_contact_times(Local::now().time()) {
+        return Some(format!(
+            "{} prefers to be contacted between {} and {}",
+            dog.owner,
+            preferences.contact_from.as_deref().unwrap_or_default(),
+            preferences.contact_until.as_deref().unwrap_or_default(),
+        ));
+    }
+    None
+}
+
+#[tauri::command]
+pub fn get_contact_preferences(household_id: String) -> Result<Option<ContactPreferences>, String> {
+    let data = load_app_data()?;
+    Ok(data.contact_preferences.into_iter().find(|p| p.household_id == household_id))
+}
+
+#[tauri::command]
+pub fn set_contact_preferences(
+    household_id: String,
+    preferred: Option<ContactMethod>,
+    opted_out: Vec<ContactMethod>,
+    contact_from: Option<String>,
+    contact_until: Option<String>,
+) -> Result<ContactPreferences, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if !data.dogs.iter().any(|d| d.household_key() == household_id) {
+        return Err("Household not found".to_string());
+    }
+    let contact_from = normalize_optional(contact_from, "contact from time")?;
+    let contact_until = normalize_optional(contact_until, "contact until time")?;
+    if contact_from.is_some() != contact_until.is_some() {
+        return Err("Contact times need both a start and an end time".to_string());
+    }
+    if preferred.is_some_and(|p| opted_out.contains(&p)) {
+        return Err("The preferred contact method can't also be opted out of".to_string());
+    }
+    
+    let mut unique = Vec::new();
+    for method in opted_out {
+        if !unique.contains(&method) {
+            unique.push(method);
+        }
+    }
+    let preferences = ContactPreferences {
+        household_id: household_id.clone(),
+        preferred,
+        opted_out: unique,
+        contact_from,
+        contact_until,
+        updated_at: Utc::now(),
+    };
+    data.contact_preferences.retain(|p| p.household_id != household_id);
+    data.contact_preferences.push(preferences.clone());
+    
+    save_app_data(&data)?;
+    Ok(preferences)
+}
+
+/// Check a message against the owner's preferences before sending it by hand,
+/// returning a warning to confirm with staff if it goes against them.
+#[tauri::command]
+pub fn check_contact_channel(dog_id: String, channel: MessageChannel) -> Result<Option<String>, String> {
+    let data = load_app_data()?;
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    Ok(contact_warning(&data, dog, &channel))
+}
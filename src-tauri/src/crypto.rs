@@ -0,0 +1,194 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const ENVELOPE_FORMAT: &str = "doggy-daycare-encrypted";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+
+/// Self-describing container for password-encrypted data, stored as JSON so it
+/// survives being emailed or copied around as a plain text file.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedEnvelope {
+    format: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+    key.into()
+}
+
+fn seal(cipher: &Aes256Gcm, salt: &[u8], iterations: u32, plaintext: &[u8]) -> Result<String, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext)
+        .map_err(|e| format!("Failed to encrypt data: {}", e))?;
+    
+    let envelope = EncryptedEnvelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        version: 1,
+        kdf: "pbkdf2-sha256".to_string(),
+        iterations,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    
+    serde_json::to_string_pretty(&envelope)
+        .map_err(|e| format!("Failed to serialize encrypted data: {}", e))
+}
+
+/// An envelope's key derivation parameters, nonce and ciphertext, checked.
+struct OpenedEnvelope {
+    iterations: u32,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+fn open_envelope(envelope_json: &str) -> Result<OpenedEnvelope, String> {
+    let envelope: EncryptedEnvelope = serde_json::from_str(envelope_json)
+        .map_err(|_| "Not an encrypted Doggy Daycare file".to_string())?;
+    
+    if envelope.format != ENVELOPE_FORMAT || envelope.version != 1 || envelope.kdf != "pbkdf2-sha256" {
+        return Err("Unsupported encrypted file format".to_string());
+    }
+    
+    let salt = BASE64.decode(&envelope.salt)
+        .map_err(|e| format!("Corrupt encrypted file (salt): {}", e))?;
+    let nonce = BASE64.decode(&envelope.nonce)
+        .map_err(|e| format!("Corrupt encrypted file (nonce): {}", e))?;
+    let ciphertext = BASE64.decode(&envelope.ciphertext)
+        .map_err(|e| format!("Corrupt encrypted file (data): {}", e))?;
+    
+    if nonce.len() != 12 {
+        return Err("Corrupt encrypted file (nonce)".to_string());
+    }
+    
+    Ok(OpenedEnvelope { iterations: envelope.iterations, salt, nonce, ciphertext })
+}
+
+fn unseal(cipher: &Aes256Gcm, opened: &OpenedEnvelope) -> Result<Vec<u8>, String> {
+    cipher.decrypt(Nonce::from_slice(&opened.nonce), opened.ciphertext.as_ref())
+        .map_err(|_| "Incorrect password or corrupted file".to_string())
+}
+
+pub fn encrypt_with_password(plaintext: &[u8], password: &str) -> Result<String, String> {
+    if password.is_empty() {
+        return Err("Password is required".to_string());
+    }
+    
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    
+    let cipher = Aes256Gcm::new(&derive_key(password, &salt, PBKDF2_ITERATIONS));
+    seal(&cipher, &salt, PBKDF2_ITERATIONS, plaintext)
+}
+
+pub fn decrypt_with_password(envelope_json: &str, password: &str) -> Result<Vec<u8>, String> {
+    let opened = open_envelope(envelope_json)?;
+    let cipher = Aes256Gcm::new(&derive_key(password, &opened.salt, opened.iterations));
+    unseal(&cipher, &opened)
+}
+
+/// A passphrase-derived key kept in memory for a file that's saved over and
+/// over, so the slow key derivation only happens once. Each save still gets a
+/// fresh nonce; the salt stays the same so the passphrase keeps opening it.
+pub struct DataKey {
+    cipher: Aes256Gcm,
+    salt: Vec<u8>,
+    iterations: u32,
+}
+
+impl DataKey {
+    pub fn new(passphrase: &str) -> Result<Self, String> {
+        if passphrase.is_empty() {
+            return Err("Passphrase is required".to_string());
+        }
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, PBKDF2_ITERATIONS));
+        Ok(Self { cipher, salt, iterations: PBKDF2_ITERATIONS })
+    }
+    
+    /// The key for an existing encrypted file, with the file's decrypted content
+    /// as proof the passphrase is right.
+    pub fn unlock(envelope_json: &str, passphrase: &str) -> Result<(Self, Vec<u8>), String> {
+        let opened = open_envelope(envelope_json)?;
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &opened.salt, opened.iterations));
+        let plaintext = unseal(&cipher, &opened)
+            .map_err(|_| "Incorrect passphrase or corrupted file".to_string())?;
+        Ok((Self { cipher, salt: opened.salt, iterations: opened.iterations }, plaintext))
+    }
+    
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String, String> {
+        seal(&self.cipher, &self.salt, self.iterations, plaintext)
+    }
+    
+    pub fn decrypt(&self, envelope_json: &str) -> Result<Vec<u8>, String> {
+        unseal(&self.cipher, &open_envelope(envelope_json)?)
+    }
+}
+
+/// Cheap check so import screens can decide whether to prompt for a password.
+pub fn is_encrypted_envelope(content: &str) -> bool {
+    serde_json::from_str::<EncryptedEnvelope>(content)
+        .map(|e| e.format == ENVELOPE_FORMAT)
+        .unwrap_or(false)
+}
+
+/// Random URL-safe token with `bytes` bytes of entropy, for API keys and similar secrets.
+pub fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(buf)
+}
+
+pub fn sha256_hex(input: &[u8]) -> String {
+    Sha256::digest(input).iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+const SECRET_HASH_ITERATIONS: u32 = 100_000;
+
+/// Salted hash for short local secrets such as staff PINs, stored as
+/// "iterations$salt$hash".
+pub fn hash_secret(secret: &str) -> String {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(secret.as_bytes(), &salt, SECRET_HASH_ITERATIONS, &mut hash);
+    
+    format!("{}${}${}", SECRET_HASH_ITERATIONS, BASE64.encode(salt), BASE64.encode(hash))
+}
+
+pub fn verify_secret(secret: &str, stored: &str) -> bool {
+    let mut parts = stored.splitn(3, '$');
+    let (iterations, salt, expected) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(i), Some(s), Some(h)) => (i, s, h),
+        _ => return false,
+    };
+    
+    let (iterations, salt, expected) = match (iterations.parse::<u32>(), BASE64.decode(salt), BASE64.decode(expected)) {
+        (Ok(i), Ok(s), Ok(h)) => (i, s, h),
+        _ => return false,
+    };
+    
+    let mut hash = vec![0u8; expected.len()];
+    pbkdf2::pbkdf2_hmac::<Sha256>(secret.as_bytes(), &salt, iterations, &mut hash);
+    
+    // Constant-time comparison
+    hash.len() == expected.len() && hash.iter().zip(expected.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
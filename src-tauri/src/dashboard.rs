@@ -0,0 +1,117 @@
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::tasks::{due_tasks, Task};
+use crate::{compute_area_occupancy, load_app_data, AreaOccupancy, ServiceType};
+
+/// Event name listened to by dashboard windows (e.g. the wall-mounted display).
+pub const DASHBOARD_UPDATE_EVENT: &str = "dashboard:update";
+
+/// Incremental change pushed to every open window as soon as it is saved,
+/// so displays can patch their state instead of polling commands.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DashboardEvent {
+    CheckIn {
+        date: String,
+        dog_id: String,
+        dog_name: String,
+        time: String,
+        warnings: Vec<String>,
+    },
+    CheckOut {
+        date: String,
+        dog_id: String,
+        dog_name: String,
+        time: String,
+    },
+    AttendanceChanged {
+        date: String,
+        dog_id: String,
+        service_type: Option<ServiceType>,
+        attending: bool,
+    },
+    DogMoved {
+        date: String,
+        dog_id: String,
+        area_id: String,
+        time: String,
+    },
+    CapacityChanged {
+        date: String,
+        area_id: String,
+        assigned: u32,
+        capacity: u32,
+    },
+}
+
+pub fn emit_dashboard_update(app: &tauri::AppHandle, event: DashboardEvent) {
+    if let Err(e) = app.emit(DASHBOARD_UPDATE_EVENT, event) {
+        println!("Failed to emit dashboard update: {}", e);
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OnSiteDog {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub checked_in_time: Option<String>,
+}
+
+/// Full state for a dashboard window to start from before applying incremental events.
+#[derive(Debug, Serialize, Clone)]
+pub struct DashboardSnapshot {
+    pub date: String,
+    pub expected_dogs: Vec<String>,
+    pub on_site: Vec<OnSiteDog>,
+    pub checked_out: Vec<String>,
+    pub areas: Vec<AreaOccupancy>,
+    pub due_tasks: Vec<Task>, // Open chores due that day or overdue
+}
+
+#[tauri::command]
+pub fn get_dashboard_snapshot(date: String) -> Result<DashboardSnapshot, String> {
+    let data = load_app_data()?;
+    
+    let mut snapshot = DashboardSnapshot {
+        date: date.clone(),
+        expected_dogs: Vec::new(),
+        on_site: Vec::new(),
+        checked_out: Vec::new(),
+        areas: compute_area_occupancy(&data, &date),
+        due_tasks: due_tasks(&data, &date),
+    };
+    
+    if let Some(day_data) = data.daily_data.get(&date) {
+        let mut expected: Vec<String> = day_data.attendance.entries.values()
+            .filter(|e| e.attending)
+            .map(|e| e.dog_id.clone())
+            .collect();
+        expected.sort();
+        expected.dedup();
+        snapshot.expected_dogs = expected;
+        
+        for (dog_id, record) in &day_data.records {
+            if record.checked_in_at.is_none() {
+                continue;
+            }
+            
+            if record.checked_out_at.is_some() {
+                snapshot.checked_out.push(dog_id.clone());
+            } else {
+                snapshot.on_site.push(OnSiteDog {
+                    dog_id: dog_id.clone(),
+                    dog_name: data.dogs.iter()
+                        .find(|d| d.id == *dog_id)
+                        .map(|d| d.name.clone())
+                        .unwrap_or_default(),
+                    checked_in_time: record.drop_off_time.clone(),
+                });
+            }
+        }
+        
+        snapshot.on_site.sort_by(|a, b| a.dog_name.cmp(&b.dog_name));
+    }
+    
+    Ok(snapshot)
+}
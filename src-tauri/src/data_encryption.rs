@@ -0,0 +1,198 @@
+use serde::Serialize;
+use std::borrow::Cow;
+use std::fs;
+use std::sync::Mutex;
+
+use crate::audit::record_audit;
+use crate::crypto::{is_encrypted_envelope, DataKey};
+use crate::instance::instance_mode;
+use crate::keychain;
+use crate::permissions::{require_permission, Permission};
+use crate::{get_app_data_path, health, lock_app_data, save_app_data};
+
+const MIN_PASSPHRASE_LEN: usize = 10;
+
+/// Key for the data file, held from unlocking until the app closes. Never
+/// written anywhere; only the passphrase can be kept, in the OS keychain, when
+/// someone chooses to remember it on this machine. Backups are encrypted with
+/// this key too.
+static DATA_KEY: Mutex<Option<DataKey>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub remembered: bool, // Passphrase kept in this machine's keychain
+}
+
+/// The data file's JSON, decrypted with the unlocked key when it's encrypted.
+pub fn decrypt_data(content: &str) -> Result<Cow<'_, str>, String> {
+    if !is_encrypted_envelope(content) {
+        return Ok(Cow::Borrowed(content));
+    }
+    let guard = DATA_KEY.lock().unwrap_or_else(|e| e.into_inner());
+    let key = guard.as_ref().ok_or("The data is encrypted; enter the passphrase to unlock it")?;
+    let plaintext = key.decrypt(content)?;
+    String::from_utf8(plaintext)
+        .map(Cow::Owned)
+        .map_err(|e| format!("Decrypted data is not valid text: {}", e))
+}
+
+/// What to write to the data file: encrypted while encryption is on.
+pub fn encrypt_data(json: String) -> Result<String, String> {
+    match DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(key) => key.encrypt(json.as_bytes()),
+        None => Ok(json),
+    }
+}
+
+fn saved_content() -> Result<String, String> {
+    let path = get_app_data_path()?;
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read data file: {}", e))
+}
+
+pub fn is_unlocked() -> bool {
+    DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+#[tauri::command]
+pub fn get_encryption_status() -> Result<EncryptionStatus, String> {
+    let enabled = is_encrypted_envelope(&saved_content()?);
+    let remembered = enabled && matches!(keychain::load(), Ok(Some(_)));
+    Ok(EncryptionStatus { enabled, unlocked: enabled && is_unlocked(), remembered })
+}
+
+/// Unlock at launch with the passphrase remembered on this machine, if there
+/// is one. Otherwise the app starts locked as usual.
+pub fn unlock_with_remembered_passphrase() {
+    let content = match saved_content() {
+        Ok(content) if is_encrypted_envelope(&content) => content,
+        _ => return,
+    };
+    let passphrase = match keychain::load() {
+        Ok(Some(passphrase)) => passphrase,
+        Ok(None) => return,
+        Err(e) => {
+            println!("Could not read the remembered passphrase: {}", e);
+            return;
+        }
+    };
+    match DataKey::unlock(&content, &passphrase) {
+        Ok((key, _)) => *DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(key),
+        Err(e) => println!("The remembered passphrase did not unlock the data: {}", e),
+    }
+}
+
+/// Open encrypted data with the passphrase, for the rest of this session.
+/// With `remember`, the passphrase is also kept in the OS keychain so this
+/// machine unlocks by itself from the next launch.
+#[tauri::command]
+pub fn unlock_data(passphrase: String, remember: Option<bool>) -> Result<(), String> {
+    let content = saved_content()?;
+    if !is_encrypted_envelope(&content) {
+        return Err("The data is not encrypted".to_string());
+    }
+    let (key, _) = DataKey::unlock(&content, &passphrase)?;
+    if remember.unwrap_or(false) {
+        keychain::store(&passphrase)?;
+    }
+    *DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(key);
+    
+    // The launch check couldn't look inside the data while it was locked
+    health::run_startup_check(!instance_mode().read_only);
+    Ok(())
+}
+
+/// Encrypt the data file with a passphrase from now on. Snapshots taken before
+/// are plain text, so they're removed.
+#[tauri::command]
+pub fn enable_data_encryption(passphrase: String, remember: Option<bool>) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    if get_encryption_status()?.enabled {
+        return Err("The data is already encrypted".to_string());
+    }
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("The passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    
+    let remember = remember.unwrap_or(false);
+    if remember {
+        keychain::store(&passphrase)?;
+    }
+    
+    *DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(DataKey::new(&passphrase)?);
+    record_audit(&mut data, "data_encryption_enabled", if remember {
+        "Data file encrypted with a passphrase, remembered on this machine"
+    } else {
+        "Data file encrypted with a passphrase"
+    });
+    if let Err(e) = save_app_data(&data) {
+        *DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        if remember {
+            let _ = keychain::remove();
+        }
+        return Err(e);
+    }
+    health::remove_snapshots()?;
+    Ok(())
+}
+
+/// Go back to a plain data file. Asks for the passphrase again so an unlocked,
+/// unattended app can't be used to strip the encryption.
+#[tauri::command]
+pub fn disable_data_encryption(passphrase: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let content = saved_content()?;
+    if !is_encrypted_envelope(&content) {
+        return Err("The data is not encrypted".to_string());
+    }
+    DataKey::unlock(&content, &passphrase)?;
+    
+    let key = DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()).take();
+    record_audit(&mut data, "data_encryption_disabled", "Data file no longer encrypted");
+    if let Err(e) = save_app_data(&data) {
+        *DATA_KEY.lock().unwrap_or_else(|e| e.into_inner()) = key;
+        return Err(e);
+    }
+    // A remembered passphrase has nothing left to open
+    if let Err(e) = keychain::remove() {
+        println!("Could not forget the remembered passphrase: {}", e);
+    }
+    Ok(())
+}
+
+/// Keep the passphrase in this machine's keychain so the app unlocks by itself
+/// at launch. Checked against the data file so a typo isn't remembered.
+#[tauri::command]
+pub fn remember_data_passphrase(passphrase: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let content = saved_content()?;
+    if !is_encrypted_envelope(&content) {
+        return Err("The data is not encrypted".to_string());
+    }
+    DataKey::unlock(&content, &passphrase)?;
+    
+    keychain::store(&passphrase)?;
+    record_audit(&mut data, "data_passphrase_remembered", "Data passphrase remembered on this machine");
+    save_app_data(&data)
+}
+
+/// Stop unlocking by itself; the passphrase is asked for again at launch.
+#[tauri::command]
+pub fn forget_data_passphrase() -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    keychain::remove()?;
+    record_audit(&mut data, "data_passphrase_forgotten", "Data passphrase no longer remembered on this machine");
+    save_app_data(&data)
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::record_audit;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, saved_app_data, AppData};
+
+/// A finalized day. Its attendance and daily records can't be changed until
+/// it is unlocked again, which is audited.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DayLock {
+    pub date: String,
+    pub locked_by: Option<String>,
+    pub locked_at: DateTime<Utc>,
+}
+
+pub fn is_locked(data: &AppData, date: &str) -> bool {
+    data.locked_days.iter().any(|l| l.date == date)
+}
+
+/// Locked days whose attendance or records differ from the saved data. Only
+/// days that were locked there and still are count.
+fn changed_locked_days(data: &AppData) -> Result<Vec<String>, String> {
+    if data.locked_days.is_empty() {
+        return Ok(Vec::new());
+    }
+    let saved = match saved_app_data() {
+        Some(saved) => saved,
+        None => return Ok(Vec::new()), // Nothing saved yet, or an older format being migrated
+    };
+    
+    let mut changed = Vec::new();
+    for lock in saved.locked_days.iter().filter(|l| is_locked(data, &l.date)) {
+        let before = serde_json::to_value(saved.daily_data.get(&lock.date)).map_err(|e| e.to_string())?;
+        let after = serde_json::to_value(data.daily_data.get(&lock.date)).map_err(|e| e.to_string())?;
+        if before != after {
+            changed.push(lock.date.clone());
+        }
+    }
+    Ok(changed)
+}
+
+/// Refuse a save that changes a locked day. Compares against the saved data,
+/// so whichever command made the change, it can't slip through.
+pub fn ensure_locked_days_unchanged(data: &AppData) -> Result<(), String> {
+    match changed_locked_days(data)?.first() {
+        Some(date) => Err(format!("{} is locked; unlock the day before changing its attendance or records", date)),
+        None => Ok(()),
+    }
+}
+
+/// Audit the locked days a change is allowed to touch anyway, such as deleting
+/// a dog, which takes it off finalized days too.
+pub fn audit_locked_day_changes(data: &mut AppData, reason: &str) -> Result<(), String> {
+    let changed = changed_locked_days(data)?;
+    if !changed.is_empty() {
+        record_audit(data, "locked_days_changed", &format!("{} changed locked days {}", reason, changed.join(", ")));
+    }
+    Ok(())
+}
+
+/// Finalize a day once it's closed out: it must be over or under way, with
+/// every dog that was checked in checked out again.
+#[tauri::command]
+pub fn lock_day(date: String) -> Result<DayLock, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    if day > Utc::now().date_naive() {
+        return Err("Only days that have started can be locked".to_string());
+    }
+    if is_locked(&data, &date) {
+        return Err(format!("{} is already locked", date));
+    }
+    
+    if let Some(day_data) = data.daily_data.get(&date) {
+        let mut on_site: Vec<String> = day_data.records.iter()
+            .filter(|(_, r)| r.checked_in_at.is_some() && r.checked_out_at.is_none())
+            .map(|(dog_id, _)| data.dogs.iter()
+                .find(|d| d.id == *dog_id)
+                .map(|d| d.name.clone())
+                .unwrap_or_else(|| dog_id.clone()))
+            .collect();
+        if !on_site.is_empty() {
+            on_site.sort();
+            return Err(format!("Check out {} before locking the day", on_site.join(", ")));
+        }
+    }
+    
+    let lock = DayLock {
+        date: date.clone(),
+        locked_by: current_staff_id(),
+        locked_at: Utc::now(),
+    };
+    data.locked_days.push(lock.clone());
+    record_audit(&mut data, "day_locked", &format!("Locked {}", date));
+    
+    save_app_data(&data)?;
+    Ok(lock)
+}
+
+#[tauri::command]
+pub fn unlock_day(date: String, reason: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::UnlockDays)?;
+    
+    if reason.trim().is_empty() {
+        return Err("A reason is required to unlock a day".to_string());
+    }
+    let before = data.locked_days.len();
+    data.locked_days.retain(|l| l.date != date);
+    if data.locked_days.len() == before {
+        return Err(format!("{} is not locked", date));
+    }
+    
+    record_audit(&mut data, "day_unlocked", &format!("Unlocked {}: {}", date, reason.trim()));
+    save_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_locked_days(start_date: String, end_date: String) -> Result<Vec<DayLock>, String> {
+    let data = load_app_data()?;
+    
+    let mut locks: Vec<DayLock> = data.locked_days.into_iter()
+        .filter(|l| l.date >= start_date && l.date <= end_date)
+        .collect();
+    
+    locks.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(locks)
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data};
+
+/// A note for the whole day rather than one dog, e.g. what the evening shift
+/// wants the morning shift to know.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DayNote {
+    pub id: String,
+    pub text: String,
+    pub staff_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DayNoteView {
+    pub date: String,
+    #[serde(flatten)]
+    pub note: DayNote,
+    pub staff_name: Option<String>,
+}
+
+#[tauri::command]
+pub fn add_day_note(date: String, text: String, staff_id: Option<String>) -> Result<DayNote, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    if text.trim().is_empty() {
+        return Err("Note text is required".to_string());
+    }
+    let staff_id = staff_id.or_else(current_staff_id);
+    if let Some(ref staff_id) = staff_id {
+        if !data.staff.iter().any(|s| s.id == *staff_id) {
+            return Err("Staff member not found".to_string());
+        }
+    }
+    
+    let note = DayNote {
+        id: Uuid::new_v4().to_string(),
+        text: text.trim().to_string(),
+        staff_id,
+        created_at: Utc::now(),
+    };
+    data.daily_data.entry(date).or_default().notes.push(note.clone());
+    
+    save_app_data(&data)?;
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn delete_day_note(date: String, note_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let notes = &mut data.daily_data.get_mut(&date).ok_or("Note not found")?.notes;
+    let before = notes.len();
+    notes.retain(|n| n.id != note_id);
+    if notes.len() == before {
+        return Err("Note not found".to_string());
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Handover for a day: notes left the day before followed by the day's own,
+/// oldest first.
+#[tauri::command]
+pub fn get_day_notes(date: String) -> Result<Vec<DayNoteView>, String> {
+    let data = load_app_data()?;
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    let previous = (day - Duration::days(1)).format("%Y-%m-%d").to_string();
+    
+    let staff_name = |id: &Option<String>| id.as_ref()
+        .and_then(|id| data.staff.iter().find(|s| s.id == *id))
+        .map(|s| s.name.clone());
+    
+    let mut notes: Vec<DayNoteView> = [previous, date]
+        .into_iter()
+        .flat_map(|d| {
+            let notes = data.daily_data.get(&d).map(|day_data| day_data.notes.clone()).unwrap_or_default();
+            notes.into_iter().map(move |note| (d.clone(), note))
+        })
+        .map(|(date, note)| DayNoteView {
+            date,
+            staff_name: staff_name(&note.staff_id),
+            note,
+        })
+        .collect();
+    
+    notes.sort_by_key(|n| n.note.created_at);
+    Ok(notes)
+}
@@ -0,0 +1,276 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::boarding::stay_for_night;
+use crate::consent::consent_due;
+use crate::contact_preferences::{contactable_now, opted_out};
+use crate::invoices::amount_due;
+use crate::mailer::send_email;
+use crate::messages::{record_outbound, MessageChannel, MessageDirection, MessageStatus};
+use crate::permissions::{require_permission, Permission};
+use crate::reminders::vaccine_due;
+use crate::{load_app_data, lock_app_data, save_app_data, with_app_data, with_app_data_mut, AppData, Dog, DogStatus, ServiceType};
+
+const DIGEST_TEMPLATE: &str = "weekly_digest";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DigestSettings {
+    pub send_weekday: u32, // 0-6, Sunday=0; digests cover the seven days after this
+    pub subject: String,
+    pub template: String, // {ownerName}, {dogNames}, {bookings}, {outstanding}, {businessName}
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            send_weekday: 0,
+            subject: "{dogNames}'s week at {businessName}".to_string(),
+            template: "Hi {ownerName},\n\nHere's what we have booked for {dogNames} this coming week:\n\n{bookings}\n\n{outstanding}\n\nSee you soon!\n{businessName}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DigestSummary {
+    pub sent: Vec<String>, // Owner names
+    pub skipped: Vec<String>, // "Owner: reason"
+    pub outside_contact_times: Vec<String>, // Left for a later run
+    pub failed: Vec<String>,
+}
+
+fn household_dogs<'a>(data: &'a AppData, household_id: &str) -> Vec<&'a Dog> {
+    let mut dogs: Vec<&Dog> = data.dogs.iter()
+        .filter(|d| d.household_key() == household_id && d.status == DogStatus::Active)
+        .collect();
+    dogs.sort_by(|a, b| a.name.cmp(&b.name));
+    dogs
+}
+
+fn service_name(service_type: &ServiceType) -> &'static str {
+    match service_type {
+        ServiceType::Daycare => "daycare",
+        ServiceType::Training => "training",
+        ServiceType::Boarding => "boarding",
+    }
+}
+
+fn booking_lines(data: &AppData, dogs: &[&Dog], start: NaiveDate) -> Vec<String> {
+    let mut lines = Vec::new();
+    for date in start.iter_days().take(7) {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let day_data = match data.daily_data.get(&date_str) {
+            Some(day_data) => day_data,
+            None => continue,
+        };
+        let mut booked: Vec<String> = day_data.attendance.entries.values()
+            .filter(|e| e.attending)
+            .filter_map(|e| {
+                let dog = dogs.iter().find(|d| d.id == e.dog_id)?;
+                let service = match stay_for_night(data, &dog.id, &date_str) {
+                    Some(_) => "boarding",
+                    None => service_name(&e.service_type),
+                };
+                Some(format!("{} ({})", dog.name, service))
+            })
+            .collect();
+        if booked.is_empty() {
+            continue;
+        }
+        booked.sort();
+        booked.dedup();
+        lines.push(format!("{}: {}", date.format("%a %-d %b"), booked.join(", ")));
+    }
+    lines
+}
+
+fn outstanding_items(data: &AppData, household_id: &str, dogs: &[&Dog], week_end: NaiveDate) -> Vec<String> {
+    let mut items = Vec::new();
+    for invoice in data.invoices.iter().filter(|i| i.household_id == household_id) {
+        let due = amount_due(data, invoice);
+        if due > 0.0 {
+            items.push(format!("Invoice {} has {} to pay", invoice.number, data.settings.currency.format(due)));
+        }
+    }
+    for dog in dogs {
+        if vaccine_due(dog, week_end) {
+            items.push(format!("{}'s vaccinations are due; please send us an updated certificate", dog.name));
+        }
+        if consent_due(dog, week_end, 0, data.settings.consent_requests.validity_months) {
+            items.push(format!("{}'s consent form is due for renewal", dog.name));
+        }
+    }
+    items
+}
+
+/// Subject and body of a household's digest for the week starting `start`.
+fn render_digest(data: &AppData, household_id: &str, start: NaiveDate) -> Result<(String, String), String> {
+    let dogs = household_dogs(data, household_id);
+    let owner = dogs.first().map(|d| d.owner.clone()).ok_or("Household not found")?;
+    let names: Vec<&str> = dogs.iter().map(|d| d.name.as_str()).collect();
+    
+    let bookings = booking_lines(data, &dogs, start);
+    let bookings = if bookings.is_empty() {
+        "Nothing booked this week.".to_string()
+    } else {
+        bookings.join("\n")
+    };
+    let outstanding = outstanding_items(data, household_id, &dogs, start + Duration::days(6));
+    let outstanding = if outstanding.is_empty() {
+        "Everything is up to date on our side.".to_string()
+    } else {
+        format!("A few things need your attention:\n{}", outstanding.iter().map(|i| format!("- {}", i)).collect::<Vec<_>>().join("\n"))
+    };
+    
+    let fill = |template: &str| template
+        .replace("{ownerName}", &owner)
+        .replace("{dogNames}", &names.join(" & "))
+        .replace("{bookings}", &bookings)
+        .replace("{outstanding}", &outstanding)
+        .replace("{businessName}", &data.settings.business_name);
+    Ok((fill(&data.settings.digest.subject), fill(&data.settings.digest.template)))
+}
+
+fn sent_recently(data: &AppData, dog_ids: &[String]) -> bool {
+    let since = Utc::now() - Duration::days(6);
+    data.messages.iter().any(|m| {
+        m.direction == MessageDirection::Outbound
+            && m.template.as_deref() == Some(DIGEST_TEMPLATE)
+            && m.status == MessageStatus::Sent
+            && m.timestamp >= since
+            && m.dog_ids.iter().any(|id| dog_ids.contains(id))
+    })
+}
+
+/// Email the digest to every opted-in household not sent one in the last six
+/// days, logging each in the message history.
+fn send_digests(data: &mut AppData) -> Result<DigestSummary, String> {
+    data.settings.notifications.require_channel(&MessageChannel::Email)?;
+    if !data.settings.smtp.is_configured() {
+        return Err("Set up the outgoing mail server in settings first".to_string());
+    }
+    
+    let start = Utc::now().date_naive() + Duration::days(1);
+    let mut summary = DigestSummary {
+        sent: Vec::new(),
+        skipped: Vec::new(),
+        outside_contact_times: Vec::new(),
+        failed: Vec::new(),
+    };
+    
+    for household_id in data.digest_households.clone() {
+        let dogs = household_dogs(data, &household_id);
+        let contact = match dogs.iter().find(|d| !d.email.trim().is_empty()) {
+            Some(dog) => *dog,
+            None => {
+                if let Some(dog) = dogs.first() {
+                    summary.skipped.push(format!("{}: no email address", dog.owner));
+                }
+                continue;
+            }
+        };
+        let (owner, email) = (contact.owner.clone(), contact.email.clone());
+        if opted_out(data, contact, &MessageChannel::Email) {
+            summary.skipped.push(format!("{}: opted out of email", owner));
+            continue;
+        }
+        if !contactable_now(data, contact) {
+            summary.outside_contact_times.push(owner);
+            continue;
+        }
+        let dog_ids: Vec<String> = dogs.iter().map(|d| d.id.clone()).collect();
+        if sent_recently(data, &dog_ids) {
+            summary.skipped.push(format!("{}: already sent this week", owner));
+            continue;
+        }
+        
+        let (subject, body) = render_digest(data, &household_id, start)?;
+        let status = match send_email(&data.settings.smtp, &data.settings.business_name, &email, &subject, &body, None) {
+            Ok(()) => {
+                summary.sent.push(owner);
+                MessageStatus::Sent
+            }
+            Err(e) => {
+                summary.failed.push(format!("{}: {}", owner, e));
+                MessageStatus::Failed
+            }
+        };
+        record_outbound(data, &dog_ids[0], MessageChannel::Email, &email, Some(DIGEST_TEMPLATE.to_string()), Some(subject), &body, status);
+        if let Some(message) = data.messages.last_mut() {
+            message.dog_ids = dog_ids;
+        }
+    }
+    
+    Ok(summary)
+}
+
+/// Opt a household in to (or out of) the weekly digest.
+#[tauri::command]
+pub fn set_weekly_digest(household_id: String, enabled: bool) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if !data.dogs.iter().any(|d| d.household_key() == household_id) {
+        return Err("Household not found".to_string());
+    }
+    data.digest_households.retain(|h| *h != household_id);
+    if enabled {
+        data.digest_households.push(household_id);
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// The digest a household would get for the coming week, as subject and body.
+#[tauri::command]
+pub fn preview_weekly_digest(household_id: String) -> Result<(String, String), String> {
+    let data = load_app_data()?;
+    render_digest(&data, &household_id, Utc::now().date_naive() + Duration::days(1))
+}
+
+#[tauri::command]
+pub fn send_weekly_digests() -> Result<DigestSummary, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let summary = send_digests(&mut data)?;
+    save_app_data(&data)?;
+    
+    println!("Sent {} weekly digests ({} failed)", summary.sent.len(), summary.failed.len());
+    Ok(summary)
+}
+
+/// Send digests on the configured weekday once quiet hours are over, trying
+/// owners outside their contact times again on the next hourly pass.
+pub fn spawn_digest_scheduler() {
+    std::thread::spawn(|| {
+        let mut last_run: Option<NaiveDate> = None;
+        loop {
+            let today = Utc::now().date_naive();
+            if last_run != Some(today) {
+                let due = with_app_data(|data| {
+                    !data.digest_households.is_empty()
+                        && today.weekday().num_days_from_sunday() == data.settings.digest.send_weekday
+                        && !data.settings.notifications.quiet_now()
+                });
+                match due {
+                    // Sent under the data lock, so the sent markers land on the latest data
+                    Ok(true) => match with_app_data_mut(send_digests) {
+                        Ok(summary) => {
+                            if summary.outside_contact_times.is_empty() {
+                                last_run = Some(today);
+                            }
+                        }
+                        Err(e) => {
+                            last_run = Some(today);
+                            println!("Weekly digests failed: {}", e);
+                        }
+                    },
+                    Ok(false) => {}
+                    Err(e) => println!("Failed to load data for weekly digests: {}", e),
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+        }
+    });
+}
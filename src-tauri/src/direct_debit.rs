@@ -0,0 +1,275 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::invoices::{amount_due, InvoiceStatus};
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data};
+
+/// Our side of SEPA collections, from the creditor agreement with the bank.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectDebitSettings {
+    pub creditor_name: String,
+    pub creditor_id: String, // SEPA creditor identifier, e.g. DE98ZZZ09999999999
+    pub creditor_iban: String,
+    pub creditor_bic: String,
+    pub collection_lead_days: u32, // Days between export and the requested collection date
+}
+
+impl Default for DirectDebitSettings {
+    fn default() -> Self {
+        Self {
+            creditor_name: "".to_string(),
+            creditor_id: "".to_string(),
+            creditor_iban: "".to_string(),
+            creditor_bic: "".to_string(),
+            collection_lead_days: 5,
+        }
+    }
+}
+
+/// A household's signed authorisation to collect from their account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectDebitMandate {
+    pub id: String,
+    pub household_id: String,
+    pub account_holder: String,
+    pub iban: String,
+    pub bic: Option<String>,
+    pub mandate_reference: String,
+    pub signed_on: String, // YYYY-MM-DD
+    pub active: bool,
+    pub last_collection_date: Option<String>, // None until the first collection, which SEPA flags as FRST
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Collection {
+    pub collection_date: String,
+    pub message_id: String,
+    pub submitted_at: DateTime<Utc>,
+}
+
+fn normalize_iban(iban: &str) -> String {
+    iban.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase()
+}
+
+/// ISO 13616 mod-97 check.
+fn is_valid_iban(iban: &str) -> bool {
+    if iban.len() < 15 || iban.len() > 34 || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return false;
+    }
+    
+    let rearranged = format!("{}{}", &iban[4..], &iban[..4]);
+    let mut remainder: u32 = 0;
+    for c in rearranged.chars() {
+        let value = c.to_digit(36).unwrap_or(0);
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+pub fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[tauri::command]
+pub fn get_mandates() -> Result<Vec<DirectDebitMandate>, String> {
+    let data = load_app_data()?;
+    Ok(data.mandates)
+}
+
+/// Add a mandate, or update it when `mandate.id` matches an existing one.
+#[tauri::command]
+pub fn save_mandate(mandate: DirectDebitMandate) -> Result<DirectDebitMandate, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let iban = normalize_iban(&mandate.iban);
+    if !is_valid_iban(&iban) {
+        return Err(format!("{} is not a valid IBAN", mandate.iban));
+    }
+    if mandate.mandate_reference.trim().is_empty() {
+        return Err("Mandate reference is required".to_string());
+    }
+    NaiveDate::parse_from_str(&mandate.signed_on, "%Y-%m-%d")
+        .map_err(|_| "Invalid signature date format".to_string())?;
+    
+    let mut mandate = DirectDebitMandate { iban, ..mandate };
+    
+    if let Some(existing) = data.mandates.iter_mut().find(|m| m.id == mandate.id) {
+        mandate.last_collection_date = existing.last_collection_date.clone();
+        *existing = mandate.clone();
+    } else {
+        mandate.id = Uuid::new_v4().to_string();
+        mandate.last_collection_date = None;
+        data.mandates.push(mandate.clone());
+    }
+    
+    save_app_data(&data)?;
+    Ok(mandate)
+}
+
+#[tauri::command]
+pub fn delete_mandate(mandate_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    if let Some(index) = data.mandates.iter().position(|m| m.id == mandate_id) {
+        data.mandates.remove(index);
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Mandate not found".to_string())
+    }
+}
+
+struct Transaction {
+    end_to_end_id: String,
+    amount: f64,
+    mandate: DirectDebitMandate,
+}
+
+fn write_payment_info(xml: &mut String, info_id: &str, sequence: &str, collection_date: &str, settings: &DirectDebitSettings, transactions: &[Transaction]) {
+    let total: f64 = transactions.iter().map(|t| t.amount).sum();
+    
+    let _ = write!(xml, "    <PmtInf>\n      <PmtInfId>{}</PmtInfId>\n      <PmtMtd>DD</PmtMtd>\n      <NbOfTxs>{}</NbOfTxs>\n      <CtrlSum>{:.2}</CtrlSum>\n",
+                   info_id, transactions.len(), total);
+    let _ = writeln!(xml, "      <PmtTpInf><SvcLvl><Cd>SEPA</Cd></SvcLvl><LclInstrm><Cd>CORE</Cd></LclInstrm><SeqTp>{}</SeqTp></PmtTpInf>", sequence);
+    let _ = writeln!(xml, "      <ReqdColltnDt>{}</ReqdColltnDt>", collection_date);
+    let _ = writeln!(xml, "      <Cdtr><Nm>{}</Nm></Cdtr>", xml_escape(&settings.creditor_name));
+    let _ = writeln!(xml, "      <CdtrAcct><Id><IBAN>{}</IBAN></Id></CdtrAcct>", normalize_iban(&settings.creditor_iban));
+    let _ = writeln!(xml, "      <CdtrAgt><FinInstnId><BIC>{}</BIC></FinInstnId></CdtrAgt>", xml_escape(&settings.creditor_bic));
+    let _ = writeln!(xml, "      <ChrgBr>SLEV</ChrgBr>");
+    let _ = writeln!(xml, "      <CdtrSchmeId><Id><PrvtId><Othr><Id>{}</Id><SchmeNm><Prtry>SEPA</Prtry></SchmeNm></Othr></PrvtId></Id></CdtrSchmeId>",
+                     xml_escape(&settings.creditor_id));
+    
+    for t in transactions {
+        let debtor_agent = match t.mandate.bic.as_deref().filter(|b| !b.is_empty()) {
+            Some(bic) => format!("<BIC>{}</BIC>", xml_escape(bic)),
+            None => "<Othr><Id>NOTPROVIDED</Id></Othr>".to_string(),
+        };
+        let _ = writeln!(xml, "      <DrctDbtTxInf>");
+        let _ = writeln!(xml, "        <PmtId><EndToEndId>{}</EndToEndId></PmtId>", xml_escape(&t.end_to_end_id));
+        let _ = writeln!(xml, "        <InstdAmt Ccy=\"EUR\">{:.2}</InstdAmt>", t.amount);
+        let _ = writeln!(xml, "        <DrctDbtTx><MndtRltdInf><MndtId>{}</MndtId><DtOfSgntr>{}</DtOfSgntr></MndtRltdInf></DrctDbtTx>",
+                         xml_escape(&t.mandate.mandate_reference), t.mandate.signed_on);
+        let _ = writeln!(xml, "        <DbtrAgt><FinInstnId>{}</FinInstnId></DbtrAgt>", debtor_agent);
+        let _ = writeln!(xml, "        <Dbtr><Nm>{}</Nm></Dbtr>", xml_escape(&t.mandate.account_holder));
+        let _ = writeln!(xml, "        <DbtrAcct><Id><IBAN>{}</IBAN></Id></DbtrAcct>", t.mandate.iban);
+        let _ = writeln!(xml, "        <RmtInf><Ustrd>Invoice {}</Ustrd></RmtInf>", xml_escape(&t.end_to_end_id));
+        let _ = writeln!(xml, "      </DrctDbtTxInf>");
+    }
+    
+    let _ = writeln!(xml, "    </PmtInf>");
+}
+
+/// Build a SEPA Core direct debit file (pain.008.001.02) collecting the given
+/// issued invoices, and mark them as submitted for collection. Returns the XML
+/// for upload to the bank.
+#[tauri::command]
+pub fn export_direct_debit_file(invoice_ids: Vec<String>, collection_date: Option<String>) -> Result<String, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    let settings = data.settings.direct_debit.clone();
+    
+    if data.settings.currency.code != "EUR" {
+        return Err("SEPA direct debits can only be collected in EUR".to_string());
+    }
+    if settings.creditor_id.is_empty() || settings.creditor_name.is_empty() || !is_valid_iban(&normalize_iban(&settings.creditor_iban)) {
+        return Err("Set up the creditor details for direct debits in settings first".to_string());
+    }
+    if invoice_ids.is_empty() {
+        return Err("No invoices selected".to_string());
+    }
+    
+    let collection_date = match collection_date.filter(|d| !d.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| "Invalid collection date format".to_string())?,
+        None => Utc::now().date_naive() + Duration::days(settings.collection_lead_days as i64),
+    };
+    let collection_date = collection_date.format("%Y-%m-%d").to_string();
+    
+    let mut first = Vec::new();
+    let mut recurring = Vec::new();
+    
+    for invoice_id in &invoice_ids {
+        let invoice = data.invoices.iter()
+            .find(|i| i.id == *invoice_id)
+            .ok_or("Invoice not found")?;
+        
+        if invoice.status != InvoiceStatus::Issued {
+            return Err(format!("Invoice {} must be issued before it can be collected", invoice.number));
+        }
+        // Part payments and loyalty credit are already off what is owed
+        let due = amount_due(&data, invoice);
+        if due <= 0.0 {
+            return Err(format!("Invoice {} has nothing to collect", invoice.number));
+        }
+        
+        let mandate = data.mandates.iter()
+            .find(|m| m.household_id == invoice.household_id && m.active)
+            .ok_or(format!("No active direct debit mandate for invoice {}", invoice.number))?;
+        
+        let transaction = Transaction {
+            end_to_end_id: invoice.number.clone(),
+            amount: due,
+            mandate: mandate.clone(),
+        };
+        
+        // A mandate's first collection must be flagged FRST, even if it appears twice in one file
+        let is_first = mandate.last_collection_date.is_none()
+            && !recurring.iter().chain(first.iter()).any(|t: &Transaction| t.mandate.id == mandate.id);
+        if is_first {
+            first.push(transaction);
+        } else {
+            recurring.push(transaction);
+        }
+    }
+    
+    let message_id = format!("DD-{}", Utc::now().format("%Y%m%d%H%M%S"));
+    let all: Vec<&Transaction> = first.iter().chain(recurring.iter()).collect();
+    let total: f64 = all.iter().map(|t| t.amount).sum();
+    
+    let mut xml = String::new();
+    let _ = writeln!(xml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(xml, "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.008.001.02\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">");
+    let _ = writeln!(xml, "  <CstmrDrctDbtInitn>");
+    let _ = write!(xml, "    <GrpHdr>\n      <MsgId>{}</MsgId>\n      <CreDtTm>{}</CreDtTm>\n      <NbOfTxs>{}</NbOfTxs>\n      <CtrlSum>{:.2}</CtrlSum>\n      <InitgPty><Nm>{}</Nm></InitgPty>\n    </GrpHdr>\n",
+                   message_id, Utc::now().format("%Y-%m-%dT%H:%M:%S"), all.len(), total, xml_escape(&settings.creditor_name));
+    if !first.is_empty() {
+        write_payment_info(&mut xml, &format!("{}-FRST", message_id), "FRST", &collection_date, &settings, &first);
+    }
+    if !recurring.is_empty() {
+        write_payment_info(&mut xml, &format!("{}-RCUR", message_id), "RCUR", &collection_date, &settings, &recurring);
+    }
+    let _ = writeln!(xml, "  </CstmrDrctDbtInitn>");
+    let _ = writeln!(xml, "</Document>");
+    
+    let submitted_at = Utc::now();
+    for invoice in data.invoices.iter_mut().filter(|i| invoice_ids.contains(&i.id)) {
+        invoice.status = InvoiceStatus::SubmittedForCollection;
+        invoice.collection = Some(Collection {
+            collection_date: collection_date.clone(),
+            message_id: message_id.clone(),
+            submitted_at,
+        });
+    }
+    for mandate in data.mandates.iter_mut().filter(|m| all.iter().any(|t| t.mandate.id == m.id)) {
+        mandate.last_collection_date = Some(collection_date.clone());
+    }
+    
+    record_audit(&mut data, "direct_debit_exported", &format!("{} invoices submitted for collection on {}", all.len(), collection_date));
+    save_app_data(&data)?;
+    
+    Ok(xml)
+}
@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri_plugin_opener::OpenerExt;
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::instance::ensure_writable;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{get_app_data_path, load_app_data, lock_app_data, save_app_data, AppData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum DocumentKind {
+    #[serde(rename = "vaccination_certificate")]
+    VaccinationCertificate,
+    #[serde(rename = "signed_waiver")]
+    SignedWaiver,
+    #[serde(rename = "vet_letter")]
+    VetLetter,
+    #[serde(rename = "other")]
+    Other,
+}
+
+/// A file kept on record for a dog, e.g. a scanned vaccination certificate.
+/// The copy lives in the app's documents directory; `stored_name` is relative
+/// to it so documents survive the data directory moving.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Document {
+    pub id: String,
+    pub dog_id: String,
+    pub kind: DocumentKind,
+    pub filename: String, // Name of the file as it was attached
+    pub stored_name: String,
+    pub added_by: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+fn documents_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_path()?.with_file_name("documents"))
+}
+
+/// Where a document's copy is on disk.
+pub fn document_path(document: &Document) -> Result<PathBuf, String> {
+    Ok(documents_dir()?.join(&document.stored_name))
+}
+
+/// Write a file into the documents directory and file it against the dog.
+/// The caller saves the data; if that fails the file is left unreferenced.
+pub fn store_document(
+    data: &mut AppData,
+    dog_id: &str,
+    kind: DocumentKind,
+    filename: &str,
+    contents: &[u8],
+) -> Result<Document, String> {
+    let dog_name = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.name.clone())
+        .ok_or("Dog not found")?;
+    
+    let id = Uuid::new_v4().to_string();
+    let stored_name = match Path::new(filename).extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}/{}.{}", dog_id, id, extension.to_lowercase()),
+        None => format!("{}/{}", dog_id, id),
+    };
+    let document = Document {
+        id,
+        dog_id: dog_id.to_string(),
+        kind,
+        filename: filename.to_string(),
+        stored_name,
+        added_by: current_staff_id(),
+        added_at: Utc::now(),
+    };
+    let target = document_path(&document)?;
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create documents directory: {}", e))?;
+    }
+    fs::write(&target, contents).map_err(|e| format!("Failed to store document: {}", e))?;
+    
+    data.documents.push(document.clone());
+    let details = format!("Attached {:?} {} to {}", document.kind, document.filename, dog_name);
+    record_audit(data, "document_attached", &details);
+    Ok(document)
+}
+
+/// Copy a file into the app's documents directory and file it against the dog.
+#[tauri::command]
+pub fn attach_document(dog_id: String, kind: DocumentKind, source_path: String) -> Result<Document, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    ensure_writable()?;
+    
+    let source = Path::new(&source_path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", source_path));
+    }
+    let filename = source.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("The file has no name")?;
+    let contents = fs::read(source).map_err(|e| format!("Failed to read document: {}", e))?;
+    
+    let document = store_document(&mut data, &dog_id, kind, &filename, &contents)?;
+    if let Err(e) = save_app_data(&data) {
+        let _ = fs::remove_file(document_path(&document)?);
+        return Err(e);
+    }
+    Ok(document)
+}
+
+/// A dog's documents, newest first.
+#[tauri::command]
+pub fn list_documents(dog_id: String) -> Result<Vec<Document>, String> {
+    let data = load_app_data()?;
+    
+    let mut documents: Vec<Document> = data.documents.iter()
+        .filter(|d| d.dog_id == dog_id)
+        .cloned()
+        .collect();
+    documents.sort_by_key(|d| Reverse(d.added_at));
+    Ok(documents)
+}
+
+/// Open a document in the system's default viewer.
+#[tauri::command]
+pub fn open_document(app: tauri::AppHandle, document_id: String) -> Result<(), String> {
+    let data = load_app_data()?;
+    let document = data.documents.iter()
+        .find(|d| d.id == document_id)
+        .ok_or("Document not found")?;
+    
+    let path = document_path(document)?;
+    if !path.is_file() {
+        return Err(format!("{} is missing from the documents directory", document.filename));
+    }
+    app.opener().open_path(path.to_string_lossy(), None::<String>)
+        .map_err(|e| format!("Failed to open {}: {}", document.filename, e))
+}
+
+#[tauri::command]
+pub fn remove_document(document_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    ensure_writable()?;
+    
+    let index = data.documents.iter()
+        .position(|d| d.id == document_id)
+        .ok_or("Document not found")?;
+    let document = data.documents.remove(index);
+    
+    record_audit(&mut data, "document_removed", &format!("Removed {:?} {}", document.kind, document.filename));
+    save_app_data(&data)?;
+    let _ = fs::remove_file(document_path(&document)?);
+    Ok(())
+}
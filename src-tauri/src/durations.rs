@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{load_app_data, DailyRecord};
+
+/// Minutes a dog was actually with us, from its check-in and check-out. None
+/// until it has been checked out.
+pub fn attended_minutes(record: &DailyRecord) -> Option<i64> {
+    let minutes = (record.checked_out_at? - record.checked_in_at?).num_minutes();
+    (minutes >= 0).then_some(minutes)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AttendedDuration {
+    pub date: String,
+    pub dog_id: String,
+    pub dog_name: String,
+    pub checked_in_at: DateTime<Utc>,
+    pub checked_out_at: DateTime<Utc>,
+    pub minutes: i64,
+}
+
+/// How long each dog stayed on each day it was checked in and out.
+#[tauri::command]
+pub fn get_attended_durations(start_date: String, end_date: String) -> Result<Vec<AttendedDuration>, String> {
+    let data = load_app_data()?;
+    
+    let mut durations = Vec::new();
+    for (date, day_data) in data.daily_data.iter().filter(|(d, _)| **d >= start_date && **d <= end_date) {
+        for (dog_id, record) in &day_data.records {
+            let (checked_in_at, checked_out_at, minutes) = match (record.checked_in_at, record.checked_out_at, attended_minutes(record)) {
+                (Some(checked_in_at), Some(checked_out_at), Some(minutes)) => (checked_in_at, checked_out_at, minutes),
+                _ => continue,
+            };
+            let dog_name = match data.dogs.iter().find(|d| d.id == *dog_id) {
+                Some(dog) => dog.name.clone(),
+                None => continue,
+            };
+            durations.push(AttendedDuration {
+                date: date.clone(),
+                dog_id: dog_id.clone(),
+                dog_name,
+                checked_in_at,
+                checked_out_at,
+                minutes,
+            });
+        }
+    }
+    
+    durations.sort_by(|a, b| a.date.cmp(&b.date).then(a.dog_name.cmp(&b.dog_name)));
+    Ok(durations)
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::permissions::current_staff_id;
+use crate::{load_app_data, AttendanceEntry, DailyRecord, DayData, ServiceType};
+
+/// One edit to a dog's booking or day record, kept so a disputed time or
+/// cancellation can be traced back to who changed it and when.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EntryChange {
+    pub dog_id: String,
+    pub service_type: Option<ServiceType>, // None for the day record's drop-off and pick-up times
+    pub field: String, // "attending", "drop_off_time" or "pick_up_time"
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Log a change to one field. Nothing is logged if the value didn't change.
+pub fn record_change(
+    day_data: &mut DayData,
+    dog_id: &str,
+    service_type: Option<&ServiceType>,
+    field: &str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+) {
+    if old_value == new_value {
+        return;
+    }
+    day_data.history.push(EntryChange {
+        dog_id: dog_id.to_string(),
+        service_type: service_type.cloned(),
+        field: field.to_string(),
+        old_value,
+        new_value,
+        changed_by: current_staff_id(),
+        changed_at: Utc::now(),
+    });
+}
+
+/// Log what changed between a booking before and after an edit. `old` is None
+/// when the booking is new.
+pub fn record_entry_changes(day_data: &mut DayData, old: Option<&AttendanceEntry>, new: &AttendanceEntry) {
+    let service_type = Some(&new.service_type);
+    record_change(day_data, &new.dog_id, service_type, "attending",
+        old.map(|e| e.attending.to_string()), Some(new.attending.to_string()));
+    record_change(day_data, &new.dog_id, service_type, "drop_off_time",
+        old.and_then(|e| e.drop_off_time.clone()), new.drop_off_time.clone());
+    record_change(day_data, &new.dog_id, service_type, "pick_up_time",
+        old.and_then(|e| e.pick_up_time.clone()), new.pick_up_time.clone());
+}
+
+/// Log changes to the actual drop-off and pick-up times on a dog's day record.
+pub fn record_record_changes(day_data: &mut DayData, dog_id: &str, old: Option<&DailyRecord>, new: &DailyRecord) {
+    record_change(day_data, dog_id, None, "drop_off_time",
+        old.and_then(|r| r.drop_off_time.clone()), new.drop_off_time.clone());
+    record_change(day_data, dog_id, None, "pick_up_time",
+        old.and_then(|r| r.pick_up_time.clone()), new.pick_up_time.clone());
+}
+
+/// Every recorded edit to the dog's bookings and day record on the date,
+/// oldest first.
+#[tauri::command]
+pub fn get_entry_history(date: String, dog_id: String) -> Result<Vec<EntryChange>, String> {
+    let data = load_app_data()?;
+    
+    let mut history: Vec<EntryChange> = data.daily_data.get(&date)
+        .map(|d| d.history.iter().filter(|c| c.dog_id == dog_id).cloned().collect())
+        .unwrap_or_default();
+    history.sort_by_key(|c| c.changed_at);
+    Ok(history)
+}
@@ -0,0 +1,213 @@
+use serde::Serialize;
+use std::fs;
+
+use crate::audit::record_audit;
+use crate::documents::document_path;
+use crate::instance::ensure_writable;
+use crate::permissions::{require_permission, Permission};
+use crate::{lock_app_data, save_app_data_past_day_locks, AppData};
+
+/// Stands in for an erased dog's id where the record has to stay, e.g. a
+/// package credit that was paid for and used.
+const ERASED_DOG_ID: &str = "erased";
+const ERASED_DOG_NAME: &str = "Erased dog";
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum ErasureAction {
+    #[serde(rename = "removed")]
+    Removed,
+    #[serde(rename = "anonymized")]
+    Anonymized, // Kept for the accounts with the dog taken out
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ErasureItem {
+    pub entity: String, // e.g. "attendance entries", "invoice lines"
+    pub action: ErasureAction,
+    pub count: usize,
+}
+
+/// What erasing a dog touches. A dry run reports the same without changing
+/// anything.
+#[derive(Debug, Serialize, Clone)]
+pub struct ErasureReport {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub dry_run: bool,
+    pub items: Vec<ErasureItem>,
+    pub files: Vec<String>, // Photos and documents deleted from disk
+}
+
+impl ErasureReport {
+    fn add(&mut self, entity: &str, action: ErasureAction, count: usize) {
+        if count > 0 {
+            self.items.push(ErasureItem { entity: entity.to_string(), action, count });
+        }
+    }
+}
+
+/// Remove the matching items from a list, returning how many went.
+fn remove<T>(items: &mut Vec<T>, matches: impl Fn(&T) -> bool) -> usize {
+    let before = items.len();
+    items.retain(|item| !matches(item));
+    before - items.len()
+}
+
+/// Take a dog out of every list that refers to it. Anything about the dog
+/// alone goes; records the business must keep, like invoices and package
+/// credits, stay with the dog's id and name replaced. Household records such
+/// as payments and contact details aren't touched, since they belong to the
+/// owner rather than the dog.
+pub fn erase_dog_data(data: &mut AppData, dog_id: &str) -> Result<ErasureReport, String> {
+    let index = data.dogs.iter().position(|d| d.id == dog_id).ok_or("Dog not found")?;
+    let dog = data.dogs.remove(index);
+    let mut report = ErasureReport {
+        dog_id: dog.id.clone(),
+        dog_name: dog.name.clone(),
+        dry_run: false,
+        items: Vec::new(),
+        files: Vec::new(),
+    };
+    report.add("dogs", ErasureAction::Removed, 1);
+    
+    let schedules = remove(&mut data.recurring_schedules, |s| s.dog_id == dog_id);
+    report.add("recurring schedules", ErasureAction::Removed, schedules);
+    
+    let (mut entries, mut records, mut day_items) = (0, 0, 0);
+    for day_data in data.daily_data.values_mut() {
+        let before = day_data.attendance.entries.len();
+        day_data.attendance.entries.retain(|_, e| e.dog_id != dog_id);
+        entries += before - day_data.attendance.entries.len();
+        day_items += day_data.attendance.dogs.remove(dog_id).map_or(0, |_| 1);
+        if let Some(record) = day_data.records.remove(dog_id) {
+            records += 1;
+            report.files.extend(record.photos);
+        }
+        day_items += day_data.area_assignments.remove(dog_id).map_or(0, |_| 1);
+        day_items += day_data.playgroup_assignments.remove(dog_id).map_or(0, |_| 1);
+        day_items += remove(&mut day_data.location_events, |e| e.dog_id == dog_id);
+        day_items += remove(&mut day_data.history, |c| c.dog_id == dog_id);
+    }
+    report.add("attendance entries", ErasureAction::Removed, entries);
+    report.add("daily records", ErasureAction::Removed, records);
+    report.add("areas, locations and edit history", ErasureAction::Removed, day_items);
+    
+    let trials = remove(&mut data.trials, |t| t.dog_id == dog_id);
+    report.add("trial days", ErasureAction::Removed, trials);
+    let waitlist = remove(&mut data.waitlist, |w| w.dog_id == dog_id);
+    report.add("waitlist entries", ErasureAction::Removed, waitlist);
+    let absences = remove(&mut data.absences, |a| a.dog_id == dog_id);
+    report.add("absences", ErasureAction::Removed, absences);
+    let food = remove(&mut data.owner_food, |f| f.dog_id == dog_id)
+        + remove(&mut data.feedings, |f| f.dog_id == dog_id);
+    report.add("food supplies and feedings", ErasureAction::Removed, food);
+    let consents = remove(&mut data.consent_signings, |s| s.dog_id == dog_id);
+    report.add("consent signings", ErasureAction::Removed, consents);
+    let reminder_suffix = format!(":{}", dog_id);
+    let reminders = remove(&mut data.reminders, |r| r.reminder_id.ends_with(&reminder_suffix));
+    report.add("reminders", ErasureAction::Removed, reminders);
+    
+    // Photos and documents go from disk too, unless another dog shares the photo
+    let photos: Vec<_> = data.dog_photos.iter().filter(|p| p.dog_id == dog_id).cloned().collect();
+    data.dog_photos.retain(|p| p.dog_id != dog_id);
+    for photo in &photos {
+        if !data.dog_photos.iter().any(|p| p.path == photo.path) {
+            report.files.push(photo.path.clone());
+        }
+    }
+    report.add("photos", ErasureAction::Removed, photos.len());
+    if let Some(path) = dog.photo_path.filter(|p| !p.is_empty()) {
+        if !report.files.contains(&path) && !data.dog_photos.iter().any(|p| p.path == path) {
+            report.files.push(path);
+        }
+    }
+    let documents: Vec<_> = data.documents.iter().filter(|d| d.dog_id == dog_id).cloned().collect();
+    data.documents.retain(|d| d.dog_id != dog_id);
+    for document in &documents {
+        report.files.push(document_path(document)?.to_string_lossy().to_string());
+    }
+    report.add("documents", ErasureAction::Removed, documents.len());
+    
+    // Messages about several dogs stay for the others
+    let mut messages = 0;
+    for message in data.messages.iter_mut() {
+        messages += remove(&mut message.dog_ids, |id| id == dog_id);
+    }
+    let orphaned = remove(&mut data.messages, |m| m.dog_ids.is_empty());
+    report.add("messages", ErasureAction::Removed, orphaned);
+    report.add("messages shared with other dogs", ErasureAction::Anonymized, messages - orphaned);
+    
+    let mut shared = 0;
+    for stay in data.boarding_stays.iter_mut() {
+        shared += remove(&mut stay.dog_ids, |id| id == dog_id);
+    }
+    let stays = remove(&mut data.boarding_stays, |s| s.dog_ids.is_empty());
+    report.add("boarding stays", ErasureAction::Removed, stays);
+    report.add("shared boarding stays", ErasureAction::Anonymized, shared - stays);
+    
+    let mut stops = 0;
+    for route in data.pickup_routes.iter_mut() {
+        for stop in route.stops.iter_mut() {
+            stops += remove(&mut stop.dog_ids, |id| id == dog_id);
+        }
+        route.stops.retain(|s| !s.dog_ids.is_empty());
+    }
+    report.add("pickup route stops", ErasureAction::Removed, stops);
+    
+    // Invoices are accounting records, so lines stay with the dog's name taken out
+    let prefix = format!("{}: ", dog.name);
+    let mut lines = 0;
+    for line in data.invoices.iter_mut().flat_map(|i| i.lines.iter_mut()) {
+        if line.dog_id.as_deref() == Some(dog_id) {
+            line.dog_id = None;
+            if let Some(rest) = line.description.strip_prefix(&prefix) {
+                line.description = format!("{}: {}", ERASED_DOG_NAME, rest);
+            }
+            lines += 1;
+        }
+    }
+    report.add("invoice lines", ErasureAction::Anonymized, lines);
+    
+    let mut uses = 0;
+    for package_use in data.packages.iter_mut().flat_map(|p| p.uses.iter_mut()) {
+        if package_use.dog_id == dog_id {
+            package_use.dog_id = ERASED_DOG_ID.to_string();
+            uses += 1;
+        }
+    }
+    report.add("package credits used", ErasureAction::Anonymized, uses);
+    
+    Ok(report)
+}
+
+/// Delete the photos and documents an erasure left behind. Failures are only
+/// logged, as the records are already gone.
+pub fn remove_files(report: &ErasureReport) {
+    for path in &report.files {
+        if let Err(e) = fs::remove_file(path) {
+            println!("Failed to remove {}: {}", path, e);
+        }
+    }
+}
+
+/// Permanently erase a dog and everything that refers to it, e.g. for a GDPR
+/// erasure request. With `dry_run` nothing changes and the report shows what
+/// would go.
+#[tauri::command]
+pub fn erase_dog(dog_id: String, dry_run: bool) -> Result<ErasureReport, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    let mut report = erase_dog_data(&mut data, &dog_id)?;
+    if dry_run {
+        report.dry_run = true;
+        return Ok(report);
+    }
+    ensure_writable()?;
+    
+    // Only the id goes in the audit log, so the erasure doesn't keep the name
+    record_audit(&mut data, "dog_erased", &format!("Erased dog {} and its records", dog_id));
+    save_app_data_past_day_locks(&mut data, &format!("Erasing dog {}", dog_id))?;
+    remove_files(&report);
+    Ok(report)
+}
@@ -0,0 +1,123 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum WeekStart {
+    #[serde(rename = "monday")]
+    Monday,
+    #[serde(rename = "sunday")]
+    Sunday,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum WeekNumbering {
+    #[serde(rename = "iso")]
+    Iso, // ISO 8601: week 1 is the week with the calendar year's first Thursday
+    #[serde(rename = "fiscal_year")]
+    FiscalYear, // Week 1 is the week the fiscal year starts in
+}
+
+/// How reports divide time up, so their periods match the accountant's. A
+/// fiscal year is named after the calendar year it starts in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FiscalSettings {
+    pub year_start_month: u32, // 1 = January
+    pub week_starts_on: WeekStart,
+    pub week_numbering: WeekNumbering,
+}
+
+impl Default for FiscalSettings {
+    fn default() -> Self {
+        Self {
+            year_start_month: 1,
+            week_starts_on: WeekStart::Monday,
+            week_numbering: WeekNumbering::Iso,
+        }
+    }
+}
+
+impl FiscalSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=12).contains(&self.year_start_month) {
+            return Err("The fiscal year must start in a month from 1 to 12".to_string());
+        }
+        if self.week_numbering == WeekNumbering::Iso && self.week_starts_on != WeekStart::Monday {
+            return Err("ISO week numbers need weeks to start on Monday".to_string());
+        }
+        Ok(())
+    }
+    
+    /// The fiscal year a date falls in.
+    pub fn year_of(&self, date: NaiveDate) -> i32 {
+        if date.month() >= self.year_start_month {
+            date.year()
+        } else {
+            date.year() - 1
+        }
+    }
+    
+    /// First and last day of a fiscal year.
+    pub fn year_bounds(&self, year: i32) -> Result<(NaiveDate, NaiveDate), String> {
+        let start = NaiveDate::from_ymd_opt(year, self.year_start_month, 1);
+        let next = NaiveDate::from_ymd_opt(year + 1, self.year_start_month, 1);
+        match (start, next) {
+            (Some(start), Some(next)) => Ok((start, next - Duration::days(1))),
+            _ => Err(format!("Invalid fiscal year: {}", year)),
+        }
+    }
+    
+    /// "2024" for calendar years, otherwise "2024/25".
+    pub fn year_label(&self, year: i32) -> String {
+        if self.year_start_month == 1 {
+            year.to_string()
+        } else {
+            format!("{}/{:02}", year, (year + 1) % 100)
+        }
+    }
+    
+    /// The fiscal year's twelve months, first and last day of each.
+    pub fn months(&self, year: i32) -> Result<Vec<(NaiveDate, NaiveDate)>, String> {
+        let (start, _) = self.year_bounds(year)?;
+        let months = (0..12)
+            .map(|offset| {
+                let first = start + Months::new(offset);
+                let last = start + Months::new(offset + 1) - Duration::days(1);
+                (first, last)
+            })
+            .collect();
+        Ok(months)
+    }
+    
+    /// The fiscal year's four quarters, first and last day of each.
+    pub fn quarters(&self, year: i32) -> Result<Vec<(NaiveDate, NaiveDate)>, String> {
+        let months = self.months(year)?;
+        Ok(months.chunks(3).map(|q| (q[0].0, q[2].1)).collect())
+    }
+    
+    /// First day of the week a date falls in.
+    pub fn week_start(&self, date: NaiveDate) -> NaiveDate {
+        let days_in = match self.week_starts_on {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        };
+        date - Duration::days(days_in as i64)
+    }
+    
+    /// The year a week is numbered in and its number. A week that runs into the
+    /// next fiscal year counts as that year's week 1.
+    pub fn week_number(&self, date: NaiveDate) -> (i32, u32) {
+        match self.week_numbering {
+            WeekNumbering::Iso => {
+                let week = date.iso_week();
+                (week.year(), week.week())
+            }
+            WeekNumbering::FiscalYear => {
+                let week = self.week_start(date);
+                let year = self.year_of(week + Duration::days(6));
+                let first_day = NaiveDate::from_ymd_opt(year, self.year_start_month, 1).unwrap_or(week);
+                let first_week = self.week_start(first_day);
+                (year, ((week - first_week).num_days() / 7) as u32 + 1)
+            }
+        }
+    }
+}
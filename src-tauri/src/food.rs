@@ -0,0 +1,307 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData};
+
+/// Food we keep in stock and feed to dogs whose owners don't bring their own.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HouseFood {
+    pub id: String,
+    pub name: String,
+    pub stock_grams: f64,
+    pub low_stock_grams: f64, // Warn when stock falls to this level
+    pub price_per_kg: f64, // Charged on boarding invoices; 0 to feed free of charge
+    pub active: bool,
+}
+
+/// Food an owner brought in with their dog, weighed on arrival.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OwnerFoodSupply {
+    pub id: String,
+    pub dog_id: String,
+    pub description: String,
+    pub arrival_date: String,
+    pub arrival_grams: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum FoodSource {
+    #[serde(rename = "house")]
+    House,
+    #[serde(rename = "owner")]
+    Owner,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeedingEntry {
+    pub id: String,
+    pub date: String,
+    pub dog_id: String,
+    pub source: FoodSource,
+    pub food_id: String, // HouseFood id or OwnerFoodSupply id, depending on source
+    pub grams: f64,
+    #[serde(default)]
+    pub price_per_kg: Option<f64>, // House food's price when fed, so later price changes don't rebill it
+    pub recorded_by: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OwnerFoodStatus {
+    pub supply: OwnerFoodSupply,
+    pub dog_name: String,
+    pub consumed_grams: f64,
+    pub remaining_grams: f64,
+    pub days_left: Option<f64>, // At the average daily amount fed so far
+}
+
+/// Days of food an owner's supply should still cover before we warn.
+const OWNER_FOOD_WARNING_DAYS: f64 = 2.0;
+
+fn owner_food_status(data: &AppData, supply: &OwnerFoodSupply) -> OwnerFoodStatus {
+    let feedings: Vec<&FeedingEntry> = data.feedings.iter()
+        .filter(|f| f.source == FoodSource::Owner && f.food_id == supply.id)
+        .collect();
+    let consumed_grams: f64 = feedings.iter().map(|f| f.grams).sum();
+    
+    let mut days: Vec<&String> = feedings.iter().map(|f| &f.date).collect();
+    days.sort();
+    days.dedup();
+    let days_left = if days.is_empty() {
+        None
+    } else {
+        let daily = consumed_grams / days.len() as f64;
+        (daily > 0.0).then(|| ((supply.arrival_grams - consumed_grams) / daily).max(0.0))
+    };
+    
+    OwnerFoodStatus {
+        supply: supply.clone(),
+        dog_name: data.dogs.iter()
+            .find(|d| d.id == supply.dog_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_default(),
+        consumed_grams,
+        remaining_grams: (supply.arrival_grams - consumed_grams).max(0.0),
+        days_left,
+    }
+}
+
+fn house_food_warning(food: &HouseFood) -> Option<String> {
+    (food.active && food.stock_grams <= food.low_stock_grams).then(|| {
+        format!("{} is running low ({:.1} kg left)", food.name, food.stock_grams / 1000.0)
+    })
+}
+
+fn food_warnings(data: &AppData) -> Vec<String> {
+    let mut warnings: Vec<String> = data.house_foods.iter().filter_map(house_food_warning).collect();
+    
+    for supply in &data.owner_food {
+        let status = owner_food_status(data, supply);
+        if status.remaining_grams > 0.0 && status.days_left.is_some_and(|d| d < OWNER_FOOD_WARNING_DAYS) {
+            warnings.push(format!(
+                "{}'s food ({}) will run out in about {:.0} day(s); ask the owner for more",
+                status.dog_name, supply.description, status.days_left.unwrap_or(0.0).ceil()
+            ));
+        }
+    }
+    warnings
+}
+
+#[tauri::command]
+pub fn get_house_foods() -> Result<Vec<HouseFood>, String> {
+    let data = load_app_data()?;
+    Ok(data.house_foods)
+}
+
+/// Add a house food, or update it when the id matches an existing one.
+#[tauri::command]
+pub fn save_house_food(food: HouseFood) -> Result<HouseFood, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if food.name.trim().is_empty() {
+        return Err("Food name is required".to_string());
+    }
+    if food.stock_grams < 0.0 || food.low_stock_grams < 0.0 {
+        return Err("Stock levels can't be negative".to_string());
+    }
+    if food.price_per_kg < 0.0 {
+        return Err("Price per kg can't be negative".to_string());
+    }
+    
+    let food = match data.house_foods.iter_mut().find(|f| f.id == food.id) {
+        Some(existing) => {
+            *existing = food.clone();
+            food
+        }
+        None => {
+            let food = HouseFood { id: Uuid::new_v4().to_string(), ..food };
+            data.house_foods.push(food.clone());
+            food
+        }
+    };
+    
+    save_app_data(&data)?;
+    Ok(food)
+}
+
+#[tauri::command]
+pub fn restock_house_food(food_id: String, grams: f64) -> Result<HouseFood, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if grams <= 0.0 {
+        return Err("Restock amount must be positive".to_string());
+    }
+    
+    let food = data.house_foods.iter_mut()
+        .find(|f| f.id == food_id)
+        .ok_or("Food not found")?;
+    food.stock_grams += grams;
+    let food = food.clone();
+    
+    save_app_data(&data)?;
+    Ok(food)
+}
+
+#[tauri::command]
+pub fn add_owner_food(
+    dog_id: String,
+    description: String,
+    arrival_date: String,
+    arrival_grams: f64,
+) -> Result<OwnerFoodSupply, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    NaiveDate::parse_from_str(&arrival_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid arrival date format".to_string())?;
+    
+    let supply = OwnerFoodSupply {
+        id: Uuid::new_v4().to_string(),
+        dog_id,
+        description,
+        arrival_date,
+        arrival_grams,
+        created_at: Utc::now(),
+    };
+    
+    data.owner_food.push(supply.clone());
+    save_app_data(&data)?;
+    
+    Ok(supply)
+}
+
+#[tauri::command]
+pub fn get_owner_food(dog_id: Option<String>) -> Result<Vec<OwnerFoodStatus>, String> {
+    let data = load_app_data()?;
+    
+    let mut statuses: Vec<OwnerFoodStatus> = data.owner_food.iter()
+        .filter(|s| dog_id.as_ref().is_none_or(|d| s.dog_id == *d))
+        .map(|s| owner_food_status(&data, s))
+        .collect();
+    
+    statuses.sort_by(|a, b| b.supply.arrival_date.cmp(&a.supply.arrival_date));
+    Ok(statuses)
+}
+
+/// Record what a dog was fed. House food comes off the stock level. Returns
+/// any stock warnings so staff see them straight away.
+#[tauri::command]
+pub fn record_feeding(
+    date: String,
+    dog_id: String,
+    source: FoodSource,
+    food_id: String,
+    grams: f64,
+) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    if grams <= 0.0 {
+        return Err("Amount fed must be positive".to_string());
+    }
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    
+    let price_per_kg = match source {
+        FoodSource::House => {
+            let food = data.house_foods.iter_mut()
+                .find(|f| f.id == food_id)
+                .ok_or("Food not found")?;
+            food.stock_grams = (food.stock_grams - grams).max(0.0);
+            Some(food.price_per_kg)
+        }
+        FoodSource::Owner => {
+            if !data.owner_food.iter().any(|s| s.id == food_id && s.dog_id == dog_id) {
+                return Err("Food not found".to_string());
+            }
+            None
+        }
+    };
+    
+    data.feedings.push(FeedingEntry {
+        id: Uuid::new_v4().to_string(),
+        date,
+        dog_id,
+        source,
+        food_id,
+        grams,
+        price_per_kg,
+        recorded_by: current_staff_id(),
+        recorded_at: Utc::now(),
+    });
+    
+    let warnings = food_warnings(&data);
+    save_app_data(&data)?;
+    
+    Ok(warnings)
+}
+
+/// Remove a mistaken feeding entry, putting house food back into stock.
+#[tauri::command]
+pub fn delete_feeding(feeding_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let index = data.feedings.iter()
+        .position(|f| f.id == feeding_id)
+        .ok_or("Feeding not found")?;
+    let feeding = data.feedings.remove(index);
+    
+    if feeding.source == FoodSource::House {
+        if let Some(food) = data.house_foods.iter_mut().find(|f| f.id == feeding.food_id) {
+            food.stock_grams += feeding.grams;
+        }
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_feedings(date: String) -> Result<Vec<FeedingEntry>, String> {
+    let data = load_app_data()?;
+    
+    let mut feedings: Vec<FeedingEntry> = data.feedings.into_iter()
+        .filter(|f| f.date == date)
+        .collect();
+    
+    feedings.sort_by_key(|f| f.recorded_at);
+    Ok(feedings)
+}
+
+#[tauri::command]
+pub fn get_food_warnings() -> Result<Vec<String>, String> {
+    let data = load_app_data()?;
+    Ok(food_warnings(&data))
+}
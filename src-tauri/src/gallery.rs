@@ -0,0 +1,183 @@
+use printpdf::image_crate;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::instance::ensure_writable;
+use crate::permissions::{require_permission, Permission};
+use crate::{get_app_data_path, load_app_data, lock_app_data, save_app_data, AppData};
+
+const PAGE_SIZE: usize = 24;
+const THUMBNAIL_SIZE: u32 = 320;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum PhotoSource {
+    #[serde(rename = "profile")]
+    Profile,
+    #[serde(rename = "daily")]
+    Daily,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GalleryPhoto {
+    pub path: String,
+    pub thumbnail_path: Option<String>, // None when the image couldn't be read
+    pub date: Option<String>, // Day the photo was taken, for daily photos
+    pub source: PhotoSource,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GalleryPage {
+    pub photos: Vec<GalleryPhoto>,
+    pub page: usize,
+    pub page_size: usize,
+    pub total: usize,
+}
+
+fn photos_dir(dog_id: &str) -> Result<PathBuf, String> {
+    Ok(get_app_data_path()?.with_file_name("photos").join(dog_id))
+}
+
+/// Every photo of a dog that's still on disk: the profile photo first, then
+/// daily photos newest first.
+fn gallery_index(data: &AppData, dog_id: &str) -> Vec<(String, Option<String>, PhotoSource)> {
+    let mut index = Vec::new();
+    if let Some(path) = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .and_then(|d| d.photo_path.clone())
+        .filter(|p| !p.is_empty())
+    {
+        index.push((path, None, PhotoSource::Profile));
+    }
+    
+    let mut dates: Vec<&String> = data.daily_data.keys().collect();
+    dates.sort_by(|a, b| b.cmp(a));
+    for date in dates {
+        if let Some(record) = data.daily_data[date].records.get(dog_id) {
+            for path in record.photos.iter().rev() {
+                index.push((path.clone(), Some(date.clone()), PhotoSource::Daily));
+            }
+        }
+    }
+    
+    index.retain(|(path, _, _)| Path::new(path).is_file());
+    index
+}
+
+/// Thumbnail for a photo, made the first time it's asked for and again if the
+/// photo changes. Kept in the app's thumbnails directory under a hash of the
+/// photo's path.
+pub fn thumbnail(path: &str) -> Result<String, String> {
+    let dir = get_app_data_path()?.with_file_name("thumbnails");
+    let name = Sha256::digest(path.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    let target = dir.join(format!("{}.jpg", &name[..32]));
+    
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    let fresh = match (modified(&target), modified(Path::new(path))) {
+        (Some(thumb), Some(source)) => thumb >= source,
+        _ => false,
+    };
+    if !fresh {
+        let image = image_crate::open(path)
+            .map_err(|e| format!("Failed to read image {}: {}", path, e))?
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .to_rgb8();
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
+        image.save(&target).map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+    }
+    
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// One page of a dog's photos, with thumbnails for just that page.
+#[tauri::command]
+pub fn get_dog_gallery(dog_id: String, page: usize) -> Result<GalleryPage, String> {
+    let data = load_app_data()?;
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    
+    let index = gallery_index(&data, &dog_id);
+    let photos = index.iter()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|(path, date, source)| GalleryPhoto {
+            path: path.clone(),
+            thumbnail_path: thumbnail(path)
+                .map_err(|e| println!("No thumbnail for {}: {}", path, e))
+                .ok(),
+            date: date.clone(),
+            source: source.clone(),
+        })
+        .collect();
+    
+    Ok(GalleryPage {
+        photos,
+        page,
+        page_size: PAGE_SIZE,
+        total: index.len(),
+    })
+}
+
+/// Copy a photo taken on a day into the dog's photo directory and attach it to
+/// that day's record.
+#[tauri::command]
+pub fn add_daily_photo(date: String, dog_id: String, source_path: String) -> Result<GalleryPhoto, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    ensure_writable()?;
+    
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    let source = Path::new(&source_path);
+    let extension = source.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| ["png", "jpg", "jpeg"].contains(&e.as_str()))
+        .ok_or("Photo must be a PNG or JPEG image")?;
+    image_crate::open(source)
+        .map_err(|e| format!("Failed to read image {}: {}", source_path, e))?;
+    
+    let dir = photos_dir(&dog_id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create photos directory: {}", e))?;
+    let target = dir.join(format!("{}-{}.{}", date, Uuid::new_v4(), extension));
+    fs::copy(source, &target).map_err(|e| format!("Failed to copy photo: {}", e))?;
+    let path = target.to_string_lossy().to_string();
+    
+    data.daily_data.entry(date.clone()).or_default()
+        .records.entry(dog_id).or_default()
+        .photos.push(path.clone());
+    save_app_data(&data)?;
+    
+    Ok(GalleryPhoto {
+        thumbnail_path: thumbnail(&path).ok(),
+        path,
+        date: Some(date),
+        source: PhotoSource::Daily,
+    })
+}
+
+#[tauri::command]
+pub fn delete_daily_photo(date: String, dog_id: String, path: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    ensure_writable()?;
+    
+    let record = data.daily_data.get_mut(&date)
+        .and_then(|d| d.records.get_mut(&dog_id))
+        .ok_or("Daily record not found")?;
+    let before = record.photos.len();
+    record.photos.retain(|p| *p != path);
+    if record.photos.len() == before {
+        return Err("Photo not found".to_string());
+    }
+    
+    save_app_data(&data)?;
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
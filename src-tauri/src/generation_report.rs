@@ -0,0 +1,181 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::absences::is_absent;
+use crate::attendance_keys::entry_key;
+use crate::day_locks::is_locked;
+use crate::jobs::{self, JobContext, JobKind};
+use crate::quarantine::is_quarantined;
+use crate::{load_app_data, should_generate_attendance, AppData, EntrySource, RecurringSchedule, ServiceType};
+
+const MAX_REPORT_DAYS: i64 = 366;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DaySourceCounts {
+    pub date: String,
+    pub auto_generated: usize,
+    pub manual: usize,
+    pub imported: usize,
+    pub waitlist: usize,
+    pub modified_after_generation: usize, // Generated by a schedule, then edited by staff (counted under manual)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SilentSchedule {
+    pub schedule_id: String,
+    pub dog_id: String,
+    pub dog_name: String,
+    pub service_type: ServiceType,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GenerationReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: Vec<DaySourceCounts>,
+    pub silent_schedules: Vec<SilentSchedule>, // Schedules with no booking anywhere in the period
+}
+
+/// Why a schedule has no bookings in the period, or None if it has some.
+fn silence_reason(data: &AppData, schedule: &RecurringSchedule, start: NaiveDate, end: NaiveDate) -> Option<String> {
+    let (first, last) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    let produced = data.daily_data.iter()
+        .filter(|(date, _)| **date >= first && **date <= last)
+        .any(|(_, day)| day.attendance.entries.values().any(|e| {
+            e.generated_from.as_deref() == Some(schedule.id.as_str())
+                || (e.dog_id == schedule.dog_id && e.service_type == schedule.service_type && e.attending)
+        }));
+    if produced {
+        return None;
+    }
+    
+    if !schedule.active {
+        return Some("Schedule is paused".to_string());
+    }
+    let schedule_start = match NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => return Some(format!("Invalid start date '{}'", schedule.start_date)),
+    };
+    let schedule_end = match schedule.end_date.as_deref().filter(|d| !d.is_empty()) {
+        Some(end_date) => match NaiveDate::parse_from_str(end_date, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => return Some(format!("Invalid end date '{}'", end_date)),
+        },
+        None => None,
+    };
+    if schedule_start > end {
+        return Some(format!("Schedule starts on {}", schedule.start_date));
+    }
+    if schedule_end.is_some_and(|e| e < start) {
+        return Some(format!("Schedule ended on {}", schedule.end_date.clone().unwrap_or_default()));
+    }
+    
+    let from = start.max(schedule_start);
+    let to = schedule_end.map_or(end, |e| e.min(end));
+    let due: Vec<String> = from.iter_days()
+        .take_while(|d| *d <= to)
+        .filter(|d| should_generate_attendance(*d, schedule_start, &schedule.pattern))
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect();
+    if due.is_empty() {
+        return Some("Pattern falls on none of the days in the period".to_string());
+    }
+    
+    let dog = data.dogs.iter().find(|d| d.id == schedule.dog_id);
+    let absent = due.iter().filter(|d| is_absent(&data.absences, &schedule.dog_id, d)).count();
+    let quarantined = due.iter().filter(|d| dog.is_some_and(|dog| is_quarantined(dog, d))).count();
+    let locked = due.iter().filter(|d| is_locked(data, d)).count();
+    let cancelled = due.iter()
+        .filter(|d| data.daily_data.get(*d)
+            .and_then(|day| day.attendance.entries.get(&entry_key(&schedule.dog_id, &schedule.service_type)))
+            .is_some_and(|e| !e.attending))
+        .count();
+    
+    let mut causes = Vec::new();
+    for (count, cause) in [(absent, "owner absence"), (quarantined, "quarantine"), (locked, "locked day"), (cancelled, "cancelled by hand")] {
+        if count > 0 {
+            causes.push(format!("{} {}", count, cause));
+        }
+    }
+    Some(if causes.is_empty() {
+        format!("Due on {} days but never generated; run generation for the period", due.len())
+    } else {
+        format!("Due on {} days, skipped for: {}", due.len(), causes.join(", "))
+    })
+}
+
+/// Where each day's attendance came from, and which schedules booked nothing,
+/// to track down dogs that keep missing from the roster.
+#[tauri::command]
+pub fn get_generation_report(start: String, end: String) -> Result<GenerationReport, String> {
+    generation_report(start, end, &jobs::untracked(JobKind::Report))
+}
+
+/// Build the generation report in the background. Returns the job id; the
+/// report is the job's result.
+#[tauri::command]
+pub fn start_generation_report(app: tauri::AppHandle, start: String, end: String) -> Result<String, String> {
+    jobs::start_job(app, JobKind::Report, move |job| generation_report(start, end, job))
+}
+
+fn generation_report(start: String, end: String, job: &JobContext) -> Result<GenerationReport, String> {
+    let data = load_app_data()?;
+    
+    let start_date = NaiveDate::parse_from_str(&start, "%Y-%m-%d")
+        .map_err(|_| "Invalid start date format".to_string())?;
+    let end_date = NaiveDate::parse_from_str(&end, "%Y-%m-%d")
+        .map_err(|_| "Invalid end date format".to_string())?;
+    if end_date < start_date {
+        return Err("End date is before start date".to_string());
+    }
+    if (end_date - start_date).num_days() >= MAX_REPORT_DAYS {
+        return Err(format!("Reports cover at most {} days", MAX_REPORT_DAYS));
+    }
+    
+    let total_days = (end_date - start_date).num_days() as usize + 1;
+    let mut days = Vec::new();
+    for date in start_date.iter_days().take_while(|d| *d <= end_date) {
+        job.progress(days.len(), total_days)?;
+        let date = date.format("%Y-%m-%d").to_string();
+        let mut counts = DaySourceCounts { date: date.clone(), ..Default::default() };
+        if let Some(day_data) = data.daily_data.get(&date) {
+            for entry in day_data.attendance.entries.values() {
+                match entry.source {
+                    EntrySource::AutoSchedule => counts.auto_generated += 1,
+                    EntrySource::Manual => counts.manual += 1,
+                    EntrySource::Import => counts.imported += 1,
+                    EntrySource::WaitlistPromotion => counts.waitlist += 1,
+                }
+                if entry.source != EntrySource::AutoSchedule && entry.generated_from.is_some() {
+                    counts.modified_after_generation += 1;
+                }
+            }
+        }
+        days.push(counts);
+    }
+    
+    let mut silent_schedules: Vec<SilentSchedule> = data.recurring_schedules.iter()
+        .filter_map(|schedule| {
+            let reason = silence_reason(&data, schedule, start_date, end_date)?;
+            Some(SilentSchedule {
+                schedule_id: schedule.id.clone(),
+                dog_id: schedule.dog_id.clone(),
+                dog_name: data.dogs.iter()
+                    .find(|d| d.id == schedule.dog_id)
+                    .map(|d| d.name.clone())
+                    .unwrap_or_else(|| "Unknown dog".to_string()),
+                service_type: schedule.service_type.clone(),
+                reason,
+            })
+        })
+        .collect();
+    silent_schedules.sort_by(|a, b| a.dog_name.cmp(&b.dog_name));
+    
+    Ok(GenerationReport {
+        start_date: start,
+        end_date: end,
+        days,
+        silent_schedules,
+    })
+}
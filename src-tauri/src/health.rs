@@ -0,0 +1,363 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::audit::record_audit;
+use crate::crypto::is_encrypted_envelope;
+use crate::data_encryption;
+use crate::instance::ensure_writable;
+use crate::{get_app_data_path, migrate_app_data, owners, replace_app_data, AppData};
+
+/// Daily copies of data.json kept next to it, so a damaged file can be rolled
+/// back without a cloud backup.
+const SNAPSHOT_DIR: &str = "snapshots";
+const MAX_SNAPSHOTS: usize = 7;
+
+/// Room needed to save safely, on top of the data file's own size.
+const MIN_FREE_BYTES: usize = 5 * 1024 * 1024;
+
+/// A backup older than this is reported, since the sync has probably stopped.
+const STALE_BACKUP_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub enum HealthStatus {
+    #[serde(rename = "ok")]
+    Ok,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum RecoveryKind {
+    #[serde(rename = "latest_snapshot")]
+    LatestSnapshot,
+    #[serde(rename = "latest_cloud_backup")]
+    LatestCloudBackup,
+    #[serde(rename = "start_fresh")]
+    StartFresh,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecoveryOption {
+    pub kind: RecoveryKind,
+    pub description: String,
+    pub source_path: Option<String>,
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StartupHealth {
+    pub checked_at: DateTime<Utc>,
+    pub data_readable: bool,
+    pub checks: Vec<HealthCheck>,
+    pub recovery_options: Vec<RecoveryOption>, // Only offered when the data can't be read
+}
+
+static STARTUP_HEALTH: Mutex<Option<StartupHealth>> = Mutex::new(None);
+
+fn check(name: &str, status: HealthStatus, message: impl Into<String>) -> HealthCheck {
+    HealthCheck { name: name.to_string(), status, message: message.into() }
+}
+
+fn parse_data(content: &str) -> Result<AppData, String> {
+    let content = data_encryption::decrypt_data(content)?;
+    serde_json::from_str::<AppData>(&content).or_else(|_| migrate_app_data(&content))
+}
+
+fn modified_at(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+fn snapshot_dir(data_path: &Path) -> PathBuf {
+    data_path.with_file_name(SNAPSHOT_DIR)
+}
+
+/// Files in `dir` whose names match, newest first.
+fn files_newest_first(dir: &Path, matches: impl Fn(&str) -> bool) -> Vec<(PathBuf, SystemTime)> {
+    let mut files: Vec<(PathBuf, SystemTime)> = fs::read_dir(dir)
+        .map(|entries| entries.flatten()
+            .filter(|e| e.file_name().to_str().is_some_and(&matches))
+            .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+            .collect())
+        .unwrap_or_default();
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    files
+}
+
+fn snapshots(data_path: &Path) -> Vec<(PathBuf, SystemTime)> {
+    files_newest_first(&snapshot_dir(data_path), |name| name.starts_with("data-") && name.ends_with(".json"))
+}
+
+fn cloud_backups(directory: &str) -> Vec<(PathBuf, SystemTime)> {
+    files_newest_first(Path::new(directory), |name| name.starts_with("doggy-daycare-backup-") && name.ends_with(".json"))
+}
+
+/// Copy today's data to the snapshot folder if it isn't there yet, dropping the
+/// oldest beyond the limit.
+fn take_snapshot(data_path: &Path) -> Result<(), String> {
+    let dir = snapshot_dir(data_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+    let path = dir.join(format!("data-{}.json", Utc::now().format("%Y-%m-%d")));
+    if !path.exists() {
+        fs::copy(data_path, &path).map_err(|e| format!("Failed to take snapshot: {}", e))?;
+    }
+    for (old, _) in snapshots(data_path).into_iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = fs::remove_file(&old) {
+            println!("Failed to remove old snapshot {}: {}", old.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// Drop every snapshot, e.g. once they'd hold data that should now be encrypted.
+pub fn remove_snapshots() -> Result<(), String> {
+    for (path, _) in snapshots(&get_app_data_path()?) {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove snapshot {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// The backup folder from the settings, read from whatever can still be parsed:
+/// the damaged file if it's valid JSON, otherwise the newest snapshot.
+fn cloud_directory(data: Option<&AppData>, content: Option<&str>, data_path: &Path) -> Option<String> {
+    let from_json = |content: &str| {
+        let json: serde_json::Value = serde_json::from_str(content).ok()?;
+        json.pointer("/settings/cloud_backup/cloud_directory")?.as_str().map(str::to_string)
+    };
+    let directory = match data {
+        Some(data) => data.settings.cloud_backup.as_ref().map(|c| c.cloud_directory.clone()),
+        None => content.and_then(from_json).or_else(|| {
+            let (path, _) = snapshots(data_path).into_iter().next()?;
+            from_json(&fs::read_to_string(path).ok()?)
+        }),
+    };
+    directory.filter(|d| !d.trim().is_empty())
+}
+
+/// Newest file that still parses; damaged backups are skipped.
+fn latest_readable(files: Vec<(PathBuf, SystemTime)>) -> Option<(PathBuf, SystemTime)> {
+    files.into_iter().find(|(path, _)| fs::read_to_string(path).is_ok_and(|c| parse_data(&c).is_ok()))
+}
+
+fn recovery_options(data_path: &Path, content: Option<&str>) -> Vec<RecoveryOption> {
+    let mut options = Vec::new();
+    if let Some((path, modified)) = latest_readable(snapshots(data_path)) {
+        options.push(RecoveryOption {
+            kind: RecoveryKind::LatestSnapshot,
+            description: "Restore the most recent daily snapshot kept on this computer".to_string(),
+            source_path: Some(path.to_string_lossy().to_string()),
+            saved_at: Some(modified.into()),
+        });
+    }
+    if let Some(directory) = cloud_directory(None, content, data_path) {
+        if let Some((path, modified)) = latest_readable(cloud_backups(&directory)) {
+            options.push(RecoveryOption {
+                kind: RecoveryKind::LatestCloudBackup,
+                description: format!("Restore the most recent backup from {}", directory),
+                source_path: Some(path.to_string_lossy().to_string()),
+                saved_at: Some(modified.into()),
+            });
+        }
+    }
+    options.push(RecoveryOption {
+        kind: RecoveryKind::StartFresh,
+        description: "Start with empty data. The damaged file is kept aside, not deleted".to_string(),
+        source_path: None,
+        saved_at: None,
+    });
+    options
+}
+
+fn check_disk_space(data_path: &Path) -> HealthCheck {
+    // Writing a probe the size of a save plus headroom is the portable way to
+    // know the next save will fit
+    let needed = fs::metadata(data_path).map(|m| m.len() as usize).unwrap_or(0) + MIN_FREE_BYTES;
+    let probe = data_path.with_file_name("space-check.tmp");
+    let result = fs::write(&probe, vec![0u8; needed]);
+    let _ = fs::remove_file(&probe);
+    match result {
+        Ok(()) => check("Disk space", HealthStatus::Ok, "There is room to save changes"),
+        Err(e) => check("Disk space", HealthStatus::Failed, format!("Changes may not save: {}", e)),
+    }
+}
+
+fn check_backups(data: Option<&AppData>, content: Option<&str>, data_path: &Path) -> HealthCheck {
+    let enabled = data.is_none_or(|d| d.settings.cloud_backup.as_ref().is_some_and(|c| c.enabled));
+    let directory = match cloud_directory(data, content, data_path) {
+        Some(directory) if enabled => directory,
+        _ => return check("Backups", HealthStatus::Warning, "Cloud backup is not set up"),
+    };
+    if !Path::new(&directory).is_dir() {
+        return check("Backups", HealthStatus::Failed, format!("The backup folder {} can't be reached", directory));
+    }
+    match cloud_backups(&directory).first() {
+        Some((_, modified)) => {
+            let modified: DateTime<Utc> = (*modified).into();
+            if Utc::now() - modified > Duration::days(STALE_BACKUP_DAYS) {
+                check("Backups", HealthStatus::Warning, format!("The latest backup is from {}", modified.format("%Y-%m-%d")))
+            } else {
+                check("Backups", HealthStatus::Ok, format!("Latest backup {}", modified.format("%Y-%m-%d %H:%M UTC")))
+            }
+        }
+        None => check("Backups", HealthStatus::Warning, format!("No backups in {} yet", directory)),
+    }
+}
+
+/// A clock running behind makes new records look older than saved ones, which
+/// throws off reminders, billing runs and day locks.
+fn check_clock(data: Option<&AppData>, data_path: &Path) -> HealthCheck {
+    let now = Utc::now();
+    let latest_saved = data.and_then(|d| d.audit_log.iter().map(|e| e.timestamp).max())
+        .into_iter()
+        .chain(modified_at(data_path))
+        .max();
+    match latest_saved {
+        Some(latest) if latest - now > Duration::minutes(5) => check(
+            "Clock",
+            HealthStatus::Warning,
+            format!("The computer's clock is behind: data was saved at {}", latest.format("%Y-%m-%d %H:%M UTC")),
+        ),
+        _ => check("Clock", HealthStatus::Ok, format!("The clock reads {}", now.format("%Y-%m-%d %H:%M UTC"))),
+    }
+}
+
+/// Check the data file, backups, disk space and clock. A readable data file is
+/// snapshotted; an unreadable one comes with the ways to recover it.
+pub fn run_health_check(take_snapshots: bool) -> Result<StartupHealth, String> {
+    let data_path = get_app_data_path()?;
+    let mut checks = Vec::new();
+    
+    let content = if data_path.exists() {
+        match fs::read_to_string(&data_path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                checks.push(check("Data", HealthStatus::Failed, format!("The data file can't be read: {}", e)));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Encrypted data can only be looked into once the passphrase is entered
+    let locked = content.as_deref().is_some_and(is_encrypted_envelope) && !data_encryption::is_unlocked();
+    let data = match content {
+        Some(_) if locked => {
+            checks.push(check("Data", HealthStatus::Ok, "Encrypted; enter the passphrase to unlock it"));
+            None
+        }
+        Some(ref content) if content.trim().is_empty() => {
+            checks.push(check("Data", HealthStatus::Failed, "The data file is empty"));
+            None
+        }
+        Some(ref content) => match parse_data(content) {
+            Ok(data) => {
+                checks.push(check("Data", HealthStatus::Ok, format!("{} dogs loaded", data.dogs.len())));
+                Some(data)
+            }
+            Err(e) => {
+                checks.push(check("Data", HealthStatus::Failed, format!("The data file is damaged: {}", e)));
+                None
+            }
+        },
+        None if !data_path.exists() => {
+            checks.push(check("Data", HealthStatus::Ok, "No data yet; a new file will be created"));
+            None
+        }
+        None => None,
+    };
+    let data_readable = data.is_some() || locked || !data_path.exists();
+    
+    if data.is_some() && take_snapshots {
+        if let Err(e) = take_snapshot(&data_path) {
+            checks.push(check("Snapshot", HealthStatus::Warning, e));
+        }
+    }
+    if locked {
+        checks.push(check("Backups", HealthStatus::Warning, "Checked once the data is unlocked"));
+    } else {
+        checks.push(check_backups(data.as_ref(), content.as_deref(), &data_path));
+    }
+    checks.push(check_disk_space(&data_path));
+    checks.push(check_clock(data.as_ref(), &data_path));
+    
+    let recovery_options = if data_readable {
+        Vec::new()
+    } else {
+        recovery_options(&data_path, content.as_deref())
+    };
+    
+    Ok(StartupHealth { checked_at: Utc::now(), data_readable, checks, recovery_options })
+}
+
+/// Run at launch, before anything loads the data.
+pub fn run_startup_check(writable: bool) {
+    match run_health_check(writable) {
+        Ok(health) => {
+            for c in health.checks.iter().filter(|c| c.status != HealthStatus::Ok) {
+                println!("Startup check - {}: {}", c.name, c.message);
+            }
+            *STARTUP_HEALTH.lock().unwrap_or_else(|e| e.into_inner()) = Some(health);
+        }
+        Err(e) => println!("Startup health check failed: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn get_startup_health() -> Result<StartupHealth, String> {
+    if let Some(health) = STARTUP_HEALTH.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok(health);
+    }
+    run_health_check(false)
+}
+
+/// Replace a data file that can't be read using one of the recovery options.
+/// The damaged file is renamed rather than deleted, in case it can be repaired
+/// by hand. Refused while the data reads fine; restore a backup instead.
+#[tauri::command]
+pub fn recover_data(kind: RecoveryKind) -> Result<StartupHealth, String> {
+    ensure_writable()?;
+    let current = run_health_check(false)?;
+    if current.data_readable {
+        return Err("The data file reads fine; restore a backup from Settings instead".to_string());
+    }
+    let option = current.recovery_options.iter()
+        .find(|o| o.kind == kind)
+        .ok_or("That recovery option isn't available")?;
+    
+    let mut data = match option.source_path {
+        Some(ref source) => {
+            let content = fs::read_to_string(source).map_err(|e| format!("Failed to read {}: {}", source, e))?;
+            let mut data = parse_data(&content)?;
+            owners::link_owners(&mut data);
+            data
+        }
+        None => AppData::default(),
+    };
+    
+    let data_path = get_app_data_path()?;
+    let damaged = data_path.with_file_name(format!("data.damaged-{}.json", Utc::now().format("%Y%m%d-%H%M%S")));
+    fs::rename(&data_path, &damaged).map_err(|e| format!("Failed to move the damaged data file aside: {}", e))?;
+    
+    let details = match option.source_path {
+        Some(ref source) => format!("Damaged data file replaced from {}; kept as {}", source, damaged.display()),
+        None => format!("Damaged data file replaced with empty data; kept as {}", damaged.display()),
+    };
+    record_audit(&mut data, "data_recovered", &details);
+    replace_app_data(&data)?;
+    
+    let health = run_health_check(true)?;
+    *STARTUP_HEALTH.lock().unwrap_or_else(|e| e.into_inner()) = Some(health.clone());
+    Ok(health)
+}
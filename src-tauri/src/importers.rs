@@ -0,0 +1,665 @@
+use chrono::{Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::attendance_keys::entry_key;
+use crate::audit::record_audit;
+use crate::breeds::normalize_breed;
+use crate::jobs::{self, JobContext, JobKind};
+use crate::owners::link_owners;
+use crate::permissions::{require_permission, Permission};
+use crate::times::normalize_time;
+use crate::vaccinations::{Vaccination, ANNUAL_VACCINATION};
+use crate::{
+    generate_recurring_attendance_internal, generate_schedules_for_dog, load_app_data, lock_app_data, save_app_data,
+    schedule_generation_window, AppData, AttendanceEntry, Dog, DogSchedule, DogStatus, EntrySource, NeuterStatus,
+    ServiceType,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ImportSource {
+    #[serde(rename = "gingr")]
+    Gingr,
+    #[serde(rename = "pawpartner")]
+    PawPartner,
+    #[serde(rename = "doggiedashboard")]
+    DoggieDashboard,
+}
+
+impl ImportSource {
+    fn label(&self) -> &'static str {
+        match self {
+            ImportSource::Gingr => "Gingr",
+            ImportSource::PawPartner => "PawPartner",
+            ImportSource::DoggieDashboard => "DoggieDashboard",
+        }
+    }
+    
+    /// Date formats to try after ISO, in the order the vendor is known to use them.
+    fn date_formats(&self) -> &'static [&'static str] {
+        match self {
+            ImportSource::Gingr => &["%m/%d/%Y", "%m/%d/%y"],
+            ImportSource::PawPartner => &["%d/%m/%Y", "%d/%m/%y", "%d.%m.%Y"],
+            ImportSource::DoggieDashboard => &["%Y-%m-%dT%H:%M:%S%.fZ", "%d/%m/%Y"],
+        }
+    }
+}
+
+/// Column aliases for one of our fields. Vendor-specific names are tried first,
+/// then the generic names shared by most exports. Matching is case-insensitive
+/// and ignores spaces, dashes and underscores.
+struct FieldAliases {
+    vendor: &'static [&'static str],
+    generic: &'static [&'static str],
+}
+
+struct SourceMapping {
+    dog_name: FieldAliases,
+    owner_name: FieldAliases,
+    owner_first_name: FieldAliases,
+    owner_last_name: FieldAliases,
+    phone: FieldAliases,
+    email: FieldAliases,
+    breed: FieldAliases,
+    date_of_birth: FieldAliases,
+    vaccine_date: FieldAliases,
+    vaccine_expiry: &'static [(&'static str, &'static str)],
+    household: FieldAliases,
+    daycare_days: FieldAliases,
+    training_days: FieldAliases,
+    boarding_days: FieldAliases,
+    visit_date: FieldAliases,
+    service: FieldAliases,
+    drop_off: FieldAliases,
+    pick_up: FieldAliases,
+}
+
+/// Vendor-specific column names, taken from each system's standard customer/pet
+/// and reservation exports.
+struct VendorColumns {
+    dog_name: &'static [&'static str],
+    owner_name: &'static [&'static str],
+    owner_first_name: &'static [&'static str],
+    owner_last_name: &'static [&'static str],
+    phone: &'static [&'static str],
+    email: &'static [&'static str],
+    breed: &'static [&'static str],
+    date_of_birth: &'static [&'static str],
+    vaccine_expiry: &'static [(&'static str, &'static str)], // Expiry column and the vaccine it's for
+    household: &'static [&'static str],
+    daycare_days: &'static [&'static str],
+    visit_date: &'static [&'static str],
+    service: &'static [&'static str],
+}
+
+const GINGR_COLUMNS: VendorColumns = VendorColumns {
+    dog_name: &["animal name", "animal"],
+    owner_name: &["owner name", "owner"],
+    owner_first_name: &["owner first name"],
+    owner_last_name: &["owner last name"],
+    phone: &["cell phone", "home phone", "owner phone"],
+    email: &["owner email", "email address"],
+    breed: &["animal breed"],
+    date_of_birth: &["birthday", "animal birthday"],
+    vaccine_expiry: &[("rabies expiration", "rabies"), ("vaccination expiration", ANNUAL_VACCINATION)],
+    household: &["owner id", "account id"],
+    daycare_days: &["regular days"],
+    visit_date: &["reservation date", "check in date", "start date"],
+    service: &["reservation type"],
+};
+
+const PAWPARTNER_COLUMNS: VendorColumns = VendorColumns {
+    dog_name: &["pet name", "pet"],
+    owner_name: &["client name"],
+    owner_first_name: &["client first name"],
+    owner_last_name: &["client surname", "client last name"],
+    phone: &["client mobile", "telephone"],
+    email: &["client email"],
+    breed: &["pet breed"],
+    date_of_birth: &["pet dob"],
+    vaccine_expiry: &[("vaccinations due", ANNUAL_VACCINATION), ("booster due", "booster")],
+    household: &["client id", "account number"],
+    daycare_days: &["regular days", "usual days"],
+    visit_date: &["booking date"],
+    service: &["booking type"],
+};
+
+const DOGGIEDASHBOARD_COLUMNS: VendorColumns = VendorColumns {
+    dog_name: &["dog_name"],
+    owner_name: &["owner_name"],
+    owner_first_name: &["owner_first_name"],
+    owner_last_name: &["owner_last_name"],
+    phone: &["owner_phone"],
+    email: &["owner_email"],
+    breed: &["dog_breed"],
+    date_of_birth: &["dog_dob", "dog_birthday"],
+    vaccine_expiry: &[("vaccines_expire", ANNUAL_VACCINATION)],
+    household: &["family_id"],
+    daycare_days: &["daycare_days"],
+    visit_date: &["visit_date"],
+    service: &["visit_type"],
+};
+
+fn mapping_for(source: ImportSource) -> SourceMapping {
+    let vendor = match source {
+        ImportSource::Gingr => &GINGR_COLUMNS,
+        ImportSource::PawPartner => &PAWPARTNER_COLUMNS,
+        ImportSource::DoggieDashboard => &DOGGIEDASHBOARD_COLUMNS,
+    };
+    
+    SourceMapping {
+        dog_name: FieldAliases { vendor: vendor.dog_name, generic: &["dog name", "name", "dog"] },
+        owner_name: FieldAliases { vendor: vendor.owner_name, generic: &["owner", "owner name", "customer"] },
+        owner_first_name: FieldAliases { vendor: vendor.owner_first_name, generic: &["first name"] },
+        owner_last_name: FieldAliases { vendor: vendor.owner_last_name, generic: &["last name", "surname"] },
+        phone: FieldAliases { vendor: vendor.phone, generic: &["phone", "phone number", "mobile"] },
+        email: FieldAliases { vendor: vendor.email, generic: &["email", "e-mail"] },
+        breed: FieldAliases { vendor: vendor.breed, generic: &["breed"] },
+        date_of_birth: FieldAliases { vendor: vendor.date_of_birth, generic: &["date of birth", "dob", "birthday"] },
+        vaccine_date: FieldAliases { vendor: &[], generic: &["vaccine date", "vaccination date"] },
+        vaccine_expiry: vendor.vaccine_expiry,
+        household: FieldAliases { vendor: vendor.household, generic: &["household", "household id", "family"] },
+        daycare_days: FieldAliases { vendor: vendor.daycare_days, generic: &["daycare days"] },
+        training_days: FieldAliases { vendor: &[], generic: &["training days"] },
+        boarding_days: FieldAliases { vendor: &[], generic: &["boarding days"] },
+        visit_date: FieldAliases { vendor: vendor.visit_date, generic: &["date", "visit date"] },
+        service: FieldAliases { vendor: vendor.service, generic: &["service", "service type"] },
+        drop_off: FieldAliases { vendor: &[], generic: &["drop off", "drop off time", "check in time", "arrival"] },
+        pick_up: FieldAliases { vendor: &[], generic: &["pick up", "pick up time", "check out time", "departure"] },
+    }
+}
+
+fn normalize_header(header: &str) -> String {
+    header.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+type Row = HashMap<String, String>;
+
+fn field<'a>(row: &'a Row, aliases: &FieldAliases) -> Option<&'a str> {
+    aliases.vendor.iter()
+        .chain(aliases.generic.iter())
+        .filter_map(|alias| row.get(&normalize_header(alias)))
+        .map(|v| v.trim())
+        .find(|v| !v.is_empty())
+}
+
+fn parse_rows(content: &str) -> Result<Vec<Row>, String> {
+    let trimmed = content.trim_start();
+    
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(trimmed)
+            .map_err(|e| format!("Failed to parse JSON export: {}", e))?;
+        
+        // Accept either a bare array or an object wrapping the records
+        let records = match value {
+            serde_json::Value::Array(records) => records,
+            serde_json::Value::Object(mut obj) => ["dogs", "pets", "animals", "records", "data"].iter()
+                .find_map(|key| obj.remove(*key).and_then(|v| match v {
+                    serde_json::Value::Array(records) => Some(records),
+                    _ => None,
+                }))
+                .ok_or("JSON export does not contain a list of records")?,
+            _ => return Err("JSON export does not contain a list of records".to_string()),
+        };
+        
+        Ok(records.into_iter()
+            .filter_map(|record| record.as_object().cloned())
+            .map(|obj| obj.into_iter()
+                .map(|(key, value)| {
+                    let text = match value {
+                        serde_json::Value::String(s) => s,
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::Array(items) => items.iter()
+                            .map(|i| i.as_str().map(str::to_string).unwrap_or_else(|| i.to_string()))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                        other => other.to_string(),
+                    };
+                    (normalize_header(&key), text)
+                })
+                .collect())
+            .collect())
+    } else {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        
+        let headers: Vec<String> = reader.headers()
+            .map_err(|e| format!("Failed to read CSV header: {}", e))?
+            .iter()
+            .map(normalize_header)
+            .collect();
+        
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("Failed to read CSV row: {}", e))?;
+            rows.push(headers.iter().cloned().zip(record.iter().map(str::to_string)).collect());
+        }
+        Ok(rows)
+    }
+}
+
+fn parse_date(value: &str, source: ImportSource) -> Option<String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+    
+    // Timestamps like "2024-03-01 08:00" or "2024-03-01T08:00:00Z"
+    if let Some(date) = value.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+    
+    source.date_formats().iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(value, fmt).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// Parse a weekday list such as "Mon, Wed, Fri", "monday/tuesday" or "1,3,5"
+/// into our 0-6 (Sunday=0) indices.
+fn parse_weekdays(value: &str) -> Result<Vec<u32>, String> {
+    let mut days = Vec::new();
+    
+    for token in value.split([',', ';', '/', '|', ' ']).map(str::trim).filter(|t| !t.is_empty()) {
+        let lower = token.to_lowercase();
+        let day = match lower.get(..3).unwrap_or(&lower) {
+            "sun" => 0,
+            "mon" => 1,
+            "tue" => 2,
+            "wed" => 3,
+            "thu" => 4,
+            "fri" => 5,
+            "sat" => 6,
+            other => match other.parse::<u32>() {
+                Ok(n) if n <= 6 => n,
+                _ => return Err(format!("Unrecognized weekday '{}'", token)),
+            },
+        };
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+    
+    days.sort();
+    Ok(days)
+}
+
+fn parse_service(value: Option<&str>) -> ServiceType {
+    match value.map(|v| v.to_lowercase()) {
+        Some(v) if v.contains("board") || v.contains("overnight") || v.contains("kennel") => ServiceType::Boarding,
+        Some(v) if v.contains("train") || v.contains("class") => ServiceType::Training,
+        _ => ServiceType::Daycare,
+    }
+}
+
+/// Times as HH:MM where they can be read; anything else is kept as exported so
+/// it shows up in the time normalization report rather than being lost.
+fn import_time(value: &str) -> String {
+    normalize_time(value).unwrap_or_else(|| value.to_string())
+}
+
+/// Vendor exports give the date each vaccine runs out rather than the date it
+/// was given, so each becomes a vaccination of its type with that expiry. The
+/// date given isn't exported and is taken as a year before.
+fn imported_vaccinations(
+    row: &Row,
+    mapping: &SourceMapping,
+    source: ImportSource,
+    row_number: usize,
+    warnings: &mut Vec<ImportIssue>,
+) -> Vec<Vaccination> {
+    let mut vaccinations = Vec::new();
+    for (column, vaccine_type) in mapping.vaccine_expiry {
+        let value = match row.get(&normalize_header(column)).map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            Some(value) => value,
+            None => continue,
+        };
+        let expires = match parse_date(value, source).and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()) {
+            Some(expires) => expires,
+            None => {
+                warnings.push(ImportIssue { row: row_number, message: format!("Unrecognized {} date '{}'", column, value) });
+                continue;
+            }
+        };
+        let administered = expires.checked_sub_months(Months::new(12)).unwrap_or(expires);
+        vaccinations.push(Vaccination {
+            id: Uuid::new_v4().to_string(),
+            vaccine_type: vaccine_type.to_string(),
+            administered: administered.format("%Y-%m-%d").to_string(),
+            expires: expires.format("%Y-%m-%d").to_string(),
+            certificate_ref: None,
+        });
+    }
+    vaccinations
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportIssue {
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub dry_run: bool,
+    pub rows_read: usize,
+    pub dogs_created: Vec<String>,
+    pub dogs_skipped: Vec<ImportIssue>,
+    pub households_created: usize,
+    pub schedules_created: usize,
+    pub attendance_created: usize,
+    pub warnings: Vec<ImportIssue>,
+}
+
+fn dog_key(name: &str, owner: &str) -> String {
+    format!("{}|{}", name.trim().to_lowercase(), owner.trim().to_lowercase())
+}
+
+fn import_rows(
+    data: &mut AppData,
+    source: ImportSource,
+    rows: &[Row],
+    report: &mut ImportReport,
+    job: &JobContext,
+) -> Result<(), String> {
+    let mapping = mapping_for(source);
+    
+    // Existing dogs by name+owner so re-running an import doesn't duplicate them
+    let mut dogs_by_key: HashMap<String, String> = data.dogs.iter()
+        .map(|d| (dog_key(&d.name, &d.owner), d.id.clone()))
+        .collect();
+    
+    // Household ids by vendor account id, email or phone, seeded from existing dogs
+    let mut households: HashMap<String, String> = HashMap::new();
+    for dog in &data.dogs {
+        if let Some(ref household_id) = dog.household_id {
+            if !dog.email.is_empty() {
+                households.insert(format!("email:{}", dog.email.to_lowercase()), household_id.clone());
+            }
+            if !dog.phone.is_empty() {
+                households.insert(format!("phone:{}", dog.phone), household_id.clone());
+            }
+        }
+    }
+    
+    for (index, row) in rows.iter().enumerate() {
+        job.progress(index, rows.len())?;
+        let row_number = index + 2; // account for the header row, 1-based
+        
+        let dog_name = match field(row, &mapping.dog_name) {
+            Some(name) => name.to_string(),
+            None => {
+                report.dogs_skipped.push(ImportIssue { row: row_number, message: "Missing dog name".to_string() });
+                continue;
+            }
+        };
+        
+        let owner = field(row, &mapping.owner_name)
+            .map(str::to_string)
+            .or_else(|| {
+                let first = field(row, &mapping.owner_first_name).unwrap_or("");
+                let last = field(row, &mapping.owner_last_name).unwrap_or("");
+                let full = format!("{} {}", first, last).trim().to_string();
+                if full.is_empty() { None } else { Some(full) }
+            })
+            .unwrap_or_default();
+        
+        let key = dog_key(&dog_name, &owner);
+        
+        // Rows with a visit date are attendance history; everything else is a customer/pet record
+        if let Some(visit) = field(row, &mapping.visit_date) {
+            let date = match parse_date(visit, source) {
+                Some(date) => date,
+                None => {
+                    report.warnings.push(ImportIssue { row: row_number, message: format!("Unrecognized visit date '{}'", visit) });
+                    continue;
+                }
+            };
+            
+            let dog_id = match dogs_by_key.get(&key) {
+                Some(id) => id.clone(),
+                None => {
+                    report.warnings.push(ImportIssue {
+                        row: row_number,
+                        message: format!("Visit for unknown dog '{}' ({}) - import the customer list first", dog_name, owner),
+                    });
+                    continue;
+                }
+            };
+            
+            let service_type = parse_service(field(row, &mapping.service));
+            let entry_key = entry_key(&dog_id, &service_type);
+            let day_data = data.daily_data.entry(date).or_default();
+            
+            if day_data.attendance.entries.contains_key(&entry_key) {
+                continue;
+            }
+            
+            if service_type == ServiceType::Daycare {
+                day_data.attendance.dogs.insert(dog_id.clone(), true);
+            }
+            day_data.attendance.entries.insert(entry_key, AttendanceEntry {
+                dog_id,
+                service_type,
+                attending: true,
+                drop_off_time: field(row, &mapping.drop_off).map(import_time),
+                pick_up_time: field(row, &mapping.pick_up).map(import_time),
+                notes: Some(format!("Imported from {}", source.label())),
+                handler_id: None,
+                updated_by: None,
+                trial: false,
+                awaiting_confirmation: false,
+                excused: false,
+                generated_from: None,
+                source: EntrySource::Import,
+                allergies: Vec::new(),
+            });
+            report.attendance_created += 1;
+            continue;
+        }
+        
+        if dogs_by_key.contains_key(&key) {
+            report.dogs_skipped.push(ImportIssue {
+                row: row_number,
+                message: format!("{} ({}) already exists", dog_name, owner),
+            });
+            continue;
+        }
+        
+        let phone = field(row, &mapping.phone).unwrap_or("").to_string();
+        let email = field(row, &mapping.email).unwrap_or("").to_string();
+        
+        let mut parse_optional_date = |aliases: &FieldAliases, label: &str| {
+            field(row, aliases).and_then(|value| {
+                let parsed = parse_date(value, source);
+                if parsed.is_none() {
+                    report.warnings.push(ImportIssue { row: row_number, message: format!("Unrecognized {} '{}'", label, value) });
+                }
+                parsed
+            })
+        };
+        let date_of_birth = parse_optional_date(&mapping.date_of_birth, "date of birth");
+        let vaccine_date = parse_optional_date(&mapping.vaccine_date, "vaccine date");
+        let vaccinations = imported_vaccinations(row, &mapping, source, row_number, &mut report.warnings);
+        
+        let mut schedule = DogSchedule::default();
+        for (aliases, days) in [
+            (&mapping.daycare_days, &mut schedule.daycare_days),
+            (&mapping.training_days, &mut schedule.training_days),
+            (&mapping.boarding_days, &mut schedule.boarding_days),
+        ] {
+            if let Some(value) = field(row, aliases) {
+                match parse_weekdays(value) {
+                    Ok(parsed) => *days = parsed,
+                    Err(e) => report.warnings.push(ImportIssue { row: row_number, message: e }),
+                }
+            }
+        }
+        
+        // Group dogs into households by vendor account id, then email, then phone
+        let household_keys: Vec<String> = [
+            field(row, &mapping.household).map(|v| format!("vendor:{}", v)),
+            Some(email.to_lowercase()).filter(|e| !e.is_empty()).map(|e| format!("email:{}", e)),
+            Some(phone.clone()).filter(|p| !p.is_empty()).map(|p| format!("phone:{}", p)),
+        ].into_iter().flatten().collect();
+        
+        let household_id = household_keys.iter()
+            .find_map(|k| households.get(k).cloned())
+            .or_else(|| {
+                if household_keys.is_empty() {
+                    None
+                } else {
+                    report.households_created += 1;
+                    Some(Uuid::new_v4().to_string())
+                }
+            });
+        if let Some(ref household_id) = household_id {
+            for k in household_keys {
+                households.entry(k).or_insert_with(|| household_id.clone());
+            }
+        }
+        
+        let dog = Dog {
+            id: Uuid::new_v4().to_string(),
+            name: dog_name.clone(),
+            owner,
+            phone,
+            email,
+            breed: normalize_breed(field(row, &mapping.breed).unwrap_or("")),
+            date_of_birth,
+            vaccine_date,
+            vaccinations,
+            consent_last_signed: None,
+            created_at: Utc::now(),
+            schedule,
+            household_id,
+            owner_id: None,
+            status: DogStatus::Active,
+            incompatible_dog_ids: Vec::new(),
+            solo_care: false,
+            size: None,
+            neuter_status: NeuterStatus::Unknown,
+            photo_path: None,
+            vet: None,
+            microchip_number: None,
+            allergies: Vec::new(),
+            insurance: None,
+            van_pickup: None,
+            quarantine: None,
+            intake: None,
+            age_class: None,
+            breed_group: None,
+        };
+        
+        let has_schedule = !dog.schedule.daycare_days.is_empty()
+            || !dog.schedule.training_days.is_empty()
+            || !dog.schedule.boarding_days.is_empty();
+        
+        dogs_by_key.insert(key, dog.id.clone());
+        data.dogs.push(dog.clone());
+        report.dogs_created.push(dog_name);
+        
+        if has_schedule {
+            let schedules_before = data.recurring_schedules.len();
+            generate_schedules_for_dog(data, &dog)?;
+            report.schedules_created += data.recurring_schedules.len() - schedules_before;
+            
+            let (start_str, end_str) = schedule_generation_window(&dog);
+            generate_recurring_attendance_internal(data, &start_str, &end_str)?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Import a customer/pet or visit-history export from another daycare system.
+/// With `dry_run` set, the full mapping runs against a copy of the data and the
+/// report is returned without saving anything.
+#[tauri::command]
+pub fn import_from_competitor(source: ImportSource, content: String, dry_run: bool) -> Result<ImportReport, String> {
+    jobs::run_exclusive(JobKind::Import, |job| run_import(source, &content, dry_run, job))
+}
+
+/// Run the import in the background for large exports. Returns the job id; the
+/// report is the job's result.
+#[tauri::command]
+pub fn start_competitor_import(
+    app: tauri::AppHandle,
+    source: ImportSource,
+    content: String,
+    dry_run: bool,
+) -> Result<String, String> {
+    require_permission(&load_app_data()?, Permission::ImportData)?;
+    jobs::start_job(app, JobKind::Import, move |job| run_import(source, &content, dry_run, job))
+}
+
+fn run_import(source: ImportSource, content: &str, dry_run: bool, job: &JobContext) -> Result<ImportReport, String> {
+    let rows = parse_rows(content)?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ImportData)?;
+    
+    let mut report = ImportReport {
+        source,
+        dry_run,
+        rows_read: rows.len(),
+        dogs_created: Vec::new(),
+        dogs_skipped: Vec::new(),
+        households_created: 0,
+        schedules_created: 0,
+        attendance_created: 0,
+        warnings: Vec::new(),
+    };
+    
+    import_rows(&mut data, source, &rows, &mut report, job)?;
+    
+    if !dry_run {
+        link_owners(&mut data);
+        let details = format!("Imported {} dogs from {}", report.dogs_created.len(), source.label());
+        record_audit(&mut data, "competitor_import", &details);
+        save_app_data(&data)?;
+        println!("Imported {} dogs and {} attendance entries from {}",
+                 report.dogs_created.len(), report.attendance_created, source.label());
+    }
+    
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn vendor_vaccine_columns_are_expiry_dates() {
+        let csv = "Animal Name,Owner Name,Animal Breed,Rabies Expiration,Vaccination Expiration\n\
+                   Biscuit,Jane Doe,Labrador Retriever,03/15/2026,11/02/2025\n";
+        let rows = parse_rows(csv).unwrap();
+        let mut warnings = Vec::new();
+        let vaccinations = imported_vaccinations(&rows[0], &mapping_for(ImportSource::Gingr), ImportSource::Gingr, 2, &mut warnings);
+        
+        assert!(warnings.is_empty());
+        assert_eq!(vaccinations.len(), 2);
+        assert_eq!(vaccinations[0].vaccine_type, "rabies");
+        assert_eq!(vaccinations[0].expires, "2026-03-15");
+        assert_eq!(vaccinations[0].administered, "2025-03-15");
+        assert_eq!(vaccinations[1].vaccine_type, ANNUAL_VACCINATION);
+        assert_eq!(vaccinations[1].expires, "2025-11-02");
+    }
+    
+    #[test]
+    fn pawpartner_due_dates_are_day_first() {
+        let csv = "Pet Name,Client Name,Vaccinations Due,Booster Due\nMolly,Sam Smith,01/04/2026,not known\n";
+        let rows = parse_rows(csv).unwrap();
+        let mut warnings = Vec::new();
+        let vaccinations = imported_vaccinations(&rows[0], &mapping_for(ImportSource::PawPartner), ImportSource::PawPartner, 2, &mut warnings);
+        
+        assert_eq!(vaccinations.len(), 1);
+        assert_eq!(vaccinations[0].expires, "2026-04-01");
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use mailparse::{addrparse_header, dateparse, parse_mail, MailHeaderMap, ParsedMail};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::messages::{Message, MessageChannel, MessageDirection, MessageStatus};
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, with_app_data, with_app_data_mut, AppData};
+
+/// Mailbox that owners' replies to reminder emails arrive in. Polling is opt-in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImapSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub mailbox: String,
+    pub poll_interval_minutes: u32,
+}
+
+impl Default for ImapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "".to_string(),
+            port: 993,
+            username: "".to_string(),
+            password: "".to_string(),
+            mailbox: "INBOX".to_string(),
+            poll_interval_minutes: 15,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InboxPollResult {
+    pub messages_checked: u32,
+    pub replies_imported: u32,
+}
+
+/// Plain-text body without the quoted original, so the history shows what the
+/// owner actually wrote.
+fn reply_text(mail: &ParsedMail) -> String {
+    let body = if mail.subparts.is_empty() {
+        mail.get_body().unwrap_or_default()
+    } else {
+        mail.subparts.iter()
+            .find(|p| p.ctype.mimetype == "text/plain")
+            .or_else(|| mail.subparts.iter().flat_map(|p| p.subparts.iter()).find(|p| p.ctype.mimetype == "text/plain"))
+            .and_then(|p| p.get_body().ok())
+            .unwrap_or_default()
+    };
+    
+    let mut lines = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        // Most clients introduce the quote with "On <date>, <name> wrote:"
+        if trimmed.starts_with("On ") && trimmed.ends_with("wrote:") {
+            break;
+        }
+        if trimmed == "-----Original Message-----" {
+            break;
+        }
+        lines.push(line);
+    }
+    
+    lines.join("\n").trim().to_string()
+}
+
+/// Turn a raw email into a message against the sender's dogs. Mail from
+/// addresses that don't belong to any owner is ignored.
+fn import_reply(data: &mut AppData, raw: &[u8]) -> bool {
+    let mail = match parse_mail(raw) {
+        Ok(mail) => mail,
+        Err(e) => {
+            println!("Skipping unreadable email: {}", e);
+            return false;
+        }
+    };
+    
+    let sender = mail.headers.get_first_header("From")
+        .and_then(|h| addrparse_header(h).ok())
+        .and_then(|list| list.extract_single_info())
+        .map(|info| info.addr.to_lowercase());
+    let sender = match sender {
+        Some(sender) => sender,
+        None => return false,
+    };
+    
+    let dog_ids: Vec<String> = data.dogs.iter()
+        .filter(|d| d.email.trim().to_lowercase() == sender)
+        .map(|d| d.id.clone())
+        .collect();
+    if dog_ids.is_empty() {
+        return false;
+    }
+    
+    let external_id = mail.headers.get_first_value("Message-ID");
+    if external_id.is_some() && data.messages.iter().any(|m| m.external_id == external_id) {
+        return false;
+    }
+    
+    let timestamp = mail.headers.get_first_value("Date")
+        .and_then(|d| dateparse(&d).ok())
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .unwrap_or_else(Utc::now);
+    
+    data.messages.push(Message {
+        id: Uuid::new_v4().to_string(),
+        direction: MessageDirection::Inbound,
+        channel: MessageChannel::Email,
+        dog_ids,
+        counterpart: sender,
+        subject: mail.headers.get_first_value("Subject"),
+        content: reply_text(&mail),
+        template: None,
+        status: MessageStatus::Received,
+        timestamp,
+        external_id,
+    });
+    true
+}
+
+/// Fetch mail with a UID above `last_uid`, oldest first. Messages are read
+/// with BODY.PEEK so they stay unread in the mailbox.
+fn fetch_new_mail(settings: &ImapSettings, last_uid: u32) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    if settings.host.is_empty() || settings.username.is_empty() {
+        return Err("Set up the reply mailbox in settings first".to_string());
+    }
+    
+    let tls = native_tls::TlsConnector::builder().build()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    let client = imap::connect((settings.host.as_str(), settings.port), &settings.host, &tls)
+        .map_err(|e| format!("Failed to connect to {}: {}", settings.host, e))?;
+    let mut session = client.login(&settings.username, &settings.password)
+        .map_err(|(e, _)| format!("Mailbox login failed: {}", e))?;
+    
+    session.select(&settings.mailbox)
+        .map_err(|e| format!("Failed to open mailbox {}: {}", settings.mailbox, e))?;
+    
+    // "n:*" always matches the newest message, even when it's older than n
+    let uids: Vec<u32> = session.uid_search(format!("UID {}:*", last_uid + 1))
+        .map_err(|e| format!("Failed to search mailbox: {}", e))?
+        .into_iter()
+        .filter(|uid| *uid > last_uid)
+        .collect();
+    
+    let mut mail = Vec::new();
+    if !uids.is_empty() {
+        let uid_set = uids.iter().map(|u| u.to_string()).collect::<Vec<_>>().join(",");
+        let fetched = session.uid_fetch(&uid_set, "BODY.PEEK[]")
+            .map_err(|e| format!("Failed to fetch messages: {}", e))?;
+        for message in fetched.iter() {
+            if let Some(uid) = message.uid {
+                mail.push((uid, message.body().unwrap_or_default().to_vec()));
+            }
+        }
+        // Messages without a body still count as seen
+        for uid in uids {
+            if !mail.iter().any(|(fetched, _)| *fetched == uid) {
+                mail.push((uid, Vec::new()));
+            }
+        }
+    }
+    
+    let _ = session.logout();
+    mail.sort_by_key(|(uid, _)| *uid);
+    Ok(mail)
+}
+
+/// Fetch mail that arrived since the last poll and file owners' replies. The
+/// mailbox is read before taking the data lock, so a slow server doesn't hold
+/// up everything else; mail another poll filed in the meantime is skipped.
+fn poll_inbox() -> Result<InboxPollResult, String> {
+    let (settings, last_uid) = with_app_data(|data| (data.settings.imap.clone(), data.inbox_last_uid))?;
+    let mail = fetch_new_mail(&settings, last_uid)?;
+    if mail.is_empty() {
+        return Ok(InboxPollResult { messages_checked: 0, replies_imported: 0 });
+    }
+    
+    with_app_data_mut(|data| {
+        let mut result = InboxPollResult { messages_checked: 0, replies_imported: 0 };
+        for (uid, body) in &mail {
+            if *uid <= data.inbox_last_uid {
+                continue;
+            }
+            result.messages_checked += 1;
+            if !body.is_empty() && import_reply(data, body) {
+                result.replies_imported += 1;
+            }
+            data.inbox_last_uid = *uid;
+        }
+        Ok(result)
+    })
+}
+
+#[tauri::command]
+pub fn poll_owner_replies() -> Result<InboxPollResult, String> {
+    require_permission(&load_app_data()?, Permission::ManageDogs)?;
+    let result = poll_inbox()?;
+    
+    println!("Checked {} new emails, imported {} owner replies", result.messages_checked, result.replies_imported);
+    Ok(result)
+}
+
+/// Poll the reply mailbox in the background at the configured interval.
+pub fn spawn_inbox_poller() {
+    std::thread::spawn(|| loop {
+        let interval = match with_app_data(|data| data.settings.imap.clone()) {
+            Ok(settings) if settings.enabled => {
+                if let Err(e) = poll_inbox() {
+                    println!("Reply mailbox poll failed: {}", e);
+                }
+                settings.poll_interval_minutes.max(1)
+            }
+            _ => 15,
+        };
+        std::thread::sleep(std::time::Duration::from_secs(interval as u64 * 60));
+    });
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::sync::Mutex;
+
+use crate::get_app_data_path;
+
+/// Flag a window can be launched with to open read-only on purpose, e.g. the
+/// office machine that only looks at reports.
+const READ_ONLY_FLAG: &str = "--read-only";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InstanceMode {
+    pub read_only: bool,
+    pub reason: Option<String>,
+}
+
+struct Instance {
+    mode: InstanceMode,
+    _lock: Option<File>, // Held for the life of the process; the OS releases it on exit
+}
+
+static INSTANCE: Mutex<Option<Instance>> = Mutex::new(None);
+
+fn read_only(reason: &str) -> Instance {
+    Instance {
+        mode: InstanceMode { read_only: true, reason: Some(reason.to_string()) },
+        _lock: None,
+    }
+}
+
+/// Take the data directory's lock at startup. Only one instance can hold it;
+/// any other instance runs read-only so two writers never clobber data.json.
+pub fn acquire_instance_lock() -> InstanceMode {
+    let instance = if std::env::args().any(|a| a == READ_ONLY_FLAG) {
+        read_only("Opened in read-only mode")
+    } else {
+        match lock_data_dir() {
+            Ok(lock) => Instance {
+                mode: InstanceMode { read_only: false, reason: None },
+                _lock: Some(lock),
+            },
+            Err(reason) => read_only(&reason),
+        }
+    };
+    
+    let mode = instance.mode.clone();
+    if let Some(ref reason) = mode.reason {
+        println!("Read-only mode: {}", reason);
+    }
+    *INSTANCE.lock().unwrap_or_else(|e| e.into_inner()) = Some(instance);
+    mode
+}
+
+fn lock_data_dir() -> Result<File, String> {
+    let path = get_app_data_path()?.with_file_name("data.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open lock file: {}", e))?;
+    
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => {
+            Err("The app is already open in another window; changes can only be made there".to_string())
+        }
+        Err(TryLockError::Error(e)) => Err(format!("Failed to lock data directory: {}", e)),
+    }
+}
+
+pub fn instance_mode() -> InstanceMode {
+    INSTANCE.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|i| i.mode.clone())
+        .unwrap_or(InstanceMode { read_only: false, reason: None })
+}
+
+/// Refuse to change anything from a read-only instance.
+pub fn ensure_writable() -> Result<(), String> {
+    match instance_mode() {
+        InstanceMode { read_only: true, reason } => Err(format!(
+            "This window is read-only and can't save changes. {}",
+            reason.unwrap_or_default()
+        ).trim().to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn get_instance_mode() -> InstanceMode {
+    instance_mode()
+}
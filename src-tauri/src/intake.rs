@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, Dog, DogStatus};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum IntakeCategory {
+    #[serde(rename = "behavior")]
+    Behavior,
+    #[serde(rename = "medical")]
+    Medical,
+    #[serde(rename = "general")]
+    General,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntakeQuestion {
+    pub id: String, // Stable key for answers; keep it when rewording the question
+    pub text: String,
+    pub category: IntakeCategory,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntakeSettings {
+    pub questions: Vec<IntakeQuestion>,
+}
+
+fn question(id: &str, text: &str, category: IntakeCategory) -> IntakeQuestion {
+    IntakeQuestion {
+        id: id.to_string(),
+        text: text.to_string(),
+        category,
+        required: true,
+    }
+}
+
+impl Default for IntakeSettings {
+    fn default() -> Self {
+        use IntakeCategory::*;
+        
+        Self {
+            questions: vec![
+                question("other_dogs", "How does your dog behave around other dogs?", Behavior),
+                question("people", "How does your dog react to strangers and children?", Behavior),
+                question("resource_guarding", "Does your dog guard food, toys or spaces?", Behavior),
+                question("bite_history", "Has your dog ever bitten or injured a person or dog?", Behavior),
+                question("separation", "How does your dog cope with being left or separated from you?", Behavior),
+                question("medical_conditions", "Any medical conditions, allergies or injuries?", Medical),
+                question("medication", "Any current medication and dosage?", Medical),
+                question("diet", "Feeding routine and dietary restrictions", Medical),
+                question("daycare_experience", "Has your dog attended daycare or boarding before?", General),
+            ],
+        }
+    }
+}
+
+impl IntakeSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = Vec::new();
+        for question in &self.questions {
+            if question.id.trim().is_empty() || question.text.trim().is_empty() {
+                return Err("Intake questions need an id and some text".to_string());
+            }
+            if ids.contains(&question.id.as_str()) {
+                return Err(format!("Intake question id '{}' is used more than once", question.id));
+            }
+            ids.push(question.id.as_str());
+        }
+        Ok(())
+    }
+}
+
+/// A dog's answers to the intake questionnaire, keyed by question id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntakeAnswers {
+    pub answers: HashMap<String, String>,
+    pub completed_date: Option<String>, // When every required question was first answered
+    pub updated_by: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn missing_questions<'a>(settings: &'a IntakeSettings, dog: &Dog) -> Vec<&'a IntakeQuestion> {
+    settings.questions.iter()
+        .filter(|q| q.required)
+        .filter(|q| {
+            dog.intake.as_ref()
+                .and_then(|i| i.answers.get(&q.id))
+                .is_none_or(|a| a.trim().is_empty())
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IncompleteIntake {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub created_at: DateTime<Utc>,
+    pub missing: Vec<IntakeQuestion>,
+}
+
+/// Record intake answers for a dog. Blank answers are dropped, as are answers to
+/// questions no longer in the questionnaire.
+#[tauri::command]
+pub fn save_intake_answers(dog_id: String, answers: HashMap<String, String>) -> Result<IntakeAnswers, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let answers: HashMap<String, String> = answers.into_iter()
+        .filter(|(id, _)| data.settings.intake.questions.iter().any(|q| q.id == *id))
+        .map(|(id, answer)| (id, answer.trim().to_string()))
+        .filter(|(_, answer)| !answer.is_empty())
+        .collect();
+    
+    let index = data.dogs.iter()
+        .position(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    let previous = data.dogs[index].intake.as_ref().and_then(|i| i.completed_date.clone());
+    data.dogs[index].intake = Some(IntakeAnswers {
+        answers,
+        completed_date: None,
+        updated_by: current_staff_id(),
+        updated_at: Utc::now(),
+    });
+    
+    let complete = missing_questions(&data.settings.intake, &data.dogs[index]).is_empty();
+    let intake = data.dogs[index].intake.as_mut().ok_or("Dog not found")?;
+    if complete {
+        intake.completed_date = previous.or_else(|| Some(Utc::now().format("%Y-%m-%d").to_string()));
+    }
+    let intake = intake.clone();
+    
+    save_app_data(&data)?;
+    Ok(intake)
+}
+
+/// Active dogs with required intake questions still unanswered, newest first.
+#[tauri::command]
+pub fn get_incomplete_intakes() -> Result<Vec<IncompleteIntake>, String> {
+    let data = load_app_data()?;
+    
+    let mut incomplete: Vec<IncompleteIntake> = data.dogs.iter()
+        .filter(|d| d.status == DogStatus::Active)
+        .filter_map(|dog| {
+            let missing = missing_questions(&data.settings.intake, dog);
+            if missing.is_empty() {
+                return None;
+            }
+            Some(IncompleteIntake {
+                dog_id: dog.id.clone(),
+                dog_name: dog.name.clone(),
+                owner: dog.owner.clone(),
+                created_at: dog.created_at,
+                missing: missing.into_iter().cloned().collect(),
+            })
+        })
+        .collect();
+    
+    incomplete.sort_by_key(|i| std::cmp::Reverse(i.created_at));
+    Ok(incomplete)
+}
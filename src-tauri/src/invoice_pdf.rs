@@ -0,0 +1,116 @@
+use chrono::{Duration, Utc};
+use std::fs;
+
+use crate::accounting_export::customer;
+use crate::invoices::{amount_due, period_bounds, Invoice, InvoiceStatus};
+use crate::pdf::{fit_text, PdfWriter};
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, AppData};
+
+const MARGIN: f32 = 20.0;
+const ROW_HEIGHT: f32 = 7.0;
+
+/// An invoice laid out on A4 under the business name and address, ready to
+/// email or print. Long invoices run on to further pages.
+fn invoice_pdf(data: &AppData, invoice: &Invoice) -> Result<Vec<u8>, String> {
+    let settings = &data.settings;
+    let currency = &settings.currency;
+    let title = format!("{} - invoice {}", settings.business_name, invoice.number);
+    let mut pdf = PdfWriter::a4(&title, false, &settings.branding)?;
+    
+    let mut y = 25.0;
+    pdf.heading(&settings.business_name, 16.0, MARGIN, y);
+    let contact = settings.business_address.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .chain(Some(settings.business_phone.trim()).filter(|p| !p.is_empty()));
+    for line in contact {
+        y += 5.0;
+        pdf.text(line, 9.0, MARGIN, y, false);
+    }
+    
+    y += 15.0;
+    let heading = match invoice.status {
+        InvoiceStatus::Draft => "Invoice (draft)",
+        InvoiceStatus::Void => "Invoice (void)",
+        _ => "Invoice",
+    };
+    pdf.heading(heading, 14.0, MARGIN, y);
+    y += 10.0;
+    
+    let (_, period_end) = period_bounds(&invoice.period)?;
+    let date = invoice.issued_at.map(|at| at.date_naive()).unwrap_or(period_end);
+    let due = date + Duration::days(settings.accounting.payment_terms_days as i64);
+    let (name, email) = customer(data, &invoice.household_id);
+    let details = [
+        ("Invoice number", invoice.number.clone()),
+        ("Date", date.format("%-d %B %Y").to_string()),
+        ("Due", due.format("%-d %B %Y").to_string()),
+        ("Period", period_end.format("%B %Y").to_string()),
+        ("Bill to", name),
+        ("Email", email),
+    ];
+    for (label, value) in details.iter().filter(|(_, v)| !v.is_empty()) {
+        pdf.text(label, 10.0, MARGIN, y, true);
+        pdf.text(value, 10.0, MARGIN + 35.0, y, false);
+        y += 6.0;
+    }
+    y += 8.0;
+    
+    let right = pdf.width - MARGIN;
+    let columns = [("Qty", right - 70.0), ("Unit price", right - 50.0), ("Amount", right - 22.0)];
+    let header = |pdf: &PdfWriter, y: f32| {
+        pdf.shade(MARGIN, y - 5.0, right - MARGIN, ROW_HEIGHT);
+        pdf.text("Description", 10.0, MARGIN + 2.0, y, true);
+        for (label, x) in columns {
+            pdf.text(label, 10.0, x, y, true);
+        }
+    };
+    header(&pdf, y);
+    y += ROW_HEIGHT + 1.0;
+    
+    for line in &invoice.lines {
+        if y > pdf.height - 40.0 {
+            pdf.new_page();
+            y = 25.0;
+            header(&pdf, y);
+            y += ROW_HEIGHT + 1.0;
+        }
+        let description = fit_text(&line.description, 10.0, columns[0].1 - MARGIN - 6.0);
+        pdf.text(&description, 10.0, MARGIN + 2.0, y, false);
+        pdf.text(&line.quantity.to_string(), 10.0, columns[0].1, y, false);
+        pdf.text(&currency.format(line.unit_price), 10.0, columns[1].1, y, false);
+        pdf.text(&currency.format(line.amount), 10.0, columns[2].1, y, false);
+        y += ROW_HEIGHT;
+    }
+    
+    pdf.line(MARGIN, y - 3.0, right, y - 3.0);
+    y += 3.0;
+    pdf.text("Total", 11.0, columns[1].1, y, true);
+    pdf.text(&currency.format(invoice.total), 11.0, columns[2].1, y, true);
+    let due_now = amount_due(data, invoice);
+    if due_now != invoice.total && invoice.status != InvoiceStatus::Draft {
+        y += ROW_HEIGHT;
+        pdf.text("Amount due", 11.0, columns[1].1, y, true);
+        pdf.text(&currency.format(due_now), 11.0, columns[2].1, y, true);
+    }
+    pdf.text(&format!("Produced {}", Utc::now().format("%-d %B %Y")), 9.0, MARGIN, y + 12.0, false);
+    
+    pdf.finish()
+}
+
+/// Render an invoice to a PDF file at `output_path`, e.g. to attach to an
+/// email. Returns the path written.
+#[tauri::command]
+pub fn render_invoice_pdf(invoice_id: String, output_path: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let invoice = data.invoices.iter()
+        .find(|i| i.id == invoice_id)
+        .ok_or("Invoice not found")?;
+    let bytes = invoice_pdf(&data, invoice)?;
+    fs::write(&output_path, bytes)
+        .map_err(|e| format!("Failed to write invoice PDF: {}", e))?;
+    Ok(output_path)
+}
@@ -0,0 +1,686 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::Emitter;
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::boarding::{boarding_charges, peak_surcharge_label, stay_for_night, BoardingStay};
+use crate::direct_debit::Collection;
+use crate::durations::attended_minutes;
+use crate::food::FoodSource;
+use crate::packages::covered_by_package;
+use crate::permissions::{require_permission, Permission};
+use crate::age::age_class_on;
+use crate::pricing::{rate_label, service_label};
+use crate::staff::parse_period;
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, AttendanceType, Dog, ServiceType};
+
+/// Event emitted when a billing run finishes, with a `BillingRunSummary` payload.
+pub const BILLING_RUN_EVENT: &str = "billing:run_completed";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum InvoiceStatus {
+    #[serde(rename = "draft")]
+    Draft, // Generated by a billing run, waiting for review
+    #[serde(rename = "issued")]
+    Issued,
+    #[serde(rename = "submitted_for_collection")]
+    SubmittedForCollection, // Included in a direct debit file sent to the bank
+    #[serde(rename = "void")]
+    Void,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub dog_id: Option<String>,
+    pub quantity: u32,
+    pub unit_price: f64,
+    pub amount: f64,
+    #[serde(default)]
+    pub service_type: Option<ServiceType>, // None for extras like house food and discounts
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Invoice {
+    pub id: String,
+    pub number: String,
+    pub household_id: String,
+    pub period: String, // YYYY-MM
+    pub lines: Vec<InvoiceLine>,
+    pub total: f64,
+    pub status: InvoiceStatus,
+    pub created_at: DateTime<Utc>,
+    pub issued_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub collection: Option<Collection>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BillingSettings {
+    pub auto_run_enabled: bool,
+    pub run_day_of_month: u32, // 1-28; invoices for the previous month are drafted on this day
+    #[serde(default)]
+    pub hourly_services: Vec<ServiceType>, // Billed by time on site at the rate's hourly price
+    #[serde(default)]
+    pub check_in_alert_balance: f64, // Warn at check-in when the household owes more than this
+}
+
+impl Default for BillingSettings {
+    fn default() -> Self {
+        Self {
+            auto_run_enabled: false,
+            run_day_of_month: 1,
+            hourly_services: Vec::new(),
+            check_in_alert_balance: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum BillingTrigger {
+    #[serde(rename = "manual")]
+    Manual,
+    #[serde(rename = "scheduled")]
+    Scheduled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BillingRunSummary {
+    pub period: String,
+    pub trigger: BillingTrigger,
+    pub run_at: DateTime<Utc>,
+    pub invoices_created: u32,
+    pub drafts_replaced: u32,
+    pub households_skipped: Vec<String>, // Already have an issued invoice for the period
+    pub total_invoiced: f64,
+    pub warnings: Vec<String>,
+}
+
+/// First and last day of a YYYY-MM period.
+pub fn period_bounds(period: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid billing period '{}', expected YYYY-MM", period))?;
+    let next_month = if start.month() == 12 {
+        NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+    };
+    let end = next_month.ok_or("Invalid billing period")? - Duration::days(1);
+    Ok((start, end))
+}
+
+pub fn previous_period(today: NaiveDate) -> String {
+    let first_of_month = today.with_day(1).unwrap_or(today);
+    (first_of_month - Duration::days(1)).format("%Y-%m").to_string()
+}
+
+fn next_invoice_number(data: &AppData, year: i32) -> String {
+    let prefix = format!("INV-{}-", year);
+    let last = data.invoices.iter()
+        .filter_map(|i| i.number.strip_prefix(&prefix))
+        .filter_map(|n| n.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{}{:04}", prefix, last + 1)
+}
+
+/// Time on site is billed in started blocks of this many minutes.
+const HOURLY_INCREMENT_MINUTES: i64 = 15;
+
+#[derive(Default)]
+struct DogCharges {
+    days: HashMap<String, (u32, f64, ServiceType)>, // service label -> (days, day rate, service)
+    hours: HashMap<String, (f64, f64, ServiceType)>, // service label -> (hours, hourly rate, service)
+    discount: f64,
+    house_food: HashMap<String, (f64, f64)>, // food name -> (grams fed, amount charged)
+    peak_surcharges: Vec<(String, f64)>, // Boarding nights booked day by day on peak dates
+}
+
+/// What one dog's booking on a day is charged, as a billing run works it out.
+pub struct EntryPrice {
+    pub label: String,
+    pub rate: f64, // Day rate, or the hourly price when billed by the hour
+    pub hours: Option<f64>,
+    pub discount: f64, // Multi-dog discount
+    pub peak_surcharge: Option<(String, f64)>,
+}
+
+impl EntryPrice {
+    pub fn total(&self) -> f64 {
+        let charged = self.hours.map_or(self.rate, |hours| hours * self.rate);
+        charged - self.discount + self.peak_surcharge.as_ref().map_or(0.0, |(_, surcharge)| *surcharge)
+    }
+}
+
+/// Why a booking isn't charged as a day of its own, if it isn't.
+pub fn billed_elsewhere(data: &AppData, dog_id: &str, date: &str, service_type: &ServiceType) -> Option<&'static str> {
+    // Nights booked as part of a stay are billed with the stay
+    if *service_type == ServiceType::Boarding && stay_for_night(data, dog_id, date).is_some() {
+        return Some("Boarding stay");
+    }
+    // Days paid for with a package credit were charged when the package was sold
+    if covered_by_package(data, dog_id, date, service_type) {
+        return Some("Package credit");
+    }
+    None
+}
+
+fn add_warning(warnings: &mut Vec<String>, warning: String) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+/// Price the day's bookings that are charged by the day. Multi-dog discounts
+/// apply to a household's dogs sharing a service that day. Bookings without a
+/// price set are left out, with a warning.
+pub fn price_day<'a>(
+    data: &'a AppData,
+    date: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<(&'a Dog, ServiceType, AttendanceType, EntryPrice)>, String> {
+    let day_data = match data.daily_data.get(date) {
+        Some(day_data) => day_data,
+        None => return Ok(Vec::new()),
+    };
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date in attendance: {}", date))?;
+    let prices = &data.settings.pricing;
+    
+    let mut groups: HashMap<(String, String), Vec<(&Dog, ServiceType)>> = HashMap::new();
+    for entry in day_data.attendance.entries.values().filter(|e| e.attending) {
+        let dog = match data.dogs.iter().find(|d| d.id == entry.dog_id) {
+            Some(dog) => dog,
+            None => continue,
+        };
+        if billed_elsewhere(data, &dog.id, date, &entry.service_type).is_some() {
+            continue;
+        }
+        groups.entry((dog.household_key().to_string(), format!("{:?}", entry.service_type)))
+            .or_default()
+            .push((dog, entry.service_type.clone()));
+    }
+    
+    let mut priced = Vec::new();
+    for (_, mut dogs) in groups {
+        dogs.sort_by_key(|(dog, _)| dog.created_at);
+        
+        for (rank, (dog, service_type)) in dogs.into_iter().enumerate() {
+            let attendance_type = match service_type {
+                ServiceType::Daycare => day_data.attendance.types.get(&dog.id).cloned().unwrap_or(AttendanceType::FullDay),
+                _ => AttendanceType::FullDay,
+            };
+            if attendance_type == AttendanceType::NotAttending {
+                continue;
+            }
+            
+            let age_class = age_class_on(dog, &data.settings.age_classes, day);
+            let (label, rate, hourly_price) = match prices.rate(&service_type, &attendance_type, age_class, day) {
+                Some(rate) => (rate_label(rate), rate.price, rate.hourly_price),
+                None => {
+                    let label = service_label(&service_type, &attendance_type);
+                    add_warning(warnings, format!("No price set for {}", label.to_lowercase()));
+                    continue;
+                }
+            };
+            
+            // Hourly services fall back to the day rate when the time on site
+            // isn't known or there's no hourly price
+            let mut price = EntryPrice { label, rate, hours: None, discount: 0.0, peak_surcharge: None };
+            if data.settings.billing.hourly_services.contains(&service_type) {
+                let minutes = day_data.records.get(&dog.id).and_then(attended_minutes);
+                match (hourly_price, minutes) {
+                    (Some(hourly_price), Some(minutes)) => {
+                        let blocks = (minutes + HOURLY_INCREMENT_MINUTES - 1) / HOURLY_INCREMENT_MINUTES;
+                        price.hours = Some(blocks as f64 * HOURLY_INCREMENT_MINUTES as f64 / 60.0);
+                        price.rate = hourly_price;
+                    }
+                    (None, _) => add_warning(warnings, format!(
+                        "No hourly price set for {}; billed at the day rate", price.label.to_lowercase()
+                    )),
+                    (Some(_), None) => warnings.push(format!(
+                        "{} has no check-in and check-out on {}; billed at the day rate", dog.name, date
+                    )),
+                }
+            }
+            
+            let charged = price.hours.map_or(price.rate, |hours| hours * price.rate);
+            price.discount = charged * prices.multi_dog_percent(rank as u32 + 1) / 100.0;
+            if service_type == ServiceType::Boarding {
+                if let Some(peak) = data.settings.boarding.peak_date(date) {
+                    price.peak_surcharge = Some((peak_surcharge_label(peak, day), rate * peak.surcharge_percent / 100.0));
+                }
+            }
+            priced.push((dog, service_type, attendance_type, price));
+        }
+    }
+    Ok(priced)
+}
+
+/// Draft invoices for every household with attendance in the period. Drafts
+/// from an earlier run are regenerated; issued invoices are left alone.
+pub fn run_billing(data: &mut AppData, period: &str, trigger: BillingTrigger) -> Result<BillingRunSummary, String> {
+    let (start, end) = period_bounds(period)?;
+    let start_str = start.format("%Y-%m-%d").to_string();
+    let end_str = end.format("%Y-%m-%d").to_string();
+    let currency = data.settings.currency.clone();
+    
+    let mut warnings = Vec::new();
+    let mut charges: HashMap<String, HashMap<String, DogCharges>> = HashMap::new(); // household -> dog -> charges
+    
+    let mut dates: Vec<&String> = data.daily_data.keys()
+        .filter(|d| **d >= start_str && **d <= end_str)
+        .collect();
+    dates.sort();
+    
+    for date in dates {
+        for (dog, service_type, _, price) in price_day(data, date, &mut warnings)? {
+            let dog_charges = charges.entry(dog.household_key().to_string())
+                .or_default()
+                .entry(dog.id.clone())
+                .or_default();
+            match price.hours {
+                Some(hours) => {
+                    let line = dog_charges.hours.entry(price.label.clone()).or_insert((0.0, price.rate, service_type));
+                    line.0 += hours;
+                }
+                None => {
+                    let line = dog_charges.days.entry(price.label.clone()).or_insert((0, price.rate, service_type));
+                    line.0 += 1;
+                }
+            }
+            dog_charges.discount += price.discount;
+            if let Some(peak) = price.peak_surcharge {
+                dog_charges.peak_surcharges.push(peak);
+            }
+        }
+    }
+    
+    // House food fed to boarders is charged by weight
+    for feeding in data.feedings.iter().filter(|f| f.date >= start_str && f.date <= end_str) {
+        if feeding.source != FoodSource::House {
+            continue;
+        }
+        let food = match data.house_foods.iter().find(|f| f.id == feeding.food_id) {
+            Some(food) => food,
+            None => continue,
+        };
+        // Feedings recorded before prices were kept on them use today's price
+        let price_per_kg = feeding.price_per_kg.unwrap_or(food.price_per_kg);
+        if price_per_kg <= 0.0 {
+            continue;
+        }
+        let dog = match data.dogs.iter().find(|d| d.id == feeding.dog_id) {
+            Some(dog) => dog,
+            None => continue,
+        };
+        
+        let line = charges.entry(dog.household_key().to_string())
+            .or_default()
+            .entry(dog.id.clone())
+            .or_default()
+            .house_food.entry(food.name.clone())
+            .or_insert((0.0, 0.0));
+        line.0 += feeding.grams;
+        line.1 += feeding.grams / 1000.0 * price_per_kg;
+    }
+    
+    // Boarding stays are billed whole in the month the dogs go home
+    let mut stay_lines: HashMap<String, Vec<InvoiceLine>> = HashMap::new();
+    let mut stays: Vec<&BoardingStay> = data.boarding_stays.iter()
+        .filter(|s| s.check_out_date >= start_str && s.check_out_date <= end_str)
+        .collect();
+    stays.sort_by(|a, b| a.check_in_date.cmp(&b.check_in_date));
+    for stay in stays {
+        let household_id = match stay.dog_ids.iter().find_map(|id| data.dogs.iter().find(|d| d.id == *id)) {
+            Some(dog) => dog.household_key().to_string(),
+            None => continue,
+        };
+        let stay_charges = boarding_charges(data, stay)?;
+        for warning in stay_charges.warnings {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+        charges.entry(household_id.clone()).or_default();
+        stay_lines.entry(household_id).or_default().extend(stay_charges.lines);
+    }
+    
+    let mut summary = BillingRunSummary {
+        period: period.to_string(),
+        trigger,
+        run_at: Utc::now(),
+        invoices_created: 0,
+        drafts_replaced: 0,
+        households_skipped: Vec::new(),
+        total_invoiced: 0.0,
+        warnings,
+    };
+    
+    let mut household_ids: Vec<String> = charges.keys().cloned().collect();
+    household_ids.sort();
+    
+    for household_id in household_ids {
+        let already_issued = data.invoices.iter().any(|i| {
+            i.household_id == household_id && i.period == period
+                && matches!(i.status, InvoiceStatus::Issued | InvoiceStatus::SubmittedForCollection)
+        });
+        if already_issued {
+            summary.households_skipped.push(household_id);
+            continue;
+        }
+        
+        // Regenerated drafts keep their number so there are no gaps in the sequence
+        let is_old_draft = |i: &Invoice| i.household_id == household_id && i.period == period && i.status == InvoiceStatus::Draft;
+        let old_number = data.invoices.iter().find(|i| is_old_draft(i)).map(|i| i.number.clone());
+        if old_number.is_some() {
+            data.invoices.retain(|i| !is_old_draft(i));
+            summary.drafts_replaced += 1;
+        }
+        
+        let mut lines = Vec::new();
+        let mut dog_ids: Vec<&String> = charges[&household_id].keys().collect();
+        dog_ids.sort_by_key(|id| data.dogs.iter().find(|d| d.id == **id).map(|d| d.name.clone()));
+        
+        for dog_id in dog_ids {
+            let dog_charges = &charges[&household_id][dog_id];
+            let dog_name = data.dogs.iter()
+                .find(|d| d.id == *dog_id)
+                .map(|d| d.name.clone())
+                .unwrap_or_default();
+            
+            let mut day_lines: Vec<_> = dog_charges.days.iter().collect();
+            day_lines.sort_by(|a, b| a.0.cmp(b.0));
+            for (label, (days, rate, service_type)) in day_lines {
+                lines.push(InvoiceLine {
+                    description: format!("{}: {}", dog_name, label),
+                    dog_id: Some(dog_id.clone()),
+                    quantity: *days,
+                    unit_price: currency.round(*rate),
+                    amount: currency.round(*rate * *days as f64),
+                    service_type: Some(service_type.clone()),
+                });
+            }
+            
+            let mut hour_lines: Vec<_> = dog_charges.hours.iter().collect();
+            hour_lines.sort_by(|a, b| a.0.cmp(b.0));
+            for (label, (hours, hourly_price, service_type)) in hour_lines {
+                let amount = currency.round(hours * hourly_price);
+                lines.push(InvoiceLine {
+                    description: format!("{}: {}, {:.2} hours at {}/hour", dog_name, label, hours, currency.format(*hourly_price)),
+                    dog_id: Some(dog_id.clone()),
+                    quantity: 1,
+                    unit_price: amount,
+                    amount,
+                    service_type: Some(service_type.clone()),
+                });
+            }
+            
+            for (label, amount) in &dog_charges.peak_surcharges {
+                lines.push(InvoiceLine {
+                    description: format!("{}: {}", dog_name, label),
+                    dog_id: Some(dog_id.clone()),
+                    quantity: 1,
+                    unit_price: currency.round(*amount),
+                    amount: currency.round(*amount),
+                    service_type: Some(ServiceType::Boarding),
+                });
+            }
+            
+            let mut food_lines: Vec<_> = dog_charges.house_food.iter().collect();
+            food_lines.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, (grams, amount)) in food_lines {
+                let amount = currency.round(*amount);
+                lines.push(InvoiceLine {
+                    description: format!("{}: house food, {} ({:.2} kg)", dog_name, name, grams / 1000.0),
+                    dog_id: Some(dog_id.clone()),
+                    quantity: 1,
+                    unit_price: amount,
+                    amount,
+                    service_type: None,
+                });
+            }
+            
+            if dog_charges.discount > 0.0 {
+                let discount = currency.round(dog_charges.discount);
+                lines.push(InvoiceLine {
+                    description: format!("{}: multi-dog discount", dog_name),
+                    dog_id: Some(dog_id.clone()),
+                    quantity: 1,
+                    unit_price: -discount,
+                    amount: -discount,
+                    service_type: None,
+                });
+            }
+        }
+        lines.extend(stay_lines.remove(&household_id).unwrap_or_default());
+        
+        let total = currency.round(lines.iter().map(|l| l.amount).sum());
+        let invoice = Invoice {
+            id: Uuid::new_v4().to_string(),
+            number: old_number.unwrap_or_else(|| next_invoice_number(data, start.year())),
+            household_id,
+            period: period.to_string(),
+            lines,
+            total,
+            status: InvoiceStatus::Draft,
+            created_at: Utc::now(),
+            issued_at: None,
+            collection: None,
+        };
+        
+        summary.invoices_created += 1;
+        summary.total_invoiced += total;
+        data.invoices.push(invoice);
+    }
+    
+    summary.total_invoiced = currency.round(summary.total_invoiced);
+    data.billing_runs.push(summary.clone());
+    
+    println!("Billing run for {}: {} invoices drafted, {} skipped",
+             period, summary.invoices_created, summary.households_skipped.len());
+    Ok(summary)
+}
+
+fn emit_billing_summary(app: &tauri::AppHandle, summary: &BillingRunSummary) {
+    if let Err(e) = app.emit(BILLING_RUN_EVENT, summary) {
+        println!("Failed to emit billing summary: {}", e);
+    }
+}
+
+/// Run billing for the previous month if automatic billing is enabled, today is
+/// on or after the configured day, and that month hasn't been billed yet.
+fn run_scheduled_billing(app: &tauri::AppHandle) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    let settings = &data.settings.billing;
+    let today = Utc::now().date_naive();
+    
+    if !settings.auto_run_enabled || today.day() < settings.run_day_of_month.clamp(1, 28) {
+        return Ok(());
+    }
+    
+    let period = previous_period(today);
+    if data.billing_runs.iter().any(|r| r.period == period) {
+        return Ok(());
+    }
+    
+    let summary = run_billing(&mut data, &period, BillingTrigger::Scheduled)?;
+    save_app_data(&data)?;
+    emit_billing_summary(app, &summary);
+    Ok(())
+}
+
+/// Check hourly in the background whether the monthly billing run is due.
+pub fn spawn_billing_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = run_scheduled_billing(&app) {
+            println!("Scheduled billing run failed: {}", e);
+        }
+        std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+    });
+}
+
+#[tauri::command]
+pub fn run_billing_now(app: tauri::AppHandle, period: String) -> Result<BillingRunSummary, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let summary = run_billing(&mut data, &period, BillingTrigger::Manual)?;
+    record_audit(&mut data, "billing_run", &format!("Billing run for {} drafted {} invoices", period, summary.invoices_created));
+    save_app_data(&data)?;
+    
+    emit_billing_summary(&app, &summary);
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn get_invoices(period: Option<String>) -> Result<Vec<Invoice>, String> {
+    let data = load_app_data()?;
+    
+    let mut invoices: Vec<Invoice> = data.invoices.into_iter()
+        .filter(|i| period.as_ref().is_none_or(|p| i.period == *p))
+        .collect();
+    
+    invoices.sort_by(|a, b| b.number.cmp(&a.number));
+    Ok(invoices)
+}
+
+#[tauri::command]
+pub fn get_billing_runs() -> Result<Vec<BillingRunSummary>, String> {
+    let data = load_app_data()?;
+    let mut runs = data.billing_runs;
+    runs.sort_by_key(|r| std::cmp::Reverse(r.run_at));
+    Ok(runs)
+}
+
+/// Loyalty points redeemed as credit against an invoice.
+pub fn loyalty_credit(data: &AppData, invoice_id: &str) -> f64 {
+    data.loyalty_redemptions.iter()
+        .filter(|r| r.invoice_id == invoice_id)
+        .map(|r| r.value)
+        .sum()
+}
+
+/// What's still to pay on an issued invoice after the payments and loyalty
+/// credit recorded against it; zero for drafts and voided invoices.
+pub fn amount_due(data: &AppData, invoice: &Invoice) -> f64 {
+    if !matches!(invoice.status, InvoiceStatus::Issued | InvoiceStatus::SubmittedForCollection) {
+        return 0.0;
+    }
+    let paid: f64 = data.payments.iter()
+        .filter(|p| p.invoice_id.as_deref() == Some(invoice.id.as_str()))
+        .map(|p| p.amount)
+        .sum();
+    data.settings.currency.round(invoice.total - paid - loyalty_credit(data, &invoice.id)).max(0.0)
+}
+
+/// Approve a reviewed draft so it's sent to the household.
+#[tauri::command]
+pub fn issue_invoice(invoice_id: String) -> Result<Invoice, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let invoice = data.invoices.iter_mut()
+        .find(|i| i.id == invoice_id)
+        .ok_or("Invoice not found")?;
+    
+    if invoice.status != InvoiceStatus::Draft {
+        return Err(format!("Invoice {} is not a draft", invoice.number));
+    }
+    
+    invoice.status = InvoiceStatus::Issued;
+    invoice.issued_at = Some(Utc::now());
+    let invoice = invoice.clone();
+    
+    save_app_data(&data)?;
+    Ok(invoice)
+}
+
+#[tauri::command]
+pub fn void_invoice(invoice_id: String) -> Result<Invoice, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let invoice = data.invoices.iter_mut()
+        .find(|i| i.id == invoice_id)
+        .ok_or("Invoice not found")?;
+    
+    invoice.status = InvoiceStatus::Void;
+    let invoice = invoice.clone();
+    
+    record_audit(&mut data, "invoice_voided", &format!("Voided invoice {}", invoice.number));
+    save_app_data(&data)?;
+    Ok(invoice)
+}
+
+/// One row per booking attended between the dates, priced the way a billing
+/// run would, for a spreadsheet or the accountant's software. Bookings billed
+/// with a stay or a package, or with no price set, are listed without a price
+/// and a note saying why. Returns the CSV text.
+#[tauri::command]
+pub fn export_billing_csv(start_date: String, end_date: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    parse_period(&start_date, &end_date)?;
+    
+    let mut dates: Vec<&String> = data.daily_data.keys()
+        .filter(|d| **d >= start_date && **d <= end_date)
+        .collect();
+    dates.sort();
+    
+    let csv_error = |e: csv::Error| format!("Failed to write billing CSV: {}", e);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["Date", "Dog", "Owner", "Service", "Attendance Type", "Hours", "Price", "Note"])
+        .map_err(csv_error)?;
+    
+    let mut warnings = Vec::new();
+    for date in dates {
+        let mut priced = price_day(&data, date, &mut warnings)?;
+        let mut entries: Vec<_> = data.daily_data[date].attendance.entries.values()
+            .filter(|e| e.attending)
+            .filter_map(|e| Some((data.dogs.iter().find(|d| d.id == e.dog_id)?, e)))
+            .collect();
+        entries.sort_by(|a, b| a.0.name.cmp(&b.0.name).then(format!("{:?}", a.1.service_type).cmp(&format!("{:?}", b.1.service_type))));
+        
+        for (dog, entry) in entries {
+            let position = priced.iter().position(|(d, s, _, _)| d.id == dog.id && *s == entry.service_type);
+            let row = match position.map(|index| priced.remove(index)) {
+                Some((_, _, attendance_type, price)) => {
+                    let attendance_type = match (&entry.service_type, attendance_type) {
+                        (ServiceType::Daycare, AttendanceType::HalfDay) => "Half day",
+                        (ServiceType::Daycare, _) => "Full day",
+                        _ => "",
+                    };
+                    [
+                        attendance_type.to_string(),
+                        price.hours.map(|h| format!("{:.2}", h)).unwrap_or_default(),
+                        format!("{:.2}", data.settings.currency.round(price.total())),
+                        String::new(),
+                    ]
+                }
+                None => {
+                    let note = billed_elsewhere(&data, &dog.id, date, &entry.service_type)
+                        .unwrap_or("No price set");
+                    [String::new(), String::new(), String::new(), note.to_string()]
+                }
+            };
+            let [attendance_type, hours, price, note] = row;
+            writer.write_record([
+                date.clone(),
+                dog.name.clone(),
+                dog.owner.clone(),
+                format!("{:?}", entry.service_type),
+                attendance_type,
+                hours,
+                price,
+                note,
+            ]).map_err(csv_error)?;
+        }
+    }
+    
+    let bytes = writer.into_inner()
+        .map_err(|e| format!("Failed to write billing CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write billing CSV: {}", e))
+}
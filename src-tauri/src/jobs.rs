@@ -0,0 +1,247 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// Finished jobs kept for status lookups; older ones are dropped.
+const MAX_FINISHED_JOBS: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum JobKind {
+    #[serde(rename = "generation")]
+    Generation,
+    #[serde(rename = "import")]
+    Import,
+    #[serde(rename = "export")]
+    Export,
+    #[serde(rename = "report")]
+    Report,
+}
+
+impl JobKind {
+    fn key(&self) -> &'static str {
+        match self {
+            JobKind::Generation => "generation",
+            JobKind::Import => "import",
+            JobKind::Export => "export",
+            JobKind::Report => "report",
+        }
+    }
+    
+    fn description(&self) -> &'static str {
+        match self {
+            JobKind::Generation => "Attendance generation",
+            JobKind::Import => "An import",
+            JobKind::Export => "An export",
+            JobKind::Report => "A report",
+        }
+    }
+    
+    /// Event progress and the outcome are emitted on, e.g. "generation:progress".
+    fn event(&self) -> String {
+        format!("{}:progress", self.key())
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub enum JobStatus {
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub done: usize,
+    pub total: usize,
+    pub error: Option<String>, // Why it failed, or "Cancelled"
+    pub result: Option<Value>, // What the command would have returned, once completed
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+struct JobEntry {
+    job: Job,
+    cancelled: Arc<AtomicBool>,
+}
+
+static JOBS: Mutex<Vec<JobEntry>> = Mutex::new(Vec::new());
+
+const CANCELLED: &str = "Cancelled";
+
+/// Handed to a job's work so it can report progress and notice cancellation.
+pub struct JobContext {
+    app: Option<tauri::AppHandle>,
+    id: Option<String>,
+    kind: JobKind,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    /// Record progress and let the UI know. Returns an error once the job has
+    /// been cancelled, so work can stop with `?` and nothing gets saved.
+    pub fn progress(&self, done: usize, total: usize) -> Result<(), String> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(CANCELLED.to_string());
+        }
+        if let Some(job) = self.id.as_deref().and_then(|id| update(id, |job| {
+            job.done = done;
+            job.total = total;
+        })) {
+            self.emit(&job);
+        }
+        Ok(())
+    }
+    
+    fn emit(&self, job: &Job) {
+        if let Some(ref app) = self.app {
+            if let Err(e) = app.emit(&self.kind.event(), job) {
+                println!("Failed to emit job progress: {}", e);
+            }
+        }
+    }
+}
+
+fn update(id: &str, change: impl FnOnce(&mut Job)) -> Option<Job> {
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = jobs.iter_mut().find(|e| e.job.id == id)?;
+    change(&mut entry.job);
+    Some(entry.job.clone())
+}
+
+/// Refuse to start a second run of the same kind while one is going, e.g. from
+/// a double click.
+fn ensure_not_running(jobs: &[JobEntry], kind: JobKind) -> Result<(), String> {
+    if jobs.iter().any(|e| e.job.kind == kind && e.job.status == JobStatus::Running) {
+        return Err(format!("{} is already running", kind.description()));
+    }
+    Ok(())
+}
+
+fn register(kind: JobKind) -> Result<(String, Arc<AtomicBool>), String> {
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    ensure_not_running(&jobs, kind)?;
+    
+    let finished: Vec<usize> = jobs.iter().enumerate()
+        .filter(|(_, e)| e.job.status != JobStatus::Running)
+        .map(|(index, _)| index)
+        .collect();
+    if finished.len() >= MAX_FINISHED_JOBS {
+        jobs.remove(finished[0]);
+    }
+    
+    let id = Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    jobs.push(JobEntry {
+        job: Job {
+            id: id.clone(),
+            kind,
+            status: JobStatus::Running,
+            done: 0,
+            total: 0,
+            error: None,
+            result: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        },
+        cancelled: cancelled.clone(),
+    });
+    Ok((id, cancelled))
+}
+
+fn finish(context: &JobContext, result: Result<Option<Value>, String>) {
+    let id = match context.id {
+        Some(ref id) => id,
+        None => return,
+    };
+    let job = update(id, |job| {
+        match result {
+            Ok(result) => {
+                job.status = JobStatus::Completed;
+                job.done = job.total;
+                job.result = result;
+            }
+            Err(e) => {
+                let cancelled = context.cancelled.load(Ordering::Relaxed) && e == CANCELLED;
+                job.status = if cancelled { JobStatus::Cancelled } else { JobStatus::Failed };
+                job.error = Some(e);
+            }
+        }
+        job.finished_at = Some(Utc::now());
+    });
+    if let Some(job) = job {
+        context.emit(&job);
+    }
+}
+
+/// Run `work` on a background thread as a job, returning its id straight away.
+/// Progress and the outcome are emitted on the kind's progress event, and the
+/// value `work` returns is kept as the job's result.
+pub fn start_job<T, F>(app: tauri::AppHandle, kind: JobKind, work: F) -> Result<String, String>
+where
+    T: Serialize,
+    F: FnOnce(&JobContext) -> Result<T, String> + Send + 'static,
+{
+    let (id, cancelled) = register(kind)?;
+    let context = JobContext { app: Some(app), id: Some(id.clone()), kind, cancelled };
+    
+    std::thread::spawn(move || {
+        let result = work(&context).and_then(|value| serde_json::to_value(value)
+            .map(Some)
+            .map_err(|e| format!("Failed to serialize job result: {}", e)));
+        finish(&context, result);
+    });
+    Ok(id)
+}
+
+/// Run `work` on the calling thread, still refusing to overlap with a job of
+/// the same kind, for commands the UI waits on directly.
+pub fn run_exclusive<T>(kind: JobKind, work: impl FnOnce(&JobContext) -> Result<T, String>) -> Result<T, String> {
+    let (id, cancelled) = register(kind)?;
+    let context = JobContext { app: None, id: Some(id), kind, cancelled };
+    let result = work(&context);
+    finish(&context, result.as_ref().map(|_| None).map_err(|e| e.clone()));
+    result
+}
+
+/// A context that never reports or cancels, for work run as part of something else.
+pub fn untracked(kind: JobKind) -> JobContext {
+    JobContext { app: None, id: None, kind, cancelled: Arc::new(AtomicBool::new(false)) }
+}
+
+/// A job's progress, and its result or error once it has finished. Only the
+/// most recent finished jobs are kept.
+#[tauri::command]
+pub fn get_job_status(job_id: String) -> Result<Job, String> {
+    let jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    jobs.iter()
+        .find(|e| e.job.id == job_id)
+        .map(|e| e.job.clone())
+        .ok_or("Job not found".to_string())
+}
+
+/// Ask a running job to stop. It stops at its next progress report without
+/// saving anything.
+#[tauri::command]
+pub fn cancel_job(job_id: String) -> Result<(), String> {
+    let jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = jobs.iter()
+        .find(|e| e.job.id == job_id)
+        .ok_or("Job not found")?;
+    if entry.job.status != JobStatus::Running {
+        return Err("The job has already finished".to_string());
+    }
+    entry.cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+}
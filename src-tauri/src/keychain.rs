@@ -0,0 +1,218 @@
+/// Where the data passphrase is remembered in the operating system's credential
+/// store: the Keychain on macOS, Credential Manager on Windows and the Secret
+/// Service keyring (through `secret-tool`) on Linux.
+const SERVICE: &str = "doggy-daycare-app";
+const ACCOUNT: &str = "data-passphrase";
+
+/// Remember the passphrase, replacing any remembered before.
+pub fn store(passphrase: &str) -> Result<(), String> {
+    platform::store(passphrase)
+}
+
+/// The remembered passphrase, or None when there isn't one.
+pub fn load() -> Result<Option<String>, String> {
+    platform::load()
+}
+
+/// Forget the passphrase. Nothing being remembered isn't an error.
+pub fn remove() -> Result<(), String> {
+    platform::remove()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use security_framework::passwords::{
+        delete_generic_password, generic_password, set_generic_password, PasswordOptions,
+    };
+
+    use super::{ACCOUNT, SERVICE};
+
+    /// errSecItemNotFound
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn store(passphrase: &str) -> Result<(), String> {
+        set_generic_password(SERVICE, ACCOUNT, passphrase.as_bytes())
+            .map_err(|e| format!("Failed to save the passphrase to the keychain: {}", e))
+    }
+
+    pub fn load() -> Result<Option<String>, String> {
+        match generic_password(PasswordOptions::new_generic_password(SERVICE, ACCOUNT)) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|e| format!("The remembered passphrase is not valid text: {}", e)),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(format!("Failed to read the keychain: {}", e)),
+        }
+    }
+
+    pub fn remove() -> Result<(), String> {
+        match delete_generic_password(SERVICE, ACCOUNT) {
+            Err(e) if e.code() != ITEM_NOT_FOUND => {
+                Err(format!("Failed to remove the passphrase from the keychain: {}", e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows_sys::Win32::Security::Credentials::{
+        CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+        CRED_TYPE_GENERIC,
+    };
+
+    use super::{ACCOUNT, SERVICE};
+
+    /// Credential name as a null-terminated wide string.
+    fn target_name() -> Vec<u16> {
+        format!("{}/{}", SERVICE, ACCOUNT).encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn is_not_found(error: &io::Error) -> bool {
+        error.raw_os_error() == Some(ERROR_NOT_FOUND as i32)
+    }
+
+    pub fn store(passphrase: &str) -> Result<(), String> {
+        let mut target = target_name();
+        let mut blob = passphrase.as_bytes().to_vec();
+        // All-zero is an empty credential: null strings, no attributes
+        let mut credential: CREDENTIALW = unsafe { std::mem::zeroed() };
+        credential.Type = CRED_TYPE_GENERIC;
+        credential.TargetName = target.as_mut_ptr();
+        credential.CredentialBlobSize = blob.len() as u32;
+        credential.CredentialBlob = blob.as_mut_ptr();
+        credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+        
+        if unsafe { CredWriteW(&credential, 0) } == 0 {
+            return Err(format!(
+                "Failed to save the passphrase to Credential Manager: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn load() -> Result<Option<String>, String> {
+        let target = target_name();
+        let mut credential: *mut CREDENTIALW = ptr::null_mut();
+        if unsafe { CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) } == 0 {
+            let error = io::Error::last_os_error();
+            if is_not_found(&error) {
+                return Ok(None);
+            }
+            return Err(format!("Failed to read Credential Manager: {}", error));
+        }
+        
+        // Copy the secret out before Credential Manager's buffer is freed
+        let bytes = unsafe {
+            let credential = &*credential;
+            if credential.CredentialBlob.is_null() {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(credential.CredentialBlob, credential.CredentialBlobSize as usize).to_vec()
+            }
+        };
+        unsafe { CredFree(credential as *const _) };
+        
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|e| format!("The remembered passphrase is not valid text: {}", e))
+    }
+
+    pub fn remove() -> Result<(), String> {
+        let target = target_name();
+        if unsafe { CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) } == 0 {
+            let error = io::Error::last_os_error();
+            if !is_not_found(&error) {
+                return Err(format!("Failed to remove the passphrase from Credential Manager: {}", error));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use std::io::{ErrorKind, Write};
+    use std::process::{Command, Output, Stdio};
+
+    use super::{ACCOUNT, SERVICE};
+
+    const LABEL: &str = "Doggy Daycare data passphrase";
+
+    fn secret_tool(action: &str) -> Command {
+        let mut command = Command::new("secret-tool");
+        command.arg(action);
+        if action == "store" {
+            command.arg(format!("--label={}", LABEL));
+        }
+        command.args(["service", SERVICE, "account", ACCOUNT]);
+        command
+    }
+
+    fn failure(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stderr).trim().to_string()
+    }
+
+    pub fn store(passphrase: &str) -> Result<(), String> {
+        let mut child = secret_tool("store")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run secret-tool (from the libsecret tools package): {}", e))?;
+        
+        // secret-tool reads the secret from standard input
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(passphrase.as_bytes())
+                .map_err(|e| format!("Failed to pass the passphrase to secret-tool: {}", e))?;
+        }
+        let output = child.wait_with_output()
+            .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+        
+        if !output.status.success() {
+            return Err(format!("Failed to save the passphrase to the keyring: {}", failure(&output)));
+        }
+        Ok(())
+    }
+
+    pub fn load() -> Result<Option<String>, String> {
+        let output = match secret_tool("lookup").stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            // Without secret-tool nothing can have been remembered
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to run secret-tool: {}", e)),
+        };
+        
+        if !output.status.success() {
+            // A lookup that finds nothing fails without saying anything
+            let message = failure(&output);
+            return if message.is_empty() {
+                Ok(None)
+            } else {
+                Err(format!("Failed to read the keyring: {}", message))
+            };
+        }
+        
+        let passphrase = String::from_utf8(output.stdout)
+            .map_err(|e| format!("The remembered passphrase is not valid text: {}", e))?;
+        Ok(Some(passphrase.trim_end_matches('\n').to_string()))
+    }
+
+    pub fn remove() -> Result<(), String> {
+        let output = match secret_tool("clear").stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(format!("Failed to run secret-tool: {}", e)),
+        };
+        
+        if !output.status.success() && !failure(&output).is_empty() {
+            return Err(format!("Failed to remove the passphrase from the keyring: {}", failure(&output)));
+        }
+        Ok(())
+    }
+}
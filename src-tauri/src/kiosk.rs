@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::audit::record_audit;
+use crate::crypto::verify_secret;
+use crate::permissions::{current_staff_id, require_permission, role_permissions, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, ServiceType};
+
+/// Commands the door tablet can still run in kiosk mode. Everything else is
+/// refused before it reaches its handler.
+const KIOSK_COMMANDS: &[&str] = &[
+    "check_in_dog",
+    "check_out_dog",
+    "get_kiosk_roster",
+    "get_kiosk_status",
+    "exit_kiosk_mode",
+    "get_instance_mode",
+];
+
+/// Kiosk mode is saved so restarting the tablet doesn't get out of it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KioskSession {
+    pub started_at: DateTime<Utc>,
+    pub started_by: Option<String>, // Staff id
+}
+
+static KIOSK: Mutex<Option<KioskSession>> = Mutex::new(None);
+
+fn kiosk_session() -> Option<KioskSession> {
+    KIOSK.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn set_kiosk_session(session: Option<KioskSession>) {
+    *KIOSK.lock().unwrap_or_else(|e| e.into_inner()) = session;
+}
+
+pub fn is_active() -> bool {
+    kiosk_session().is_some()
+}
+
+/// Pick up kiosk mode left on from the last run.
+pub fn restore_kiosk_mode() {
+    match load_app_data() {
+        Ok(data) => set_kiosk_session(data.kiosk),
+        Err(e) => println!("Failed to check kiosk mode: {}", e),
+    }
+}
+
+/// Wrap the app's invoke handler so kiosk mode refuses every command that
+/// isn't on the kiosk list.
+pub fn guard<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        if is_active() && !KIOSK_COMMANDS.contains(&invoke.message.command()) {
+            invoke.resolver.reject("Not available in kiosk mode");
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct KioskRosterEntry {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub photo_path: Option<String>,
+    pub service_type: ServiceType,
+    pub drop_off_time: Option<String>,
+    pub pick_up_time: Option<String>,
+    pub checked_in: bool,
+    pub checked_out: bool,
+}
+
+/// The day's bookings with just enough to check dogs in and out: no owner
+/// names, phone numbers or notes.
+#[tauri::command]
+pub fn get_kiosk_roster(date: String) -> Result<Vec<KioskRosterEntry>, String> {
+    let data = load_app_data()?;
+    let day_data = match data.daily_data.get(&date) {
+        Some(day_data) => day_data,
+        None => return Ok(Vec::new()),
+    };
+    
+    let mut roster: Vec<KioskRosterEntry> = day_data.attendance.entries.values()
+        .filter(|e| e.attending)
+        .filter_map(|entry| {
+            let dog = data.dogs.iter().find(|d| d.id == entry.dog_id)?;
+            let record = day_data.records.get(&dog.id);
+            Some(KioskRosterEntry {
+                dog_id: dog.id.clone(),
+                dog_name: dog.name.clone(),
+                photo_path: dog.photo_path.clone(),
+                service_type: entry.service_type.clone(),
+                drop_off_time: data.settings.default_times.drop_off(&entry.service_type, &entry.drop_off_time),
+                pick_up_time: data.settings.default_times.pick_up(&entry.service_type, &entry.pick_up_time),
+                checked_in: record.is_some_and(|r| r.checked_in_at.is_some()),
+                checked_out: record.is_some_and(|r| r.checked_out_at.is_some()),
+            })
+        })
+        .collect();
+    
+    roster.sort_by(|a, b| a.dog_name.cmp(&b.dog_name));
+    Ok(roster)
+}
+
+#[tauri::command]
+pub fn get_kiosk_status() -> Option<KioskSession> {
+    kiosk_session()
+}
+
+/// Lock this machine into kiosk mode. Getting out needs the PIN of someone
+/// allowed to change settings, so there must be such a staff member first.
+#[tauri::command]
+pub fn start_kiosk_mode() -> Result<KioskSession, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let can_exit = data.staff.iter().any(|s| {
+        s.active && s.pin_hash.is_some() && role_permissions(s.role).contains(&Permission::EditSettings)
+    });
+    if !can_exit {
+        return Err("Add a staff member with a PIN who can change settings first, so kiosk mode can be turned off again".to_string());
+    }
+    
+    let session = KioskSession {
+        started_at: Utc::now(),
+        started_by: current_staff_id(),
+    };
+    data.kiosk = Some(session.clone());
+    record_audit(&mut data, "kiosk_started", "Kiosk mode turned on");
+    save_app_data(&data)?;
+    
+    set_kiosk_session(Some(session.clone()));
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn exit_kiosk_mode(staff_id: String, pin: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    
+    let member = data.staff.iter()
+        .find(|s| s.id == staff_id && s.active)
+        .cloned()
+        .ok_or("Staff member not found")?;
+    let pin_ok = member.pin_hash.as_deref().is_some_and(|hash| verify_secret(&pin, hash));
+    if !pin_ok {
+        record_audit(&mut data, "kiosk_exit_failed", &format!("Failed attempt to leave kiosk mode as {}", member.name));
+        save_app_data(&data)?;
+        return Err("Incorrect PIN".to_string());
+    }
+    if !role_permissions(member.role).contains(&Permission::EditSettings) {
+        return Err(format!("{} is not allowed to turn off kiosk mode", member.name));
+    }
+    
+    data.kiosk = None;
+    record_audit(&mut data, "kiosk_stopped", &format!("{} turned off kiosk mode", member.name));
+    save_app_data(&data)?;
+    
+    set_kiosk_session(None);
+    Ok(())
+}
@@ -1,11 +1,140 @@
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
 use chrono::{DateTime, Utc, NaiveDate, Datelike};
 use uuid::Uuid;
 use tauri_plugin_opener::OpenerExt;
 
+mod absences;
+mod accounting_export;
+mod actions;
+mod age;
+mod annual_review;
+mod anonymized_export;
+mod api_keys;
+mod attendance_keys;
+mod audit;
+mod backup_destinations;
+mod belongings;
+mod billing;
+mod boarding;
+mod booking_notices;
+mod branding;
+mod breeds;
+mod capacity;
+mod check_in_alerts;
+mod compliance;
+mod consent;
+mod contact_preferences;
+mod crypto;
+mod dashboard;
+mod data_encryption;
+mod day_locks;
+mod day_notes;
+mod digest;
+mod direct_debit;
+mod documents;
+mod durations;
+mod entry_history;
+mod erasure;
+mod fiscal;
+mod food;
+mod gallery;
+mod generation_report;
+mod health;
+mod importers;
+mod inbox;
+mod intake;
+mod instance;
+mod invoice_pdf;
+mod invoices;
+mod jobs;
+mod keychain;
+mod kiosk;
+mod mailer;
+mod media;
+mod medical;
+mod messages;
+mod notifications;
+mod opening_hours;
+mod owner_schedule;
+mod owners;
+mod packages;
+mod pdf;
+mod permissions;
+mod playgroups;
+mod pricing;
+mod printouts;
+mod quarantine;
+mod reconciliation;
+mod reminders;
+mod revenue_report;
+mod routes;
+mod schedule_conflicts;
+mod staff;
+mod tasks;
+mod times;
+mod trials;
+mod vaccinations;
+mod waitlist;
+
+use absences::Absence;
+use accounting_export::AccountingSettings;
+use age::{AgeClass, AgeClassSettings, IntactPolicy};
+use api_keys::ApiKey;
+use attendance_keys::entry_key;
+use audit::{record_audit, AuditEntry};
+use backup_destinations::{BackupDestination, DestinationKind, DestinationStatus};
+use belongings::Belonging;
+use billing::{LoyaltyRedemption, Payment};
+use boarding::{BoardingSettings, BoardingStay};
+use booking_notices::{BookingNotice, BookingNoticeSettings};
+use branding::Branding;
+use breeds::BreedGroup;
+use capacity::CapacitySettings;
+use consent::{ConsentRequestSettings, ConsentSigning};
+use contact_preferences::ContactPreferences;
+use dashboard::{emit_dashboard_update, DashboardEvent};
+use day_locks::DayLock;
+use day_notes::DayNote;
+use entry_history::EntryChange;
+use digest::DigestSettings;
+use direct_debit::{DirectDebitMandate, DirectDebitSettings};
+use documents::Document;
+use fiscal::FiscalSettings;
+use food::{FeedingEntry, HouseFood, OwnerFoodSupply};
+use inbox::ImapSettings;
+use intake::{IntakeAnswers, IntakeSettings};
+use invoices::{BillingRunSummary, BillingSettings, Invoice};
+use jobs::{JobContext, JobKind};
+use kiosk::KioskSession;
+use mailer::SmtpSettings;
+use media::DogPhoto;
+use medical::{Allergy, PetInsurance};
+use messages::{Message, MessageChannel, MessageStatus};
+use notifications::NotificationSettings;
+use opening_hours::{OpeningHours, WithWarnings};
+use owners::Owner;
+use packages::Package;
+use permissions::{require_permission, Permission};
+use playgroups::{DogSize, Playgroup};
+use pricing::PriceList;
+use quarantine::Quarantine;
+use reconciliation::BankTransaction;
+use reminders::ReminderState;
+use routes::PickupRoute;
+use staff::{Staff, TimeClockEntry};
+use tasks::Task;
+use times::DefaultTimes;
+use trials::Trial;
+use vaccinations::Vaccination;
+use waitlist::WaitlistEntry;
+
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DogSchedule {
@@ -47,21 +176,113 @@ pub struct Dog {
     pub email: String,
     pub breed: String,
     pub date_of_birth: Option<String>,
-    pub vaccine_date: Option<String>,
-    pub consent_last_signed: Option<String>,
+    pub vaccine_date: Option<String>, // Single date from before vaccinations were recorded per type
+    #[serde(default)]
+    pub vaccinations: Vec<Vaccination>,
+    pub consent_last_signed: Option<String>, // Latest date in the consent signing history
     pub created_at: DateTime<Utc>,
     #[serde(default)]
     pub schedule: DogSchedule,
     pub household_id: Option<String>,
+    #[serde(default)]
+    pub owner_id: Option<String>, // Owner, phone and email above are copies of this owner's details
+    #[serde(default)]
+    pub status: DogStatus,
+    #[serde(default)]
+    pub incompatible_dog_ids: Vec<String>, // Dogs this one must not share a day with
+    #[serde(default)]
+    pub solo_care: bool, // Needs a handler to themselves and stays out of group play
+    #[serde(default)]
+    pub size: Option<DogSize>,
+    #[serde(default)]
+    pub neuter_status: NeuterStatus,
+    #[serde(default)]
+    pub photo_path: Option<String>, // Profile photo on disk
+    #[serde(default)]
+    pub vet: Option<VetDetails>,
+    #[serde(default)]
+    pub microchip_number: Option<String>,
+    #[serde(default)]
+    pub allergies: Vec<Allergy>,
+    #[serde(default)]
+    pub insurance: Option<PetInsurance>,
+    #[serde(default)]
+    pub van_pickup: Option<VanPickup>, // Set for dogs we collect by van
+    #[serde(default)]
+    pub quarantine: Option<Quarantine>,
+    #[serde(default)]
+    pub intake: Option<IntakeAnswers>,
+    #[serde(default, skip_deserializing)]
+    pub age_class: Option<AgeClass>, // Derived from date_of_birth whenever dogs are returned
+    #[serde(default, skip_deserializing)]
+    pub breed_group: Option<BreedGroup>, // Derived from breed whenever dogs are returned
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum DogStatus {
+    #[default]
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "trial")]
+    Trial, // Awaiting or booked for a trial day; recurring schedules are held back until accepted
+    #[serde(rename = "declined")]
+    Declined,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VanPickup {
+    pub address: String,
+    pub window_start: Option<String>, // HH:MM
+    pub window_end: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VetDetails {
+    pub name: String,
+    pub clinic: String,
+    pub phone: String,
+    pub address: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum NeuterStatus {
+    #[default]
+    #[serde(rename = "unknown")]
+    Unknown,
+    #[serde(rename = "neutered")]
+    Neutered,
+    #[serde(rename = "intact")]
+    Intact,
+}
+
+impl Dog {
+    /// Household the dog belongs to; dogs without a household are their own household.
+    pub fn household_key(&self) -> &str {
+        self.household_id.as_deref().filter(|h| !h.is_empty()).unwrap_or(&self.id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DailyRecord {
     pub checklist: Option<HashMap<String, bool>>,
     pub feeding_times: Option<String>,
     pub drop_off_time: Option<String>,
     pub pick_up_time: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub checked_in_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub checked_out_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_by: Option<String>, // Staff id of the last person to change the record
+    #[serde(default)]
+    pub belongings: Vec<Belonging>, // Items brought in at drop-off, ticked off at pick-up
+    #[serde(default)]
+    pub photos: Vec<String>, // Photos taken that day, copied into the app's photos directory
+    #[serde(default, skip_deserializing)]
+    pub allergies: Vec<Allergy>, // The dog's, filled in whenever the day is returned
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -71,6 +292,24 @@ pub enum ServiceType {
     Boarding,
 }
 
+impl ServiceType {
+    pub const ALL: [ServiceType; 3] = [ServiceType::Daycare, ServiceType::Training, ServiceType::Boarding];
+    
+    /// Name used in attendance entry keys. Spelled out rather than taken from
+    /// Debug so keys in saved data and the frontend can't drift from the enum.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ServiceType::Daycare => "Daycare",
+            ServiceType::Training => "Training",
+            ServiceType::Boarding => "Boarding",
+        }
+    }
+    
+    pub fn from_key(key: &str) -> Option<ServiceType> {
+        ServiceType::ALL.into_iter().find(|s| s.key() == key)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum AttendanceType {
     #[serde(rename = "not_attending")]
@@ -99,6 +338,50 @@ pub struct AttendanceEntry {
     pub drop_off_time: Option<String>,
     pub pick_up_time: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub handler_id: Option<String>, // Staff member primarily responsible for the dog that day
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub trial: bool, // Booked as a trial day
+    #[serde(default)]
+    pub awaiting_confirmation: bool, // Booked on the owner's behalf (e.g. from the waitlist), not yet confirmed with them
+    #[serde(default)]
+    pub excused: bool, // Not attending because of an owner's absence notice, rather than cancelled
+    #[serde(default)]
+    pub generated_from: Option<String>, // Schedule that first booked the entry; kept when staff edit it
+    pub source: EntrySource, // Entries saved before this existed are given one by migrate_daily_data
+    #[serde(default, skip_deserializing)]
+    pub allergies: Vec<Allergy>, // The dog's, filled in whenever the day is returned
+}
+
+/// What created an attendance entry. Entries staff edit by hand become Manual.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum EntrySource {
+    #[serde(rename = "manual")]
+    Manual,
+    #[serde(rename = "auto_schedule")]
+    AutoSchedule,
+    #[serde(rename = "import")]
+    Import,
+    #[serde(rename = "waitlist_promotion")]
+    WaitlistPromotion,
+}
+
+impl EntrySource {
+    /// Best guess for entries saved before the source was recorded, from the
+    /// notes each generator used to leave on them.
+    fn from_legacy_notes(notes: &str) -> Self {
+        if notes.contains("Auto-generated") || notes.contains("Scheduled (not confirmed)") || notes.contains("Auto-scheduled") {
+            EntrySource::AutoSchedule
+        } else if notes.starts_with("Imported from") {
+            EntrySource::Import
+        } else if notes.starts_with("Promoted from waitlist") {
+            EntrySource::WaitlistPromotion
+        } else {
+            EntrySource::Manual
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -115,7 +398,7 @@ pub struct RecurringSchedule {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DayAttendance {
     pub dogs: HashMap<String, bool>, // Keep for backward compatibility
     pub entries: HashMap<String, AttendanceEntry>, // New detailed attendance
@@ -123,12 +406,70 @@ pub struct DayAttendance {
     pub types: HashMap<String, AttendanceType>, // New attendance types for Half-Day support
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DayData {
     pub attendance: DayAttendance,
     pub records: HashMap<String, DailyRecord>,
     pub am_temp: Option<String>,
     pub pm_temp: Option<String>,
+    #[serde(default)]
+    pub area_assignments: HashMap<String, String>, // dog_id -> area_id (room/kennel for the day)
+    #[serde(default)]
+    pub playgroup_assignments: HashMap<String, String>, // dog_id -> playgroup id for the day
+    #[serde(default)]
+    pub location_events: Vec<LocationEvent>,
+    #[serde(default)]
+    pub notes: Vec<DayNote>, // Staff handover notes for the day as a whole
+    #[serde(default)]
+    pub history: Vec<EntryChange>, // Edits to bookings and day records, for settling disputes
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocationEvent {
+    pub dog_id: String,
+    pub area_id: String,
+    pub time: String, // HH:MM
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DogLocation {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub area_id: String,
+    pub area_name: String,
+    pub since: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum AreaType {
+    #[serde(rename = "playroom")]
+    Playroom,
+    #[serde(rename = "yard")]
+    Yard,
+    #[serde(rename = "kennel_block")]
+    KennelBlock,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Area {
+    pub id: String,
+    pub name: String,
+    pub area_type: AreaType,
+    pub capacity: u32,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub max_puppies: Option<u32>, // Limit on puppies sharing the area
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AreaOccupancy {
+    pub area_id: String,
+    pub name: String,
+    pub area_type: AreaType,
+    pub capacity: u32,
+    pub assigned_dogs: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -155,6 +496,8 @@ pub struct CloudBackupConfig {
     pub cloud_directory: String,
     pub max_backups: u32,
     pub sync_interval_minutes: u32,
+    #[serde(default)]
+    pub destinations: Vec<BackupDestination>, // Written to as well as cloud_directory
 }
 
 impl Default for CloudBackupConfig {
@@ -164,6 +507,65 @@ impl Default for CloudBackupConfig {
             cloud_directory: String::new(),
             max_backups: 100,
             sync_interval_minutes: 30,
+            destinations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CurrencySettings {
+    pub code: String, // ISO 4217, e.g. "USD", "EUR", "GBP"
+    pub symbol: String,
+    pub decimal_places: u32,
+    #[serde(default)]
+    pub symbol_after_amount: bool, // "12.50 €" instead of "€12.50"
+}
+
+impl Default for CurrencySettings {
+    fn default() -> Self {
+        Self {
+            code: "USD".to_string(),
+            symbol: "$".to_string(),
+            decimal_places: 2,
+            symbol_after_amount: false,
+        }
+    }
+}
+
+impl CurrencySettings {
+    /// Round an amount to the currency's smallest unit (e.g. cents).
+    pub fn round(&self, amount: f64) -> f64 {
+        let factor = 10f64.powi(self.decimal_places as i32);
+        (amount * factor).round() / factor
+    }
+    
+    /// Format an amount for display on screen and in generated documents.
+    pub fn format(&self, amount: f64) -> String {
+        let sign = if amount < 0.0 { "-" } else { "" };
+        let number = format!("{:.*}", self.decimal_places as usize, self.round(amount).abs());
+        if self.symbol_after_amount {
+            format!("{}{} {}", sign, number, self.symbol)
+        } else {
+            format!("{}{}{}", sign, self.symbol, number)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoyaltySettings {
+    pub enabled: bool,
+    pub points_per_attended_day: u32,
+    pub redemption_value_per_point: f64, // In the configured currency
+    pub minimum_redemption_points: u32,
+}
+
+impl Default for LoyaltySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            points_per_attended_day: 10,
+            redemption_value_per_point: 0.01,
+            minimum_redemption_points: 500,
         }
     }
 }
@@ -173,6 +575,8 @@ pub struct Settings {
     pub business_name: String,
     #[serde(default = "default_business_phone")]
     pub business_phone: String,
+    #[serde(default)]
+    pub business_address: String, // Printed on invoices; one line per address line
     pub auto_backup: bool,
     #[serde(default)]
     pub cloud_backup: Option<CloudBackupConfig>,
@@ -180,6 +584,58 @@ pub struct Settings {
     pub email_subjects: EmailSubject,
     #[serde(default = "default_whatsapp_templates")]
     pub whatsapp_templates: WhatsAppTemplate,
+    #[serde(default)]
+    pub currency: CurrencySettings,
+    #[serde(default)]
+    pub loyalty: LoyaltySettings,
+    #[serde(default = "default_auto_lock_minutes")]
+    pub auto_lock_minutes: u32, // 0 disables auto-lock
+    #[serde(default)]
+    pub opening_hours: OpeningHours,
+    #[serde(default)]
+    pub pricing: PriceList,
+    #[serde(default)]
+    pub billing: BillingSettings,
+    #[serde(default)]
+    pub direct_debit: DirectDebitSettings,
+    #[serde(default)]
+    pub capacity: CapacitySettings,
+    #[serde(default)]
+    pub imap: ImapSettings,
+    #[serde(default)]
+    pub smtp: SmtpSettings,
+    #[serde(default)]
+    pub consent_requests: ConsentRequestSettings,
+    #[serde(default)]
+    pub age_classes: AgeClassSettings,
+    #[serde(default)]
+    pub intact_policy: IntactPolicy,
+    #[serde(default)]
+    pub restricted_breeds: Vec<String>, // Breeds our insurance excludes or restricts
+    #[serde(default)]
+    pub require_assessment: bool, // New dogs start on trial and can't be scheduled until they pass
+    #[serde(default)]
+    pub branding: Branding,
+    #[serde(default)]
+    pub boarding: BoardingSettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub digest: DigestSettings,
+    #[serde(default)]
+    pub intake: IntakeSettings,
+    #[serde(default)]
+    pub booking_notices: BookingNoticeSettings,
+    #[serde(default)]
+    pub default_times: DefaultTimes,
+    #[serde(default)]
+    pub fiscal: FiscalSettings,
+    #[serde(default)]
+    pub accounting: AccountingSettings,
+}
+
+fn default_auto_lock_minutes() -> u32 {
+    15
 }
 
 fn default_business_phone() -> String {
@@ -193,12 +649,80 @@ fn default_whatsapp_templates() -> WhatsAppTemplate {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppData {
     pub dogs: Vec<Dog>,
     pub daily_data: HashMap<String, DayData>,
     pub recurring_schedules: Vec<RecurringSchedule>,
     pub settings: Settings,
+    #[serde(default)]
+    pub areas: Vec<Area>,
+    #[serde(default)]
+    pub staff: Vec<Staff>,
+    #[serde(default)]
+    pub time_clock: Vec<TimeClockEntry>,
+    #[serde(default)]
+    pub payments: Vec<Payment>,
+    #[serde(default)]
+    pub loyalty_redemptions: Vec<LoyaltyRedemption>,
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+    #[serde(default)]
+    pub audit_log: Vec<AuditEntry>,
+    #[serde(default)]
+    pub trials: Vec<Trial>,
+    #[serde(default)]
+    pub invoices: Vec<Invoice>,
+    #[serde(default)]
+    pub billing_runs: Vec<BillingRunSummary>,
+    #[serde(default)]
+    pub mandates: Vec<DirectDebitMandate>,
+    #[serde(default)]
+    pub waitlist: Vec<WaitlistEntry>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub inbox_last_uid: u32, // Highest IMAP UID already checked for owner replies
+    #[serde(default)]
+    pub house_foods: Vec<HouseFood>,
+    #[serde(default)]
+    pub owner_food: Vec<OwnerFoodSupply>,
+    #[serde(default)]
+    pub feedings: Vec<FeedingEntry>,
+    #[serde(default)]
+    pub pickup_routes: Vec<PickupRoute>,
+    #[serde(default)]
+    pub boarding_stays: Vec<BoardingStay>,
+    #[serde(default)]
+    pub kiosk: Option<KioskSession>, // Set while this install is locked into kiosk mode
+    #[serde(default)]
+    pub absences: Vec<Absence>,
+    #[serde(default)]
+    pub reminders: Vec<ReminderState>, // Snoozed and dismissed reminders
+    #[serde(default)]
+    pub consent_signings: Vec<ConsentSigning>,
+    #[serde(default)]
+    pub locked_days: Vec<DayLock>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub digest_households: Vec<String>, // Households opted in to the weekly digest
+    #[serde(default)]
+    pub contact_preferences: Vec<ContactPreferences>,
+    #[serde(default)]
+    pub bank_transactions: Vec<BankTransaction>,
+    #[serde(default)]
+    pub owners: Vec<Owner>,
+    #[serde(default)]
+    pub packages: Vec<Package>,
+    #[serde(default)]
+    pub dog_photos: Vec<DogPhoto>,
+    #[serde(default)]
+    pub documents: Vec<Document>,
+    #[serde(default)]
+    pub backup_status: Vec<DestinationStatus>,
+    #[serde(default)]
+    pub playgroups: Vec<Playgroup>,
 }
 
 impl Default for AppData {
@@ -207,9 +731,44 @@ impl Default for AppData {
             dogs: Vec::new(),
             daily_data: HashMap::new(),
             recurring_schedules: Vec::new(),
+            areas: Vec::new(),
+            staff: Vec::new(),
+            time_clock: Vec::new(),
+            payments: Vec::new(),
+            loyalty_redemptions: Vec::new(),
+            api_keys: Vec::new(),
+            audit_log: Vec::new(),
+            trials: Vec::new(),
+            invoices: Vec::new(),
+            billing_runs: Vec::new(),
+            mandates: Vec::new(),
+            waitlist: Vec::new(),
+            messages: Vec::new(),
+            inbox_last_uid: 0,
+            house_foods: Vec::new(),
+            owner_food: Vec::new(),
+            feedings: Vec::new(),
+            pickup_routes: Vec::new(),
+            boarding_stays: Vec::new(),
+            kiosk: None,
+            absences: Vec::new(),
+            reminders: Vec::new(),
+            consent_signings: Vec::new(),
+            locked_days: Vec::new(),
+            tasks: Vec::new(),
+            digest_households: Vec::new(),
+            contact_preferences: Vec::new(),
+            bank_transactions: Vec::new(),
+            owners: Vec::new(),
+            packages: Vec::new(),
+            dog_photos: Vec::new(),
+            documents: Vec::new(),
+            backup_status: Vec::new(),
+            playgroups: Vec::new(),
             settings: Settings {
                 business_name: "Your Doggy Daycare".to_string(),
                 business_phone: "".to_string(),
+                business_address: "".to_string(),
                 auto_backup: true,
                 cloud_backup: None,
                 email_templates: EmailTemplate {
@@ -221,6 +780,30 @@ impl Default for AppData {
                     vaccine_reminder: "Vaccine Record Update Required - {dogName}".to_string(),
                 },
                 whatsapp_templates: default_whatsapp_templates(),
+                currency: CurrencySettings::default(),
+                loyalty: LoyaltySettings::default(),
+                auto_lock_minutes: default_auto_lock_minutes(),
+                opening_hours: OpeningHours::default(),
+                pricing: PriceList::default(),
+                billing: BillingSettings::default(),
+                direct_debit: DirectDebitSettings::default(),
+                capacity: CapacitySettings::default(),
+                imap: ImapSettings::default(),
+                smtp: SmtpSettings::default(),
+                consent_requests: ConsentRequestSettings::default(),
+                age_classes: AgeClassSettings::default(),
+                intact_policy: IntactPolicy::default(),
+                restricted_breeds: Vec::new(),
+                require_assessment: false,
+                branding: Branding::default(),
+                boarding: BoardingSettings::default(),
+                notifications: NotificationSettings::default(),
+                digest: DigestSettings::default(),
+                intake: IntakeSettings::default(),
+                booking_notices: BookingNoticeSettings::default(),
+                default_times: DefaultTimes::default(),
+                fiscal: FiscalSettings::default(),
+                accounting: AccountingSettings::default(),
             },
         }
     }
@@ -288,9 +871,177 @@ fn get_app_data_path() -> Result<PathBuf, String> {
     Ok(path)
 }
 
+/// The data as last read from or written to the data file, with the file's
+/// modification time and size at that point. Commands use it instead of
+/// re-reading and re-parsing the whole file; if the file has changed since
+/// (another instance, a restore), it's read again.
+struct CachedAppData {
+    modified: SystemTime,
+    len: u64,
+    data: AppData,
+}
+
+static APP_DATA_CACHE: Mutex<Option<CachedAppData>> = Mutex::new(None);
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn cache_app_data(path: &Path, data: &AppData) {
+    let cached = file_stamp(path).map(|(modified, len)| CachedAppData { modified, len, data: data.clone() });
+    if let Ok(mut cache) = APP_DATA_CACHE.lock() {
+        *cache = cached;
+    }
+}
+
+fn cached_app_data(path: &Path) -> Option<AppData> {
+    let (modified, len) = file_stamp(path)?;
+    let cache = APP_DATA_CACHE.lock().ok()?;
+    cache.as_ref()
+        .filter(|c| c.modified == modified && c.len == len)
+        .map(|c| c.data.clone())
+}
+
+/// Move the cached data out for a change, rather than copying it. Readers
+/// meanwhile read the file, which still holds the last saved state.
+fn take_cached_app_data(path: &Path) -> Option<AppData> {
+    let (modified, len) = file_stamp(path)?;
+    let mut cache = APP_DATA_CACHE.lock().ok()?;
+    match cache.take() {
+        Some(c) if c.modified == modified && c.len == len => Some(c.data),
+        _ => None,
+    }
+}
+
+/// Held for the whole of a load, change and save, so two commands or a
+/// command and a background job can't each save over the other's changes.
+static APP_DATA_WRITE: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    // Set while this thread holds APP_DATA_WRITE, so a command that calls
+    // another command doesn't wait on itself
+    static HOLDS_APP_DATA_WRITE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The data, locked for changing until dropped. Saving it with save_app_data
+/// works as for a loaded copy; nothing else can change the data meanwhile.
+pub struct AppDataGuard {
+    data: AppData,
+    stamp: Option<(SystemTime, u64)>, // The data file's, when the data was loaded
+    changed: bool, // Borrowed mutably, so it may differ from the file
+    lock: Option<MutexGuard<'static, ()>>, // None when this thread already held the lock
+}
+
+impl Deref for AppDataGuard {
+    type Target = AppData;
+    
+    fn deref(&self) -> &AppData {
+        &self.data
+    }
+}
+
+impl DerefMut for AppDataGuard {
+    fn deref_mut(&mut self) -> &mut AppData {
+        self.changed = true;
+        &mut self.data
+    }
+}
+
+impl Drop for AppDataGuard {
+    fn drop(&mut self) {
+        // Data that was only read goes back in the cache, as long as the file
+        // is still what it was loaded from
+        if !self.changed {
+            if let (Ok(path), Some(stamp)) = (get_app_data_path(), self.stamp) {
+                if file_stamp(&path) == Some(stamp) {
+                    if let Ok(mut cache) = APP_DATA_CACHE.lock() {
+                        if cache.is_none() {
+                            let (modified, len) = stamp;
+                            *cache = Some(CachedAppData { modified, len, data: std::mem::take(&mut self.data) });
+                        }
+                    }
+                }
+            }
+        }
+        if self.lock.is_some() {
+            HOLDS_APP_DATA_WRITE.with(|held| held.set(false));
+        }
+    }
+}
+
+/// Take the write lock, unless this thread already has it.
+fn write_lock() -> Option<MutexGuard<'static, ()>> {
+    if HOLDS_APP_DATA_WRITE.with(|held| held.get()) {
+        return None;
+    }
+    // A panic mid-change leaves the file as last saved, so the lock is still good
+    let guard = APP_DATA_WRITE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    HOLDS_APP_DATA_WRITE.with(|held| held.set(true));
+    Some(guard)
+}
+
+/// Load the data to change it, waiting for any change in progress elsewhere
+/// to be saved first. Commands that save should load through this.
+fn lock_app_data() -> Result<AppDataGuard, String> {
+    let lock = write_lock();
+    let path = get_app_data_path()?;
+    let data = match take_cached_app_data(&path) {
+        Some(data) => data,
+        None => load_app_data()?,
+    };
+    Ok(AppDataGuard { data, stamp: file_stamp(&path), changed: false, lock })
+}
+
+/// Save data that replaces everything, e.g. an import or a restored backup,
+/// once any change in progress has been saved.
+fn replace_app_data(data: &AppData) -> Result<(), String> {
+    let lock = write_lock();
+    let result = save_app_data(data);
+    if lock.is_some() {
+        HOLDS_APP_DATA_WRITE.with(|held| held.set(false));
+    }
+    result
+}
+
+/// Change the data and save it, with nothing else able to change it in
+/// between. Nothing is saved if `change` fails.
+pub fn with_app_data_mut<T>(change: impl FnOnce(&mut AppData) -> Result<T, String>) -> Result<T, String> {
+    let mut data = lock_app_data()?;
+    let result = change(&mut data)?;
+    save_app_data(&data)?;
+    Ok(result)
+}
+
+/// Read the data in place, without copying it. `read` mustn't load or save
+/// the data itself.
+pub fn with_app_data<T>(read: impl FnOnce(&AppData) -> T) -> Result<T, String> {
+    let path = get_app_data_path()?;
+    if let Some((modified, len)) = file_stamp(&path) {
+        let cache = APP_DATA_CACHE.lock().map_err(|_| "App data is unavailable".to_string())?;
+        if let Some(cached) = cache.as_ref().filter(|c| c.modified == modified && c.len == len) {
+            return Ok(read(&cached.data));
+        }
+    }
+    Ok(read(&load_app_data()?))
+}
+
+/// What's currently in the data file, without creating or migrating it.
+fn saved_app_data() -> Option<AppData> {
+    let path = get_app_data_path().ok()?;
+    cached_app_data(&path).or_else(|| {
+        let content = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&data_encryption::decrypt_data(&content).ok()?).ok()
+    })
+}
+
 fn load_app_data() -> Result<AppData, String> {
     let path = get_app_data_path()?;
     
+    if let Some(data) = cached_app_data(&path) {
+        return Ok(data);
+    }
+    
     println!("Loading app data from: {:?}", path);
     
     if !path.exists() {
@@ -313,78 +1064,90 @@ fn load_app_data() -> Result<AppData, String> {
         return Ok(default_data);
     }
     
+    let content = data_encryption::decrypt_data(&content)?;
     println!("Parsing data file content");
     
     // Try to parse normally first
     match serde_json::from_str::<AppData>(&content) {
-        Ok(data) => {
+        Ok(mut data) => {
             println!("Successfully parsed data file");
+            // Data from before owners existed gets them on first load, and
+            // entries filed under a stale key are moved to the right one
+            let linked = owners::link_owners(&mut data);
+            let rekeyed = attendance_keys::rekey_entries(&mut data);
+            if linked || rekeyed {
+                if let Err(e) = save_app_data_past_day_locks(&mut data, "Updating the data format") {
+                    println!("Failed to save migrated data: {}", e);
+                }
+            }
+            cache_app_data(&path, &data);
             Ok(data)
         },
         Err(e) => {
             println!("Failed to parse data file, attempting migration: {}", e);
-            
-            // Try to parse as a generic JSON value to perform migration
-            match serde_json::from_str::<serde_json::Value>(&content) {
-                Ok(mut json_data) => {
-                    println!("Successfully parsed as JSON, performing migration");
-                    
-                    // Migrate settings if needed
-                    if let Some(settings) = json_data.get_mut("settings") {
-                        migrate_settings(settings);
-                    }
-                    
-                    // Migrate dogs if needed
-                    if let Some(dogs) = json_data.get_mut("dogs") {
-                        migrate_dogs(dogs);
-                    }
-                    
-                    // Add recurring schedules if missing
-                    if !json_data.get("recurring_schedules").is_some() {
-                        println!("Adding missing recurring_schedules field");
-                        json_data["recurring_schedules"] = serde_json::Value::Array(vec![]);
-                    }
-                    
-                    // Migrate daily attendance data
-                    if let Some(daily_data) = json_data.get_mut("daily_data") {
-                        migrate_daily_data(daily_data);
-                    }
-                    
-                    // Try to parse the migrated data
-                    match serde_json::from_value::<AppData>(json_data) {
-                        Ok(migrated_data) => {
-                            println!("Successfully migrated data, saving updated version");
-                            // Save the migrated data to update the file
-                            save_app_data(&migrated_data)?;
-                            Ok(migrated_data)
-                        },
-                        Err(migration_error) => {
-                            println!("Migration failed: {}", migration_error);
-                            Err(format!("Failed to migrate data: {}", migration_error))
-                        }
-                    }
-                },
-                Err(json_error) => {
-                    println!("Failed to parse as JSON: {}", json_error);
-                    Err(format!("Failed to parse data file: {}", e))
-                }
-            }
+            let mut migrated_data = migrate_app_data(&content)
+                .map_err(|e| format!("The data file is damaged and needs recovering: {}", e))?;
+            owners::link_owners(&mut migrated_data);
+            attendance_keys::rekey_entries(&mut migrated_data);
+            println!("Successfully migrated data, saving updated version");
+            // Save the migrated data to update the file
+            save_app_data(&migrated_data)?;
+            Ok(migrated_data)
         }
     }
 }
 
-fn migrate_settings(settings: &mut serde_json::Value) {
-    println!("Migrating settings");
+/// Parse data saved by an older version, bringing its shape up to date. Used
+/// for the data file and for backups and exports being restored.
+fn migrate_app_data(content: &str) -> Result<AppData, String> {
+    // Try to parse as a generic JSON value to perform migration
+    let mut json_data = serde_json::from_str::<serde_json::Value>(content)
+        .map_err(|json_error| {
+            println!("Failed to parse as JSON: {}", json_error);
+            format!("Failed to parse data file: {}", json_error)
+        })?;
+    println!("Successfully parsed as JSON, performing migration");
     
-    // Add business_phone if missing
-    if !settings.get("business_phone").is_some() {
-        println!("Adding missing business_phone field");
-        settings["business_phone"] = serde_json::Value::String("".to_string());
+    // Migrate settings if needed
+    if let Some(settings) = json_data.get_mut("settings") {
+        migrate_settings(settings);
     }
     
-    // Add whatsapp_templates if missing
-    if !settings.get("whatsapp_templates").is_some() {
-        println!("Adding missing whatsapp_templates field");
+    // Migrate dogs if needed
+    if let Some(dogs) = json_data.get_mut("dogs") {
+        migrate_dogs(dogs);
+    }
+    
+    // Add recurring schedules if missing
+    if !json_data.get("recurring_schedules").is_some() {
+        println!("Adding missing recurring_schedules field");
+        json_data["recurring_schedules"] = serde_json::Value::Array(vec![]);
+    }
+    
+    // Migrate daily attendance data
+    if let Some(daily_data) = json_data.get_mut("daily_data") {
+        migrate_daily_data(daily_data);
+    }
+    
+    // Try to parse the migrated data
+    serde_json::from_value::<AppData>(json_data).map_err(|migration_error| {
+        println!("Migration failed: {}", migration_error);
+        format!("Failed to migrate data: {}", migration_error)
+    })
+}
+
+fn migrate_settings(settings: &mut serde_json::Value) {
+    println!("Migrating settings");
+    
+    // Add business_phone if missing
+    if !settings.get("business_phone").is_some() {
+        println!("Adding missing business_phone field");
+        settings["business_phone"] = serde_json::Value::String("".to_string());
+    }
+    
+    // Add whatsapp_templates if missing
+    if !settings.get("whatsapp_templates").is_some() {
+        println!("Adding missing whatsapp_templates field");
         settings["whatsapp_templates"] = serde_json::json!({
             "consent_form": "Hi {ownerName}! 🐕 This is a friendly reminder that {dogName} needs their monthly consent form completed for continued daycare services. Please complete it at your earliest convenience. Thanks!",
             "vaccine_reminder": "Hi {ownerName}! 🐕 Just a reminder that {dogName}'s {vaccineType} vaccination expires on {expirationDate}. Please update their vaccination records to continue daycare services. Thanks!"
@@ -451,12 +1214,13 @@ fn migrate_daily_data(daily_data: &mut serde_json::Value) {
                             
                             for (dog_id, attending) in dogs_map {
                                 if attending.as_bool().unwrap_or(false) {
-                                    let entry_key = format!("{}_Daycare", dog_id);
+                                    let entry_key = entry_key(dog_id, &ServiceType::Daycare);
                                     let mut entry = serde_json::json!({
                                         "dog_id": dog_id,
                                         "service_type": "Daycare",
                                         "attending": true,
-                                        "notes": "Migrated from legacy attendance"
+                                        "notes": "Migrated from legacy attendance",
+                                        "source": "manual"
                                     });
                                     
                                     // Add times from daily records if available
@@ -479,6 +1243,17 @@ fn migrate_daily_data(daily_data: &mut serde_json::Value) {
                         }
                     }
                 }
+                
+                // Entries from before sources were recorded
+                if let Some(entries) = attendance.get_mut("entries").and_then(|e| e.as_object_mut()) {
+                    for entry in entries.values_mut() {
+                        if entry.get("source").is_none() {
+                            let notes = entry.get("notes").and_then(|n| n.as_str()).unwrap_or("");
+                            let source = EntrySource::from_legacy_notes(notes);
+                            entry["source"] = serde_json::to_value(source).unwrap_or_default();
+                        }
+                    }
+                }
             }
         }
     }
@@ -487,7 +1262,7 @@ fn migrate_daily_data(daily_data: &mut serde_json::Value) {
 }
 
 fn generate_schedules_for_dog(data: &mut AppData, dog: &Dog) -> Result<(), String> {
-    if !dog.schedule.active {
+    if !dog.schedule.active || dog.status != DogStatus::Active {
         return Ok(());
     }
     
@@ -550,6 +1325,148 @@ fn generate_schedules_for_dog(data: &mut AppData, dog: &Dog) -> Result<(), Strin
     Ok(())
 }
 
+fn same_schedule(a: &RecurringSchedule, b: &RecurringSchedule) -> bool {
+    a.service_type == b.service_type
+        && a.pattern == b.pattern
+        && a.start_date == b.start_date
+        && a.end_date.as_deref().unwrap_or("") == b.end_date.as_deref().unwrap_or("")
+        && a.drop_off_time == b.drop_off_time
+        && a.pick_up_time == b.pick_up_time
+        && a.active == b.active
+}
+
+/// Whether a schedule books the dog in on a date.
+fn schedule_applies_on(schedule: &RecurringSchedule, date: NaiveDate) -> bool {
+    let start = match NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d") {
+        Ok(start) => start,
+        Err(_) => return false,
+    };
+    let ended = schedule.end_date.as_ref()
+        .filter(|e| !e.is_empty())
+        .and_then(|e| NaiveDate::parse_from_str(e, "%Y-%m-%d").ok())
+        .is_some_and(|end| date > end);
+    schedule.active && date >= start && !ended && should_generate_attendance(date, start, &schedule.pattern)
+}
+
+/// Replace a dog's recurring schedules with the ones its edited weekly schedule
+/// gives, touching as little future attendance as possible. Schedules that
+/// haven't changed are kept as they are. Future entries the old schedules
+/// generated are updated or removed only where the new schedules disagree;
+/// bookings made or edited by hand are never touched. Returns whether the
+/// schedules changed, in which case the caller should generate attendance to
+/// fill in newly scheduled days.
+fn reconcile_dog_schedules(data: &mut AppData, dog: &Dog) -> Result<bool, String> {
+    let (old, others): (Vec<RecurringSchedule>, Vec<RecurringSchedule>) = std::mem::take(&mut data.recurring_schedules)
+        .into_iter()
+        .partition(|s| s.dog_id == dog.id);
+    data.recurring_schedules = others;
+    generate_schedules_for_dog(data, dog)?;
+    
+    // Keep existing schedules (and their ids) where nothing changed
+    let mut unchanged = 0;
+    for schedule in data.recurring_schedules.iter_mut().filter(|s| s.dog_id == dog.id) {
+        if let Some(existing) = old.iter().find(|o| same_schedule(o, schedule)) {
+            *schedule = existing.clone();
+            unchanged += 1;
+        }
+    }
+    let new_count = data.recurring_schedules.iter().filter(|s| s.dog_id == dog.id).count();
+    if unchanged == old.len() && unchanged == new_count {
+        return Ok(false);
+    }
+    
+    let schedules: Vec<RecurringSchedule> = data.recurring_schedules.iter()
+        .filter(|s| s.dog_id == dog.id)
+        .cloned()
+        .collect();
+    let today = Utc::now().date_naive();
+    
+    for (date_str, day_data) in data.daily_data.iter_mut() {
+        if data.locked_days.iter().any(|l| l.date == *date_str) {
+            continue;
+        }
+        let date = match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+            Ok(date) if date >= today => date,
+            _ => continue,
+        };
+        
+        let mut removed_daycare = false;
+        day_data.attendance.entries.retain(|_, entry| {
+            if entry.dog_id != dog.id || entry.source != EntrySource::AutoSchedule {
+                return true;
+            }
+            let schedule = schedules.iter()
+                .find(|s| s.service_type == entry.service_type && schedule_applies_on(s, date));
+            match schedule {
+                Some(schedule) => {
+                    if entry.drop_off_time != schedule.drop_off_time || entry.pick_up_time != schedule.pick_up_time {
+                        entry.drop_off_time = schedule.drop_off_time.clone();
+                        entry.pick_up_time = schedule.pick_up_time.clone();
+                    }
+                    true
+                }
+                None => {
+                    removed_daycare |= entry.service_type == ServiceType::Daycare;
+                    false
+                }
+            }
+        });
+        
+        // Keep the legacy attendance format and daily record times in step
+        let daycare = day_data.attendance.entries.get(&entry_key(&dog.id, &ServiceType::Daycare));
+        match daycare {
+            Some(entry) if entry.source == EntrySource::AutoSchedule
+                && (entry.drop_off_time.is_some() || entry.pick_up_time.is_some()) => {
+                let record = day_data.records.entry(dog.id.clone()).or_default();
+                record.drop_off_time = entry.drop_off_time.clone();
+                record.pick_up_time = entry.pick_up_time.clone();
+            }
+            None if removed_daycare => {
+                day_data.attendance.dogs.remove(&dog.id);
+            }
+            _ => {}
+        }
+    }
+    
+    Ok(true)
+}
+
+/// Date range (inclusive, YYYY-MM-DD) over which attendance is generated for a dog's
+/// schedule: from the earlier of today or the schedule start, to at least 30 days out.
+fn schedule_generation_window(dog: &Dog) -> (String, String) {
+    let today = Utc::now().date_naive();
+    let start_date_for_generation = if let Some(ref schedule_start) = dog.schedule.start_date {
+        if schedule_start.is_empty() {
+            today
+        } else {
+            // Use the earlier of today or schedule start
+            let schedule_start_date = NaiveDate::parse_from_str(schedule_start, "%Y-%m-%d")
+                .unwrap_or(today);
+            std::cmp::min(today, schedule_start_date)
+        }
+    } else {
+        today
+    };
+    
+    let end_date_for_generation = if let Some(ref schedule_end) = dog.schedule.end_date {
+        if schedule_end.is_empty() {
+            today + chrono::Duration::days(30)
+        } else {
+            // Use schedule end date, but at least 30 days from today
+            let schedule_end_date = NaiveDate::parse_from_str(schedule_end, "%Y-%m-%d")
+                .unwrap_or(today + chrono::Duration::days(30));
+            std::cmp::max(today + chrono::Duration::days(30), schedule_end_date)
+        }
+    } else {
+        today + chrono::Duration::days(30)
+    };
+    
+    (
+        start_date_for_generation.format("%Y-%m-%d").to_string(),
+        end_date_for_generation.format("%Y-%m-%d").to_string(),
+    )
+}
+
 fn calculate_age_from_birth_date(date_of_birth: &str) -> Result<String, String> {
     let birth_date = NaiveDate::parse_from_str(date_of_birth, "%Y-%m-%d")
         .map_err(|_| "Invalid date format. Expected YYYY-MM-DD".to_string())?;
@@ -605,8 +1522,27 @@ fn add_recurring_schedule(
     end_date: Option<String>,
     drop_off_time: Option<String>,
     pick_up_time: Option<String>,
-) -> Result<RecurringSchedule, String> {
-    let mut data = load_app_data()?;
+) -> Result<WithWarnings<RecurringSchedule>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    let drop_off_time = times::normalize_optional(drop_off_time, "drop-off time")?;
+    let pick_up_time = times::normalize_optional(pick_up_time, "pick-up time")?;
+    
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    if dog.status != DogStatus::Active {
+        return Err(format!("{} needs to pass a trial day before recurring schedules can be added", dog.name));
+    }
+    
+    let warnings = opening_hours::validate_schedule_times(
+        &data,
+        &pattern,
+        &start_date,
+        &service_type,
+        drop_off_time.as_deref(),
+        pick_up_time.as_deref(),
+    )?;
     
     let schedule = RecurringSchedule {
         id: Uuid::new_v4().to_string(),
@@ -620,21 +1556,39 @@ fn add_recurring_schedule(
         active: true,
         created_at: Utc::now(),
     };
+    schedule_conflicts::check_overlaps(&data, &schedule)?;
     
     data.recurring_schedules.push(schedule.clone());
     save_app_data(&data)?;
     
-    Ok(schedule)
+    Ok(WithWarnings { record: schedule, warnings })
 }
 
 #[tauri::command]
-fn update_recurring_schedule(schedule: RecurringSchedule) -> Result<(), String> {
-    let mut data = load_app_data()?;
+fn update_recurring_schedule(mut schedule: RecurringSchedule) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    schedule.drop_off_time = times::normalize_optional(schedule.drop_off_time, "drop-off time")?;
+    schedule.pick_up_time = times::normalize_optional(schedule.pick_up_time, "pick-up time")?;
+    
+    let warnings = if schedule.active {
+        opening_hours::validate_schedule_times(
+            &data,
+            &schedule.pattern,
+            &schedule.start_date,
+            &schedule.service_type,
+            schedule.drop_off_time.as_deref(),
+            schedule.pick_up_time.as_deref(),
+        )?
+    } else {
+        Vec::new()
+    };
+    schedule_conflicts::check_overlaps(&data, &schedule)?;
     
     if let Some(index) = data.recurring_schedules.iter().position(|s| s.id == schedule.id) {
         data.recurring_schedules[index] = schedule;
         save_app_data(&data)?;
-        Ok(())
+        Ok(warnings)
     } else {
         Err("Schedule not found".to_string())
     }
@@ -642,7 +1596,8 @@ fn update_recurring_schedule(schedule: RecurringSchedule) -> Result<(), String>
 
 #[tauri::command]
 fn delete_recurring_schedule(schedule_id: String) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
     
     if let Some(index) = data.recurring_schedules.iter().position(|s| s.id == schedule_id) {
         data.recurring_schedules.remove(index);
@@ -654,7 +1609,9 @@ fn delete_recurring_schedule(schedule_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn update_detailed_attendance(
+    app: tauri::AppHandle,
     date: String,
     dog_id: String,
     service_type: ServiceType,
@@ -662,21 +1619,29 @@ fn update_detailed_attendance(
     drop_off_time: Option<String>,
     pick_up_time: Option<String>,
     notes: Option<String>,
-) -> Result<(), String> {
-    let mut data = load_app_data()?;
+) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    let drop_off_time = times::normalize_optional(drop_off_time, "drop-off time")?;
+    let pick_up_time = times::normalize_optional(pick_up_time, "pick-up time")?;
+    
+    let mut warnings = Vec::new();
+    if attending {
+        warnings = opening_hours::validate_attendance_times(
+            &data,
+            &date,
+            &service_type,
+            drop_off_time.as_deref(),
+            pick_up_time.as_deref(),
+        )?;
+        if let Some(dog) = data.dogs.iter().find(|d| d.id == dog_id) {
+            warnings.extend(breeds::booking_warnings(&data, dog));
+        }
+    }
     
-    let day_data = data.daily_data.entry(date).or_insert_with(|| DayData {
-        attendance: DayAttendance { 
-            dogs: HashMap::new(),
-            entries: HashMap::new(),
-            types: HashMap::new(),
-        },
-        records: HashMap::new(),
-        am_temp: None,
-        pm_temp: None,
-    });
+    let day_data = data.daily_data.entry(date.clone()).or_default();
     
-    let entry_key = format!("{}_{:?}", dog_id, service_type);
+    let entry_key = entry_key(&dog_id, &service_type);
     
     // Update legacy dogs field for backward compatibility (only for Daycare service)
     let is_daycare = service_type == ServiceType::Daycare;
@@ -684,17 +1649,117 @@ fn update_detailed_attendance(
         day_data.attendance.dogs.insert(dog_id.clone(), attending);
     }
     
+    // Keep the assigned handler and booking flags when staff edit times or notes
+    let existing = day_data.attendance.entries.get(&entry_key);
+    let handler_id = existing.and_then(|e| e.handler_id.clone());
+    let trial = existing.map(|e| e.trial).unwrap_or(false);
+    let awaiting_confirmation = existing.map(|e| e.awaiting_confirmation).unwrap_or(false);
+    let excused = !attending && existing.is_some_and(|e| e.excused);
+    let generated_from = existing.and_then(|e| e.generated_from.clone());
+    
     let entry = AttendanceEntry {
         dog_id: dog_id.clone(),
-        service_type,
+        service_type: service_type.clone(),
         attending,
         drop_off_time,
         pick_up_time,
         notes,
+        handler_id,
+        updated_by: permissions::current_staff_id(),
+        trial,
+        awaiting_confirmation,
+        excused,
+        generated_from,
+        source: EntrySource::Manual,
+        allergies: Vec::new(),
     };
     
+    let old = day_data.attendance.entries.get(&entry_key).cloned();
+    entry_history::record_entry_changes(day_data, old.as_ref(), &entry);
     day_data.attendance.entries.insert(entry_key, entry);
     
+    save_app_data(&data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+        date,
+        dog_id,
+        service_type: Some(service_type),
+        attending,
+    });
+    Ok(warnings)
+}
+
+/// Cancel a booking. If that frees a place on a day that was full, the first
+/// eligible dog on the waitlist is booked in and staff are notified.
+#[tauri::command]
+fn cancel_attendance(
+    app: tauri::AppHandle,
+    date: String,
+    dog_id: String,
+    service_type: ServiceType,
+) -> Result<Option<waitlist::WaitlistPromotion>, String> {
+    let mut guard = lock_app_data()?;
+    let data = &mut *guard;
+    require_permission(data, Permission::EditAttendance)?;
+    
+    let entry_key = entry_key(&dog_id, &service_type);
+    let day_data = data.daily_data.get_mut(&date)
+        .filter(|d| d.attendance.entries.get(&entry_key).is_some_and(|e| e.attending))
+        .ok_or("Dog is not booked in on this date")?;
+    
+    let was_full = capacity::is_full(&data.settings.capacity, &data.dogs, day_data, &service_type);
+    
+    if let Some(entry) = day_data.attendance.entries.get_mut(&entry_key) {
+        entry.attending = false;
+        entry.awaiting_confirmation = false;
+        entry.updated_by = permissions::current_staff_id();
+    }
+    entry_history::record_change(day_data, &dog_id, Some(&service_type), "attending", Some(true.to_string()), Some(false.to_string()));
+    if service_type == ServiceType::Daycare {
+        day_data.attendance.dogs.insert(dog_id.clone(), false);
+    }
+    
+    let promotion = if was_full {
+        waitlist::promote_from_waitlist(data, &date, &service_type)
+    } else {
+        None
+    };
+    
+    save_app_data(data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+        date: date.clone(),
+        dog_id,
+        service_type: Some(service_type.clone()),
+        attending: false,
+    });
+    if let Some(ref promotion) = promotion {
+        waitlist::emit_waitlist_promotion(&app, promotion);
+        emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+            date,
+            dog_id: promotion.dog_id.clone(),
+            service_type: Some(service_type),
+            attending: true,
+        });
+    }
+    
+    Ok(promotion)
+}
+
+/// Mark a booking made on the owner's behalf as confirmed with them.
+#[tauri::command]
+fn confirm_attendance(date: String, dog_id: String, service_type: ServiceType) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let entry = data.daily_data.get_mut(&date)
+        .and_then(|d| d.attendance.entries.get_mut(&entry_key(&dog_id, &service_type)))
+        .ok_or("Attendance entry not found")?;
+    
+    entry.awaiting_confirmation = false;
+    entry.updated_by = permissions::current_staff_id();
+    booking_notices::notify_owner(&mut data, BookingNotice::BookingConfirmed, &dog_id, &date, &service_type);
+    
     save_app_data(&data)?;
     Ok(())
 }
@@ -706,7 +1771,9 @@ fn get_attendance_for_date(date: String) -> Result<HashMap<String, AttendanceEnt
     if let Some(day_data) = data.daily_data.get(&date) {
         // Return only the modern attendance entries, no legacy data injection
         // This ensures all views see the same consistent data
-        Ok(day_data.attendance.entries.clone())
+        let mut day_data = day_data.clone();
+        medical::flag_allergies(&data, &mut day_data);
+        Ok(day_data.attendance.entries)
     } else {
         Ok(HashMap::new())
     }
@@ -772,18 +1839,41 @@ fn should_generate_attendance(
 }
 
 fn generate_recurring_attendance_internal(data: &mut AppData, start_date: &str, end_date: &str) -> Result<(), String> {
+    generate_attendance_for_schedules(data, start_date, end_date, &|_| true, &jobs::untracked(JobKind::Generation))
+}
+
+/// Generate attendance from the recurring schedules `include` accepts,
+/// reporting progress a day at a time to `job`.
+fn generate_attendance_for_schedules(
+    data: &mut AppData,
+    start_date: &str,
+    end_date: &str,
+    include: &dyn Fn(&RecurringSchedule) -> bool,
+    job: &JobContext,
+) -> Result<(), String> {
     let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d")
         .map_err(|_| "Invalid start date format".to_string())?;
     let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d")
         .map_err(|_| "Invalid end date format".to_string())?;
+    if end < start {
+        return Err("End date must not be before the start date".to_string());
+    }
+    let total_days = (end - start).num_days() as usize + 1;
 
     let mut current_date = start;
     
     while current_date <= end {
+        job.progress((current_date - start).num_days() as usize, total_days)?;
         let date_str = current_date.format("%Y-%m-%d").to_string();
         
+        // Finalized days are left exactly as they were locked
+        if day_locks::is_locked(data, &date_str) {
+            current_date = current_date.succ_opt().ok_or("Date overflow")?;
+            continue;
+        }
+        
         for schedule in &data.recurring_schedules {
-            if !schedule.active {
+            if !schedule.active || !include(schedule) {
                 continue;
             }
             
@@ -815,19 +1905,17 @@ fn generate_recurring_attendance_internal(data: &mut AppData, start_date: &str,
             println!("Date {}, Dog {}, Service {:?}: should_attend = {}",
                     date_str, schedule.dog_id, schedule.service_type, should_attend);
 
-            if should_attend {
-                let day_data = data.daily_data.entry(date_str.clone()).or_insert_with(|| DayData {
-                    attendance: DayAttendance { 
-                        dogs: HashMap::new(),
-                        entries: HashMap::new(),
-                        types: HashMap::new(),
-                    },
-                    records: HashMap::new(),
-                    am_temp: None,
-                    pm_temp: None,
-                });
+            let quarantined = data.dogs.iter()
+                .find(|d| d.id == schedule.dog_id)
+                .is_some_and(|d| quarantine::is_quarantined(d, &date_str));
+            if should_attend && absences::is_absent(&data.absences, &schedule.dog_id, &date_str) {
+                println!("Skipping {} on {}: owner has reported an absence", schedule.dog_id, date_str);
+            } else if should_attend && quarantined {
+                println!("Skipping {} on {}: dog is in quarantine", schedule.dog_id, date_str);
+            } else if should_attend {
+                let day_data = data.daily_data.entry(date_str.clone()).or_default();
                 
-                let entry_key = format!("{}_{:?}", schedule.dog_id, schedule.service_type);
+                let entry_key = entry_key(&schedule.dog_id, &schedule.service_type);
                 
                 // Only add if not already exists (don't override manual entries)
                 if !day_data.attendance.entries.contains_key(&entry_key) {
@@ -839,6 +1927,14 @@ fn generate_recurring_attendance_internal(data: &mut AppData, start_date: &str,
                         drop_off_time: schedule.drop_off_time.clone(),
                         pick_up_time: schedule.pick_up_time.clone(),
                         notes: Some("Auto-scheduled".to_string()),
+                        handler_id: None,
+                        updated_by: None,
+                        trial: false,
+                        awaiting_confirmation: false,
+                        excused: false,
+                        generated_from: Some(schedule.id.clone()),
+                        source: EntrySource::AutoSchedule,
+                        allergies: Vec::new(),
                     };
                     
                     day_data.attendance.entries.insert(entry_key, entry);
@@ -849,13 +1945,7 @@ fn generate_recurring_attendance_internal(data: &mut AppData, start_date: &str,
                         
                         // Also update daily records with times if provided
                         if schedule.drop_off_time.is_some() || schedule.pick_up_time.is_some() {
-                            let current_record = day_data.records.entry(schedule.dog_id.clone()).or_insert_with(|| DailyRecord {
-                                checklist: None,
-                                feeding_times: None,
-                                drop_off_time: None,
-                                pick_up_time: None,
-                                notes: None,
-                            });
+                            let current_record = day_data.records.entry(schedule.dog_id.clone()).or_default();
                             
                             if let Some(ref drop_off) = schedule.drop_off_time {
                                 current_record.drop_off_time = Some(drop_off.clone());
@@ -871,39 +1961,163 @@ fn generate_recurring_attendance_internal(data: &mut AppData, start_date: &str,
         
         current_date = current_date.succ_opt().ok_or("Date overflow")?;
     }
+    job.progress(total_days, total_days)?;
     
     Ok(())
 }
 
 #[tauri::command]
 fn generate_recurring_attendance(start_date: String, end_date: String) -> Result<(), String> {
-    let mut data = load_app_data()?;
-    generate_recurring_attendance_internal(&mut data, &start_date, &end_date)?;
-    save_app_data(&data)?;
-    Ok(())
+    let data = load_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    jobs::run_exclusive(JobKind::Generation, |job| {
+        let mut data = lock_app_data()?;
+        generate_attendance_for_schedules(&mut data, &start_date, &end_date, &|_| true, job)?;
+        save_app_data(&data)
+    })
+}
+
+/// Generate attendance in the background for long ranges. Returns the job id;
+/// progress arrives on "generation:progress" and the run can be stopped with
+/// `cancel_job`, in which case nothing is saved.
+#[tauri::command]
+fn start_attendance_generation(app: tauri::AppHandle, start_date: String, end_date: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    jobs::start_job(app, JobKind::Generation, move |job| {
+        let mut data = lock_app_data()?;
+        generate_attendance_for_schedules(&mut data, &start_date, &end_date, &|_| true, job)?;
+        record_audit(&mut data, "attendance_generated", &format!(
+            "Generated scheduled attendance {} to {}", start_date, end_date
+        ));
+        save_app_data(&data)
+    })
 }
 
 #[tauri::command]
 fn clear_auto_generated_attendance() -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
     
-    for (_date, day_data) in data.daily_data.iter_mut() {
+    // Finalized days keep their entries
+    let locked: Vec<String> = data.locked_days.iter().map(|l| l.date.clone()).collect();
+    for (_date, day_data) in data.daily_data.iter_mut().filter(|(date, _)| !locked.contains(date)) {
         // Remove entries that were auto-generated from schedules
+        day_data.attendance.entries.retain(|_, entry| entry.source != EntrySource::AutoSchedule);
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Which schedule-generated entries `regenerate_attendance` rebuilds; fields
+/// left out match everything.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RegenerationScope {
+    pub dog_id: Option<String>,
+    pub service_type: Option<ServiceType>,
+}
+
+impl RegenerationScope {
+    fn matches(&self, dog_id: &str, service_type: &ServiceType) -> bool {
+        self.dog_id.as_deref().is_none_or(|d| d == dog_id)
+            && self.service_type.as_ref().is_none_or(|s| s == service_type)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RegenerationReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub removed: usize,
+    pub created: usize,
+}
+
+/// Rebuild schedule-generated attendance from today over the next `horizon_days`
+/// days for the dogs and services in scope. Bookings made or edited by hand and
+/// locked days are left alone.
+#[tauri::command]
+fn regenerate_attendance(horizon_days: u32, scope: Option<RegenerationScope>) -> Result<RegenerationReport, String> {
+    jobs::run_exclusive(JobKind::Generation, |job| regenerate_attendance_internal(horizon_days, scope, job))
+}
+
+fn regenerate_attendance_internal(
+    horizon_days: u32,
+    scope: Option<RegenerationScope>,
+    job: &JobContext,
+) -> Result<RegenerationReport, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if horizon_days == 0 || horizon_days > 365 {
+        return Err("Horizon must be between 1 and 365 days".to_string());
+    }
+    let scope = scope.unwrap_or_default();
+    if let Some(ref dog_id) = scope.dog_id {
+        if !data.dogs.iter().any(|d| d.id == *dog_id) {
+            return Err("Dog not found".to_string());
+        }
+    }
+    
+    let today = Utc::now().date_naive();
+    let start_date = today.format("%Y-%m-%d").to_string();
+    let end_date = (today + chrono::Duration::days(horizon_days as i64 - 1)).format("%Y-%m-%d").to_string();
+    let in_scope = |date: &str, entry: &AttendanceEntry| {
+        date >= start_date.as_str() && date <= end_date.as_str()
+            && entry.source == EntrySource::AutoSchedule
+            && scope.matches(&entry.dog_id, &entry.service_type)
+    };
+    let count = |data: &AppData| data.daily_data.iter()
+        .filter(|(date, _)| !day_locks::is_locked(data, date))
+        .map(|(date, d)| d.attendance.entries.values().filter(|e| in_scope(date, e)).count())
+        .sum::<usize>();
+    
+    let removed = count(&data);
+    let locked: Vec<String> = data.locked_days.iter().map(|l| l.date.clone()).collect();
+    for (date, day_data) in data.daily_data.iter_mut().filter(|(date, _)| !locked.contains(date)) {
+        let mut removed_daycare = Vec::new();
         day_data.attendance.entries.retain(|_, entry| {
-            // Keep entries that don't have auto-generated notes
-            !entry.notes.as_ref().map_or(false, |n|
-                n.contains("Auto-generated") ||
-                n.contains("Scheduled (not confirmed)") ||
-                n.contains("Auto-scheduled")
-            )
+            if !in_scope(date, entry) {
+                return true;
+            }
+            if entry.service_type == ServiceType::Daycare {
+                removed_daycare.push(entry.dog_id.clone());
+            }
+            false
         });
+        for dog_id in removed_daycare {
+            day_data.attendance.dogs.remove(&dog_id);
+        }
     }
     
+    generate_attendance_for_schedules(&mut data, &start_date, &end_date, &|s| scope.matches(&s.dog_id, &s.service_type), job)?;
+    let created = count(&data);
+    
+    record_audit(&mut data, "attendance_regenerated", &format!(
+        "Regenerated scheduled attendance {} to {}: {} removed, {} created",
+        start_date, end_date, removed, created
+    ));
     save_app_data(&data)?;
-    Ok(())
+    
+    Ok(RegenerationReport { start_date, end_date, removed, created })
 }
 
 fn save_app_data(data: &AppData) -> Result<(), String> {
+    day_locks::ensure_locked_days_unchanged(data)?;
+    write_app_data(data)
+}
+
+/// Save a change that may touch locked days: removing a dog from everywhere,
+/// or bringing stored data up to date. The locked days it changes are audited.
+fn save_app_data_past_day_locks(data: &mut AppData, reason: &str) -> Result<(), String> {
+    day_locks::audit_locked_day_changes(data, reason)?;
+    write_app_data(data)
+}
+
+fn write_app_data(data: &AppData) -> Result<(), String> {
+    instance::ensure_writable()?;
     let path = get_app_data_path()?;
     
     println!("Saving app data to: {:?}", path);
@@ -914,16 +2128,21 @@ fn save_app_data(data: &AppData) -> Result<(), String> {
             format!("Failed to serialize data: {}", e)
         })?;
     
+    let content = data_encryption::encrypt_data(content)?;
     fs::write(&path, content)
         .map_err(|e| {
             println!("Failed to write data file: {}", e);
             format!("Failed to write data file: {}", e)
-        })
+        })?;
+    cache_app_data(&path, data);
+    Ok(())
 }
 
 #[tauri::command]
 fn get_all_dogs() -> Result<Vec<Dog>, String> {
-    let data = load_app_data()?;
+    let mut data = load_app_data()?;
+    age::classify_dogs(&mut data.dogs, &data.settings.age_classes, Utc::now().date_naive());
+    breeds::group_dogs(&mut data.dogs);
     Ok(data.dogs)
 }
 
@@ -943,7 +2162,7 @@ fn test_parameter_names(household_id: Option<String>, family_id: Option<String>,
 }
 
 #[tauri::command]
-fn add_dog(name: String, owner: String, phone: String, email: String, breed: String, dateOfBirth: Option<String>, vaccineDate: Option<String>, schedule: Option<DogSchedule>, householdId: String) -> Result<Dog, String> {
+fn add_dog(name: String, owner: String, phone: String, email: String, breed: String, dateOfBirth: Option<String>, vaccineDate: Option<String>, schedule: Option<DogSchedule>, householdId: String) -> Result<WithWarnings<Dog>, String> {
     println!("Backend add_dog called with:");
     println!("  name: {:?}", name);
     println!("  owner: {:?}", owner);
@@ -954,9 +2173,12 @@ fn add_dog(name: String, owner: String, phone: String, email: String, breed: Str
     println!("  vaccineDate: {:?}", vaccineDate);
     println!("  schedule: {:?}", schedule.as_ref().map(|_| "Some(DogSchedule)"));
     println!("  householdId: {:?}", householdId);
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
     
-    let dog_schedule = schedule.unwrap_or_default();
+    let mut dog_schedule = schedule.unwrap_or_default();
+    times::normalize_dog_schedule(&mut dog_schedule)?;
+    let warnings = opening_hours::validate_dog_schedule(&data, &dog_schedule);
     let has_schedule = dog_schedule.active && (
         !dog_schedule.daycare_days.is_empty() ||
         !dog_schedule.training_days.is_empty() ||
@@ -966,20 +2188,40 @@ fn add_dog(name: String, owner: String, phone: String, email: String, breed: Str
     println!("Dog schedule: active={}, daycare_days={:?}, has_schedule={}",
              dog_schedule.active, dog_schedule.daycare_days, has_schedule);
     
-    let dog = Dog {
+    let mut dog = Dog {
         id: Uuid::new_v4().to_string(),
         name,
         owner,
         phone,
         email,
-        breed,
+        breed: breeds::normalize_breed(&breed),
         date_of_birth: dateOfBirth,
         vaccine_date: vaccineDate,
+        vaccinations: Vec::new(),
         consent_last_signed: None,
         created_at: Utc::now(),
         schedule: dog_schedule,
         household_id: if householdId.is_empty() { None } else { Some(householdId) },
+        owner_id: None,
+        status: if data.settings.require_assessment { DogStatus::Trial } else { DogStatus::Active },
+        incompatible_dog_ids: Vec::new(),
+        solo_care: false,
+        size: None,
+        neuter_status: NeuterStatus::Unknown,
+        photo_path: None,
+        vet: None,
+        microchip_number: None,
+        allergies: Vec::new(),
+        insurance: None,
+        van_pickup: None,
+        quarantine: None,
+        intake: None,
+        age_class: None,
+        breed_group: None,
     };
+    dog.age_class = age::age_class_on(&dog, &data.settings.age_classes, Utc::now().date_naive());
+    dog.breed_group = breeds::breed_group(&dog.breed);
+    dog.owner_id = Some(owners::owner_for(&mut data, &dog));
     data.dogs.push(dog.clone());
     
     // Auto-generate recurring schedules for this dog
@@ -991,35 +2233,7 @@ fn add_dog(name: String, owner: String, phone: String, email: String, breed: Str
         println!("Schedules created: {} -> {} (+{})", schedules_before, schedules_after, schedules_after - schedules_before);
         
         // Generate attendance for the dog's schedule period
-        let today = Utc::now().date_naive();
-        let start_date_for_generation = if let Some(ref schedule_start) = dog.schedule.start_date {
-            if schedule_start.is_empty() {
-                today
-            } else {
-                // Use the earlier of today or schedule start
-                let schedule_start_date = NaiveDate::parse_from_str(schedule_start, "%Y-%m-%d")
-                    .unwrap_or(today);
-                std::cmp::min(today, schedule_start_date)
-            }
-        } else {
-            today
-        };
-        
-        let end_date_for_generation = if let Some(ref schedule_end) = dog.schedule.end_date {
-            if schedule_end.is_empty() {
-                today + chrono::Duration::days(30)
-            } else {
-                // Use schedule end date, but at least 30 days from today
-                let schedule_end_date = NaiveDate::parse_from_str(schedule_end, "%Y-%m-%d")
-                    .unwrap_or(today + chrono::Duration::days(30));
-                std::cmp::max(today + chrono::Duration::days(30), schedule_end_date)
-            }
-        } else {
-            today + chrono::Duration::days(30)
-        };
-        
-        let start_str = start_date_for_generation.format("%Y-%m-%d").to_string();
-        let end_str = end_date_for_generation.format("%Y-%m-%d").to_string();
+        let (start_str, end_str) = schedule_generation_window(&dog);
         println!("Generating attendance from {} to {} for {} schedules", start_str, end_str, data.recurring_schedules.len());
         generate_recurring_attendance_internal(&mut data, &start_str, &end_str)?;
         println!("Finished generating attendance");
@@ -1027,23 +2241,27 @@ fn add_dog(name: String, owner: String, phone: String, email: String, breed: Str
 
     save_app_data(&data)?;
     
-    Ok(dog)
+    Ok(WithWarnings { record: dog, warnings })
 }
 
 fn clear_future_attendance_for_dog(data: &mut AppData, dog_id: &str) -> Result<(), String> {
     let today = Utc::now().date_naive();
     
-    // Remove attendance entries for this dog from today onwards
+    // Remove schedule-generated attendance for this dog from today onwards;
+    // bookings staff made by hand, imports and waitlist promotions stay
     data.daily_data.retain(|date_str, day_data| {
         if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
             if date >= today {
-                // Remove all attendance entries for this dog
-                day_data.attendance.entries.retain(|key, _| {
-                    !key.starts_with(&format!("{}_", dog_id))
+                day_data.attendance.entries.retain(|_, entry| {
+                    entry.dog_id != dog_id || entry.source != EntrySource::AutoSchedule
                 });
                 
-                // Also remove from legacy attendance format
-                day_data.attendance.dogs.remove(dog_id);
+                // Keep the legacy attendance format in step
+                let still_booked = day_data.attendance.entries.values()
+                    .any(|e| e.dog_id == dog_id && e.service_type == ServiceType::Daycare && e.attending);
+                if !still_booked {
+                    day_data.attendance.dogs.remove(dog_id);
+                }
             }
         }
         true
@@ -1053,56 +2271,37 @@ fn clear_future_attendance_for_dog(data: &mut AppData, dog_id: &str) -> Result<(
 }
 
 #[tauri::command]
-fn update_dog(dog: Dog) -> Result<(), String> {
-    let mut data = load_app_data()?;
+fn update_dog(mut dog: Dog) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    times::normalize_dog_times(&mut dog)?;
+    let warnings = opening_hours::validate_dog_schedule(&data, &dog.schedule);
+    dog.breed = breeds::normalize_breed(&dog.breed);
+    medical::normalize_medical(&mut dog)?;
+    // Derived from the signing history once there is one
+    if let Some(signed) = consent::last_signed(&data, &dog.id) {
+        dog.consent_last_signed = Some(signed);
+    }
     
     if let Some(index) = data.dogs.iter().position(|d| d.id == dog.id) {
-        // Remove old schedules for this dog
-        data.recurring_schedules.retain(|s| s.dog_id != dog.id);
-        
-        // Clear all future attendance for this dog
-        clear_future_attendance_for_dog(&mut data, &dog.id)?;
+        // Quarantine, intake answers and vaccinations are only changed through their own commands
+        dog.quarantine = data.dogs[index].quarantine.clone();
+        dog.intake = data.dogs[index].intake.clone();
+        dog.vaccinations = data.dogs[index].vaccinations.clone();
+        dog.owner_id = data.dogs[index].owner_id.clone();
         
         // Update dog
         data.dogs[index] = dog.clone();
+        owners::apply_contact_change(&mut data, &dog);
         
-        // Generate new schedules
-        generate_schedules_for_dog(&mut data, &dog)?;
-        
-        // Generate attendance for the dog's schedule period
-        let today = Utc::now().date_naive();
-        let start_date_for_generation = if let Some(ref schedule_start) = dog.schedule.start_date {
-            if schedule_start.is_empty() {
-                today
-            } else {
-                // Use the earlier of today or schedule start
-                let schedule_start_date = NaiveDate::parse_from_str(schedule_start, "%Y-%m-%d")
-                    .unwrap_or(today);
-                std::cmp::min(today, schedule_start_date)
-            }
-        } else {
-            today
-        };
-        
-        let end_date_for_generation = if let Some(ref schedule_end) = dog.schedule.end_date {
-            if schedule_end.is_empty() {
-                today + chrono::Duration::days(30)
-            } else {
-                // Use schedule end date, but at least 30 days from today
-                let schedule_end_date = NaiveDate::parse_from_str(schedule_end, "%Y-%m-%d")
-                    .unwrap_or(today + chrono::Duration::days(30));
-                std::cmp::max(today + chrono::Duration::days(30), schedule_end_date)
-            }
-        } else {
-            today + chrono::Duration::days(30)
-        };
-        
-        let start_str = start_date_for_generation.format("%Y-%m-%d").to_string();
-        let end_str = end_date_for_generation.format("%Y-%m-%d").to_string();
-        generate_recurring_attendance_internal(&mut data, &start_str, &end_str)?;
+        if reconcile_dog_schedules(&mut data, &dog)? {
+            // Generate attendance for the dog's schedule period
+            let (start_str, end_str) = schedule_generation_window(&dog);
+            generate_recurring_attendance_internal(&mut data, &start_str, &end_str)?;
+        }
         
         save_app_data(&data)?;
-        Ok(())
+        Ok(warnings)
     } else {
         Err("Dog not found".to_string())
     }
@@ -1110,62 +2309,55 @@ fn update_dog(dog: Dog) -> Result<(), String> {
 
 #[tauri::command]
 fn delete_dog(dog_id: String) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
     
-    if let Some(index) = data.dogs.iter().position(|d| d.id == dog_id) {
-        data.dogs.remove(index);
-        
-        // Also remove all schedules for this dog
-        data.recurring_schedules.retain(|s| s.dog_id != dog_id);
-        
-        save_app_data(&data)?;
-        Ok(())
-    } else {
-        Err("Dog not found".to_string())
-    }
+    let owner = data.dogs.iter().find(|d| d.id == dog_id).map(|d| d.owner.clone()).unwrap_or_default();
+    
+    // Also remove everything else that refers to this dog
+    let report = erasure::erase_dog_data(&mut data, &dog_id)?;
+    
+    record_audit(&mut data, "dog_deleted", &format!("Deleted {} ({})", report.dog_name, owner));
+    save_app_data_past_day_locks(&mut data, &format!("Deleting {}", report.dog_name))?;
+    erasure::remove_files(&report);
+    Ok(())
 }
 
 #[tauri::command]
 fn get_daily_data(date: String) -> Result<Option<DayData>, String> {
     let data = load_app_data()?;
-    Ok(data.daily_data.get(&date).cloned())
+    let mut day_data = data.daily_data.get(&date).cloned();
+    if let Some(day_data) = day_data.as_mut() {
+        medical::flag_allergies(&data, day_data);
+    }
+    Ok(day_data)
 }
 
 #[tauri::command]
-fn update_attendance(date: String, dog_id: String, attending: bool) -> Result<(), String> {
-    let mut data = load_app_data()?;
+fn update_attendance(app: tauri::AppHandle, date: String, dog_id: String, attending: bool) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
     
-    let day_data = data.daily_data.entry(date).or_insert_with(|| DayData {
-        attendance: DayAttendance { 
-            dogs: HashMap::new(),
-            entries: HashMap::new(),
-            types: HashMap::new(),
-        },
-        records: HashMap::new(),
-        am_temp: None,
-        pm_temp: None,
-    });
+    let day_data = data.daily_data.entry(date.clone()).or_default();
     
-    day_data.attendance.dogs.insert(dog_id, attending);
+    day_data.attendance.dogs.insert(dog_id.clone(), attending);
     save_app_data(&data)?;
     
+    emit_dashboard_update(&app, DashboardEvent::AttendanceChanged {
+        date,
+        dog_id,
+        service_type: None,
+        attending,
+    });
     Ok(())
 }
 
 #[tauri::command]
 fn update_attendance_type(date: String, dog_id: String, attendance_type: AttendanceType) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
     
-    let day_data = data.daily_data.entry(date).or_insert_with(|| DayData {
-        attendance: DayAttendance { 
-            dogs: HashMap::new(),
-            entries: HashMap::new(),
-            types: HashMap::new(),
-        },
-        records: HashMap::new(),
-        am_temp: None,
-        pm_temp: None,
-    });
+    let day_data = data.daily_data.entry(date).or_default();
     
     day_data.attendance.types.insert(dog_id, attendance_type);
     save_app_data(&data)?;
@@ -1175,19 +2367,33 @@ fn update_attendance_type(date: String, dog_id: String, attendance_type: Attenda
 
 #[tauri::command]
 fn update_daily_record(date: String, dog_id: String, record: DailyRecord) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
     
-    let day_data = data.daily_data.entry(date).or_insert_with(|| DayData {
-        attendance: DayAttendance { 
-            dogs: HashMap::new(),
-            entries: HashMap::new(),
-            types: HashMap::new(),
-        },
-        records: HashMap::new(),
-        am_temp: None,
-        pm_temp: None,
-    });
+    let record = DailyRecord {
+        drop_off_time: times::normalize_optional(record.drop_off_time, "drop-off time")?,
+        pick_up_time: times::normalize_optional(record.pick_up_time, "pick-up time")?,
+        ..record
+    };
+    let day_data = data.daily_data.entry(date).or_default();
     
+    // Belongings are managed with their own commands; the checklist form doesn't send them
+    let belongings = match day_data.records.get(&dog_id) {
+        Some(existing) if record.belongings.is_empty() => existing.belongings.clone(),
+        _ => record.belongings.clone(),
+    };
+    // Photos likewise only change through the gallery commands
+    let photos = day_data.records.get(&dog_id)
+        .map(|existing| existing.photos.clone())
+        .unwrap_or_default();
+    let record = DailyRecord {
+        updated_by: permissions::current_staff_id(),
+        belongings,
+        photos,
+        ..record
+    };
+    let old = day_data.records.get(&dog_id).cloned();
+    entry_history::record_record_changes(day_data, &dog_id, old.as_ref(), &record);
     day_data.records.insert(dog_id, record);
     save_app_data(&data)?;
     
@@ -1196,18 +2402,10 @@ fn update_daily_record(date: String, dog_id: String, record: DailyRecord) -> Res
 
 #[tauri::command]
 fn update_temperature(date: String, am_temp: Option<String>, pm_temp: Option<String>) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
     
-    let day_data = data.daily_data.entry(date).or_insert_with(|| DayData {
-        attendance: DayAttendance { 
-            dogs: HashMap::new(),
-            entries: HashMap::new(),
-            types: HashMap::new(),
-        },
-        records: HashMap::new(),
-        am_temp: None,
-        pm_temp: None,
-    });
+    let day_data = data.daily_data.entry(date).or_default();
     
     if let Some(temp) = am_temp {
         day_data.am_temp = Some(temp);
@@ -1220,6 +2418,521 @@ fn update_temperature(date: String, am_temp: Option<String>, pm_temp: Option<Str
     Ok(())
 }
 
+#[tauri::command]
+fn get_areas() -> Result<Vec<Area>, String> {
+    let data = load_app_data()?;
+    Ok(data.areas)
+}
+
+#[tauri::command]
+fn add_area(name: String, area_type: AreaType, capacity: u32) -> Result<Area, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    if name.trim().is_empty() {
+        return Err("Area name is required".to_string());
+    }
+    
+    let area = Area {
+        id: Uuid::new_v4().to_string(),
+        name,
+        area_type,
+        capacity,
+        active: true,
+        max_puppies: None,
+        created_at: Utc::now(),
+    };
+    
+    data.areas.push(area.clone());
+    save_app_data(&data)?;
+    
+    Ok(area)
+}
+
+#[tauri::command]
+fn update_area(area: Area) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    if let Some(index) = data.areas.iter().position(|a| a.id == area.id) {
+        data.areas[index] = area;
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Area not found".to_string())
+    }
+}
+
+#[tauri::command]
+fn delete_area(area_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    if let Some(index) = data.areas.iter().position(|a| a.id == area_id) {
+        data.areas.remove(index);
+        
+        // Also remove any day assignments pointing at this area
+        for day_data in data.daily_data.values_mut() {
+            day_data.area_assignments.retain(|_, assigned| *assigned != area_id);
+        }
+        for group in data.playgroups.iter_mut().filter(|g| g.area_id.as_deref() == Some(area_id.as_str())) {
+            group.area_id = None;
+        }
+        
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Area not found".to_string())
+    }
+}
+
+/// Number of dogs assigned to an area on a given day, excluding `ignore_dog_id`
+/// so that re-assigning a dog to its current area doesn't count it twice.
+fn area_headcount(day_data: &DayData, area_id: &str, ignore_dog_id: &str) -> u32 {
+    day_data.area_assignments.iter()
+        .filter(|(dog_id, assigned)| assigned.as_str() == area_id && dog_id.as_str() != ignore_dog_id)
+        .count() as u32
+}
+
+/// Refuse to put another puppy in an area that already holds its limit.
+fn check_puppy_limit(data: &AppData, area: &Area, date: &str, dog_id: &str, occupants: &[String]) -> Result<(), String> {
+    let max_puppies = match area.max_puppies {
+        Some(max_puppies) => max_puppies,
+        None => return Ok(()),
+    };
+    let on = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    let is_puppy = |id: &str| data.dogs.iter()
+        .find(|d| d.id == id)
+        .and_then(|d| age::age_class_on(d, &data.settings.age_classes, on))
+        == Some(AgeClass::Puppy);
+    
+    if is_puppy(dog_id) && occupants.iter().filter(|id| is_puppy(id)).count() as u32 >= max_puppies {
+        return Err(format!("Area '{}' already has its limit of {} puppies", area.name, max_puppies));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn assign_dog_to_area(app: tauri::AppHandle, date: String, dog_id: String, area_id: String) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let area = data.areas.iter()
+        .find(|a| a.id == area_id)
+        .cloned()
+        .ok_or("Area not found")?;
+    
+    if !area.active {
+        return Err(format!("Area '{}' is not active", area.name));
+    }
+    
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    // Kennel blocks are individual housing; the other areas are group play
+    let warnings: Vec<String> = match (&area.area_type, NaiveDate::parse_from_str(&date, "%Y-%m-%d")) {
+        (AreaType::KennelBlock, _) | (_, Err(_)) => Vec::new(),
+        (_, Ok(on)) => age::intact_warning(dog, &data.settings.intact_policy, on).into_iter().collect(),
+    };
+    
+    let occupants: Vec<String> = data.daily_data.get(&date)
+        .map(|d| d.area_assignments.iter()
+            .filter(|(other, assigned)| **assigned == area.id && **other != dog_id)
+            .map(|(other, _)| other.clone())
+            .collect())
+        .unwrap_or_default();
+    check_puppy_limit(&data, &area, &date, &dog_id, &occupants)?;
+    // Solo-care dogs stay out of group play, so they never share an area
+    if dog.solo_care && !occupants.is_empty() {
+        return Err(format!("{} needs solo care and can't share '{}' with other dogs", dog.name, area.name));
+    }
+    if let Some(solo) = data.dogs.iter().find(|d| d.solo_care && occupants.contains(&d.id)) {
+        return Err(format!("'{}' has {} in solo care, so no other dogs can join", area.name, solo.name));
+    }
+    
+    let day_data = data.daily_data.entry(date.clone()).or_default();
+    
+    if area_headcount(day_data, &area.id, &dog_id) >= area.capacity {
+        return Err(format!("Area '{}' is full ({} dogs)", area.name, area.capacity));
+    }
+    
+    day_data.area_assignments.insert(dog_id, area.id.clone());
+    let assigned = area_headcount(day_data, &area.id, "");
+    save_app_data(&data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::CapacityChanged {
+        date,
+        area_id: area.id,
+        assigned,
+        capacity: area.capacity,
+    });
+    Ok(warnings)
+}
+
+#[tauri::command]
+fn unassign_dog_from_area(app: tauri::AppHandle, date: String, dog_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let area_id = match data.daily_data.get_mut(&date).and_then(|d| d.area_assignments.remove(&dog_id)) {
+        Some(area_id) => area_id,
+        None => return Ok(()),
+    };
+    save_app_data(&data)?;
+    
+    if let (Some(area), Some(day_data)) = (data.areas.iter().find(|a| a.id == area_id), data.daily_data.get(&date)) {
+        emit_dashboard_update(&app, DashboardEvent::CapacityChanged {
+            date: date.clone(),
+            area_id: area.id.clone(),
+            assigned: area_headcount(day_data, &area.id, ""),
+            capacity: area.capacity,
+        });
+    }
+    Ok(())
+}
+
+fn compute_area_occupancy(data: &AppData, date: &str) -> Vec<AreaOccupancy> {
+    let day_data = data.daily_data.get(date);
+    
+    data.areas.iter()
+        .filter(|a| a.active)
+        .map(|area| {
+            let assigned_dogs = day_data
+                .map(|d| d.area_assignments.iter()
+                    .filter(|(_, assigned)| **assigned == area.id)
+                    .map(|(dog_id, _)| dog_id.clone())
+                    .collect())
+                .unwrap_or_default();
+            
+            AreaOccupancy {
+                area_id: area.id.clone(),
+                name: area.name.clone(),
+                area_type: area.area_type.clone(),
+                capacity: area.capacity,
+                assigned_dogs,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_area_occupancy(date: String) -> Result<Vec<AreaOccupancy>, String> {
+    let data = load_app_data()?;
+    Ok(compute_area_occupancy(&data, &date))
+}
+
+/// Latest location event per dog for a day, ordered by event time and then by
+/// when it was recorded (so a correction entered later for the same time wins).
+fn latest_locations(day_data: &DayData) -> HashMap<String, LocationEvent> {
+    let mut events: Vec<&LocationEvent> = day_data.location_events.iter().collect();
+    events.sort_by(|a, b| a.time.cmp(&b.time).then(a.recorded_at.cmp(&b.recorded_at)));
+    
+    let mut latest = HashMap::new();
+    for event in events {
+        latest.insert(event.dog_id.clone(), event.clone());
+    }
+    latest
+}
+
+#[tauri::command]
+fn move_dog(app: tauri::AppHandle, date: String, dog_id: String, area_id: String, time: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let time = times::normalize_time(&time)
+        .ok_or("Invalid time format. Expected HH:MM")?;
+    
+    let area = data.areas.iter()
+        .find(|a| a.id == area_id)
+        .cloned()
+        .ok_or("Area not found")?;
+    
+    if !area.active {
+        return Err(format!("Area '{}' is not active", area.name));
+    }
+    
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    
+    let day_data = data.daily_data.entry(date.clone()).or_default();
+    
+    let occupants: Vec<String> = latest_locations(day_data).into_values()
+        .filter(|e| e.area_id == area.id && e.dog_id != dog_id)
+        .map(|e| e.dog_id)
+        .collect();
+    if occupants.len() as u32 >= area.capacity {
+        return Err(format!("Area '{}' is full ({} dogs)", area.name, area.capacity));
+    }
+    check_puppy_limit(&data, &area, &date, &dog_id, &occupants)?;
+    
+    let day_data = data.daily_data.entry(date.clone()).or_default();
+    
+    day_data.location_events.push(LocationEvent {
+        dog_id: dog_id.clone(),
+        area_id: area_id.clone(),
+        time: time.clone(),
+        recorded_at: Utc::now(),
+    });
+    
+    save_app_data(&data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::DogMoved {
+        date,
+        dog_id,
+        area_id,
+        time,
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn get_current_locations(date: String) -> Result<Vec<DogLocation>, String> {
+    let data = load_app_data()?;
+    
+    let day_data = match data.daily_data.get(&date) {
+        Some(day_data) => day_data,
+        None => return Ok(Vec::new()),
+    };
+    
+    let mut locations: Vec<DogLocation> = latest_locations(day_data).into_values()
+        .map(|event| DogLocation {
+            dog_name: data.dogs.iter()
+                .find(|d| d.id == event.dog_id)
+                .map(|d| d.name.clone())
+                .unwrap_or_default(),
+            area_name: data.areas.iter()
+                .find(|a| a.id == event.area_id)
+                .map(|a| a.name.clone())
+                .unwrap_or_default(),
+            dog_id: event.dog_id,
+            area_id: event.area_id,
+            since: event.time,
+        })
+        .collect();
+    
+    locations.sort_by(|a, b| a.dog_name.cmp(&b.dog_name));
+    Ok(locations)
+}
+
+#[tauri::command]
+fn get_location_history(date: String, dog_id: String) -> Result<Vec<LocationEvent>, String> {
+    let data = load_app_data()?;
+    
+    let mut events: Vec<LocationEvent> = data.daily_data.get(&date)
+        .map(|d| d.location_events.iter()
+            .filter(|e| e.dog_id == dog_id)
+            .cloned()
+            .collect())
+        .unwrap_or_default();
+    
+    events.sort_by(|a, b| a.time.cmp(&b.time).then(a.recorded_at.cmp(&b.recorded_at)));
+    Ok(events)
+}
+
+/// Current local time as HH:MM, used when staff check a dog in/out without typing a time.
+fn current_time_string() -> String {
+    chrono::Local::now().format("%H:%M").to_string()
+}
+
+fn mark_checked_in<'a>(data: &'a mut AppData, date: &str, dog_id: &str, time: &str) -> &'a mut DailyRecord {
+    packages::use_credits(data, dog_id, date);
+    let day_data = data.daily_data.entry(date.to_string()).or_default();
+    let old = day_data.records.get(dog_id).and_then(|r| r.drop_off_time.clone());
+    entry_history::record_change(day_data, dog_id, None, "drop_off_time", old, Some(time.to_string()));
+    let record = day_data.records.entry(dog_id.to_string()).or_default();
+    record.drop_off_time = Some(time.to_string());
+    record.checked_in_at = Some(Utc::now());
+    record.checked_out_at = None;
+    record.updated_by = permissions::current_staff_id();
+    record
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CheckInResult {
+    #[serde(flatten)]
+    pub record: DailyRecord,
+    pub warnings: Vec<String>, // Issues staff should act on, e.g. intact dogs in group play or money owed
+}
+
+fn check_in_warnings(data: &AppData, dog: &Dog, date: &str) -> Vec<String> {
+    let on = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(on) => on,
+        Err(_) => return Vec::new(),
+    };
+    
+    let mut warnings = Vec::new();
+    let in_group_care = data.daily_data.get(date)
+        .and_then(|d| d.attendance.entries.get(&entry_key(&dog.id, &ServiceType::Daycare)))
+        .is_some_and(|e| e.attending);
+    if in_group_care {
+        warnings.extend(age::intact_warning(dog, &data.settings.intact_policy, on));
+    }
+    warnings
+}
+
+#[tauri::command]
+fn check_in_dog(
+    app: tauri::AppHandle,
+    date: String,
+    dog_id: String,
+    time: Option<String>,
+    belongings: Option<Vec<String>>,
+) -> Result<CheckInResult, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    quarantine::ensure_not_quarantined(dog, &date)?;
+    let dog_name = dog.name.clone();
+    let mut warnings = check_in_warnings(&data, dog, &date);
+    
+    let time = times::time_or_now(time, "check-in time")?;
+    
+    let record = mark_checked_in(&mut data, &date, &dog_id, &time);
+    if let Some(items) = belongings {
+        record.belongings = belongings::belongings_from(items);
+    }
+    let record = record.clone();
+    warnings.extend(check_in_alerts::account_warnings(&data, &dog_id, &date));
+    
+    save_app_data(&data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::CheckIn {
+        date,
+        dog_id,
+        dog_name,
+        time,
+        warnings: warnings.clone(),
+    });
+    Ok(CheckInResult { record, warnings })
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HouseholdCheckIn {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub result: Option<CheckInResult>,
+    pub error: Option<String>, // Why this dog wasn't checked in; the others still are
+}
+
+/// Check in every dog in the household booked for the service on the date,
+/// saving once for all of them. Dogs that can't come in (quarantined or
+/// already here) are reported without holding the rest up.
+#[tauri::command]
+fn check_in_household(
+    app: tauri::AppHandle,
+    household_id: String,
+    date: String,
+    service_type: ServiceType,
+) -> Result<Vec<HouseholdCheckIn>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let day_data = data.daily_data.get(&date).ok_or("Nothing is booked on this date")?;
+    let mut dogs: Vec<(String, String)> = data.dogs.iter()
+        .filter(|d| d.household_key() == household_id)
+        .filter(|d| day_data.attendance.entries.get(&entry_key(&d.id, &service_type)).is_some_and(|e| e.attending))
+        .map(|d| (d.id.clone(), d.name.clone()))
+        .collect();
+    if dogs.is_empty() {
+        return Err(format!("No dogs in the household are booked for {} on {}", service_type.key().to_lowercase(), date));
+    }
+    dogs.sort_by(|a, b| a.1.cmp(&b.1));
+    
+    let time = times::time_or_now(None, "check-in time")?;
+    let mut results = Vec::new();
+    for (dog_id, dog_name) in dogs {
+        let dog = data.dogs.iter().find(|d| d.id == dog_id).ok_or("Dog not found")?;
+        let here = data.daily_data.get(&date)
+            .and_then(|d| d.records.get(&dog_id))
+            .is_some_and(|r| r.checked_in_at.is_some() && r.checked_out_at.is_none());
+        let allowed = if here {
+            Err(format!("{} is already checked in", dog_name))
+        } else {
+            quarantine::ensure_not_quarantined(dog, &date)
+        };
+        if let Err(error) = allowed {
+            results.push(HouseholdCheckIn { dog_id, dog_name, result: None, error: Some(error) });
+            continue;
+        }
+        
+        let mut warnings = check_in_warnings(&data, dog, &date);
+        let record = mark_checked_in(&mut data, &date, &dog_id, &time).clone();
+        warnings.extend(check_in_alerts::account_warnings(&data, &dog_id, &date));
+        results.push(HouseholdCheckIn {
+            dog_id,
+            dog_name,
+            result: Some(CheckInResult { record, warnings }),
+            error: None,
+        });
+    }
+    
+    save_app_data(&data)?;
+    
+    for checked_in in &results {
+        if let Some(ref result) = checked_in.result {
+            emit_dashboard_update(&app, DashboardEvent::CheckIn {
+                date: date.clone(),
+                dog_id: checked_in.dog_id.clone(),
+                dog_name: checked_in.dog_name.clone(),
+                time: time.clone(),
+                warnings: result.warnings.clone(),
+            });
+        }
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+fn check_out_dog(
+    app: tauri::AppHandle,
+    date: String,
+    dog_id: String,
+    time: Option<String>,
+    returned_items: Option<Vec<String>>,
+) -> Result<DailyRecord, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let dog_name = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.name.clone())
+        .ok_or("Dog not found")?;
+    
+    let time = times::time_or_now(time, "check-out time")?;
+    
+    let day_data = data.daily_data.get_mut(&date)
+        .filter(|d| d.records.get(&dog_id).is_some_and(|r| r.checked_in_at.is_some()))
+        .ok_or(format!("{} is not checked in", dog_name))?;
+    let old = day_data.records.get(&dog_id).and_then(|r| r.pick_up_time.clone());
+    entry_history::record_change(day_data, &dog_id, None, "pick_up_time", old, Some(time.clone()));
+    let record = day_data.records.get_mut(&dog_id).ok_or("Daily record not found")?;
+    record.pick_up_time = Some(time.clone());
+    record.checked_out_at = Some(Utc::now());
+    record.updated_by = permissions::current_staff_id();
+    for item in returned_items.unwrap_or_default() {
+        if let Some(belonging) = record.belongings.iter_mut().find(|b| b.item == item) {
+            belonging.returned = true;
+        }
+    }
+    let record = record.clone();
+    
+    save_app_data(&data)?;
+    
+    emit_dashboard_update(&app, DashboardEvent::CheckOut {
+        date,
+        dog_id,
+        dog_name,
+        time,
+    });
+    Ok(record)
+}
+
 #[tauri::command]
 fn get_settings() -> Result<Settings, String> {
     println!("Getting settings...");
@@ -1229,46 +2942,304 @@ fn get_settings() -> Result<Settings, String> {
     Ok(data.settings)
 }
 
+fn checked_settings(settings: Settings) -> Result<Settings, String> {
+    if branding::parse_color(&settings.branding.primary_color).is_none() {
+        return Err("Brand colour must be a hex colour like #1f4e79".to_string());
+    }
+    settings.intake.validate()?;
+    settings.consent_requests.validate()?;
+    settings.capacity.validate()?;
+    settings.pricing.validate()?;
+    settings.fiscal.validate()?;
+    let notifications = settings.notifications.normalized()?;
+    let default_times = settings.default_times.normalized()?;
+    Ok(Settings { notifications, default_times, ..settings })
+}
+
 #[tauri::command]
 fn update_settings(settings: Settings) -> Result<(), String> {
-    let mut data = load_app_data()?;
-    data.settings = settings;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    data.settings = checked_settings(settings)?;
+    record_audit(&mut data, "settings_updated", "Settings updated");
     save_app_data(&data)?;
     Ok(())
 }
 
 #[tauri::command]
-async fn open_email(app: tauri::AppHandle, to: String, subject: String, body: String) -> Result<(), String> {
+fn format_currency(amount: f64) -> Result<String, String> {
+    let data = load_app_data()?;
+    Ok(data.settings.currency.format(amount))
+}
+
+#[tauri::command]
+async fn open_email(
+    app: tauri::AppHandle,
+    to: String,
+    subject: String,
+    body: String,
+    dog_id: Option<String>,
+    template: Option<String>,
+) -> Result<(), String> {
     let mailto_url = format!("mailto:{}?subject={}&body={}", 
         urlencoding::encode(&to),
         urlencoding::encode(&subject),
         urlencoding::encode(&body)
     );
     
+    load_app_data()?.settings.notifications.require_channel(&MessageChannel::Email)?;
     println!("Opening email URL: {}", mailto_url);
     
-    app.opener().open_url(mailto_url, None::<String>)
-        .map_err(|e| format!("Failed to open email client: {}", e))?;
+    let opened = app.opener().open_url(mailto_url, None::<String>)
+        .map_err(|e| format!("Failed to open email client: {}", e));
     
-    Ok(())
+    if let Some(dog_id) = dog_id {
+        let mut data = lock_app_data()?;
+        let status = if opened.is_ok() { MessageStatus::Drafted } else { MessageStatus::Failed };
+        messages::record_outbound(&mut data, &dog_id, MessageChannel::Email, &to, template, Some(subject), &body, status);
+        save_app_data(&data)?;
+    }
+    
+    opened
 }
 
+/// Call the dog's vet, or email them if there's no phone number on file.
+/// Opens whatever handles tel: or mailto: links on this computer.
 #[tauri::command]
-fn export_data() -> Result<String, String> {
+async fn contact_vet(app: tauri::AppHandle, dog_id: String) -> Result<(), String> {
     let data = load_app_data()?;
-    serde_json::to_string_pretty(&data)
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    let vet = dog.vet.as_ref().ok_or(format!("No vet on file for {}", dog.name))?;
+    
+    let phone: String = vet.phone.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
+    let url = if !phone.is_empty() {
+        format!("tel:{}", phone)
+    } else if !vet.email.trim().is_empty() {
+        format!("mailto:{}?subject={}", urlencoding::encode(vet.email.trim()), urlencoding::encode(&dog.name))
+    } else {
+        return Err(format!("No phone number or email for {}'s vet", dog.name));
+    };
+    
+    app.opener().open_url(url, None::<String>)
+        .map_err(|e| format!("Failed to contact the vet: {}", e))
+}
+
+/// Digits of a phone number in international format, as wa.me links need.
+/// Numbers may be written with spaces, dashes or brackets, and a leading + or 00.
+fn normalize_whatsapp_phone(phone: &str) -> Result<String, String> {
+    let trimmed = phone.trim();
+    let digits: String = trimmed.chars().filter(|c| c.is_ascii_digit()).collect();
+    let international = if trimmed.starts_with('+') {
+        digits
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        rest.to_string()
+    } else if digits.starts_with('0') {
+        return Err(format!("{} needs its international dialling code for WhatsApp, e.g. +44", trimmed));
+    } else {
+        digits
+    };
+    if international.len() < 8 || international.len() > 15 {
+        return Err(format!("{} is not a valid phone number", trimmed));
+    }
+    Ok(international)
+}
+
+/// Open a WhatsApp chat with the owner, pre-filled from one of the WhatsApp
+/// templates ("consent_form" or "vaccine_reminder"), and log it against the dog.
+#[tauri::command]
+async fn open_whatsapp(
+    app: tauri::AppHandle,
+    phone: String,
+    template_key: String,
+    dog_id: String,
+) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    data.settings.notifications.require_channel(&MessageChannel::WhatsApp)?;
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    let templates = &data.settings.whatsapp_templates;
+    let template = match template_key.as_str() {
+        "consent_form" => &templates.consent_form,
+        "vaccine_reminder" => &templates.vaccine_reminder,
+        _ => return Err(format!("Unknown WhatsApp template '{}'", template_key)),
+    };
+    let (vaccine_type, expires) = vaccinations::soonest_expiry(dog)
+        .map(|(vaccine_type, expires)| (vaccine_type, expires.format("%-d %B %Y").to_string()))
+        .unwrap_or_else(|| ("annual vaccination".to_string(), "Not available".to_string()));
+    let message = messages::render_template(template, dog)
+        .replace("{vaccineType}", &vaccine_type)
+        .replace("{expirationDate}", &expires);
+    
+    let url = format!("https://wa.me/{}?text={}", normalize_whatsapp_phone(&phone)?, urlencoding::encode(&message));
+    let opened = app.opener().open_url(url, None::<String>)
+        .map_err(|e| format!("Failed to open WhatsApp: {}", e));
+    
+    let status = if opened.is_ok() { MessageStatus::Drafted } else { MessageStatus::Failed };
+    messages::record_outbound(&mut data, &dog_id, MessageChannel::WhatsApp, phone.trim(), Some(template_key), None, &message, status);
+    save_app_data(&data)?;
+    
+    opened
+}
+
+/// The data as it leaves this machine in an export or backup. Mail passwords,
+/// backup storage keys and staff PINs stay behind.
+fn without_credentials(data: &AppData) -> AppData {
+    let mut data = data.clone();
+    data.settings.imap.password.clear();
+    data.settings.smtp.password.clear();
+    for member in &mut data.staff {
+        member.pin_hash = None;
+        member.failed_logins = 0;
+        member.locked_until = None;
+    }
+    if let Some(ref mut config) = data.settings.cloud_backup {
+        for destination in &mut config.destinations {
+            if let DestinationKind::S3 { ref mut secret_access_key, .. } = destination.kind {
+                secret_access_key.clear();
+            }
+        }
+    }
+    data
+}
+
+/// Keep this machine's passwords, storage keys and staff PINs where imported
+/// data has none, as exports and backups leave them out.
+fn keep_local_credentials(data: &mut AppData, current: &AppData) {
+    for member in data.staff.iter_mut().filter(|s| s.pin_hash.is_none()) {
+        if let Some(local) = current.staff.iter().find(|s| s.id == member.id) {
+            member.pin_hash = local.pin_hash.clone();
+        }
+    }
+    if data.settings.imap.password.is_empty() {
+        data.settings.imap.password = current.settings.imap.password.clone();
+    }
+    if data.settings.smtp.password.is_empty() {
+        data.settings.smtp.password = current.settings.smtp.password.clone();
+    }
+    let (Some(config), Some(current_config)) = (data.settings.cloud_backup.as_mut(), current.settings.cloud_backup.as_ref()) else {
+        return;
+    };
+    for destination in &mut config.destinations {
+        let local = current_config.destinations.iter().find(|d| d.id == destination.id);
+        if let (DestinationKind::S3 { secret_access_key, .. }, Some(local)) = (&mut destination.kind, local) {
+            if let DestinationKind::S3 { secret_access_key: local_key, .. } = &local.kind {
+                if secret_access_key.is_empty() {
+                    *secret_access_key = local_key.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Everything as exported or backed up, without this machine's credentials.
+fn export_json(data: &AppData) -> Result<String, String> {
+    serde_json::to_string_pretty(&without_credentials(data))
         .map_err(|e| format!("Failed to export data: {}", e))
 }
 
+#[tauri::command]
+fn export_data() -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ExportData)?;
+    export_json(&data)
+}
+
 #[tauri::command]
 fn import_data(json_data: String) -> Result<(), String> {
-    let data: AppData = serde_json::from_str(&json_data)
+    let current = load_app_data()?;
+    require_permission(&current, Permission::ImportData)?;
+    
+    let mut data: AppData = serde_json::from_str(&json_data)
+        .or_else(|_| migrate_app_data(&json_data))
         .map_err(|e| format!("Failed to parse import data: {}", e))?;
     
+    owners::link_owners(&mut data);
+    keep_local_credentials(&mut data, &current);
+    record_audit(&mut data, "data_imported", "Replaced all data from an import");
+    replace_app_data(&data)?;
+    Ok(())
+}
+
+/// Settings on their own, so another location can be set up the same way
+/// without copying any customer data. Mail accounts, the backup folder and the
+/// logo file belong to this machine and are left out.
+#[tauri::command]
+fn export_settings() -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let settings = Settings {
+        cloud_backup: None,
+        imap: ImapSettings::default(),
+        smtp: SmtpSettings::default(),
+        branding: Branding { logo_path: None, ..data.settings.branding.clone() },
+        ..data.settings
+    };
+    serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to export settings: {}", e))
+}
+
+/// Adopt settings exported from another location, keeping this machine's mail
+/// accounts, backup folder and logo.
+#[tauri::command]
+fn import_settings(json: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let imported: Settings = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    let current = &data.settings;
+    let settings = Settings {
+        cloud_backup: current.cloud_backup.clone(),
+        imap: current.imap.clone(),
+        smtp: current.smtp.clone(),
+        branding: Branding { logo_path: current.branding.logo_path.clone(), ..imported.branding },
+        ..imported
+    };
+    
+    data.settings = checked_settings(settings)?;
+    record_audit(&mut data, "settings_imported", "Settings replaced from another location's export");
     save_app_data(&data)?;
     Ok(())
 }
 
+#[tauri::command]
+fn export_data_encrypted(password: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ExportData)?;
+    
+    let json = export_json(&data)?;
+    crypto::encrypt_with_password(json.as_bytes(), &password)
+}
+
+#[tauri::command]
+fn import_data_encrypted(encrypted_data: String, password: String) -> Result<(), String> {
+    let current = load_app_data()?;
+    require_permission(&current, Permission::ImportData)?;
+    
+    let decrypted = crypto::decrypt_with_password(&encrypted_data, &password)?;
+    let decrypted = String::from_utf8(decrypted)
+        .map_err(|e| format!("Failed to parse import data: {}", e))?;
+    let mut data: AppData = serde_json::from_str(&decrypted)
+        .or_else(|_| migrate_app_data(&decrypted))
+        .map_err(|e| format!("Failed to parse import data: {}", e))?;
+    
+    owners::link_owners(&mut data);
+    keep_local_credentials(&mut data, &current);
+    record_audit(&mut data, "data_imported", "Replaced all data from an encrypted import");
+    replace_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn is_encrypted_export(content: String) -> bool {
+    crypto::is_encrypted_envelope(&content)
+}
+
 #[tauri::command]
 fn get_cloud_backup_config() -> Result<CloudBackupConfig, String> {
     let data = load_app_data()?;
@@ -1277,7 +3248,8 @@ fn get_cloud_backup_config() -> Result<CloudBackupConfig, String> {
 
 #[tauri::command]
 fn update_cloud_backup_config(config: CloudBackupConfig) -> Result<(), String> {
-    let mut data = load_app_data()?;
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
     data.settings.cloud_backup = Some(config);
     save_app_data(&data)?;
     Ok(())
@@ -1365,6 +3337,9 @@ fn list_backup_files(cloud_directory: String) -> Result<Vec<BackupFileInfo>, Str
 
 #[tauri::command]
 fn restore_from_backup(backup_filepath: String) -> Result<(), String> {
+    let current = load_app_data()?;
+    require_permission(&current, Permission::ImportData)?;
+    
     let backup_path = PathBuf::from(&backup_filepath);
     
     if !backup_path.exists() {
@@ -1374,13 +3349,20 @@ fn restore_from_backup(backup_filepath: String) -> Result<(), String> {
     // Read backup file content
     let backup_content = fs::read_to_string(&backup_path)
         .map_err(|e| format!("Failed to read backup file: {}", e))?;
+    // Backups taken while the data was encrypted are encrypted the same way
+    let backup_content = data_encryption::decrypt_data(&backup_content)?;
     
     // Parse as AppData to validate
-    let backup_data: AppData = serde_json::from_str(&backup_content)
+    let mut backup_data: AppData = serde_json::from_str(&backup_content)
+        .or_else(|_| migrate_app_data(&backup_content))
         .map_err(|e| format!("Failed to parse backup file: {}", e))?;
     
+    owners::link_owners(&mut backup_data);
+    keep_local_credentials(&mut backup_data, &current);
+    record_audit(&mut backup_data, "backup_restored", &format!("Restored from {}", backup_filepath));
+    
     // Save the backup data as current data
-    save_app_data(&backup_data)?;
+    replace_app_data(&backup_data)?;
     
     println!("Successfully restored data from backup: {}", backup_filepath);
     Ok(())
@@ -1441,10 +3423,28 @@ fn cleanup_old_backups(cloud_directory: String, max_backups: u32) -> Result<(),
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let mode = instance::acquire_instance_lock();
+    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![
+        .setup(move |app| {
+            data_encryption::unlock_with_remembered_passphrase();
+            health::run_startup_check(!mode.read_only);
+            kiosk::restore_kiosk_mode();
+            // Background jobs all write data, so only the writing instance runs them
+            if !mode.read_only {
+                times::migrate_stored_times();
+                invoices::spawn_billing_scheduler(app.handle().clone());
+                inbox::spawn_inbox_poller();
+                consent::spawn_consent_scheduler();
+                digest::spawn_digest_scheduler();
+            }
+            // Only reads data, so every instance alerts its own staff
+            notifications::spawn_notification_scheduler(app.handle().clone());
+            Ok(())
+        })
+        .invoke_handler(kiosk::guard(tauri::generate_handler![
             get_all_dogs,
             add_dog,
             update_dog,
@@ -1456,9 +3456,40 @@ pub fn run() {
             update_temperature,
             get_settings,
             update_settings,
+            format_currency,
             open_email,
             export_data,
             import_data,
+            export_data_encrypted,
+            import_data_encrypted,
+            is_encrypted_export,
+            importers::import_from_competitor,
+            staff::get_staff,
+            staff::add_staff,
+            staff::update_staff,
+            staff::delete_staff,
+            staff::assign_handler,
+            staff::get_staff_daily_list,
+            staff::clock_in,
+            staff::clock_out,
+            staff::update_time_clock_entry,
+            staff::get_time_clock_entries,
+            staff::export_payroll_csv,
+            billing::record_payment,
+            billing::get_payments,
+            billing::delete_payment,
+            billing::get_tip_report,
+            billing::get_loyalty_balance,
+            billing::redeem_points,
+            api_keys::list_api_keys,
+            api_keys::issue_api_key,
+            api_keys::revoke_api_key,
+            permissions::get_current_permissions,
+            permissions::login,
+            permissions::logout,
+            permissions::get_session,
+            permissions::set_staff_pin,
+            audit::get_audit_log,
             get_cloud_backup_config,
             update_cloud_backup_config,
             save_cloud_backup,
@@ -1471,12 +3502,192 @@ pub fn run() {
             update_recurring_schedule,
             delete_recurring_schedule,
             update_detailed_attendance,
+            cancel_attendance,
+            confirm_attendance,
             get_attendance_for_date,
             generate_recurring_attendance,
             clear_auto_generated_attendance,
+            get_areas,
+            add_area,
+            update_area,
+            delete_area,
+            assign_dog_to_area,
+            unassign_dog_from_area,
+            get_area_occupancy,
+            move_dog,
+            get_current_locations,
+            get_location_history,
+            check_in_dog,
+            check_out_dog,
+            dashboard::get_dashboard_snapshot,
+            trials::get_trials,
+            trials::book_trial,
+            trials::record_trial_assessment,
+            trials::complete_trial,
+            pricing::quote_booking,
+            invoices::run_billing_now,
+            invoices::get_invoices,
+            invoices::get_billing_runs,
+            invoices::issue_invoice,
+            invoices::void_invoice,
+            direct_debit::get_mandates,
+            direct_debit::save_mandate,
+            direct_debit::delete_mandate,
+            direct_debit::export_direct_debit_file,
+            waitlist::get_waitlist,
+            waitlist::add_to_waitlist,
+            waitlist::remove_from_waitlist,
+            capacity::get_weekly_utilization,
+            printouts::export_week_planner_pdf,
+            messages::get_message_history,
+            messages::log_outbound_message,
+            inbox::poll_owner_replies,
+            printouts::export_consent_form_pdf,
+            consent::request_consents_due,
+            breeds::get_breeds,
+            breeds::query_dogs,
+            printouts::export_evacuation_sheet,
+            belongings::set_belongings,
+            belongings::set_belonging_returned,
+            belongings::get_unreturned_belongings,
+            food::get_house_foods,
+            food::save_house_food,
+            food::restock_house_food,
+            food::add_owner_food,
+            food::get_owner_food,
+            food::record_feeding,
+            food::delete_feeding,
+            food::get_feedings,
+            food::get_food_warnings,
+            routes::get_pickup_routes,
+            routes::build_pickup_route,
+            routes::reorder_route_stops,
+            routes::set_route_driver,
+            routes::delete_pickup_route,
+            routes::complete_route_stop,
+            branding::set_branding_logo,
+            branding::clear_branding_logo,
+            instance::get_instance_mode,
+            schedule_conflicts::get_schedule_conflicts,
+            times::normalize_stored_times,
+            boarding::create_boarding_stay,
+            boarding::update_stay_checkout,
+            boarding::cancel_boarding_stay,
+            boarding::get_boarding_stays,
+            boarding::calculate_boarding_charges,
+            boarding::quote_boarding_stay,
+            kiosk::get_kiosk_roster,
+            kiosk::get_kiosk_status,
+            kiosk::start_kiosk_mode,
+            kiosk::exit_kiosk_mode,
+            owner_schedule::export_owner_schedule_html,
+            absences::record_absence,
+            absences::delete_absence,
+            absences::get_absences,
+            reminders::get_reminders,
+            reminders::snooze_reminder,
+            reminders::dismiss_reminder,
+            consent::record_consent_signing,
+            consent::record_consent_signed,
+            consent::get_consent_history,
+            consent::get_overdue_consents,
+            day_locks::lock_day,
+            day_locks::unlock_day,
+            day_locks::get_locked_days,
+            compliance::export_compliance_pack,
+            quarantine::set_quarantine,
+            quarantine::clear_quarantine,
+            quarantine::get_current_quarantines,
+            day_notes::add_day_note,
+            day_notes::delete_day_note,
+            day_notes::get_day_notes,
+            tasks::get_tasks,
+            tasks::save_task,
+            tasks::complete_task,
+            tasks::delete_task,
+            regenerate_attendance,
+            digest::set_weekly_digest,
+            digest::preview_weekly_digest,
+            digest::send_weekly_digests,
+            export_settings,
+            import_settings,
+            intake::save_intake_answers,
+            intake::get_incomplete_intakes,
+            generation_report::get_generation_report,
+            contact_preferences::get_contact_preferences,
+            contact_preferences::set_contact_preferences,
+            contact_preferences::check_contact_channel,
+            gallery::get_dog_gallery,
+            gallery::add_daily_photo,
+            gallery::delete_daily_photo,
+            reconciliation::import_bank_statement_csv,
+            reconciliation::get_unmatched_bank_transactions,
+            reconciliation::match_bank_transaction,
+            durations::get_attended_durations,
+            owners::get_owners,
+            owners::update_owner,
+            owners::set_dog_owner,
+            vaccinations::get_vaccinations,
+            vaccinations::add_vaccination,
+            vaccinations::update_vaccination,
+            vaccinations::get_expiring_vaccines,
+            health::get_startup_health,
+            health::recover_data,
+            data_encryption::get_encryption_status,
+            data_encryption::unlock_data,
+            data_encryption::enable_data_encryption,
+            data_encryption::disable_data_encryption,
+            data_encryption::remember_data_passphrase,
+            data_encryption::forget_data_passphrase,
+            annual_review::get_annual_review,
+            annual_review::export_annual_review,
+            start_attendance_generation,
+            jobs::get_job_status,
+            jobs::cancel_job,
+            importers::start_competitor_import,
+            compliance::start_compliance_pack_export,
+            generation_report::start_generation_report,
+            notifications::notify_due_reminders,
+            open_whatsapp,
+            anonymized_export::export_anonymized,
+            packages::purchase_package,
+            packages::adjust_package_credits,
+            packages::get_package_balances,
+            entry_history::get_entry_history,
+            revenue_report::get_revenue_report,
+            invoices::export_billing_csv,
+            accounting_export::export_accounting,
+            actions::get_actions,
+            actions::execute_action,
+            invoice_pdf::render_invoice_pdf,
+            printouts::render_daily_roster_pdf,
+            check_in_household,
+            media::attach_dog_photo,
+            media::list_dog_photos,
+            media::delete_photo,
+            documents::attach_document,
+            documents::list_documents,
+            documents::open_document,
+            documents::remove_document,
+            pricing::get_scheduled_price_changes,
+            pricing::schedule_price_change,
+            pricing::cancel_price_change,
+            backup_destinations::run_backup,
+            backup_destinations::get_backup_status,
+            consent::get_consent_form,
+            consent::sign_consent_form,
+            erasure::erase_dog,
+            contact_vet,
+            playgroups::get_playgroups,
+            playgroups::add_playgroup,
+            playgroups::update_playgroup,
+            playgroups::delete_playgroup,
+            playgroups::get_playgroup_assignments,
+            playgroups::assign_dog_to_playgroup,
+            playgroups::unassign_dog_from_playgroup,
             test_household_id,
             test_parameter_names
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message as Email, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+
+/// Outgoing mail server, for messages the app sends on its own rather than
+/// through the user's mail client.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub from_address: String,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        Self {
+            host: "".to_string(),
+            port: 587,
+            username: "".to_string(),
+            password: "".to_string(),
+            from_address: "".to_string(),
+        }
+    }
+}
+
+impl SmtpSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.host.trim().is_empty() && !self.from_address.trim().is_empty()
+    }
+}
+
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// Send a plain-text email over STARTTLS, optionally with one attachment.
+pub fn send_email(
+    settings: &SmtpSettings,
+    from_name: &str,
+    to: &str,
+    subject: &str,
+    body: &str,
+    attachment: Option<EmailAttachment>,
+) -> Result<(), String> {
+    if !settings.is_configured() {
+        return Err("Set up the outgoing mail server in settings first".to_string());
+    }
+    
+    let from = Mailbox::new(
+        Some(from_name.to_string()),
+        settings.from_address.trim().parse().map_err(|e| format!("Invalid sender address: {}", e))?,
+    );
+    let to: Mailbox = to.trim().parse().map_err(|e| format!("Invalid recipient address {}: {}", to, e))?;
+    
+    let builder = Email::builder().from(from).to(to).subject(subject);
+    let email = match attachment {
+        Some(attachment) => {
+            let content_type = ContentType::parse(&attachment.content_type)
+                .map_err(|e| format!("Invalid attachment type: {}", e))?;
+            builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(body.to_string()))
+                    .singlepart(Attachment::new(attachment.filename).body(attachment.content, content_type)),
+            )
+        }
+        None => builder.body(body.to_string()),
+    }
+    .map_err(|e| format!("Failed to build email: {}", e))?;
+    
+    let mut transport = SmtpTransport::starttls_relay(&settings.host)
+        .map_err(|e| format!("Failed to set up mail server {}: {}", settings.host, e))?
+        .port(settings.port);
+    if !settings.username.is_empty() {
+        transport = transport.credentials(Credentials::new(settings.username.clone(), settings.password.clone()));
+    }
+    
+    transport.build().send(&email)
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use printpdf::image_crate;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::gallery::thumbnail;
+use crate::instance::ensure_writable;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{get_app_data_path, load_app_data, lock_app_data, save_app_data};
+
+/// A photo attached to a dog's profile. The image itself lives in the app's
+/// media directory under its content hash, so the same picture imported twice,
+/// or of two dogs together, is only stored once.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DogPhoto {
+    pub id: String,
+    pub dog_id: String,
+    pub path: String,
+    pub hash: String, // SHA-256 of the image file
+    pub original_name: String,
+    pub added_by: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DogPhotoView {
+    #[serde(flatten)]
+    pub photo: DogPhoto,
+    pub thumbnail_path: Option<String>, // None when the image couldn't be read
+    pub is_profile: bool,
+}
+
+fn media_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_path()?.with_file_name("media"))
+}
+
+fn view(photo: &DogPhoto, profile: Option<&str>) -> DogPhotoView {
+    DogPhotoView {
+        thumbnail_path: thumbnail(&photo.path)
+            .map_err(|e| println!("No thumbnail for {}: {}", photo.path, e))
+            .ok(),
+        is_profile: profile == Some(photo.path.as_str()),
+        photo: photo.clone(),
+    }
+}
+
+/// Copy an image into the media directory and attach it to the dog. The first
+/// photo a dog gets, or any with `make_profile`, becomes its profile photo.
+/// Attaching an image the dog already has returns the existing photo.
+#[tauri::command]
+pub fn attach_dog_photo(dog_id: String, source_path: String, make_profile: Option<bool>) -> Result<DogPhotoView, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    ensure_writable()?;
+    
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    let source = Path::new(&source_path);
+    let extension = source.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| ["png", "jpg", "jpeg"].contains(&e.as_str()))
+        .ok_or("Photo must be a PNG or JPEG image")?;
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read photo: {}", e))?;
+    image_crate::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to read image {}: {}", source_path, e))?;
+    let hash = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    
+    let existing = data.dog_photos.iter().find(|p| p.dog_id == dog_id && p.hash == hash).cloned();
+    let photo = match existing {
+        Some(photo) => photo,
+        None => {
+            let stored = data.dog_photos.iter()
+                .find(|p| p.hash == hash && Path::new(&p.path).is_file())
+                .map(|p| p.path.clone());
+            let path = match stored {
+                Some(path) => path,
+                None => {
+                    let dir = media_dir()?;
+                    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create media directory: {}", e))?;
+                    let target = dir.join(format!("{}.{}", hash, extension));
+                    fs::write(&target, &bytes).map_err(|e| format!("Failed to copy photo: {}", e))?;
+                    target.to_string_lossy().to_string()
+                }
+            };
+            let photo = DogPhoto {
+                id: Uuid::new_v4().to_string(),
+                dog_id: dog_id.clone(),
+                path,
+                hash,
+                original_name: source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                added_by: current_staff_id(),
+                added_at: Utc::now(),
+            };
+            data.dog_photos.push(photo.clone());
+            photo
+        }
+    };
+    
+    let dog = data.dogs.iter_mut().find(|d| d.id == dog_id).ok_or("Dog not found")?;
+    let no_profile = dog.photo_path.as_deref().is_none_or(|p| p.is_empty());
+    if no_profile || make_profile.unwrap_or(false) {
+        dog.photo_path = Some(photo.path.clone());
+    }
+    let profile = dog.photo_path.clone();
+    let details = format!("Attached photo {} to {}", photo.original_name, dog.name);
+    record_audit(&mut data, "dog_photo_attached", &details);
+    save_app_data(&data)?;
+    
+    Ok(view(&photo, profile.as_deref()))
+}
+
+/// A dog's attached photos, newest first, with thumbnails.
+#[tauri::command]
+pub fn list_dog_photos(dog_id: String) -> Result<Vec<DogPhotoView>, String> {
+    let data = load_app_data()?;
+    let dog = data.dogs.iter().find(|d| d.id == dog_id).ok_or("Dog not found")?;
+    
+    let mut photos: Vec<&DogPhoto> = data.dog_photos.iter().filter(|p| p.dog_id == dog_id).collect();
+    photos.sort_by_key(|p| Reverse(p.added_at));
+    Ok(photos.into_iter().map(|p| view(p, dog.photo_path.as_deref())).collect())
+}
+
+/// Detach a photo from its dog. The image file is removed once no dog uses it;
+/// a dog whose profile photo it was is left without one.
+#[tauri::command]
+pub fn delete_photo(photo_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    ensure_writable()?;
+    
+    let index = data.dog_photos.iter()
+        .position(|p| p.id == photo_id)
+        .ok_or("Photo not found")?;
+    let photo = data.dog_photos.remove(index);
+    if let Some(dog) = data.dogs.iter_mut().find(|d| d.id == photo.dog_id) {
+        if dog.photo_path.as_deref() == Some(photo.path.as_str()) {
+            dog.photo_path = None;
+        }
+    }
+    let still_used = data.dog_photos.iter().any(|p| p.path == photo.path);
+    
+    record_audit(&mut data, "dog_photo_deleted", &format!("Deleted photo {} of dog {}", photo.original_name, photo.dog_id));
+    save_app_data(&data)?;
+    if !still_used {
+        let _ = fs::remove_file(&photo.path);
+    }
+    Ok(())
+}
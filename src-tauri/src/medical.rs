@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{AppData, DayData, Dog};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum AllergySeverity {
+    #[default]
+    #[serde(rename = "mild")]
+    Mild,
+    #[serde(rename = "moderate")]
+    Moderate,
+    #[serde(rename = "severe")]
+    Severe, // Needs the vet straight away, e.g. anaphylaxis
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Allergy {
+    pub allergen: String, // e.g. "Chicken", "Bee stings"
+    pub severity: AllergySeverity,
+    #[serde(default)]
+    pub reaction: String,
+    #[serde(default)]
+    pub treatment: String, // What to do if exposed, e.g. "Give antihistamine from bag"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PetInsurance {
+    pub provider: String,
+    pub policy_number: String,
+    #[serde(default)]
+    pub claims_phone: String,
+}
+
+/// Tidy a dog's medical details before saving. Microchip numbers are stored
+/// as digits only; the 15-digit ISO chips and older 9 or 10-digit ones are
+/// accepted.
+pub fn normalize_medical(dog: &mut Dog) -> Result<(), String> {
+    if let Some(chip) = dog.microchip_number.take() {
+        let digits: String = chip.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+        if !digits.is_empty() {
+            if !digits.chars().all(|c| c.is_ascii_digit()) || ![9, 10, 15].contains(&digits.len()) {
+                return Err(format!("{} is not a valid microchip number", chip.trim()));
+            }
+            dog.microchip_number = Some(digits);
+        }
+    }
+    for allergy in dog.allergies.iter_mut() {
+        allergy.allergen = allergy.allergen.trim().to_string();
+    }
+    dog.allergies.retain(|a| !a.allergen.is_empty());
+    if dog.insurance.as_ref().is_some_and(|i| i.provider.trim().is_empty() && i.policy_number.trim().is_empty()) {
+        dog.insurance = None;
+    }
+    Ok(())
+}
+
+/// Copy each dog's allergies onto its bookings and day record before the day
+/// is returned to the frontend, so they show wherever the dog does.
+pub fn flag_allergies(data: &AppData, day_data: &mut DayData) {
+    let allergies = |dog_id: &str| data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .map(|d| d.allergies.clone())
+        .unwrap_or_default();
+    for entry in day_data.attendance.entries.values_mut() {
+        entry.allergies = allergies(&entry.dog_id);
+    }
+    for (dog_id, record) in day_data.records.iter_mut() {
+        record.allergies = allergies(dog_id);
+    }
+}
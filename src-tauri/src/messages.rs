@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, Dog};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum MessageDirection {
+    #[serde(rename = "inbound")]
+    Inbound,
+    #[serde(rename = "outbound")]
+    Outbound,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum MessageChannel {
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "whatsapp")]
+    WhatsApp,
+    #[serde(rename = "sms")]
+    Sms,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum MessageStatus {
+    #[serde(rename = "drafted")]
+    Drafted, // Handed to the mail client or WhatsApp; we can't see whether it went out
+    #[serde(rename = "queued")]
+    Queued, // Written for staff to send, e.g. over WhatsApp
+    #[serde(rename = "sent")]
+    Sent,
+    #[serde(rename = "failed")]
+    Failed,
+    #[default]
+    #[serde(rename = "received")]
+    Received,
+}
+
+/// Correspondence with an owner, kept against each of their dogs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Message {
+    pub id: String,
+    pub direction: MessageDirection,
+    pub channel: MessageChannel,
+    pub dog_ids: Vec<String>,
+    pub counterpart: String, // Owner's email address or phone number
+    pub subject: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub template: Option<String>, // e.g. "consent_form", "vaccine_reminder"
+    #[serde(default)]
+    pub status: MessageStatus,
+    pub timestamp: DateTime<Utc>,
+    pub external_id: Option<String>, // e.g. the email Message-ID, to avoid importing twice
+}
+
+/// Fill in a reminder template's placeholders for a dog, as the frontend does
+/// for drafts it opens itself.
+pub fn render_template(template: &str, dog: &Dog) -> String {
+    template
+        .replace("{dogName}", &dog.name)
+        .replace("{ownerName}", &dog.owner)
+        .replace("{ownerEmail}", &dog.email)
+        .replace("{currentDate}", &Utc::now().format("%-d %B %Y").to_string())
+}
+
+/// Log an outbound message against a dog, whatever transport it went through.
+#[allow(clippy::too_many_arguments)]
+pub fn record_outbound(
+    data: &mut AppData,
+    dog_id: &str,
+    channel: MessageChannel,
+    recipient: &str,
+    template: Option<String>,
+    subject: Option<String>,
+    content: &str,
+    status: MessageStatus,
+) -> Message {
+    let message = Message {
+        id: Uuid::new_v4().to_string(),
+        direction: MessageDirection::Outbound,
+        channel,
+        dog_ids: vec![dog_id.to_string()],
+        counterpart: recipient.to_string(),
+        subject,
+        content: content.to_string(),
+        template,
+        status,
+        timestamp: Utc::now(),
+        external_id: None,
+    };
+    
+    data.messages.push(message.clone());
+    message
+}
+
+/// Record a message the frontend sent itself, e.g. by opening a WhatsApp link.
+#[tauri::command]
+pub fn log_outbound_message(
+    dog_id: String,
+    channel: MessageChannel,
+    recipient: String,
+    template: Option<String>,
+    subject: Option<String>,
+    content: String,
+    status: MessageStatus,
+) -> Result<Message, String> {
+    let mut data = lock_app_data()?;
+    
+    if !data.dogs.iter().any(|d| d.id == dog_id) {
+        return Err("Dog not found".to_string());
+    }
+    data.settings.notifications.require_channel(&channel)?;
+    
+    let message = record_outbound(&mut data, &dog_id, channel, &recipient, template, subject, &content, status);
+    save_app_data(&data)?;
+    
+    Ok(message)
+}
+
+/// Messages exchanged about a dog, newest first.
+#[tauri::command]
+pub fn get_message_history(dog_id: String) -> Result<Vec<Message>, String> {
+    let data = load_app_data()?;
+    
+    let mut messages: Vec<Message> = data.messages.into_iter()
+        .filter(|m| m.dog_ids.contains(&dog_id))
+        .collect();
+    
+    messages.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+    Ok(messages)
+}
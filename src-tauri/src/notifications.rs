@@ -0,0 +1,209 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::Emitter;
+
+use crate::messages::MessageChannel;
+use crate::reminders::{active_reminders, ReminderKind};
+use crate::times::normalize_optional;
+use crate::vaccinations::expiring_vaccines;
+use crate::{load_app_data, AppData, ServiceType};
+
+/// How and when we contact owners, and whether staff get desktop alerts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationSettings {
+    pub email_enabled: bool,
+    pub whatsapp_enabled: bool,
+    pub desktop_enabled: bool,
+    pub reminder_advance_days: u32, // Remind owners this many days before consent lapses
+    pub quiet_hours_start: Option<String>, // HH:MM local time; nothing is sent in the background in between
+    pub quiet_hours_end: Option<String>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            email_enabled: true,
+            whatsapp_enabled: true,
+            desktop_enabled: true,
+            reminder_advance_days: 7,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn channel_enabled(&self, channel: &MessageChannel) -> bool {
+        match channel {
+            MessageChannel::Email => self.email_enabled,
+            MessageChannel::WhatsApp => self.whatsapp_enabled,
+            MessageChannel::Sms => true,
+        }
+    }
+    
+    pub fn require_channel(&self, channel: &MessageChannel) -> Result<(), String> {
+        if self.channel_enabled(channel) {
+            return Ok(());
+        }
+        let name = match channel {
+            MessageChannel::Email => "Email",
+            MessageChannel::WhatsApp => "WhatsApp",
+            MessageChannel::Sms => "SMS",
+        };
+        Err(format!("{} messages are turned off in notification settings", name))
+    }
+    
+    pub fn require_desktop(&self) -> Result<(), String> {
+        if !self.desktop_enabled {
+            return Err("Desktop notifications are turned off in notification settings".to_string());
+        }
+        Ok(())
+    }
+    
+    /// Quiet hours may run past midnight, e.g. 21:00 to 08:00.
+    pub fn in_quiet_hours(&self, time: NaiveTime) -> bool {
+        let parse = |t: &Option<String>| t.as_deref().and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok());
+        match (parse(&self.quiet_hours_start), parse(&self.quiet_hours_end)) {
+            (Some(start), Some(end)) if start <= end => time >= start && time < end,
+            (Some(start), Some(end)) => time >= start || time < end,
+            _ => false,
+        }
+    }
+    
+    pub fn quiet_now(&self) -> bool {
+        self.in_quiet_hours(Local::now().time())
+    }
+    
+    /// Normalize the quiet hours, which must be given together or not at all.
+    pub fn normalized(self) -> Result<Self, String> {
+        let quiet_hours_start = normalize_optional(self.quiet_hours_start, "quiet hours start")?;
+        let quiet_hours_end = normalize_optional(self.quiet_hours_end, "quiet hours end")?;
+        if quiet_hours_start.is_some() != quiet_hours_end.is_some() {
+            return Err("Quiet hours need both a start and an end time".to_string());
+        }
+        if self.reminder_advance_days > 28 {
+            return Err("Reminders can be sent at most 28 days in advance".to_string());
+        }
+        Ok(Self { quiet_hours_start, quiet_hours_end, ..self })
+    }
+}
+
+/// Event the window shows desktop notifications for, carrying a
+/// `Vec<DesktopNotification>`.
+pub const DESKTOP_NOTIFICATION_EVENT: &str = "desktop-notifications";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DesktopNotification {
+    pub id: String, // Stays the same while the thing it's about is unresolved
+    pub title: String,
+    pub body: String,
+}
+
+/// Dogs checked in today and not yet collected after closing time. Boarders are
+/// meant to stay, and nothing is flagged until opening hours are set up.
+fn past_closing(data: &AppData, now: DateTime<Local>) -> Vec<DesktopNotification> {
+    let hours = &data.settings.opening_hours;
+    let close = hours.for_weekday(now.weekday().num_days_from_sunday())
+        .and_then(|h| NaiveTime::parse_from_str(&h.close, "%H:%M").ok());
+    let close = match close {
+        Some(close) if hours.enabled && now.time() > close => close,
+        _ => return Vec::new(),
+    };
+    let date = now.format("%Y-%m-%d").to_string();
+    let day_data = match data.daily_data.get(&date) {
+        Some(day_data) => day_data,
+        None => return Vec::new(),
+    };
+    
+    day_data.records.iter()
+        .filter(|(_, r)| r.checked_in_at.is_some() && r.checked_out_at.is_none())
+        .filter(|(dog_id, _)| !day_data.attendance.entries.values()
+            .any(|e| e.dog_id == **dog_id && e.attending && e.service_type == ServiceType::Boarding))
+        .map(|(dog_id, _)| {
+            let name = data.dogs.iter()
+                .find(|d| d.id == *dog_id)
+                .map(|d| d.name.clone())
+                .unwrap_or_else(|| dog_id.clone());
+            DesktopNotification {
+                id: format!("closing:{}:{}", dog_id, date),
+                title: "Still on site after closing".to_string(),
+                body: format!("{} hasn't been picked up and we closed at {}", name, close.format("%H:%M")),
+            }
+        })
+        .collect()
+}
+
+/// Everything staff should be alerted to now: vaccines running out within the
+/// reminder window, consent forms due, and dogs left after closing time.
+pub fn due_notifications(data: &AppData) -> Vec<DesktopNotification> {
+    let mut notifications: Vec<DesktopNotification> = expiring_vaccines(data, data.settings.notifications.reminder_advance_days)
+        .into_iter()
+        .map(|v| DesktopNotification {
+            id: format!("vaccine:{}:{}", v.dog_id, v.vaccine_type.to_lowercase()),
+            title: if v.days_remaining < 0 { "Vaccination expired" } else { "Vaccination expiring" }.to_string(),
+            body: format!("{}'s {} {} on {}", v.dog_name, v.vaccine_type,
+                          if v.days_remaining < 0 { "expired" } else { "expires" }, v.expiration_date),
+        })
+        .collect();
+    notifications.extend(active_reminders(data).into_iter()
+        .filter(|r| r.kind == ReminderKind::Consent)
+        .map(|r| DesktopNotification {
+            id: r.id,
+            title: "Consent form needed".to_string(),
+            body: r.message,
+        }));
+    notifications.extend(past_closing(data, Local::now()));
+    notifications
+}
+
+fn emit_notifications(app: &tauri::AppHandle, notifications: &[DesktopNotification]) {
+    if let Err(e) = app.emit(DESKTOP_NOTIFICATION_EVENT, notifications) {
+        println!("Failed to emit desktop notifications: {}", e);
+    }
+}
+
+/// Show desktop notifications for everything currently due, e.g. from a
+/// button on the reminders screen. Returns what was sent.
+#[tauri::command]
+pub fn notify_due_reminders(app: tauri::AppHandle) -> Result<Vec<DesktopNotification>, String> {
+    let data = load_app_data()?;
+    data.settings.notifications.require_desktop()?;
+    
+    let notifications = due_notifications(&data);
+    if !notifications.is_empty() {
+        emit_notifications(&app, &notifications);
+    }
+    Ok(notifications)
+}
+
+/// Check every 15 minutes for anything newly due, outside quiet hours. Each
+/// notification is shown once a day, so an unresolved one comes back tomorrow
+/// rather than every pass.
+pub fn spawn_notification_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut shown: HashSet<String> = HashSet::new();
+        let mut shown_on: Option<NaiveDate> = None;
+        loop {
+            let today = Local::now().date_naive();
+            if shown_on != Some(today) {
+                shown.clear();
+                shown_on = Some(today);
+            }
+            match load_app_data() {
+                Ok(data) if !data.settings.notifications.desktop_enabled => {}
+                Ok(data) if data.settings.notifications.quiet_now() => {}
+                Ok(data) => {
+                    let new: Vec<DesktopNotification> = due_notifications(&data).into_iter()
+                        .filter(|n| shown.insert(n.id.clone()))
+                        .collect();
+                    if !new.is_empty() {
+                        emit_notifications(&app, &new);
+                    }
+                }
+                Err(e) => println!("Failed to load data for desktop notifications: {}", e),
+            }
+            std::thread::sleep(std::time::Duration::from_secs(15 * 60));
+        }
+    });
+}
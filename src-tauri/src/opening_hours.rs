@@ -0,0 +1,152 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{get_weekday_index, AppData, DogSchedule, RecurrencePattern, ServiceType};
+
+const WEEKDAY_NAMES: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DayHours {
+    pub open: String,  // HH:MM
+    pub close: String, // HH:MM
+}
+
+/// A saved record with any times outside opening hours. These are warnings,
+/// not errors: staff may agree an early drop-off, but should know about it.
+#[derive(Debug, Serialize, Clone)]
+pub struct WithWarnings<T> {
+    #[serde(flatten)]
+    pub record: T,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpeningHours {
+    pub enabled: bool, // Only validate drop-off/pick-up times once hours are configured
+    pub days: Vec<Option<DayHours>>, // Indexed by weekday (0-6, Sunday=0); None when closed
+}
+
+impl Default for OpeningHours {
+    fn default() -> Self {
+        let weekday = Some(DayHours {
+            open: "07:00".to_string(),
+            close: "18:00".to_string(),
+        });
+        Self {
+            enabled: false,
+            days: vec![None, weekday.clone(), weekday.clone(), weekday.clone(), weekday.clone(), weekday, None],
+        }
+    }
+}
+
+impl OpeningHours {
+    pub fn for_weekday(&self, weekday: u32) -> Option<&DayHours> {
+        self.days.get(weekday as usize).and_then(|d| d.as_ref())
+    }
+    
+    /// Check drop-off and pick-up times against one weekday's hours. Times that
+    /// don't parse as HH:MM are left alone.
+    fn check_times(&self, weekday: u32, drop_off: Option<&str>, pick_up: Option<&str>) -> Vec<String> {
+        let day_name = WEEKDAY_NAMES[weekday as usize % 7];
+        let hours = match self.for_weekday(weekday) {
+            Some(hours) => hours,
+            None => return vec![format!("We are closed on {}s", day_name)],
+        };
+        
+        let mut warnings = Vec::new();
+        if let (Some(time), Some(open)) = (drop_off.and_then(parse_time), parse_time(&hours.open)) {
+            if time < open {
+                warnings.push(format!("Drop-off at {} is before opening time ({}) on {}s",
+                                      time.format("%H:%M"), hours.open, day_name));
+            }
+        }
+        
+        if let (Some(time), Some(close)) = (pick_up.and_then(parse_time), parse_time(&hours.close)) {
+            if time > close {
+                warnings.push(format!("Pick-up at {} is after closing time ({}) on {}s",
+                                      time.format("%H:%M"), hours.close, day_name));
+            }
+        }
+        
+        warnings
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Check a single day's drop-off/pick-up times, returning warnings for any
+/// outside opening hours. Boarding dogs stay overnight, so their times aren't
+/// bound by the daytime hours.
+pub fn validate_attendance_times(
+    data: &AppData,
+    date: &str,
+    service_type: &ServiceType,
+    drop_off: Option<&str>,
+    pick_up: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let hours = &data.settings.opening_hours;
+    if !hours.enabled || *service_type == ServiceType::Boarding {
+        return Ok(Vec::new());
+    }
+    
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    
+    Ok(hours.check_times(get_weekday_index(date), drop_off, pick_up))
+}
+
+/// Check a recurring schedule's times on every weekday the pattern can land on.
+/// Daily and monthly patterns naturally span days we're closed, so they're only
+/// checked against open days.
+pub fn validate_schedule_times(
+    data: &AppData,
+    pattern: &RecurrencePattern,
+    start_date: &str,
+    service_type: &ServiceType,
+    drop_off: Option<&str>,
+    pick_up: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let hours = &data.settings.opening_hours;
+    if !hours.enabled || *service_type == ServiceType::Boarding {
+        return Ok(Vec::new());
+    }
+    
+    let weekdays: Vec<u32> = match pattern {
+        RecurrencePattern::None => Vec::new(),
+        RecurrencePattern::Weekly | RecurrencePattern::BiWeekly => {
+            let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d")
+                .map_err(|_| "Invalid start date format".to_string())?;
+            vec![get_weekday_index(start)]
+        },
+        RecurrencePattern::Custom(ref days) => days.clone(),
+        RecurrencePattern::Daily | RecurrencePattern::Monthly => {
+            (0..7).filter(|&d| hours.for_weekday(d).is_some()).collect()
+        },
+    };
+    
+    Ok(weekdays.into_iter().flat_map(|weekday| hours.check_times(weekday, drop_off, pick_up)).collect())
+}
+
+/// Check the weekly schedule stored on a dog before it's turned into
+/// recurring schedules.
+pub fn validate_dog_schedule(data: &AppData, schedule: &DogSchedule) -> Vec<String> {
+    let hours = &data.settings.opening_hours;
+    if !hours.enabled || !schedule.active {
+        return Vec::new();
+    }
+    
+    let mut warnings = Vec::new();
+    for &weekday in &schedule.daycare_days {
+        let checked = hours.check_times(weekday, schedule.daycare_drop_off.as_deref(), schedule.daycare_pick_up.as_deref());
+        warnings.extend(checked.into_iter().map(|w| format!("Daycare: {}", w)));
+    }
+    
+    for &weekday in &schedule.training_days {
+        let checked = hours.check_times(weekday, schedule.training_drop_off.as_deref(), schedule.training_pick_up.as_deref());
+        warnings.extend(checked.into_iter().map(|w| format!("Training: {}", w)));
+    }
+    
+    warnings
+}
@@ -0,0 +1,165 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::boarding::stay_for_night;
+use crate::direct_debit::xml_escape;
+use crate::{load_app_data, AppData, Dog, ServiceType};
+
+fn service_name(service_type: &ServiceType) -> &'static str {
+    match service_type {
+        ServiceType::Daycare => "Daycare",
+        ServiceType::Training => "Training",
+        ServiceType::Boarding => "Boarding",
+    }
+}
+
+fn times_text(drop_off: Option<&str>, pick_up: Option<&str>) -> String {
+    match (drop_off.filter(|t| !t.is_empty()), pick_up.filter(|t| !t.is_empty())) {
+        (Some(drop_off), Some(pick_up)) => format!("{} – {}", drop_off, pick_up),
+        (Some(drop_off), None) => format!("from {}", drop_off),
+        (None, Some(pick_up)) => format!("until {}", pick_up),
+        (None, None) => "".to_string(),
+    }
+}
+
+/// Rows of (date, dog, service, times) for days booked in the range. Nights
+/// that are part of a boarding stay are left out; stays get their own list.
+fn booked_days(data: &AppData, dogs: &[&Dog], start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, String, String, String)> {
+    let defaults = &data.settings.default_times;
+    let mut rows = Vec::new();
+    for date in start.iter_days().take_while(|d| *d <= end) {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let day_data = match data.daily_data.get(&date_str) {
+            Some(day_data) => day_data,
+            None => continue,
+        };
+        
+        let mut day_rows: Vec<(String, String, String)> = day_data.attendance.entries.values()
+            .filter(|e| e.attending)
+            .filter_map(|entry| {
+                let dog = dogs.iter().find(|d| d.id == entry.dog_id)?;
+                if entry.service_type == ServiceType::Boarding && stay_for_night(data, &dog.id, &date_str).is_some() {
+                    return None;
+                }
+                Some((
+                    dog.name.clone(),
+                    service_name(&entry.service_type).to_string(),
+                    times_text(
+                        defaults.drop_off(&entry.service_type, &entry.drop_off_time).as_deref(),
+                        defaults.pick_up(&entry.service_type, &entry.pick_up_time).as_deref(),
+                    ),
+                ))
+            })
+            .collect();
+        day_rows.sort();
+        rows.extend(day_rows.into_iter().map(|(dog, service, times)| (date, dog, service, times)));
+    }
+    rows
+}
+
+/// Small self-contained HTML page of a household's bookings over the next
+/// `weeks` weeks, to email to the owner. Inline styles only, so it survives
+/// mail clients.
+#[tauri::command]
+pub fn export_owner_schedule_html(household_id: String, weeks: u32) -> Result<String, String> {
+    let data = load_app_data()?;
+    if weeks == 0 || weeks > 52 {
+        return Err("Weeks must be between 1 and 52".to_string());
+    }
+    
+    let mut dogs: Vec<&Dog> = data.dogs.iter()
+        .filter(|d| d.household_key() == household_id)
+        .collect();
+    if dogs.is_empty() {
+        return Err("Household not found".to_string());
+    }
+    dogs.sort_by(|a, b| a.name.cmp(&b.name));
+    
+    let start = Utc::now().date_naive();
+    let end = start + Duration::days(weeks as i64 * 7 - 1);
+    let start_str = start.format("%Y-%m-%d").to_string();
+    let end_str = end.format("%Y-%m-%d").to_string();
+    
+    let names: Vec<&str> = dogs.iter().map(|d| d.name.as_str()).collect();
+    let business = xml_escape(&data.settings.business_name);
+    let accent = xml_escape(&data.settings.branding.primary_color);
+    let cell = "padding:6px 10px;border-bottom:1px solid #e5e7eb;text-align:left";
+    
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+    html.push_str(&format!("<title>{} bookings</title></head>\n", xml_escape(&names.join(" & "))));
+    html.push_str("<body style=\"font-family:Helvetica,Arial,sans-serif;color:#1f2937;max-width:640px;margin:0 auto;padding:16px\">\n");
+    html.push_str(&format!("<h1 style=\"color:{};font-size:22px;margin-bottom:4px\">{}</h1>\n", accent, business));
+    html.push_str(&format!(
+        "<p style=\"margin-top:0\">Bookings for {} from {} to {}</p>\n",
+        xml_escape(&names.join(" & ")),
+        start.format("%-d %B"),
+        end.format("%-d %B %Y")
+    ));
+    
+    let rows = booked_days(&data, &dogs, start, end);
+    html.push_str("<h2 style=\"font-size:17px\">Booked days</h2>\n");
+    if rows.is_empty() {
+        html.push_str("<p>No days booked.</p>\n");
+    } else {
+        html.push_str("<table style=\"border-collapse:collapse;width:100%\">\n");
+        html.push_str(&format!(
+            "<tr><th style=\"{c}\">Date</th><th style=\"{c}\">Dog</th><th style=\"{c}\">Service</th><th style=\"{c}\">Times</th></tr>\n",
+            c = cell
+        ));
+        for (date, dog, service, times) in rows {
+            html.push_str(&format!(
+                "<tr><td style=\"{c}\">{}</td><td style=\"{c}\">{}</td><td style=\"{c}\">{}</td><td style=\"{c}\">{}</td></tr>\n",
+                date.format("%a %-d %b"),
+                xml_escape(&dog),
+                service,
+                xml_escape(&times),
+                c = cell
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    
+    let mut stays: Vec<_> = data.boarding_stays.iter()
+        .filter(|s| s.dog_ids.iter().any(|id| dogs.iter().any(|d| d.id == *id)))
+        .filter(|s| s.check_out_date >= start_str && s.check_in_date <= end_str)
+        .collect();
+    stays.sort_by(|a, b| a.check_in_date.cmp(&b.check_in_date));
+    if !stays.is_empty() {
+        html.push_str("<h2 style=\"font-size:17px\">Boarding stays</h2>\n<ul>\n");
+        for stay in stays {
+            let stay_dogs: Vec<&str> = dogs.iter()
+                .filter(|d| stay.dog_ids.contains(&d.id))
+                .map(|d| d.name.as_str())
+                .collect();
+            let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map(|d| d.format("%a %-d %b").to_string())
+                .unwrap_or_else(|_| d.to_string());
+            let collection = stay.check_out_time.as_deref()
+                .map(|t| format!(", collection at {}", t))
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<li>{}: {} to {}{}</li>\n",
+                xml_escape(&stay_dogs.join(" & ")),
+                date(&stay.check_in_date),
+                date(&stay.check_out_date),
+                xml_escape(&collection)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    
+    let mut contact = "Need to change a booking? Get in touch".to_string();
+    if !data.settings.business_phone.is_empty() {
+        contact.push_str(&format!(" on {}", data.settings.business_phone));
+    }
+    html.push_str(&format!("<p style=\"color:#6b7280;font-size:13px\">{}.</p>\n", xml_escape(&contact)));
+    if !data.settings.branding.footer_text.trim().is_empty() {
+        html.push_str(&format!(
+            "<p style=\"color:#6b7280;font-size:12px\">{}</p>\n",
+            xml_escape(data.settings.branding.footer_text.trim())
+        ));
+    }
+    html.push_str("</body></html>\n");
+    
+    Ok(html)
+}
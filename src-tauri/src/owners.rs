@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, Dog};
+
+/// A dog owner's contact details, shared by all their dogs. The owner, phone and
+/// email fields on each dog are kept as copies of these.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Owner {
+    pub id: String,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// The owner a dog belongs to: another dog's owner in the same household with
+/// the same name, or else a new owner made from the dog's contact fields.
+pub fn owner_for(data: &mut AppData, dog: &Dog) -> String {
+    let existing = data.dogs.iter()
+        .filter(|d| d.id != dog.id && d.household_key() == dog.household_key())
+        .filter_map(|d| d.owner_id.as_ref())
+        .find(|id| data.owners.iter().any(|o| o.id == **id && same_name(&o.name, &dog.owner)));
+    if let Some(id) = existing {
+        return id.clone();
+    }
+    
+    let owner = Owner {
+        id: Uuid::new_v4().to_string(),
+        name: dog.owner.trim().to_string(),
+        phone: dog.phone.clone(),
+        email: dog.email.clone(),
+        created_at: Utc::now(),
+    };
+    let id = owner.id.clone();
+    data.owners.push(owner);
+    id
+}
+
+/// Link every dog without a (known) owner to one, moving its contact fields
+/// onto the owner. Returns whether anything changed.
+pub fn link_owners(data: &mut AppData) -> bool {
+    let mut changed = false;
+    for index in 0..data.dogs.len() {
+        let linked = data.dogs[index].owner_id.as_ref()
+            .is_some_and(|id| data.owners.iter().any(|o| o.id == *id));
+        if linked {
+            continue;
+        }
+        let dog = data.dogs[index].clone();
+        data.dogs[index].owner_id = Some(owner_for(data, &dog));
+        changed = true;
+    }
+    if changed {
+        println!("Linked dogs to {} owners", data.owners.len());
+    }
+    changed
+}
+
+/// Copy an owner's contact details onto all of their dogs.
+fn sync_dogs(data: &mut AppData, owner_id: &str) {
+    let owner = match data.owners.iter().find(|o| o.id == owner_id) {
+        Some(owner) => owner.clone(),
+        None => return,
+    };
+    for dog in data.dogs.iter_mut().filter(|d| d.owner_id.as_deref() == Some(owner_id)) {
+        dog.owner = owner.name.clone();
+        dog.phone = owner.phone.clone();
+        dog.email = owner.email.clone();
+    }
+}
+
+/// Contact details edited on a dog apply to its owner, and so to the owner's
+/// other dogs too.
+pub fn apply_contact_change(data: &mut AppData, dog: &Dog) {
+    let owner_id = match dog.owner_id.as_deref() {
+        Some(owner_id) => owner_id,
+        None => return,
+    };
+    if let Some(owner) = data.owners.iter_mut().find(|o| o.id == owner_id) {
+        if owner.name == dog.owner && owner.phone == dog.phone && owner.email == dog.email {
+            return;
+        }
+        owner.name = dog.owner.clone();
+        owner.phone = dog.phone.clone();
+        owner.email = dog.email.clone();
+    }
+    sync_dogs(data, owner_id);
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OwnerWithDogs {
+    pub owner: Owner,
+    pub dog_ids: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_owners() -> Result<Vec<OwnerWithDogs>, String> {
+    let data = load_app_data()?;
+    
+    let mut owners: Vec<OwnerWithDogs> = data.owners.iter()
+        .map(|owner| OwnerWithDogs {
+            owner: owner.clone(),
+            dog_ids: data.dogs.iter()
+                .filter(|d| d.owner_id.as_deref() == Some(owner.id.as_str()))
+                .map(|d| d.id.clone())
+                .collect(),
+        })
+        .collect();
+    owners.sort_by_key(|o| o.owner.name.to_lowercase());
+    Ok(owners)
+}
+
+/// Change an owner's contact details once for all of their dogs.
+#[tauri::command]
+pub fn update_owner(owner_id: String, name: String, phone: String, email: String) -> Result<Owner, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if name.trim().is_empty() {
+        return Err("Owner name is required".to_string());
+    }
+    let owner = data.owners.iter_mut()
+        .find(|o| o.id == owner_id)
+        .ok_or("Owner not found")?;
+    owner.name = name.trim().to_string();
+    owner.phone = phone.trim().to_string();
+    owner.email = email.trim().to_string();
+    let owner = owner.clone();
+    sync_dogs(&mut data, &owner_id);
+    
+    record_audit(&mut data, "owner_updated", &format!("Updated contact details for {}", owner.name));
+    save_app_data(&data)?;
+    Ok(owner)
+}
+
+/// Move a dog to another owner, e.g. when two records turn out to be the same
+/// person. Owners left without dogs are removed.
+#[tauri::command]
+pub fn set_dog_owner(dog_id: String, owner_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if !data.owners.iter().any(|o| o.id == owner_id) {
+        return Err("Owner not found".to_string());
+    }
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    dog.owner_id = Some(owner_id.clone());
+    sync_dogs(&mut data, &owner_id);
+    
+    let owned: Vec<String> = data.dogs.iter().filter_map(|d| d.owner_id.clone()).collect();
+    data.owners.retain(|o| owned.contains(&o.id));
+    
+    save_app_data(&data)?;
+    Ok(())
+}
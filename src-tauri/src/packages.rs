@@ -0,0 +1,193 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, ServiceType};
+
+/// One credit used up by a dog attending on a day.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageUse {
+    pub dog_id: String,
+    pub date: String,
+    pub used_at: DateTime<Utc>,
+}
+
+/// Credits a household has paid for up front, e.g. a 10-day daycare pass. Any
+/// of the household's dogs can use them, one credit per dog per day attended.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Package {
+    pub id: String,
+    pub household_id: String,
+    pub service_type: ServiceType,
+    pub credits_purchased: u32,
+    pub credits_remaining: u32,
+    pub expiry: Option<String>, // Last day the credits can be used, YYYY-MM-DD
+    pub price: f64,
+    #[serde(default)]
+    pub uses: Vec<PackageUse>,
+    pub purchased_by: Option<String>,
+    pub purchased_at: DateTime<Utc>,
+}
+
+impl Package {
+    pub fn valid_on(&self, date: &str) -> bool {
+        self.expiry.as_deref().is_none_or(|expiry| date <= expiry)
+    }
+    
+    fn used_for(&self, dog_id: &str, date: &str) -> bool {
+        self.uses.iter().any(|u| u.dog_id == dog_id && u.date == date)
+    }
+}
+
+/// Whether the dog's day of this service was paid for with a package credit,
+/// so billing doesn't charge for it again.
+pub fn covered_by_package(data: &AppData, dog_id: &str, date: &str, service_type: &ServiceType) -> bool {
+    data.packages.iter()
+        .any(|p| p.service_type == *service_type && p.used_for(dog_id, date))
+}
+
+/// Use a credit for each service the dog is booked into on the day, from the
+/// household's package that expires soonest. Days already paid for by a credit
+/// are left alone, so checking a dog in twice doesn't use two.
+pub fn use_credits(data: &mut AppData, dog_id: &str, date: &str) {
+    let household_id = match data.dogs.iter().find(|d| d.id == dog_id) {
+        Some(dog) => dog.household_key().to_string(),
+        None => return,
+    };
+    let services: Vec<ServiceType> = data.daily_data.get(date)
+        .map(|d| d.attendance.entries.values()
+            .filter(|e| e.dog_id == dog_id && e.attending)
+            .map(|e| e.service_type.clone())
+            .collect())
+        .unwrap_or_default();
+    
+    for service_type in services {
+        if covered_by_package(data, dog_id, date, &service_type) {
+            continue;
+        }
+        let package = data.packages.iter_mut()
+            .filter(|p| p.household_id == household_id && p.service_type == service_type)
+            .filter(|p| p.credits_remaining > 0 && p.valid_on(date))
+            .min_by(|a, b| match (&a.expiry, &b.expiry) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.purchased_at.cmp(&b.purchased_at),
+            });
+        let package = match package {
+            Some(package) => package,
+            None => continue,
+        };
+        package.credits_remaining -= 1;
+        package.uses.push(PackageUse {
+            dog_id: dog_id.to_string(),
+            date: date.to_string(),
+            used_at: Utc::now(),
+        });
+        let details = format!(
+            "Used a {:?} credit for dog {} on {}; {} left on package {}",
+            service_type, dog_id, date, package.credits_remaining, package.id
+        );
+        record_audit(data, "package_credit_used", &details);
+    }
+}
+
+#[tauri::command]
+pub fn purchase_package(
+    household_id: String,
+    service_type: ServiceType,
+    credits: u32,
+    expiry: Option<String>,
+    price: f64,
+) -> Result<Package, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    if !data.dogs.iter().any(|d| d.household_key() == household_id) {
+        return Err("Household not found".to_string());
+    }
+    if credits == 0 {
+        return Err("A package needs at least one credit".to_string());
+    }
+    if price < 0.0 {
+        return Err("Price cannot be negative".to_string());
+    }
+    let expiry = expiry.map(|e| e.trim().to_string()).filter(|e| !e.is_empty());
+    if let Some(ref expiry) = expiry {
+        NaiveDate::parse_from_str(expiry, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid expiry date: {}", expiry))?;
+    }
+    
+    let package = Package {
+        id: Uuid::new_v4().to_string(),
+        household_id,
+        service_type,
+        credits_purchased: credits,
+        credits_remaining: credits,
+        expiry,
+        price: data.settings.currency.round(price),
+        uses: Vec::new(),
+        purchased_by: current_staff_id(),
+        purchased_at: Utc::now(),
+    };
+    data.packages.push(package.clone());
+    
+    record_audit(&mut data, "package_purchased", &format!(
+        "Sold household {} {} {:?} credits for {:.2}",
+        package.household_id, credits, package.service_type, package.price
+    ));
+    save_app_data(&data)?;
+    
+    Ok(package)
+}
+
+/// Add or take away credits by hand, e.g. a goodwill day or a correction.
+/// Credits can't go below zero.
+#[tauri::command]
+pub fn adjust_package_credits(package_id: String, change: i32, reason: String) -> Result<Package, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err("A reason is required to adjust credits".to_string());
+    }
+    if change == 0 {
+        return Err("The adjustment cannot be zero".to_string());
+    }
+    
+    let package = data.packages.iter_mut()
+        .find(|p| p.id == package_id)
+        .ok_or("Package not found")?;
+    let remaining = package.credits_remaining as i64 + change as i64;
+    if remaining < 0 {
+        return Err(format!("Only {} credits are left on this package", package.credits_remaining));
+    }
+    package.credits_remaining = remaining as u32;
+    let package = package.clone();
+    
+    record_audit(&mut data, "package_adjusted", &format!(
+        "Adjusted package {} by {:+} credits to {}: {}",
+        package.id, change, package.credits_remaining, reason
+    ));
+    save_app_data(&data)?;
+    
+    Ok(package)
+}
+
+/// Packages with credits left that haven't expired, for the front desk.
+/// Limited to one household when `household_id` is given.
+#[tauri::command]
+pub fn get_package_balances(household_id: Option<String>) -> Result<Vec<Package>, String> {
+    let data = load_app_data()?;
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    
+    let mut packages: Vec<Package> = data.packages.into_iter()
+        .filter(|p| household_id.as_deref().is_none_or(|h| p.household_id == h))
+        .filter(|p| p.credits_remaining > 0 && p.valid_on(&today))
+        .collect();
+    packages.sort_by(|a, b| a.household_id.cmp(&b.household_id).then(a.purchased_at.cmp(&b.purchased_at)));
+    Ok(packages)
+}
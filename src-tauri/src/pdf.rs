@@ -0,0 +1,182 @@
+use printpdf::image_crate::{self, DynamicImage};
+use printpdf::{
+    BuiltinFont, Color, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rect, Rgb,
+};
+
+use crate::branding::{parse_color, Branding};
+
+const PT_TO_MM: f32 = 0.3528;
+const LOGO_SIZE: f32 = 14.0;
+
+/// Thin wrapper over printpdf for the app's printouts. Coordinates are in mm
+/// from the top-left corner of the page, which is how layouts are sketched.
+/// Every page carries the business logo top-right and the footer text along
+/// the bottom edge, so layouts should keep 10mm clear at the top-right corner
+/// and 8mm at the bottom.
+pub struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold: IndirectFontRef,
+    accent: (f32, f32, f32),
+    logo: Option<DynamicImage>,
+    footer: String,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PdfWriter {
+    pub fn a4(title: &str, landscape: bool, branding: &Branding) -> Result<Self, String> {
+        let (width, height) = if landscape { (297.0, 210.0) } else { (210.0, 297.0) };
+        let (doc, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Layer 1");
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| format!("Failed to load font: {}", e))?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| format!("Failed to load font: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+        
+        // A logo that has gone missing shouldn't stop documents printing
+        let logo = branding.logo_path.as_deref().and_then(|path| load_image(path).ok());
+        let writer = Self {
+            doc,
+            layer,
+            font,
+            bold,
+            accent: parse_color(&branding.primary_color).unwrap_or((0.0, 0.0, 0.0)),
+            logo,
+            footer: branding.footer_text.trim().to_string(),
+            width,
+            height,
+        };
+        writer.decorate_page();
+        Ok(writer)
+    }
+    
+    pub fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(self.width), Mm(self.height), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.decorate_page();
+    }
+    
+    fn decorate_page(&self) {
+        if let Some(ref logo) = self.logo {
+            self.image(logo, self.width - 10.0 - LOGO_SIZE, 4.0, LOGO_SIZE);
+        }
+        if !self.footer.is_empty() {
+            let (r, g, b) = self.accent;
+            self.layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+            let footer = fit_text(&self.footer, 7.0, self.width - 50.0);
+            self.layer.use_text(footer, 7.0, Mm(10.0), Mm(4.0), &self.font);
+            self.layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        }
+    }
+    
+    /// Write text with its baseline at `y`. Builtin fonts only cover Windows-1252,
+    /// so other characters are dropped by the PDF viewer.
+    pub fn text(&self, text: &str, size: f32, x: f32, y: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.font };
+        self.layer.use_text(text, size, Mm(x), Mm(self.height - y), font);
+    }
+    
+    /// Bold text in the brand colour, for document titles.
+    pub fn heading(&self, text: &str, size: f32, x: f32, y: f32) {
+        let (r, g, b) = self.accent;
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+        self.text(text, size, x, y, true);
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
+    
+    /// Page number in the bottom-right corner, clear of the footer text.
+    pub fn page_number(&self, page: usize, pages: usize) {
+        self.text(&format!("Page {} of {}", page, pages), 7.0, self.width - 30.0, self.height - 4.0, false);
+    }
+    
+    pub fn line(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer.set_outline_color(Color::Rgb(Rgb::new(0.6, 0.6, 0.6, None)));
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(self.height - y1)), false),
+                (Point::new(Mm(x2), Mm(self.height - y2)), false),
+            ],
+            is_closed: false,
+        });
+    }
+    
+    /// Filled light-grey box, e.g. behind table headers.
+    pub fn shade(&self, x: f32, y: f32, w: f32, h: f32) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.9, 0.9, 0.9, None)));
+        self.layer.add_rect(Rect::new(Mm(x), Mm(self.height - y - h), Mm(x + w), Mm(self.height - y)));
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
+    
+    /// Draw an image file scaled to fit a `size` mm square with its top-left at
+    /// (x, y). Images are downscaled first so a page of photos stays small.
+    pub fn thumbnail(&self, path: &str, x: f32, y: f32, size: f32) -> Result<(), String> {
+        let image = load_image(path)?;
+        self.image(&image, x, y, size);
+        Ok(())
+    }
+    
+    fn image(&self, image: &DynamicImage, x: f32, y: f32, size: f32) {
+        let longest = image.width().max(image.height()) as f32;
+        let dpi = longest / (size / 25.4);
+        let height = image.height() as f32 / dpi * 25.4;
+        
+        Image::from_dynamic_image(image).add_to_layer(self.layer.clone(), ImageTransform {
+            translate_x: Some(Mm(x)),
+            translate_y: Some(Mm(self.height - y - height)),
+            dpi: Some(dpi),
+            ..Default::default()
+        });
+    }
+    
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        self.doc.save_to_bytes().map_err(|e| format!("Failed to write PDF: {}", e))
+    }
+}
+
+fn load_image(path: &str) -> Result<DynamicImage, String> {
+    let image = image_crate::open(path)
+        .map_err(|e| format!("Failed to read image {}: {}", path, e))?
+        .thumbnail(240, 240);
+    Ok(DynamicImage::ImageRgb8(image.to_rgb8()))
+}
+
+/// Shorten text to roughly fit a width in mm. Builtin fonts carry no metrics
+/// here, so this uses Helvetica's average character width.
+pub fn fit_text(text: &str, size: f32, max_width: f32) -> String {
+    let char_width = size * 0.5 * PT_TO_MM;
+    let max_chars = (max_width / char_width).floor() as usize;
+    
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    
+    let mut fitted: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    fitted.push('…');
+    fitted
+}
+
+/// Break text into lines of roughly `max_width` mm, on word boundaries.
+pub fn wrap_text(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let char_width = size * 0.5 * PT_TO_MM;
+    let max_chars = ((max_width / char_width).floor() as usize).max(1);
+    
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,324 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::audit::{record_audit, AuditEntry};
+use crate::crypto::{hash_secret, verify_secret};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum StaffRole {
+    #[serde(rename = "owner")]
+    Owner,
+    #[serde(rename = "manager")]
+    Manager,
+    #[default]
+    #[serde(rename = "handler")]
+    Handler,
+    #[serde(rename = "front_desk")]
+    FrontDesk,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Permission {
+    #[serde(rename = "edit_attendance")]
+    EditAttendance,
+    #[serde(rename = "manage_dogs")]
+    ManageDogs,
+    #[serde(rename = "manage_billing")]
+    ManageBilling,
+    #[serde(rename = "issue_refunds")]
+    IssueRefunds,
+    #[serde(rename = "edit_settings")]
+    EditSettings,
+    #[serde(rename = "manage_staff")]
+    ManageStaff,
+    #[serde(rename = "delete_data")]
+    DeleteData,
+    #[serde(rename = "import_data")]
+    ImportData,
+    #[serde(rename = "export_data")]
+    ExportData,
+    #[serde(rename = "manage_api_keys")]
+    ManageApiKeys,
+    #[serde(rename = "unlock_days")]
+    UnlockDays,
+}
+
+impl Permission {
+    fn description(&self) -> &'static str {
+        match self {
+            Permission::EditAttendance => "editing attendance",
+            Permission::ManageDogs => "managing dogs",
+            Permission::ManageBilling => "managing billing",
+            Permission::IssueRefunds => "issuing refunds",
+            Permission::EditSettings => "editing settings",
+            Permission::ManageStaff => "managing staff",
+            Permission::DeleteData => "deleting data",
+            Permission::ImportData => "importing or restoring data",
+            Permission::ExportData => "exporting data",
+            Permission::ManageApiKeys => "managing API keys",
+            Permission::UnlockDays => "unlocking finalized days",
+        }
+    }
+}
+
+/// The permission matrix: what each role is allowed to do.
+pub fn role_permissions(role: StaffRole) -> &'static [Permission] {
+    use Permission::*;
+    
+    match role {
+        StaffRole::Owner => &[
+            EditAttendance, ManageDogs, ManageBilling, IssueRefunds, EditSettings,
+            ManageStaff, DeleteData, ImportData, ExportData, ManageApiKeys, UnlockDays,
+        ],
+        StaffRole::Manager => &[
+            EditAttendance, ManageDogs, ManageBilling, IssueRefunds, EditSettings,
+            ManageStaff, DeleteData, ExportData, UnlockDays,
+        ],
+        StaffRole::FrontDesk => &[EditAttendance, ManageDogs, ManageBilling],
+        StaffRole::Handler => &[EditAttendance],
+    }
+}
+
+/// Signed-in staff member on this machine. Kept in memory only, so restarting
+/// the app always starts locked.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Session {
+    pub staff_id: String,
+    pub staff_name: String,
+    pub started_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+/// Wrong PINs allowed in a row before an account is locked for a while.
+const MAX_FAILED_LOGINS: u32 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+
+/// Staff id of the current session without touching its activity timer, for
+/// stamping records with who made a change.
+pub fn current_staff_id() -> Option<String> {
+    SESSION.lock().ok().and_then(|s| s.as_ref().map(|s| s.staff_id.clone()))
+}
+
+/// The current session if it hasn't been idle longer than the auto-lock
+/// timeout; expired sessions are cleared. Counts as activity.
+fn active_session(data: &AppData) -> Option<Session> {
+    let mut guard = SESSION.lock().ok()?;
+    let session = guard.as_mut()?;
+    let now = Utc::now();
+    
+    let timeout = data.settings.auto_lock_minutes;
+    if timeout > 0 && now - session.last_activity > Duration::minutes(timeout as i64) {
+        println!("Session for {} locked after {} minutes of inactivity", session.staff_name, timeout);
+        *guard = None;
+        return None;
+    }
+    
+    session.last_activity = now;
+    Some(session.clone())
+}
+
+/// Effective role for permission checks. An install without any staff accounts
+/// is a single-user setup and keeps full access; once staff exist, nobody has
+/// any permissions until someone signs in.
+pub fn current_role(data: &AppData) -> Option<StaffRole> {
+    if data.staff.is_empty() {
+        return Some(StaffRole::Owner);
+    }
+    
+    let session = active_session(data)?;
+    data.staff.iter()
+        .find(|s| s.id == session.staff_id && s.active)
+        .map(|s| s.role)
+}
+
+pub fn require_permission(data: &AppData, permission: Permission) -> Result<(), String> {
+    // The kiosk checks dogs in and out without anyone signed in
+    if permission == Permission::EditAttendance && crate::kiosk::is_active() {
+        return Ok(());
+    }
+    
+    let allowed = current_role(data)
+        .map(|role| role_permissions(role).contains(&permission))
+        .unwrap_or(false);
+    
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("Permission denied: {} is not allowed for the current user", permission.description()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CurrentPermissions {
+    pub staff_id: Option<String>,
+    pub staff_name: Option<String>,
+    pub role: Option<StaffRole>,
+    pub permissions: Vec<Permission>,
+}
+
+#[tauri::command]
+pub fn get_current_permissions() -> Result<CurrentPermissions, String> {
+    let data = load_app_data()?;
+    let role = current_role(&data);
+    let staff = current_staff_id()
+        .and_then(|id| data.staff.iter().find(|s| s.id == id).cloned());
+    
+    Ok(CurrentPermissions {
+        staff_id: staff.as_ref().map(|s| s.id.clone()),
+        staff_name: staff.map(|s| s.name),
+        role,
+        permissions: role.map(|r| role_permissions(r).to_vec()).unwrap_or_default(),
+    })
+}
+
+#[tauri::command]
+pub fn login(staff_id: String, pin: String) -> Result<Session, String> {
+    let mut data = lock_app_data()?;
+    
+    let member = data.staff.iter()
+        .find(|s| s.id == staff_id)
+        .cloned()
+        .ok_or("Staff member not found")?;
+    
+    if !member.active {
+        return Err(format!("{} is not an active staff member", member.name));
+    }
+    let now = Utc::now();
+    if let Some(until) = member.locked_until.filter(|until| *until > now) {
+        let minutes = (until - now).num_minutes() + 1;
+        return Err(format!("Too many wrong PINs for {}; try again in {} minutes", member.name, minutes));
+    }
+    
+    // Accounts created before PINs were required can't sign in until one is set
+    let pin_hash = member.pin_hash.as_deref()
+        .ok_or(format!("{} has no PIN yet; ask a manager to set one", member.name))?;
+    if !verify_secret(&pin, pin_hash) {
+        let mut locked = false;
+        if let Some(member) = data.staff.iter_mut().find(|s| s.id == staff_id) {
+            member.failed_logins += 1;
+            if member.failed_logins >= MAX_FAILED_LOGINS {
+                member.failed_logins = 0;
+                member.locked_until = Some(now + Duration::minutes(LOCKOUT_MINUTES));
+                locked = true;
+            }
+        }
+        record_audit(&mut data, "login_failed", &format!("Failed sign-in attempt for {}", member.name));
+        if locked {
+            record_audit(&mut data, "login_locked", &format!("{} locked for {} minutes after too many wrong PINs", member.name, LOCKOUT_MINUTES));
+        }
+        save_app_data(&data)?;
+        return Err("Incorrect PIN".to_string());
+    }
+    if let Some(member) = data.staff.iter_mut().find(|s| s.id == staff_id) {
+        member.failed_logins = 0;
+        member.locked_until = None;
+    }
+    
+    let session = Session {
+        staff_id: member.id.clone(),
+        staff_name: member.name.clone(),
+        started_at: now,
+        last_activity: now,
+    };
+    
+    if let Ok(mut guard) = SESSION.lock() {
+        *guard = Some(session.clone());
+    }
+    
+    record_audit(&mut data, "login", &format!("{} signed in", member.name));
+    save_app_data(&data)?;
+    
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn logout() -> Result<(), String> {
+    let session = SESSION.lock().ok().and_then(|mut s| s.take());
+    
+    if let Some(session) = session {
+        let mut data = lock_app_data()?;
+        data.audit_log.push(AuditEntry::new(Some(session.staff_id), "logout", &format!("{} signed out", session.staff_name)));
+        save_app_data(&data)?;
+    }
+    
+    Ok(())
+}
+
+/// Current session, or None when locked. The frontend polls this to show the
+/// lock screen once the auto-lock timeout has passed.
+#[tauri::command]
+pub fn get_session() -> Result<Option<Session>, String> {
+    let data = load_app_data()?;
+    
+    let session = SESSION.lock().ok().and_then(|s| s.clone());
+    let timeout = data.settings.auto_lock_minutes;
+    
+    match session {
+        Some(session) if timeout > 0 && Utc::now() - session.last_activity > Duration::minutes(timeout as i64) => {
+            if let Ok(mut guard) = SESSION.lock() {
+                *guard = None;
+            }
+            Ok(None)
+        }
+        other => Ok(other),
+    }
+}
+
+pub fn validate_pin(pin: &str) -> Result<(), String> {
+    if pin.len() < 4 || !pin.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("PIN must be at least 4 letters or digits".to_string());
+    }
+    Ok(())
+}
+
+/// Set a staff member's PIN. Staff may change their own PIN; resetting someone
+/// else's requires permission to manage staff, which also clears a lockout.
+/// Installs where no account has a PIN yet (from before PINs were required, or
+/// restored from a backup, which leaves PINs out) may set an owner's PIN
+/// without signing in so someone can get back in; everyone else's PIN is then
+/// set by that owner.
+#[tauri::command]
+pub fn set_staff_pin(staff_id: String, new_pin: String, current_pin: Option<String>) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    validate_pin(&new_pin)?;
+    
+    let member = data.staff.iter()
+        .find(|s| s.id == staff_id)
+        .cloned()
+        .ok_or("Staff member not found")?;
+    
+    let is_self = current_staff_id().as_deref() == Some(staff_id.as_str());
+    let knows_current = match member.pin_hash {
+        Some(ref hash) => current_pin.map(|pin| verify_secret(&pin, hash)).unwrap_or(false),
+        None => false,
+    };
+    let first_owner_setup = data.staff.iter().all(|s| s.pin_hash.is_none())
+        && member.active
+        && member.role == StaffRole::Owner;
+    
+    let allowed_without_permission = (is_self && knows_current) || first_owner_setup;
+    if !allowed_without_permission {
+        require_permission(&data, Permission::ManageStaff)?;
+    }
+    
+    let pin_hash = hash_secret(&new_pin);
+    if let Some(member) = data.staff.iter_mut().find(|s| s.id == staff_id) {
+        member.pin_hash = Some(pin_hash);
+        member.failed_logins = 0;
+        member.locked_until = None;
+    }
+    
+    let details = if first_owner_setup && current_role(&data).is_none() {
+        format!("First owner PIN set for {} without signing in", member.name)
+    } else {
+        format!("PIN set for {}", member.name)
+    };
+    record_audit(&mut data, "pin_changed", &details);
+    save_app_data(&data)?;
+    
+    Ok(())
+}
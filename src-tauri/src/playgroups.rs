@@ -0,0 +1,283 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::age::intact_warning;
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, AreaType, DayData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum DogSize {
+    #[serde(rename = "small")]
+    Small, // Under about 10kg
+    #[serde(rename = "medium")]
+    Medium,
+    #[serde(rename = "large")]
+    Large, // Over about 25kg
+}
+
+impl DogSize {
+    fn label(&self) -> &'static str {
+        match self {
+            DogSize::Small => "small",
+            DogSize::Medium => "medium",
+            DogSize::Large => "large",
+        }
+    }
+}
+
+/// A group dogs play in together during the day. Unlike areas, which are the
+/// rooms themselves, groups are about which dogs mix.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Playgroup {
+    pub id: String,
+    pub name: String,
+    pub size_class: Option<DogSize>, // None: any size
+    pub capacity: u32,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub area_id: Option<String>, // Where the group plays; None if not set
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlaygroupRoster {
+    pub group: Playgroup,
+    pub dog_ids: Vec<String>,
+}
+
+fn members<'a>(day_data: &'a DayData, group_id: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+    day_data.playgroup_assignments.iter()
+        .filter(move |(_, assigned)| assigned.as_str() == group_id)
+        .map(|(dog_id, _)| dog_id)
+}
+
+fn incompatible(data: &AppData, dog_id: &str, other_id: &str) -> bool {
+    let lists = |id: &str, other: &str| data.dogs.iter()
+        .any(|d| d.id == id && d.incompatible_dog_ids.iter().any(|i| i == other));
+    lists(dog_id, other_id) || lists(other_id, dog_id)
+}
+
+/// Dogs in an area for the day, whether assigned to it directly or playing
+/// there with their group. `ignore_dog_id` isn't counted, so moving a dog
+/// already there doesn't count it twice.
+fn area_headcount(data: &AppData, day_data: &DayData, area_id: &str, ignore_dog_id: &str) -> u32 {
+    let groups: Vec<&str> = data.playgroups.iter()
+        .filter(|g| g.area_id.as_deref() == Some(area_id))
+        .map(|g| g.id.as_str())
+        .collect();
+    let mut dogs: HashSet<&str> = day_data.area_assignments.iter()
+        .filter(|(_, assigned)| assigned.as_str() == area_id)
+        .map(|(dog_id, _)| dog_id.as_str())
+        .collect();
+    dogs.extend(day_data.playgroup_assignments.iter()
+        .filter(|(_, group_id)| groups.contains(&group_id.as_str()))
+        .map(|(dog_id, _)| dog_id.as_str()));
+    dogs.remove(ignore_dog_id);
+    dogs.len() as u32
+}
+
+/// Checks shared by adding and editing a group. Its area must be an active
+/// group-play area with room for the whole group.
+fn validate_playgroup(data: &AppData, name: &str, capacity: u32, area_id: Option<&str>) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Playgroup name is required".to_string());
+    }
+    if capacity == 0 {
+        return Err("A playgroup needs room for at least one dog".to_string());
+    }
+    if let Some(area_id) = area_id {
+        let area = data.areas.iter()
+            .find(|a| a.id == area_id)
+            .ok_or("Area not found")?;
+        if !area.active {
+            return Err(format!("Area '{}' is not active", area.name));
+        }
+        if area.area_type == AreaType::KennelBlock {
+            return Err(format!("'{}' is a kennel block, not a group play area", area.name));
+        }
+        if capacity > area.capacity {
+            return Err(format!("'{}' only has room for {} dogs", area.name, area.capacity));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_playgroups() -> Result<Vec<Playgroup>, String> {
+    let data = load_app_data()?;
+    Ok(data.playgroups)
+}
+
+#[tauri::command]
+pub fn add_playgroup(
+    name: String,
+    size_class: Option<DogSize>,
+    capacity: u32,
+    area_id: Option<String>,
+) -> Result<Playgroup, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    validate_playgroup(&data, &name, capacity, area_id.as_deref())?;
+    
+    let group = Playgroup {
+        id: Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        size_class,
+        capacity,
+        active: true,
+        created_at: Utc::now(),
+        area_id,
+    };
+    
+    data.playgroups.push(group.clone());
+    save_app_data(&data)?;
+    Ok(group)
+}
+
+#[tauri::command]
+pub fn update_playgroup(group: Playgroup) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    validate_playgroup(&data, &group.name, group.capacity, group.area_id.as_deref())?;
+    
+    // The dogs already placed from today on must still fit
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    for (date, day_data) in data.daily_data.iter().filter(|(date, _)| **date >= today) {
+        let assigned = members(day_data, &group.id).count() as u32;
+        if assigned > group.capacity {
+            return Err(format!("'{}' already has {} dogs on {}; move some out first", group.name, assigned, date));
+        }
+    }
+    
+    let existing = data.playgroups.iter_mut()
+        .find(|g| g.id == group.id)
+        .ok_or("Playgroup not found")?;
+    *existing = Playgroup {
+        name: group.name.trim().to_string(),
+        created_at: existing.created_at,
+        ..group
+    };
+    save_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_playgroup(group_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    let index = data.playgroups.iter()
+        .position(|g| g.id == group_id)
+        .ok_or("Playgroup not found")?;
+    data.playgroups.remove(index);
+    
+    // Also remove any day assignments pointing at this group
+    for day_data in data.daily_data.values_mut() {
+        day_data.playgroup_assignments.retain(|_, assigned| *assigned != group_id);
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Every active group with the dogs in it on the date.
+#[tauri::command]
+pub fn get_playgroup_assignments(date: String) -> Result<Vec<PlaygroupRoster>, String> {
+    let data = load_app_data()?;
+    let day_data = data.daily_data.get(&date);
+    
+    let rosters = data.playgroups.iter()
+        .filter(|g| g.active)
+        .map(|group| {
+            let mut dog_ids: Vec<String> = day_data
+                .map(|d| members(d, &group.id).cloned().collect())
+                .unwrap_or_default();
+            dog_ids.sort();
+            PlaygroupRoster { group: group.clone(), dog_ids }
+        })
+        .collect();
+    Ok(rosters)
+}
+
+/// Put an attending dog in a playgroup for the day, moving it out of any other
+/// group. Refused when the group is full, the dog's size doesn't fit, it needs
+/// solo care, or it's incompatible with a dog already in the group. Returns
+/// warnings for things staff should check, like an unknown size.
+#[tauri::command]
+pub fn assign_dog_to_playgroup(date: String, dog_id: String, group_id: String) -> Result<Vec<String>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    let on = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    
+    let group = data.playgroups.iter()
+        .find(|g| g.id == group_id)
+        .cloned()
+        .ok_or("Playgroup not found")?;
+    if !group.active {
+        return Err(format!("Playgroup '{}' is not active", group.name));
+    }
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    let day_data = data.daily_data.get(&date);
+    
+    let attending = day_data
+        .is_some_and(|d| d.attendance.entries.values().any(|e| e.dog_id == dog_id && e.attending));
+    if !attending {
+        return Err(format!("{} isn't booked in on {}", dog.name, date));
+    }
+    if dog.solo_care {
+        return Err(format!("{} needs solo care and stays out of group play", dog.name));
+    }
+    
+    let mut warnings: Vec<String> = intact_warning(dog, &data.settings.intact_policy, on).into_iter().collect();
+    match (group.size_class, dog.size) {
+        (Some(size_class), Some(size)) if size_class != size => {
+            return Err(format!("{} is {} and '{}' is for {} dogs", dog.name, size.label(), group.name, size_class.label()));
+        }
+        (Some(_), None) => warnings.push(format!("{} has no size recorded; check they suit '{}'", dog.name, group.name)),
+        _ => {}
+    }
+    
+    let others: Vec<&String> = day_data
+        .map(|d| members(d, &group.id).filter(|id| **id != dog_id).collect())
+        .unwrap_or_default();
+    if let Some(other) = others.iter().find(|other| incompatible(&data, &dog_id, other)) {
+        let other_name = data.dogs.iter().find(|d| d.id == **other).map_or("another dog", |d| d.name.as_str());
+        return Err(format!("{} can't be grouped with {}", dog.name, other_name));
+    }
+    if others.len() as u32 >= group.capacity {
+        return Err(format!("Playgroup '{}' is full ({} dogs)", group.name, group.capacity));
+    }
+    if let Some(ref area_id) = group.area_id {
+        let area = data.areas.iter()
+            .find(|a| a.id == *area_id)
+            .ok_or("The playgroup's area no longer exists")?;
+        if !area.active {
+            return Err(format!("Area '{}' is not active", area.name));
+        }
+        let headcount = day_data.map_or(0, |d| area_headcount(&data, d, area_id, &dog_id));
+        if headcount >= area.capacity {
+            return Err(format!("Area '{}' is full ({} dogs)", area.name, area.capacity));
+        }
+    }
+    
+    let day_data = data.daily_data.entry(date).or_default();
+    day_data.playgroup_assignments.insert(dog_id, group.id);
+    save_app_data(&data)?;
+    Ok(warnings)
+}
+
+#[tauri::command]
+pub fn unassign_dog_from_playgroup(date: String, dog_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if data.daily_data.get_mut(&date).and_then(|d| d.playgroup_assignments.remove(&dog_id)).is_some() {
+        save_app_data(&data)?;
+    }
+    Ok(())
+}
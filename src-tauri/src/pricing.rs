@@ -0,0 +1,339 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::age::AgeClass;
+use crate::audit::record_audit;
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AttendanceType, CurrencySettings, ServiceType};
+
+/// Price of one day (or night, for boarding) of a service. A rate with an age
+/// class, e.g. a puppy rate, takes precedence over the general rate for dogs
+/// in that class. A price rise is added as a new rate with the date it starts,
+/// so days before it are still charged at the old price.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Rate {
+    pub service_type: ServiceType,
+    pub attendance_type: AttendanceType,
+    pub price: f64,
+    #[serde(default)]
+    pub age_class: Option<AgeClass>,
+    #[serde(default)]
+    pub hourly_price: Option<f64>, // Used instead of `price` for services billed by the hour
+    #[serde(default)]
+    pub effective_from: Option<String>, // YYYY-MM-DD; None for a rate that has always applied
+}
+
+impl Rate {
+    fn effective_from(&self) -> Option<NaiveDate> {
+        self.effective_from.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    }
+    
+    /// Whether two rates are the same price point, so one replaces the other.
+    fn same_slot(&self, other: &Rate) -> bool {
+        self.service_type == other.service_type
+            && self.attendance_type == other.attendance_type
+            && self.age_class == other.age_class
+            && self.effective_from == other.effective_from
+    }
+}
+
+/// Percentage off for the Nth dog from the same household, e.g. 10% off the second dog.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MultiDogDiscount {
+    pub dog_number: u32,
+    pub percent: f64,
+}
+
+/// Fixed weekly price for a set number of days, used instead of the day rate
+/// when it works out cheaper.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WeeklyPackage {
+    pub name: String,
+    pub service_type: ServiceType,
+    pub attendance_type: AttendanceType,
+    pub days_per_week: u32,
+    pub weekly_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PriceList {
+    pub rates: Vec<Rate>,
+    pub multi_dog_discounts: Vec<MultiDogDiscount>,
+    pub weekly_packages: Vec<WeeklyPackage>,
+}
+
+impl PriceList {
+    /// The rate in force on `on`: the most recent one to have taken effect by then.
+    pub fn rate(
+        &self,
+        service_type: &ServiceType,
+        attendance_type: &AttendanceType,
+        age_class: Option<AgeClass>,
+        on: NaiveDate,
+    ) -> Option<&Rate> {
+        let latest = |class: Option<AgeClass>| self.rates.iter()
+            .filter(|r| r.service_type == *service_type && r.attendance_type == *attendance_type && r.age_class == class)
+            .filter(|r| r.effective_from().is_none_or(|from| from <= on))
+            .max_by_key(|r| r.effective_from());
+        
+        age_class
+            .and_then(|class| latest(Some(class)))
+            .or_else(|| latest(None))
+    }
+    
+    pub fn validate(&self) -> Result<(), String> {
+        for rate in &self.rates {
+            if let Some(ref from) = rate.effective_from {
+                NaiveDate::parse_from_str(from, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid start date '{}' for {} price", from, rate_label(rate).to_lowercase()))?;
+            }
+            if rate.price < 0.0 || rate.hourly_price.is_some_and(|p| p < 0.0) {
+                return Err(format!("The {} price can't be negative", rate_label(rate).to_lowercase()));
+            }
+        }
+        Ok(())
+    }
+
+    
+    /// Discount for the Nth dog in a household. Dogs past the last configured
+    /// number get the discount of the highest configured number below them.
+    pub fn multi_dog_percent(&self, dog_number: u32) -> f64 {
+        self.multi_dog_discounts.iter()
+            .filter(|d| d.dog_number <= dog_number)
+            .max_by_key(|d| d.dog_number)
+            .map(|d| d.percent)
+            .unwrap_or(0.0)
+    }
+    
+    pub fn weekly_package(
+        &self,
+        service_type: &ServiceType,
+        attendance_type: &AttendanceType,
+        days_per_week: u32,
+    ) -> Option<&WeeklyPackage> {
+        self.weekly_packages.iter().find(|p| {
+            p.service_type == *service_type && p.attendance_type == *attendance_type && p.days_per_week == days_per_week
+        })
+    }
+}
+
+/// Label for a rate's line on quotes and invoices, noting age-class rates.
+pub fn rate_label(rate: &Rate) -> String {
+    let label = service_label(&rate.service_type, &rate.attendance_type);
+    match rate.age_class {
+        Some(AgeClass::Puppy) => format!("{} (puppy rate)", label),
+        Some(AgeClass::Adult) => format!("{} (adult rate)", label),
+        Some(AgeClass::Senior) => format!("{} (senior rate)", label),
+        None => label,
+    }
+}
+
+pub fn service_label(service_type: &ServiceType, attendance_type: &AttendanceType) -> String {
+    let service = match service_type {
+        ServiceType::Daycare => "daycare",
+        ServiceType::Training => "training",
+        ServiceType::Boarding => "boarding",
+    };
+    match attendance_type {
+        AttendanceType::HalfDay => format!("Half-day {}", service),
+        AttendanceType::FullDay => format!("Full-day {}", service),
+        AttendanceType::NotAttending => service.to_string(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuoteLine {
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Quote {
+    pub service_type: ServiceType,
+    pub attendance_type: AttendanceType,
+    pub days_per_week: u32,
+    pub dogs_in_household: u32,
+    pub lines: Vec<QuoteLine>,
+    pub weekly_total: f64,
+    pub monthly_estimate: f64, // Weekly total averaged over 52 weeks a year
+    pub formatted_weekly_total: String,
+    pub formatted_monthly_estimate: String,
+}
+
+fn build_quote(
+    prices: &PriceList,
+    currency: &CurrencySettings,
+    service_type: ServiceType,
+    attendance_type: AttendanceType,
+    days_per_week: u32,
+    dogs_in_household: u32,
+    age_class: Option<AgeClass>,
+) -> Result<Quote, String> {
+    if attendance_type == AttendanceType::NotAttending {
+        return Err("Choose a half or full day to quote".to_string());
+    }
+    if days_per_week == 0 || days_per_week > 7 {
+        return Err("Days per week must be between 1 and 7".to_string());
+    }
+    if dogs_in_household == 0 {
+        return Err("At least one dog is required".to_string());
+    }
+    
+    let rate = prices.rate(&service_type, &attendance_type, age_class, Utc::now().date_naive())
+        .ok_or(format!("No price set for {}", service_label(&service_type, &attendance_type).to_lowercase()))?;
+    let label = rate_label(rate);
+    let day_rate = rate.price;
+    
+    // Use the weekly package when it beats paying day by day
+    let per_dog = match prices.weekly_package(&service_type, &attendance_type, days_per_week) {
+        Some(package) if package.weekly_price < day_rate * days_per_week as f64 => QuoteLine {
+            description: package.name.clone(),
+            quantity: 1,
+            unit_price: currency.round(package.weekly_price),
+            amount: currency.round(package.weekly_price),
+        },
+        _ => QuoteLine {
+            description: label,
+            quantity: days_per_week,
+            unit_price: currency.round(day_rate),
+            amount: currency.round(day_rate * days_per_week as f64),
+        },
+    };
+    
+    let mut lines = Vec::new();
+    for dog_number in 1..=dogs_in_household {
+        let mut line = per_dog.clone();
+        if dogs_in_household > 1 {
+            line.description = format!("Dog {}: {}", dog_number, line.description);
+        }
+        let amount = line.amount;
+        lines.push(line);
+        
+        let percent = prices.multi_dog_percent(dog_number);
+        if percent > 0.0 {
+            let discount = currency.round(amount * percent / 100.0);
+            lines.push(QuoteLine {
+                description: format!("Dog {}: multi-dog discount ({}%)", dog_number, percent),
+                quantity: 1,
+                unit_price: -discount,
+                amount: -discount,
+            });
+        }
+    }
+    
+    let weekly_total = currency.round(lines.iter().map(|l| l.amount).sum());
+    let monthly_estimate = currency.round(weekly_total * 52.0 / 12.0);
+    
+    Ok(Quote {
+        service_type,
+        attendance_type,
+        days_per_week,
+        dogs_in_household,
+        lines,
+        weekly_total,
+        monthly_estimate,
+        formatted_weekly_total: currency.format(weekly_total),
+        formatted_monthly_estimate: currency.format(monthly_estimate),
+    })
+}
+
+/// Itemized weekly price for an enquiry, from the price list in settings.
+/// Pass an age class to quote e.g. the puppy rate.
+#[tauri::command]
+pub fn quote_booking(
+    service_type: ServiceType,
+    attendance_type: AttendanceType,
+    days_per_week: u32,
+    dogs_in_household: u32,
+    age_class: Option<AgeClass>,
+) -> Result<Quote, String> {
+    let data = load_app_data()?;
+    build_quote(
+        &data.settings.pricing,
+        &data.settings.currency,
+        service_type,
+        attendance_type,
+        days_per_week,
+        dogs_in_household,
+        age_class,
+    )
+}
+
+/// Rates that haven't taken effect yet, soonest first.
+fn scheduled_changes(prices: &PriceList) -> Vec<Rate> {
+    let today = Utc::now().date_naive();
+    let mut scheduled: Vec<Rate> = prices.rates.iter()
+        .filter(|r| r.effective_from().is_some_and(|from| from > today))
+        .cloned()
+        .collect();
+    scheduled.sort_by(|a, b| a.effective_from.cmp(&b.effective_from).then(rate_label(a).cmp(&rate_label(b))));
+    scheduled
+}
+
+#[tauri::command]
+pub fn get_scheduled_price_changes() -> Result<Vec<Rate>, String> {
+    let data = load_app_data()?;
+    Ok(scheduled_changes(&data.settings.pricing))
+}
+
+/// Add a rate that takes over from the current one on its `effective_from`
+/// date, which must be in the future. Days before then, including any billed
+/// again later, keep the old price. Replaces a change already scheduled for
+/// the same rate and date. Returns all scheduled changes.
+#[tauri::command]
+pub fn schedule_price_change(rate: Rate) -> Result<Vec<Rate>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    if rate.attendance_type == AttendanceType::NotAttending {
+        return Err("Choose a half or full day rate to change".to_string());
+    }
+    let from = rate.effective_from.as_deref().ok_or("A price change needs the date it starts")?;
+    let starts = NaiveDate::parse_from_str(from, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid start date '{}'", from))?;
+    if starts <= Utc::now().date_naive() {
+        return Err("A scheduled price change must start after today".to_string());
+    }
+    
+    let prices = &mut data.settings.pricing;
+    prices.rates.retain(|r| !r.same_slot(&rate));
+    prices.rates.push(rate.clone());
+    prices.validate()?;
+    
+    let details = format!(
+        "{} price changes to {} from {}",
+        rate_label(&rate), data.settings.currency.format(rate.price), from
+    );
+    record_audit(&mut data, "price_change_scheduled", &details);
+    save_app_data(&data)?;
+    Ok(scheduled_changes(&data.settings.pricing))
+}
+
+/// Drop a price change that hasn't started yet. Rates already in force can
+/// only be changed in settings, since invoices may have been priced on them.
+#[tauri::command]
+pub fn cancel_price_change(
+    service_type: ServiceType,
+    attendance_type: AttendanceType,
+    age_class: Option<AgeClass>,
+    effective_from: String,
+) -> Result<Vec<Rate>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let scheduled = scheduled_changes(&data.settings.pricing);
+    let change = scheduled.iter()
+        .find(|r| r.service_type == service_type
+            && r.attendance_type == attendance_type
+            && r.age_class == age_class
+            && r.effective_from.as_deref() == Some(effective_from.as_str()))
+        .ok_or("No price change is scheduled for that rate and date")?;
+    data.settings.pricing.rates.retain(|r| !r.same_slot(change));
+    
+    let details = format!("Cancelled {} price change from {}", rate_label(change), effective_from);
+    record_audit(&mut data, "price_change_cancelled", &details);
+    save_app_data(&data)?;
+    Ok(scheduled_changes(&data.settings.pricing))
+}
@@ -0,0 +1,494 @@
+use chrono::{Duration, NaiveDate};
+use std::collections::HashSet;
+
+use crate::attendance_keys::entry_key;
+use crate::pdf::{fit_text, wrap_text, PdfWriter};
+use crate::{latest_locations, load_app_data, should_generate_attendance, AppData, Dog, DogStatus, ServiceType};
+
+struct PlannerItem {
+    drop_off: Option<String>,
+    label: String,
+}
+
+fn service_marker(service_type: &ServiceType) -> &'static str {
+    match service_type {
+        ServiceType::Daycare => "",
+        ServiceType::Training => " (T)",
+        ServiceType::Boarding => " (B)",
+    }
+}
+
+/// Dogs expected on a day: booked attendance, plus anything recurring schedules
+/// will add once attendance is generated that far ahead.
+fn expected_dogs(data: &AppData, date: NaiveDate) -> Vec<PlannerItem> {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let dog_name = |id: &str| data.dogs.iter().find(|d| d.id == id).map(|d| d.name.clone());
+    let defaults = &data.settings.default_times;
+    
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    
+    if let Some(day_data) = data.daily_data.get(&date_str) {
+        for (key, entry) in &day_data.attendance.entries {
+            seen.insert(key.clone());
+            if !entry.attending {
+                continue;
+            }
+            if let Some(name) = dog_name(&entry.dog_id) {
+                items.push(PlannerItem {
+                    drop_off: defaults.drop_off(&entry.service_type, &entry.drop_off_time),
+                    label: format!("{}{}", name, service_marker(&entry.service_type)),
+                });
+            }
+        }
+    }
+    
+    for schedule in data.recurring_schedules.iter().filter(|s| s.active) {
+        let key = entry_key(&schedule.dog_id, &schedule.service_type);
+        if seen.contains(&key) {
+            continue;
+        }
+        
+        let start = match NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d") {
+            Ok(start) => start,
+            Err(_) => continue,
+        };
+        let ended = schedule.end_date.as_ref()
+            .filter(|e| !e.is_empty())
+            .and_then(|e| NaiveDate::parse_from_str(e, "%Y-%m-%d").ok())
+            .is_some_and(|end| date > end);
+        if date < start || ended || !should_generate_attendance(date, start, &schedule.pattern) {
+            continue;
+        }
+        
+        let dog = match data.dogs.iter().find(|d| d.id == schedule.dog_id) {
+            Some(dog) if dog.status == DogStatus::Active => dog,
+            _ => continue,
+        };
+        seen.insert(key);
+        items.push(PlannerItem {
+            drop_off: defaults.drop_off(&schedule.service_type, &schedule.drop_off_time),
+            label: format!("{}{}", dog.name, service_marker(&schedule.service_type)),
+        });
+    }
+    
+    // Dogs without a drop-off time go after the timed ones
+    items.sort_by(|a, b| {
+        (a.drop_off.is_none(), &a.drop_off, &a.label).cmp(&(b.drop_off.is_none(), &b.drop_off, &b.label))
+    });
+    items
+}
+
+/// A4 landscape wall planner for the seven days from `week_start`, one column per
+/// day listing expected dogs by drop-off time. Returns the PDF bytes.
+#[tauri::command]
+pub fn export_week_planner_pdf(week_start: String) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    let start = NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|_| "Invalid week start date format".to_string())?;
+    
+    let days: Vec<(NaiveDate, Vec<PlannerItem>)> = (0..7)
+        .map(|offset| {
+            let date = start + Duration::days(offset);
+            (date, expected_dogs(&data, date))
+        })
+        .collect();
+    
+    let title = format!("{} - week of {}", data.settings.business_name, start.format("%-d %B %Y"));
+    let mut pdf = PdfWriter::a4(&title, true, &data.settings.branding)?;
+    
+    let margin = 10.0;
+    let column_width = (pdf.width - 2.0 * margin) / 7.0;
+    let grid_top = 24.0;
+    let header_height = 9.0;
+    let row_height = 4.6;
+    let font_size = 8.0;
+    let rows_per_page = ((pdf.height - margin - grid_top - header_height) / row_height).floor() as usize;
+    let most_dogs = days.iter().map(|(_, items)| items.len()).max().unwrap_or(0);
+    let pages = most_dogs.div_ceil(rows_per_page).max(1);
+    
+    for page in 0..pages {
+        if page > 0 {
+            pdf.new_page();
+        }
+        
+        pdf.heading(&title, 14.0, margin, 14.0);
+        if pages > 1 {
+            pdf.page_number(page + 1, pages);
+        }
+        pdf.text("(T) training   (B) boarding", 7.0, margin, 19.5, false);
+        
+        let grid_bottom = pdf.height - margin;
+        pdf.shade(margin, grid_top, pdf.width - 2.0 * margin, header_height);
+        pdf.line(margin, grid_top, pdf.width - margin, grid_top);
+        pdf.line(margin, grid_top + header_height, pdf.width - margin, grid_top + header_height);
+        pdf.line(margin, grid_bottom, pdf.width - margin, grid_bottom);
+        
+        for (column, (date, items)) in days.iter().enumerate() {
+            let x = margin + column as f32 * column_width;
+            pdf.line(x, grid_top, x, grid_bottom);
+            
+            let header = format!("{} ({})", date.format("%a %-d %b"), items.len());
+            pdf.text(&header, 9.0, x + 1.5, grid_top + 6.0, true);
+            
+            for (row, item) in items.iter().skip(page * rows_per_page).take(rows_per_page).enumerate() {
+                let line = match item.drop_off {
+                    Some(ref time) if !time.is_empty() => format!("{}  {}", time, item.label),
+                    _ => item.label.clone(),
+                };
+                let y = grid_top + header_height + (row as f32 + 1.0) * row_height;
+                pdf.text(&fit_text(&line, font_size, column_width - 3.0), font_size, x + 1.5, y, false);
+            }
+        }
+        pdf.line(pdf.width - margin, grid_top, pdf.width - margin, grid_bottom);
+    }
+    
+    pdf.finish()
+}
+
+/// Wording of the consent form, shared by the printed form and the one signed
+/// on a tablet.
+pub fn consent_statement(data: &AppData, dog: &Dog) -> String {
+    format!(
+        "I, the owner of {dog}, consent to {dog} attending {business} for the coming month. \
+        I confirm that {dog}'s vaccinations are up to date and that I have told {business} about any \
+        medical conditions, medication or behavioural concerns.\n\n\
+        I authorise {business} to seek veterinary treatment for {dog} if they become ill or injured \
+        while in care and I cannot be reached, and I accept responsibility for the cost of that treatment.\n\n\
+        I understand that dogs in group care may pick up minor injuries or illnesses through play, \
+        and that {business} will contact me as soon as possible if anything happens.",
+        dog = dog.name,
+        business = data.settings.business_name,
+    )
+}
+
+/// One-page consent form for a dog, pre-filled with the dog and owner details
+/// and left blank for the owner's signature.
+pub fn consent_form_pdf(data: &AppData, dog: &Dog) -> Result<Vec<u8>, String> {
+    let title = format!("{} - consent form for {}", data.settings.business_name, dog.name);
+    let pdf = PdfWriter::a4(&title, false, &data.settings.branding)?;
+    
+    let margin = 20.0;
+    let text_width = pdf.width - 2.0 * margin;
+    let mut y = 25.0;
+    
+    pdf.heading(&data.settings.business_name, 16.0, margin, y);
+    if !data.settings.business_phone.is_empty() {
+        pdf.text(&data.settings.business_phone, 9.0, margin, y + 6.0, false);
+    }
+    y += 18.0;
+    pdf.heading("Monthly Daycare Consent Form", 14.0, margin, y);
+    y += 12.0;
+    
+    let details = [
+        ("Dog", dog.name.as_str()),
+        ("Breed", dog.breed.as_str()),
+        ("Date of birth", dog.date_of_birth.as_deref().unwrap_or("")),
+        ("Owner", dog.owner.as_str()),
+        ("Phone", dog.phone.as_str()),
+        ("Email", dog.email.as_str()),
+    ];
+    for (label, value) in details {
+        pdf.text(label, 10.0, margin, y, true);
+        pdf.text(value, 10.0, margin + 35.0, y, false);
+        y += 7.0;
+    }
+    y += 6.0;
+    
+    for line in wrap_text(&consent_statement(data, dog), 10.0, text_width) {
+        pdf.text(&line, 10.0, margin, y, false);
+        y += if line.is_empty() { 3.0 } else { 5.5 };
+    }
+    y += 25.0;
+    
+    let half = text_width / 2.0 - 5.0;
+    pdf.line(margin, y, margin + half, y);
+    pdf.line(margin + half + 10.0, y, pdf.width - margin, y);
+    pdf.text("Owner signature", 9.0, margin, y + 5.0, false);
+    pdf.text("Date", 9.0, margin + half + 10.0, y + 5.0, false);
+    
+    pdf.finish()
+}
+
+#[tauri::command]
+pub fn export_consent_form_pdf(dog_id: String) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    consent_form_pdf(&data, dog)
+}
+
+struct EvacuationRow<'a> {
+    dog: &'a Dog,
+    area: String,
+}
+
+/// Dogs on site for the evacuation sheet: everyone checked in and not yet
+/// collected. When nobody has been checked in that day, everyone booked in.
+fn dogs_on_site<'a>(data: &'a AppData, date: &str) -> (Vec<EvacuationRow<'a>>, bool) {
+    let day_data = match data.daily_data.get(date) {
+        Some(day_data) => day_data,
+        None => return (Vec::new(), false),
+    };
+    
+    let checked_in: Vec<&String> = day_data.records.iter()
+        .filter(|(_, r)| r.checked_in_at.is_some() && r.checked_out_at.is_none())
+        .map(|(dog_id, _)| dog_id)
+        .collect();
+    let from_bookings = !day_data.records.values().any(|r| r.checked_in_at.is_some());
+    
+    let mut dog_ids: Vec<&String> = if from_bookings {
+        day_data.attendance.entries.values()
+            .filter(|e| e.attending)
+            .map(|e| &e.dog_id)
+            .collect()
+    } else {
+        checked_in
+    };
+    dog_ids.sort();
+    dog_ids.dedup();
+    
+    let locations = latest_locations(day_data);
+    let area_name = |dog_id: &str| {
+        let area_id = locations.get(dog_id)
+            .map(|e| &e.area_id)
+            .or_else(|| day_data.area_assignments.get(dog_id));
+        area_id
+            .and_then(|id| data.areas.iter().find(|a| a.id == *id))
+            .map(|a| a.name.clone())
+            .unwrap_or_default()
+    };
+    
+    let mut rows: Vec<EvacuationRow> = dog_ids.into_iter()
+        .filter_map(|id| data.dogs.iter().find(|d| d.id == *id))
+        .map(|dog| EvacuationRow { dog, area: area_name(&dog.id) })
+        .collect();
+    rows.sort_by(|a, b| a.area.cmp(&b.area).then(a.dog.name.cmp(&b.dog.name)));
+    (rows, from_bookings)
+}
+
+/// One-glance A4 list of every dog on site with photo, area, owner and vet
+/// contacts, plus a box to tick each dog off at the assembly point.
+#[tauri::command]
+pub fn export_evacuation_sheet(date: String) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    let (rows, from_bookings) = dogs_on_site(&data, &date);
+    
+    let title = format!("{} - evacuation sheet {}", data.settings.business_name, day.format("%-d %B %Y"));
+    let mut pdf = PdfWriter::a4(&title, false, &data.settings.branding)?;
+    
+    let margin = 10.0;
+    let top = 30.0;
+    let header_height = 7.0;
+    let row_height = 22.0;
+    let photo_size = 18.0;
+    let columns = [
+        ("Out", margin),
+        ("", margin + 10.0),
+        ("Dog", margin + 31.0),
+        ("Area", margin + 76.0),
+        ("Owner", margin + 106.0),
+        ("Vet", margin + 148.0),
+    ];
+    let right_edge = pdf.width - margin;
+    let column_width = |index: usize| {
+        columns.get(index + 1).map(|c| c.1).unwrap_or(right_edge) - columns[index].1 - 2.0
+    };
+    let rows_per_page = ((pdf.height - margin - top - header_height) / row_height).floor() as usize;
+    let pages = rows.len().div_ceil(rows_per_page).max(1);
+    
+    for page in 0..pages {
+        if page > 0 {
+            pdf.new_page();
+        }
+        
+        pdf.heading(&title, 14.0, margin, 14.0);
+        let subtitle = if from_bookings {
+            format!("{} dogs booked in (no check-ins recorded)", rows.len())
+        } else {
+            format!("{} dogs on site", rows.len())
+        };
+        pdf.text(&subtitle, 10.0, margin, 20.0, false);
+        if pages > 1 {
+            pdf.page_number(page + 1, pages);
+        }
+        if !data.settings.business_phone.is_empty() {
+            pdf.text(&format!("Site phone: {}", data.settings.business_phone), 9.0, margin, 25.5, false);
+        }
+        
+        pdf.shade(margin, top, pdf.width - 2.0 * margin, header_height);
+        for (label, x) in columns {
+            pdf.text(label, 9.0, x + 1.0, top + 5.0, true);
+        }
+        
+        for (index, row) in rows.iter().skip(page * rows_per_page).take(rows_per_page).enumerate() {
+            let y = top + header_height + index as f32 * row_height;
+            let dog = row.dog;
+            
+            pdf.line(margin + 2.0, y + 6.0, margin + 7.0, y + 6.0);
+            pdf.line(margin + 2.0, y + 11.0, margin + 7.0, y + 11.0);
+            pdf.line(margin + 2.0, y + 6.0, margin + 2.0, y + 11.0);
+            pdf.line(margin + 7.0, y + 6.0, margin + 7.0, y + 11.0);
+            
+            if let Some(path) = dog.photo_path.as_deref().filter(|p| !p.is_empty()) {
+                if let Err(e) = pdf.thumbnail(path, columns[1].1 + 1.0, y + 2.0, photo_size) {
+                    println!("Leaving out photo for {}: {}", dog.name, e);
+                }
+            }
+            
+            let cells: [Vec<String>; 4] = [
+                vec![dog.name.clone(), dog.breed.clone()],
+                vec![row.area.clone()],
+                vec![dog.owner.clone(), dog.phone.clone()],
+                match &dog.vet {
+                    Some(vet) => vec![vet.clinic.clone(), vet.phone.clone()],
+                    None => Vec::new(),
+                },
+            ];
+            for (column, lines) in cells.iter().enumerate() {
+                let width = column_width(column + 2);
+                for (line_index, line) in lines.iter().enumerate() {
+                    let bold = line_index == 0 && column != 1;
+                    pdf.text(&fit_text(line, 9.0, width), 9.0, columns[column + 2].1 + 1.0, y + 6.0 + line_index as f32 * 5.0, bold);
+                }
+            }
+            
+            pdf.line(margin, y + row_height, pdf.width - margin, y + row_height);
+        }
+    }
+    
+    pdf.finish()
+}
+
+struct RosterRow<'a> {
+    dog: &'a Dog,
+    services: String,
+    drop_off: Option<String>,
+    pick_up: Option<String>,
+    feeding: String,
+}
+
+/// One row per dog booked in on the day. Times recorded on the dog's day
+/// record win over the booking, which wins over the service's defaults.
+fn roster_rows<'a>(data: &'a AppData, date: &str) -> Vec<RosterRow<'a>> {
+    let day_data = match data.daily_data.get(date) {
+        Some(day_data) => day_data,
+        None => return Vec::new(),
+    };
+    let defaults = &data.settings.default_times;
+    
+    let mut rows: Vec<RosterRow> = Vec::new();
+    let mut entries: Vec<_> = day_data.attendance.entries.values().filter(|e| e.attending).collect();
+    entries.sort_by_key(|e| e.service_type.key());
+    for entry in entries {
+        let dog = match data.dogs.iter().find(|d| d.id == entry.dog_id) {
+            Some(dog) => dog,
+            None => continue,
+        };
+        let drop_off = defaults.drop_off(&entry.service_type, &entry.drop_off_time);
+        let pick_up = defaults.pick_up(&entry.service_type, &entry.pick_up_time);
+        if let Some(row) = rows.iter_mut().find(|r| r.dog.id == dog.id) {
+            row.services.push_str(service_marker(&entry.service_type));
+            row.drop_off = row.drop_off.take().into_iter().chain(drop_off).min();
+            row.pick_up = row.pick_up.take().into_iter().chain(pick_up).max();
+            continue;
+        }
+        
+        let record = day_data.records.get(&dog.id);
+        let recorded = |time: Option<&String>| time.filter(|t| !t.trim().is_empty()).cloned();
+        rows.push(RosterRow {
+            dog,
+            services: service_marker(&entry.service_type).to_string(),
+            drop_off: recorded(record.and_then(|r| r.drop_off_time.as_ref())).or(drop_off),
+            pick_up: recorded(record.and_then(|r| r.pick_up_time.as_ref())).or(pick_up),
+            feeding: record.and_then(|r| r.feeding_times.clone()).unwrap_or_default(),
+        });
+    }
+    
+    rows.sort_by(|a, b| {
+        (a.drop_off.is_none(), &a.drop_off, &a.dog.name).cmp(&(b.drop_off.is_none(), &b.drop_off, &b.dog.name))
+    });
+    rows
+}
+
+/// A4 landscape sign-in sheet for the day: every dog booked in with their
+/// times and feeding notes, and blank columns for whoever drops off and
+/// collects the dog to sign.
+#[tauri::command]
+pub fn render_daily_roster_pdf(date: String) -> Result<Vec<u8>, String> {
+    let data = load_app_data()?;
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| "Invalid date format".to_string())?;
+    let rows = roster_rows(&data, &date);
+    
+    let title = format!("{} - sign-in sheet {}", data.settings.business_name, day.format("%A %-d %B %Y"));
+    let mut pdf = PdfWriter::a4(&title, true, &data.settings.branding)?;
+    
+    let margin = 10.0;
+    let top = 26.0;
+    let header_height = 7.0;
+    let row_height = 12.0;
+    let columns = [
+        ("Dog", margin),
+        ("Owner", margin + 48.0),
+        ("Drop-off", margin + 88.0),
+        ("Pick-up", margin + 106.0),
+        ("Feeding", margin + 124.0),
+        ("Dropped off by (signature)", margin + 175.0),
+        ("Collected by (signature)", margin + 226.0),
+    ];
+    let right_edge = pdf.width - margin;
+    let column_width = |index: usize| {
+        columns.get(index + 1).map(|c| c.1).unwrap_or(right_edge) - columns[index].1 - 2.0
+    };
+    let rows_per_page = ((pdf.height - margin - top - header_height) / row_height).floor() as usize;
+    let pages = rows.len().div_ceil(rows_per_page).max(1);
+    
+    for page in 0..pages {
+        if page > 0 {
+            pdf.new_page();
+        }
+        
+        pdf.heading(&title, 14.0, margin, 14.0);
+        pdf.text(&format!("{} dogs booked in   (T) training   (B) boarding", rows.len()), 9.0, margin, 20.0, false);
+        if pages > 1 {
+            pdf.page_number(page + 1, pages);
+        }
+        
+        pdf.shade(margin, top, right_edge - margin, header_height);
+        for (label, x) in columns {
+            pdf.text(label, 9.0, x + 1.0, top + 5.0, true);
+        }
+        let table_top = top + header_height;
+        
+        let page_rows: Vec<&RosterRow> = rows.iter().skip(page * rows_per_page).take(rows_per_page).collect();
+        for (index, row) in page_rows.iter().enumerate() {
+            let y = table_top + index as f32 * row_height;
+            let cells = [
+                format!("{}{}", row.dog.name, row.services),
+                row.dog.owner.clone(),
+                row.drop_off.clone().unwrap_or_default(),
+                row.pick_up.clone().unwrap_or_default(),
+                row.feeding.clone(),
+            ];
+            for (column, cell) in cells.iter().enumerate() {
+                let text = fit_text(cell, 9.0, column_width(column));
+                pdf.text(&text, 9.0, columns[column].1 + 1.0, y + 7.5, column == 0);
+            }
+            pdf.line(margin, y + row_height, right_edge, y + row_height);
+        }
+        
+        let table_bottom = table_top + page_rows.len() as f32 * row_height;
+        for (_, x) in columns.iter().skip(1) {
+            pdf.line(*x, top, *x, table_bottom);
+        }
+        pdf.line(margin, top, margin, table_bottom);
+        pdf.line(right_edge, top, right_edge, table_bottom);
+    }
+    
+    pdf.finish()
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::attendance_keys::entry_key;
+use crate::audit::record_audit;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, Dog, EntrySource, ServiceType};
+
+/// A dog kept away after illness, e.g. 14 days for kennel cough. While it lasts
+/// the dog can't be checked in and schedules don't book it in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Quarantine {
+    pub until: String, // Last day excluded, YYYY-MM-DD
+    pub reason: String,
+    pub set_by: Option<String>,
+    pub set_at: DateTime<Utc>,
+}
+
+pub fn is_quarantined(dog: &Dog, date: &str) -> bool {
+    dog.quarantine.as_ref().is_some_and(|q| date <= q.until.as_str())
+}
+
+pub fn ensure_not_quarantined(dog: &Dog, date: &str) -> Result<(), String> {
+    match dog.quarantine {
+        Some(ref q) if is_quarantined(dog, date) => Err(format!(
+            "{} is in quarantine until {} ({})", dog.name, q.until, q.reason
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuarantinedDog {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub quarantine: Quarantine,
+}
+
+/// Quarantine a dog up to and including `until`. Bookings schedules already made
+/// for the period are removed; hand-made bookings are left for staff to sort out
+/// with the owner.
+#[tauri::command]
+pub fn set_quarantine(dog_id: String, until: String, reason: String) -> Result<Quarantine, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let until_date = NaiveDate::parse_from_str(&until, "%Y-%m-%d")
+        .map_err(|_| "Invalid quarantine end date format".to_string())?;
+    let today = Utc::now().date_naive();
+    if until_date < today {
+        return Err("Quarantine end date is in the past".to_string());
+    }
+    if reason.trim().is_empty() {
+        return Err("A reason is required for quarantine".to_string());
+    }
+    
+    let quarantine = Quarantine {
+        until: until.clone(),
+        reason: reason.trim().to_string(),
+        set_by: current_staff_id(),
+        set_at: Utc::now(),
+    };
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    dog.quarantine = Some(quarantine.clone());
+    let dog_name = dog.name.clone();
+    
+    let today = today.format("%Y-%m-%d").to_string();
+    let locked: Vec<String> = data.locked_days.iter().map(|l| l.date.clone()).collect();
+    let mut removed = 0;
+    for (date, day_data) in data.daily_data.iter_mut() {
+        if *date < today || *date > until || locked.contains(date) {
+            continue;
+        }
+        let before = day_data.attendance.entries.len();
+        day_data.attendance.entries.retain(|_, e| e.dog_id != dog_id || e.source != EntrySource::AutoSchedule);
+        removed += before - day_data.attendance.entries.len();
+        if !day_data.attendance.entries.contains_key(&entry_key(&dog_id, &ServiceType::Daycare)) {
+            day_data.attendance.dogs.remove(&dog_id);
+        }
+    }
+    
+    record_audit(&mut data, "quarantine_set", &format!(
+        "{} quarantined until {}: {} ({} scheduled booking(s) removed)",
+        dog_name, until, quarantine.reason, removed
+    ));
+    save_app_data(&data)?;
+    
+    Ok(quarantine)
+}
+
+/// End a quarantine early, e.g. when the vet gives the all-clear. Schedules book
+/// the dog in again the next time attendance is generated.
+#[tauri::command]
+pub fn clear_quarantine(dog_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    if dog.quarantine.take().is_none() {
+        return Err(format!("{} is not in quarantine", dog.name));
+    }
+    let dog_name = dog.name.clone();
+    
+    record_audit(&mut data, "quarantine_cleared", &format!("{} released from quarantine", dog_name));
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Dogs in quarantine today or later, soonest back first.
+#[tauri::command]
+pub fn get_current_quarantines() -> Result<Vec<QuarantinedDog>, String> {
+    let data = load_app_data()?;
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    
+    let mut dogs: Vec<QuarantinedDog> = data.dogs.iter()
+        .filter(|d| is_quarantined(d, &today))
+        .filter_map(|d| d.quarantine.clone().map(|quarantine| QuarantinedDog {
+            dog_id: d.id.clone(),
+            dog_name: d.name.clone(),
+            owner: d.owner.clone(),
+            quarantine,
+        }))
+        .collect();
+    
+    dogs.sort_by(|a, b| a.quarantine.until.cmp(&b.quarantine.until).then(a.dog_name.cmp(&b.dog_name)));
+    Ok(dogs)
+}
@@ -0,0 +1,289 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::billing::{Payment, PaymentMethod};
+use crate::invoices::{amount_due, Invoice};
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData};
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y", "%d-%m-%Y", "%Y/%m/%d", "%d/%m/%y"];
+
+/// A credit from an imported bank statement. Kept so the same statement can be
+/// imported twice without double-counting, and so unmatched credits can be
+/// matched by hand later.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BankTransaction {
+    pub id: String,
+    pub date: String,
+    pub amount: f64,
+    pub reference: String,
+    pub payer: String,
+    pub invoice_id: Option<String>, // Set once matched
+    pub payment_id: Option<String>, // Payment recorded for the match
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum MatchedBy {
+    #[serde(rename = "reference")]
+    Reference, // Invoice number found in the transaction reference
+    #[serde(rename = "amount")]
+    Amount, // Only open invoice for exactly that amount
+    #[serde(rename = "manual")]
+    Manual,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReconciledTransaction {
+    pub transaction: BankTransaction,
+    pub invoice_number: String,
+    pub matched_by: MatchedBy,
+    pub still_due: f64, // Left to pay on the invoice afterwards
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReconciliationReport {
+    pub matched: Vec<ReconciledTransaction>,
+    pub unmatched: Vec<BankTransaction>,
+    pub duplicates: usize, // Already imported from an earlier statement
+    pub debits_skipped: usize,
+    pub errors: Vec<String>, // "Row N: reason"
+}
+
+fn normalize_header(header: &str) -> String {
+    header.to_lowercase().chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+}
+
+fn column(headers: &[String], names: &[&str]) -> Option<usize> {
+    names.iter().find_map(|name| headers.iter().position(|h| h == name))
+}
+
+/// Amounts as banks export them: "1,234.56", "1.234,56", "-12.00", "(12.00)", "£40", "40 CR".
+fn parse_amount(value: &str) -> Option<f64> {
+    let negative = value.contains('-') || value.trim().starts_with('(') || value.to_uppercase().ends_with("DR");
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',').collect();
+    if digits.is_empty() {
+        return None;
+    }
+    // Whichever separator comes last is the decimal point
+    let normalized = match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => digits.replace('.', "").replace(',', "."),
+        (None, Some(comma)) if digits.len() - comma == 3 => digits.replace(',', "."),
+        _ => digits.replace(',', ""),
+    };
+    let amount: f64 = normalized.parse().ok()?;
+    Some(if negative { -amount } else { amount })
+}
+
+fn parse_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    DATE_FORMATS.iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(value, fmt).ok())
+        .or_else(|| value.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()))
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+fn squash(text: &str) -> String {
+    text.to_lowercase().chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+}
+
+/// Credits (date, amount, reference, payer) from a bank statement CSV.
+fn parse_statement(content: &str, report: &mut ReconciliationReport) -> Result<Vec<(String, f64, String, String)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    
+    let headers: Vec<String> = reader.headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .iter()
+        .map(normalize_header)
+        .collect();
+    let date_col = column(&headers, &["date", "bookingdate", "transactiondate", "valuedate", "posteddate"])
+        .ok_or("The statement has no date column")?;
+    let amount_col = column(&headers, &["amount", "value", "transactionamount"]);
+    let credit_col = column(&headers, &["credit", "paidin", "moneyin", "creditamount"]);
+    if amount_col.is_none() && credit_col.is_none() {
+        return Err("The statement has no amount or credit column".to_string());
+    }
+    let reference_col = column(&headers, &["reference", "description", "details", "memo", "narrative", "transactiondescription"]);
+    let payer_col = column(&headers, &["payer", "name", "counterparty", "payee", "from"]);
+    
+    let mut credits = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let row = index + 2;
+        let record = record.map_err(|e| format!("Failed to read CSV row: {}", e))?;
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("").to_string();
+        
+        let date = match parse_date(&field(Some(date_col))) {
+            Some(date) => date,
+            None => {
+                report.errors.push(format!("Row {}: unreadable date '{}'", row, field(Some(date_col))));
+                continue;
+            }
+        };
+        let amount = match credit_col.map(|c| field(Some(c))).filter(|v| !v.is_empty()) {
+            Some(credit) => parse_amount(&credit),
+            None => parse_amount(&field(amount_col)),
+        };
+        match amount {
+            Some(amount) if amount > 0.0 => credits.push((date, amount, field(reference_col), field(payer_col))),
+            Some(_) => report.debits_skipped += 1,
+            None if credit_col.is_some() => report.debits_skipped += 1, // Money out only fills the debit column
+            None => report.errors.push(format!("Row {}: unreadable amount", row)),
+        }
+    }
+    Ok(credits)
+}
+
+/// The open invoice a credit pays: one whose number appears in the reference,
+/// or failing that the only open invoice for exactly that amount.
+fn find_match(data: &AppData, transaction: &BankTransaction) -> Option<(String, MatchedBy)> {
+    let open: Vec<&Invoice> = data.invoices.iter().filter(|i| amount_due(data, i) > 0.0).collect();
+    let reference = squash(&transaction.reference);
+    
+    if let Some(invoice) = open.iter().find(|i| !i.number.is_empty() && reference.contains(&squash(&i.number))) {
+        return Some((invoice.id.clone(), MatchedBy::Reference));
+    }
+    
+    let currency = &data.settings.currency;
+    let exact: Vec<&&Invoice> = open.iter()
+        .filter(|i| currency.round(amount_due(data, i)) == currency.round(transaction.amount))
+        .collect();
+    match exact.as_slice() {
+        [invoice] => Some((invoice.id.clone(), MatchedBy::Amount)),
+        _ => None,
+    }
+}
+
+/// Record the credit as a bank transfer against the invoice.
+fn apply_match(data: &mut AppData, transaction_id: &str, invoice_id: &str, matched_by: MatchedBy) -> Result<ReconciledTransaction, String> {
+    let invoice = data.invoices.iter()
+        .find(|i| i.id == invoice_id)
+        .cloned()
+        .ok_or("Invoice not found")?;
+    let transaction = data.bank_transactions.iter()
+        .find(|t| t.id == transaction_id)
+        .cloned()
+        .ok_or("Bank transaction not found")?;
+    
+    let payment = Payment {
+        id: Uuid::new_v4().to_string(),
+        household_id: Some(invoice.household_id.clone()),
+        amount: data.settings.currency.round(transaction.amount),
+        method: PaymentMethod::BankTransfer,
+        date: transaction.date.clone(),
+        reference: Some(transaction.reference.clone()).filter(|r| !r.is_empty()),
+        invoice_id: Some(invoice.id.clone()),
+        tip: None,
+        created_at: Utc::now(),
+    };
+    let payment_id = payment.id.clone();
+    data.payments.push(payment);
+    
+    let transaction = data.bank_transactions.iter_mut()
+        .find(|t| t.id == transaction_id)
+        .ok_or("Bank transaction not found")?;
+    transaction.invoice_id = Some(invoice.id.clone());
+    transaction.payment_id = Some(payment_id);
+    let transaction = transaction.clone();
+    
+    Ok(ReconciledTransaction {
+        transaction,
+        still_due: amount_due(data, &invoice),
+        invoice_number: invoice.number,
+        matched_by,
+    })
+}
+
+/// Import a bank statement and mark invoices paid from the credits that can be
+/// matched to them. The rest are kept for matching by hand.
+#[tauri::command]
+pub fn import_bank_statement_csv(content: String) -> Result<ReconciliationReport, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let mut report = ReconciliationReport {
+        matched: Vec::new(),
+        unmatched: Vec::new(),
+        duplicates: 0,
+        debits_skipped: 0,
+        errors: Vec::new(),
+    };
+    let credits = parse_statement(&content, &mut report)?;
+    
+    for (date, amount, reference, payer) in credits {
+        let amount = data.settings.currency.round(amount);
+        let duplicate = data.bank_transactions.iter()
+            .any(|t| t.date == date && t.amount == amount && t.reference == reference && t.payer == payer);
+        if duplicate {
+            report.duplicates += 1;
+            continue;
+        }
+        
+        let transaction = BankTransaction {
+            id: Uuid::new_v4().to_string(),
+            date,
+            amount,
+            reference,
+            payer,
+            invoice_id: None,
+            payment_id: None,
+            imported_at: Utc::now(),
+        };
+        data.bank_transactions.push(transaction.clone());
+        
+        match find_match(&data, &transaction) {
+            Some((invoice_id, matched_by)) => {
+                let reconciled = apply_match(&mut data, &transaction.id, &invoice_id, matched_by)?;
+                report.matched.push(reconciled);
+            }
+            None => report.unmatched.push(transaction),
+        }
+    }
+    
+    record_audit(&mut data, "bank_statement_imported", &format!(
+        "Imported bank statement: {} matched, {} unmatched, {} duplicates",
+        report.matched.len(), report.unmatched.len(), report.duplicates
+    ));
+    save_app_data(&data)?;
+    
+    Ok(report)
+}
+
+/// Imported credits not yet matched to an invoice, oldest first.
+#[tauri::command]
+pub fn get_unmatched_bank_transactions() -> Result<Vec<BankTransaction>, String> {
+    let data = load_app_data()?;
+    
+    let mut transactions: Vec<BankTransaction> = data.bank_transactions.into_iter()
+        .filter(|t| t.invoice_id.is_none())
+        .collect();
+    transactions.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(transactions)
+}
+
+#[tauri::command]
+pub fn match_bank_transaction(transaction_id: String, invoice_id: String) -> Result<ReconciledTransaction, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let transaction = data.bank_transactions.iter()
+        .find(|t| t.id == transaction_id)
+        .ok_or("Bank transaction not found")?;
+    if transaction.invoice_id.is_some() {
+        return Err("This transaction is already matched to an invoice".to_string());
+    }
+    
+    let reconciled = apply_match(&mut data, &transaction_id, &invoice_id, MatchedBy::Manual)?;
+    let details = format!(
+        "Matched bank credit of {} on {} to invoice {}",
+        data.settings.currency.format(reconciled.transaction.amount), reconciled.transaction.date, reconciled.invoice_number
+    );
+    record_audit(&mut data, "bank_transaction_matched", &details);
+    save_app_data(&data)?;
+    
+    Ok(reconciled)
+}
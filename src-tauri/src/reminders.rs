@@ -0,0 +1,201 @@
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::record_audit;
+use crate::consent::consent_due;
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::vaccinations::next_expiry;
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, Dog, DogStatus};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ReminderKind {
+    #[serde(rename = "vaccine")]
+    Vaccine,
+    #[serde(rename = "consent")]
+    Consent,
+}
+
+impl ReminderKind {
+    fn key(&self) -> &'static str {
+        match self {
+            ReminderKind::Vaccine => "vaccine",
+            ReminderKind::Consent => "consent",
+        }
+    }
+    
+    /// The dog's date this reminder is worked out from. A dismissal only holds
+    /// while it's unchanged, so a newly recorded date that lapses again brings
+    /// the reminder back.
+    fn basis(&self, dog: &Dog) -> Option<String> {
+        match self {
+            ReminderKind::Vaccine if !dog.vaccinations.is_empty() => next_expiry(dog),
+            ReminderKind::Vaccine => dog.vaccine_date.clone(),
+            ReminderKind::Consent => dog.consent_last_signed.clone(),
+        }
+    }
+}
+
+/// Staff action on a reminder, keyed by reminder id ("vaccine:<dog id>").
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReminderState {
+    pub reminder_id: String,
+    pub snoozed_until: Option<String>, // YYYY-MM-DD; hidden until this date
+    pub dismissed_reason: Option<String>,
+    pub dismissed_basis: Option<String>, // Dog's vaccine or consent date when dismissed
+    pub updated_by: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Reminder {
+    pub id: String,
+    pub dog_id: String,
+    pub dog_name: String,
+    pub kind: ReminderKind,
+    pub message: String,
+}
+
+fn parse_date(value: &Option<String>) -> Option<NaiveDate> {
+    value.as_ref().and_then(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok())
+}
+
+/// Due once any vaccine type has expired. Dogs from before vaccinations were
+/// recorded per type have a single date given, which lasts a year.
+pub fn vaccine_due(dog: &Dog, today: NaiveDate) -> bool {
+    if !dog.vaccinations.is_empty() {
+        return parse_date(&next_expiry(dog)).is_none_or(|expires| expires < today);
+    }
+    match (parse_date(&dog.vaccine_date), today.checked_sub_months(Months::new(12))) {
+        (Some(vaccinated), Some(year_ago)) => vaccinated < year_ago,
+        _ => true,
+    }
+}
+
+fn reminder_message(kind: ReminderKind, dog: &Dog) -> String {
+    match (kind, kind.basis(dog)) {
+        (ReminderKind::Vaccine, None) => format!("{} has no vaccination record", dog.name),
+        (ReminderKind::Vaccine, Some(_)) => format!("{}'s vaccinations have expired", dog.name),
+        (ReminderKind::Consent, None) => format!("{} has no signed consent form", dog.name),
+        (ReminderKind::Consent, Some(_)) => format!("{}'s consent form is due for renewal", dog.name),
+    }
+}
+
+fn is_hidden(data: &AppData, id: &str, kind: ReminderKind, dog: &Dog, today: &str) -> bool {
+    data.reminders.iter().find(|r| r.reminder_id == id).is_some_and(|state| {
+        let snoozed = state.snoozed_until.as_deref().is_some_and(|until| until > today);
+        let dismissed = state.dismissed_reason.is_some() && state.dismissed_basis == kind.basis(dog);
+        snoozed || dismissed
+    })
+}
+
+/// Every reminder due for active dogs, leaving out snoozed and dismissed ones.
+pub fn active_reminders(data: &AppData) -> Vec<Reminder> {
+    let today = Utc::now().date_naive();
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let advance_days = data.settings.notifications.reminder_advance_days;
+    
+    let mut reminders = Vec::new();
+    for dog in data.dogs.iter().filter(|d| d.status == DogStatus::Active) {
+        let due = [
+            (ReminderKind::Vaccine, vaccine_due(dog, today)),
+            (ReminderKind::Consent, consent_due(dog, today, advance_days, data.settings.consent_requests.validity_months)),
+        ];
+        for (kind, _) in due.into_iter().filter(|(_, due)| *due) {
+            let id = format!("{}:{}", kind.key(), dog.id);
+            if is_hidden(data, &id, kind, dog, &today_str) {
+                continue;
+            }
+            reminders.push(Reminder {
+                id,
+                dog_id: dog.id.clone(),
+                dog_name: dog.name.clone(),
+                kind,
+                message: reminder_message(kind, dog),
+            });
+        }
+    }
+    
+    reminders.sort_by(|a, b| a.dog_name.cmp(&b.dog_name).then(a.id.cmp(&b.id)));
+    reminders
+}
+
+/// Look up the dog and kind a reminder id refers to.
+fn parse_reminder_id<'a>(data: &'a AppData, reminder_id: &str) -> Result<(ReminderKind, &'a Dog), String> {
+    let (kind, dog_id) = reminder_id.split_once(':').ok_or("Reminder not found")?;
+    let kind = match kind {
+        "vaccine" => ReminderKind::Vaccine,
+        "consent" => ReminderKind::Consent,
+        _ => return Err("Reminder not found".to_string()),
+    };
+    let dog = data.dogs.iter().find(|d| d.id == dog_id).ok_or("Reminder not found")?;
+    Ok((kind, dog))
+}
+
+fn reminder_state<'a>(data: &'a mut AppData, reminder_id: &str) -> &'a mut ReminderState {
+    if let Some(index) = data.reminders.iter().position(|r| r.reminder_id == reminder_id) {
+        return &mut data.reminders[index];
+    }
+    data.reminders.push(ReminderState {
+        reminder_id: reminder_id.to_string(),
+        snoozed_until: None,
+        dismissed_reason: None,
+        dismissed_basis: None,
+        updated_by: None,
+        updated_at: Utc::now(),
+    });
+    data.reminders.last_mut().expect("just pushed")
+}
+
+#[tauri::command]
+pub fn get_reminders() -> Result<Vec<Reminder>, String> {
+    let data = load_app_data()?;
+    Ok(active_reminders(&data))
+}
+
+/// Hide a reminder until the given date, e.g. while the owner gets a vet appointment.
+#[tauri::command]
+pub fn snooze_reminder(reminder_id: String, until_date: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    let until = NaiveDate::parse_from_str(&until_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid snooze date format".to_string())?;
+    if until <= Utc::now().date_naive() {
+        return Err("Snooze date must be in the future".to_string());
+    }
+    let dog_name = parse_reminder_id(&data, &reminder_id)?.1.name.clone();
+    
+    let state = reminder_state(&mut data, &reminder_id);
+    state.snoozed_until = Some(until_date.clone());
+    state.updated_by = current_staff_id();
+    state.updated_at = Utc::now();
+    
+    record_audit(&mut data, "snooze_reminder", &format!("{} reminder for {} snoozed until {}", reminder_id, dog_name, until_date));
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Stop showing a reminder until the dog's vaccination or consent date changes.
+#[tauri::command]
+pub fn dismiss_reminder(reminder_id: String, reason: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if reason.trim().is_empty() {
+        return Err("A reason is required to dismiss a reminder".to_string());
+    }
+    let (kind, dog) = parse_reminder_id(&data, &reminder_id)?;
+    let basis = kind.basis(dog);
+    let dog_name = dog.name.clone();
+    
+    let state = reminder_state(&mut data, &reminder_id);
+    state.dismissed_reason = Some(reason.trim().to_string());
+    state.dismissed_basis = basis;
+    state.snoozed_until = None;
+    state.updated_by = current_staff_id();
+    state.updated_at = Utc::now();
+    
+    record_audit(&mut data, "dismiss_reminder", &format!("{} reminder for {} dismissed: {}", reminder_id, dog_name, reason.trim()));
+    save_app_data(&data)?;
+    Ok(())
+}
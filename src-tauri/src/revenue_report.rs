@@ -0,0 +1,99 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, AppData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ReportPeriod {
+    #[serde(rename = "week")]
+    Week,
+    #[serde(rename = "month")]
+    Month,
+    #[serde(rename = "quarter")]
+    Quarter,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RevenuePeriod {
+    pub label: String, // "W14", "Apr 2024" or "Q1"
+    pub start_date: String,
+    pub end_date: String,
+    pub revenue: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RevenueReport {
+    pub fiscal_year: i32,
+    pub label: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub periods: Vec<RevenuePeriod>,
+    pub total: f64, // Payments received, less refunds and excluding tips
+}
+
+/// The fiscal year split into weeks, months or quarters. Weeks are the ones
+/// the fiscal settings number, so the first and last may spill over the year.
+fn periods(data: &AppData, year: i32, period: ReportPeriod) -> Result<Vec<(String, NaiveDate, NaiveDate)>, String> {
+    let fiscal = &data.settings.fiscal;
+    let periods = match period {
+        ReportPeriod::Week => {
+            let (start, end) = fiscal.year_bounds(year)?;
+            let mut weeks = Vec::new();
+            let mut week = fiscal.week_start(start);
+            while week <= end {
+                let (_, number) = fiscal.week_number(week);
+                weeks.push((format!("W{}", number), week, week + Duration::days(6)));
+                week += Duration::days(7);
+            }
+            weeks
+        }
+        ReportPeriod::Month => fiscal.months(year)?.into_iter()
+            .map(|(first, last)| (first.format("%b %Y").to_string(), first, last))
+            .collect(),
+        ReportPeriod::Quarter => fiscal.quarters(year)?.into_iter()
+            .enumerate()
+            .map(|(index, (first, last))| (format!("Q{}", index + 1), first, last))
+            .collect(),
+    };
+    Ok(periods)
+}
+
+fn revenue_between(data: &AppData, start: NaiveDate, end: NaiveDate) -> f64 {
+    let (first, last) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    let revenue: f64 = data.payments.iter()
+        .filter(|p| {
+            let day = p.date.get(..10).unwrap_or(&p.date);
+            first.as_str() <= day && day <= last.as_str()
+        })
+        .map(|p| p.amount)
+        .sum();
+    data.settings.currency.round(revenue)
+}
+
+/// Revenue for a fiscal year by week, month or quarter, lined up with the
+/// periods the accountant works in.
+#[tauri::command]
+pub fn get_revenue_report(fiscal_year: i32, period: ReportPeriod) -> Result<RevenueReport, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageBilling)?;
+    
+    let (start, end) = data.settings.fiscal.year_bounds(fiscal_year)?;
+    let periods: Vec<RevenuePeriod> = periods(&data, fiscal_year, period)?.into_iter()
+        .map(|(label, first, last)| RevenuePeriod {
+            label,
+            start_date: first.format("%Y-%m-%d").to_string(),
+            end_date: last.format("%Y-%m-%d").to_string(),
+            revenue: revenue_between(&data, first, last),
+        })
+        .collect();
+    
+    Ok(RevenueReport {
+        fiscal_year,
+        label: data.settings.fiscal.year_label(fiscal_year),
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        periods,
+        total: revenue_between(&data, start, end),
+    })
+}
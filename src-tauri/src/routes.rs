@@ -0,0 +1,240 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dashboard::{emit_dashboard_update, DashboardEvent};
+use crate::permissions::{require_permission, Permission};
+use crate::quarantine::ensure_not_quarantined;
+use crate::times::time_or_now;
+use crate::{check_in_warnings, load_app_data, lock_app_data, mark_checked_in, save_app_data};
+
+/// One address on a van run. Dogs collected from the same address share a stop.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RouteStop {
+    pub id: String,
+    pub address: String,
+    pub dog_ids: Vec<String>,
+    pub window_start: Option<String>, // HH:MM
+    pub window_end: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PickupRoute {
+    pub id: String,
+    pub date: String,
+    pub driver_id: Option<String>, // Staff id
+    pub stops: Vec<RouteStop>, // In driving order
+    pub created_at: DateTime<Utc>,
+}
+
+#[tauri::command]
+pub fn get_pickup_routes(date: String) -> Result<Vec<PickupRoute>, String> {
+    let data = load_app_data()?;
+    
+    let mut routes: Vec<PickupRoute> = data.pickup_routes.into_iter()
+        .filter(|r| r.date == date)
+        .collect();
+    
+    routes.sort_by_key(|r| r.created_at);
+    Ok(routes)
+}
+
+/// Build a van run from the day's roster: every dog booked in with van pickup
+/// details that isn't already on another route that day. Stops are ordered by
+/// the start of their pickup window; reorder them afterwards as needed.
+#[tauri::command]
+pub fn build_pickup_route(date: String, driver_id: Option<String>) -> Result<PickupRoute, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if let Some(ref driver_id) = driver_id {
+        if !data.staff.iter().any(|s| s.id == *driver_id) {
+            return Err("Staff member not found".to_string());
+        }
+    }
+    
+    let routed: Vec<&String> = data.pickup_routes.iter()
+        .filter(|r| r.date == date)
+        .flat_map(|r| r.stops.iter().flat_map(|s| &s.dog_ids))
+        .collect();
+    
+    let mut booked: Vec<&String> = data.daily_data.get(&date)
+        .map(|d| d.attendance.entries.values()
+            .filter(|e| e.attending)
+            .map(|e| &e.dog_id)
+            .collect())
+        .unwrap_or_default();
+    booked.sort();
+    booked.dedup();
+    
+    let mut stops: Vec<RouteStop> = Vec::new();
+    for dog in booked.into_iter().filter_map(|id| data.dogs.iter().find(|d| d.id == *id)) {
+        let pickup = match dog.van_pickup {
+            Some(ref pickup) if !pickup.address.trim().is_empty() => pickup,
+            _ => continue,
+        };
+        if routed.contains(&&dog.id) {
+            continue;
+        }
+        
+        let address = pickup.address.trim();
+        match stops.iter_mut().find(|s| s.address.eq_ignore_ascii_case(address)) {
+            Some(stop) => stop.dog_ids.push(dog.id.clone()),
+            None => stops.push(RouteStop {
+                id: Uuid::new_v4().to_string(),
+                address: address.to_string(),
+                dog_ids: vec![dog.id.clone()],
+                window_start: pickup.window_start.clone(),
+                window_end: pickup.window_end.clone(),
+                completed_at: None,
+            }),
+        }
+    }
+    
+    if stops.is_empty() {
+        return Err(format!("No dogs left to collect by van on {}", date));
+    }
+    
+    // Stops without a window go last
+    stops.sort_by(|a, b| {
+        (a.window_start.is_none(), &a.window_start, &a.address).cmp(&(b.window_start.is_none(), &b.window_start, &b.address))
+    });
+    
+    let route = PickupRoute {
+        id: Uuid::new_v4().to_string(),
+        date,
+        driver_id,
+        stops,
+        created_at: Utc::now(),
+    };
+    
+    data.pickup_routes.push(route.clone());
+    save_app_data(&data)?;
+    
+    Ok(route)
+}
+
+/// Put a route's stops in the given order. Every stop must be listed once.
+#[tauri::command]
+pub fn reorder_route_stops(route_id: String, stop_ids: Vec<String>) -> Result<PickupRoute, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let route = data.pickup_routes.iter_mut()
+        .find(|r| r.id == route_id)
+        .ok_or("Route not found")?;
+    
+    let mut reordered = Vec::new();
+    for stop_id in &stop_ids {
+        let index = route.stops.iter()
+            .position(|s| s.id == *stop_id)
+            .ok_or("Stop not found")?;
+        reordered.push(route.stops.remove(index));
+    }
+    if !route.stops.is_empty() {
+        return Err("Every stop on the route must be included".to_string());
+    }
+    route.stops = reordered;
+    let route = route.clone();
+    
+    save_app_data(&data)?;
+    Ok(route)
+}
+
+#[tauri::command]
+pub fn set_route_driver(route_id: String, driver_id: Option<String>) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if let Some(ref driver_id) = driver_id {
+        if !data.staff.iter().any(|s| s.id == *driver_id) {
+            return Err("Staff member not found".to_string());
+        }
+    }
+    
+    let route = data.pickup_routes.iter_mut()
+        .find(|r| r.id == route_id)
+        .ok_or("Route not found")?;
+    route.driver_id = driver_id;
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_pickup_route(route_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let before = data.pickup_routes.len();
+    data.pickup_routes.retain(|r| r.id != route_id);
+    if data.pickup_routes.len() == before {
+        return Err("Route not found".to_string());
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Mark a stop collected. The dogs picked up there are checked in at that time,
+/// since they're in our care from the moment they get in the van.
+#[tauri::command]
+pub fn complete_route_stop(
+    app: tauri::AppHandle,
+    route_id: String,
+    stop_id: String,
+    time: Option<String>,
+) -> Result<PickupRoute, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let route = data.pickup_routes.iter()
+        .find(|r| r.id == route_id)
+        .ok_or("Route not found")?;
+    let stop = route.stops.iter()
+        .find(|s| s.id == stop_id)
+        .ok_or("Stop not found")?;
+    if stop.completed_at.is_some() {
+        return Err(format!("The stop at {} is already completed", stop.address));
+    }
+    let date = route.date.clone();
+    let dog_ids = stop.dog_ids.clone();
+    // Quarantined dogs mustn't get in the van with the others
+    for dog in data.dogs.iter().filter(|d| dog_ids.contains(&d.id)) {
+        ensure_not_quarantined(dog, &date)?;
+    }
+    
+    let route = data.pickup_routes.iter_mut()
+        .find(|r| r.id == route_id)
+        .ok_or("Route not found")?;
+    if let Some(stop) = route.stops.iter_mut().find(|s| s.id == stop_id) {
+        stop.completed_at = Some(Utc::now());
+    }
+    let route = route.clone();
+    
+    let time = time_or_now(time, "pickup time")?;
+    let mut check_ins = Vec::new();
+    for dog_id in dog_ids {
+        let dog = match data.dogs.iter().find(|d| d.id == dog_id) {
+            Some(dog) => dog,
+            None => continue,
+        };
+        let event = DashboardEvent::CheckIn {
+            date: date.clone(),
+            dog_id: dog.id.clone(),
+            dog_name: dog.name.clone(),
+            time: time.clone(),
+            warnings: check_in_warnings(&data, dog, &date),
+        };
+        mark_checked_in(&mut data, &date, &dog_id, &time);
+        check_ins.push(event);
+    }
+    
+    save_app_data(&data)?;
+    
+    for event in check_ins {
+        emit_dashboard_update(&app, event);
+    }
+    Ok(route)
+}
@@ -0,0 +1,87 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::{load_app_data, schedule_applies_on, AppData, RecurringSchedule, ServiceType};
+
+/// How far past the later start date to look for a shared day. Every pattern
+/// repeats well within a year.
+const OVERLAP_SEARCH_DAYS: i64 = 400;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleConflict {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub service_type: ServiceType,
+    pub schedule_ids: (String, String),
+    pub first_date: String, // First day both schedules book the dog in
+}
+
+/// First day two schedules would both book the same dog in for the same
+/// service, if they ever do.
+pub fn first_overlap(a: &RecurringSchedule, b: &RecurringSchedule) -> Option<NaiveDate> {
+    if a.id == b.id || a.dog_id != b.dog_id || a.service_type != b.service_type || !a.active || !b.active {
+        return None;
+    }
+    
+    let start_a = NaiveDate::parse_from_str(&a.start_date, "%Y-%m-%d").ok()?;
+    let start_b = NaiveDate::parse_from_str(&b.start_date, "%Y-%m-%d").ok()?;
+    let start = start_a.max(start_b);
+    
+    (0..OVERLAP_SEARCH_DAYS)
+        .map(|offset| start + Duration::days(offset))
+        .find(|date| schedule_applies_on(a, *date) && schedule_applies_on(b, *date))
+}
+
+/// Error out if a new or edited schedule books the dog in on a day another of
+/// its schedules for the same service already covers.
+pub fn check_overlaps(data: &AppData, schedule: &RecurringSchedule) -> Result<(), String> {
+    for other in &data.recurring_schedules {
+        if let Some(date) = first_overlap(schedule, other) {
+            let dog_name = data.dogs.iter()
+                .find(|d| d.id == schedule.dog_id)
+                .map(|d| d.name.as_str())
+                .unwrap_or("This dog");
+            return Err(format!(
+                "{} already has a {:?} schedule covering {} (from {}); end or change that schedule first",
+                dog_name,
+                schedule.service_type,
+                date.format("%A %-d %B %Y"),
+                other.start_date
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn find_conflicts(data: &AppData) -> Vec<ScheduleConflict> {
+    let schedules = &data.recurring_schedules;
+    let mut conflicts = Vec::new();
+    
+    for (i, a) in schedules.iter().enumerate() {
+        for b in &schedules[i + 1..] {
+            if let Some(date) = first_overlap(a, b) {
+                conflicts.push(ScheduleConflict {
+                    dog_id: a.dog_id.clone(),
+                    dog_name: data.dogs.iter()
+                        .find(|d| d.id == a.dog_id)
+                        .map(|d| d.name.clone())
+                        .unwrap_or_default(),
+                    service_type: a.service_type.clone(),
+                    schedule_ids: (a.id.clone(), b.id.clone()),
+                    first_date: date.format("%Y-%m-%d").to_string(),
+                });
+            }
+        }
+    }
+    
+    conflicts.sort_by(|a, b| a.dog_name.cmp(&b.dog_name).then(a.first_date.cmp(&b.first_date)));
+    conflicts
+}
+
+/// Pairs of existing schedules that book the same dog in twice for the same
+/// service on some day.
+#[tauri::command]
+pub fn get_schedule_conflicts() -> Result<Vec<ScheduleConflict>, String> {
+    let data = load_app_data()?;
+    Ok(find_conflicts(&data))
+}
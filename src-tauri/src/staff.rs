@@ -0,0 +1,465 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::crypto::hash_secret;
+use crate::permissions::{current_role, current_staff_id, require_permission, validate_pin, Permission, StaffRole};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, ServiceType};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Staff {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub phone: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub contracted_hours_per_week: f64, // 0 = hourly, no overtime threshold
+    #[serde(default)]
+    pub role: StaffRole,
+    #[serde(default)]
+    pub pin_hash: Option<String>,
+    #[serde(default)]
+    pub failed_logins: u32, // Wrong PINs in a row since the last successful sign-in
+    #[serde(default)]
+    pub locked_until: Option<DateTime<Utc>>, // Set after too many wrong PINs
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A staff member as the app shows them. The PIN hash and lockout state only
+/// live in the data file, since the sign-in screen lists staff before anyone
+/// has signed in.
+#[derive(Debug, Serialize, Clone)]
+pub struct StaffSummary {
+    pub id: String,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub contracted_hours_per_week: f64,
+    pub role: StaffRole,
+    pub has_pin: bool,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Staff> for StaffSummary {
+    fn from(staff: &Staff) -> Self {
+        StaffSummary {
+            id: staff.id.clone(),
+            name: staff.name.clone(),
+            phone: staff.phone.clone(),
+            email: staff.email.clone(),
+            contracted_hours_per_week: staff.contracted_hours_per_week,
+            role: staff.role,
+            has_pin: staff.pin_hash.is_some(),
+            active: staff.active,
+            created_at: staff.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeClockEntry {
+    pub id: String,
+    pub staff_id: String,
+    pub clock_in: DateTime<Utc>,
+    pub clock_out: Option<DateTime<Utc>>,
+}
+
+/// One dog a staff member is handling on a given day.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandlerAssignment {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub service_type: ServiceType,
+    pub drop_off_time: Option<String>,
+    pub pick_up_time: Option<String>,
+}
+
+#[tauri::command]
+pub fn get_staff() -> Result<Vec<StaffSummary>, String> {
+    let data = load_app_data()?;
+    Ok(data.staff.iter().map(StaffSummary::from).collect())
+}
+
+/// Only an owner may make someone an owner or take that away, so a manager
+/// can't promote themselves past their own permissions.
+fn require_owner(data: &AppData) -> Result<(), String> {
+    if current_role(data) == Some(StaffRole::Owner) {
+        Ok(())
+    } else {
+        Err("Permission denied: only an owner can give or remove the owner role".to_string())
+    }
+}
+
+/// Refuse to leave the business without an active owner, who alone can manage
+/// the owner role.
+fn ensure_other_owner(data: &AppData, staff_id: &str) -> Result<(), String> {
+    let others = data.staff.iter()
+        .any(|s| s.id != staff_id && s.active && s.role == StaffRole::Owner);
+    if others {
+        Ok(())
+    } else {
+        Err("This is the last owner; make someone else an owner first".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn add_staff(
+    name: String,
+    role: StaffRole,
+    pin: String,
+    phone: Option<String>,
+    email: Option<String>,
+    contracted_hours_per_week: Option<f64>,
+) -> Result<StaffSummary, String> {
+    let mut data = lock_app_data()?;
+    
+    // Anyone may create the first account (it becomes the owner); after that it's restricted
+    let role = if data.staff.is_empty() { StaffRole::Owner } else { role };
+    require_permission(&data, Permission::ManageStaff)?;
+    if role == StaffRole::Owner {
+        require_owner(&data)?;
+    }
+    
+    if name.trim().is_empty() {
+        return Err("Staff name is required".to_string());
+    }
+    // Every account needs a PIN, or anyone could sign in as it
+    validate_pin(&pin)?;
+    
+    let member = Staff {
+        id: Uuid::new_v4().to_string(),
+        name,
+        phone: phone.unwrap_or_default(),
+        email: email.unwrap_or_default(),
+        contracted_hours_per_week: contracted_hours_per_week.unwrap_or(0.0),
+        role,
+        pin_hash: Some(hash_secret(&pin)),
+        failed_logins: 0,
+        locked_until: None,
+        active: true,
+        created_at: Utc::now(),
+    };
+    
+    data.staff.push(member.clone());
+    record_audit(&mut data, "staff_added", &format!("Added staff member {}", member.name));
+    save_app_data(&data)?;
+    
+    Ok(StaffSummary::from(&member))
+}
+
+#[tauri::command]
+pub fn update_staff(staff: Staff) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageStaff)?;
+    
+    if let Some(index) = data.staff.iter().position(|s| s.id == staff.id) {
+        // PINs and lockouts are only changed through signing in and set_staff_pin
+        let existing = data.staff[index].clone();
+        let was_owner = existing.role == StaffRole::Owner;
+        if was_owner != (staff.role == StaffRole::Owner) {
+            require_owner(&data)?;
+        }
+        if was_owner && existing.active && (staff.role != StaffRole::Owner || !staff.active) {
+            ensure_other_owner(&data, &staff.id)?;
+        }
+        record_audit(&mut data, "staff_updated", &format!("Updated staff member {}", staff.name));
+        data.staff[index] = Staff {
+            pin_hash: existing.pin_hash,
+            failed_logins: existing.failed_logins,
+            locked_until: existing.locked_until,
+            ..staff
+        };
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Staff member not found".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn delete_staff(staff_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::DeleteData)?;
+    
+    if let Some(index) = data.staff.iter().position(|s| s.id == staff_id) {
+        if data.staff[index].role == StaffRole::Owner {
+            require_owner(&data)?;
+            if data.staff[index].active {
+                ensure_other_owner(&data, &staff_id)?;
+            }
+        }
+        let removed = data.staff.remove(index);
+        record_audit(&mut data, "staff_deleted", &format!("Deleted staff member {}", removed.name));
+        
+        // Unassign them as handler everywhere so entries don't point at a missing person
+        for day_data in data.daily_data.values_mut() {
+            for entry in day_data.attendance.entries.values_mut() {
+                if entry.handler_id.as_deref() == Some(staff_id.as_str()) {
+                    entry.handler_id = None;
+                }
+            }
+        }
+        
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Staff member not found".to_string())
+    }
+}
+
+/// Set (or clear, with `staff_id` = None) the primary handler for all of a dog's
+/// attendance entries on a day.
+#[tauri::command]
+pub fn assign_handler(date: String, dog_id: String, staff_id: Option<String>) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageStaff)?;
+    
+    if let Some(ref staff_id) = staff_id {
+        let member = data.staff.iter()
+            .find(|s| s.id == *staff_id)
+            .ok_or("Staff member not found")?;
+        if !member.active {
+            return Err(format!("{} is not an active staff member", member.name));
+        }
+    }
+    
+    let entries: Vec<_> = data.daily_data.get_mut(&date)
+        .map(|d| d.attendance.entries.values_mut()
+            .filter(|e| e.dog_id == dog_id)
+            .collect())
+        .unwrap_or_default();
+    
+    if entries.is_empty() {
+        return Err("Dog has no attendance on this date".to_string());
+    }
+    
+    for entry in entries {
+        entry.handler_id = staff_id.clone();
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_staff_daily_list(date: String, staff_id: String) -> Result<Vec<HandlerAssignment>, String> {
+    let data = load_app_data()?;
+    
+    let mut assignments: Vec<HandlerAssignment> = data.daily_data.get(&date)
+        .map(|d| d.attendance.entries.values()
+            .filter(|e| e.attending && e.handler_id.as_deref() == Some(staff_id.as_str()))
+            .map(|e| HandlerAssignment {
+                dog_id: e.dog_id.clone(),
+                dog_name: data.dogs.iter()
+                    .find(|d| d.id == e.dog_id)
+                    .map(|d| d.name.clone())
+                    .unwrap_or_default(),
+                service_type: e.service_type.clone(),
+                drop_off_time: e.drop_off_time.clone(),
+                pick_up_time: e.pick_up_time.clone(),
+            })
+            .collect())
+        .unwrap_or_default();
+    
+    assignments.sort_by(|a, b| a.drop_off_time.cmp(&b.drop_off_time).then(a.dog_name.cmp(&b.dog_name)));
+    Ok(assignments)
+}
+
+/// Staff clock themselves in and out; anyone else's clock needs ManageStaff.
+fn require_own_clock(data: &AppData, staff_id: &str) -> Result<(), String> {
+    if current_role(data).is_some() && current_staff_id().as_deref() == Some(staff_id) {
+        return Ok(());
+    }
+    require_permission(data, Permission::ManageStaff)
+}
+
+#[tauri::command]
+pub fn clock_in(staff_id: String) -> Result<TimeClockEntry, String> {
+    let mut data = lock_app_data()?;
+    require_own_clock(&data, &staff_id)?;
+    
+    if !data.staff.iter().any(|s| s.id == staff_id) {
+        return Err("Staff member not found".to_string());
+    }
+    
+    if data.time_clock.iter().any(|e| e.staff_id == staff_id && e.clock_out.is_none()) {
+        return Err("Already clocked in".to_string());
+    }
+    
+    let entry = TimeClockEntry {
+        id: Uuid::new_v4().to_string(),
+        staff_id,
+        clock_in: Utc::now(),
+        clock_out: None,
+    };
+    
+    data.time_clock.push(entry.clone());
+    save_app_data(&data)?;
+    
+    Ok(entry)
+}
+
+#[tauri::command]
+pub fn clock_out(staff_id: String) -> Result<TimeClockEntry, String> {
+    let mut data = lock_app_data()?;
+    require_own_clock(&data, &staff_id)?;
+    
+    let entry = data.time_clock.iter_mut()
+        .find(|e| e.staff_id == staff_id && e.clock_out.is_none())
+        .ok_or("Not clocked in")?;
+    entry.clock_out = Some(Utc::now());
+    let entry = entry.clone();
+    
+    save_app_data(&data)?;
+    Ok(entry)
+}
+
+/// Correct a forgotten or mistyped clock entry.
+#[tauri::command]
+pub fn update_time_clock_entry(entry: TimeClockEntry) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageStaff)?;
+    
+    if let Some(clock_out) = entry.clock_out {
+        if clock_out < entry.clock_in {
+            return Err("Clock out must be after clock in".to_string());
+        }
+    }
+    
+    if let Some(index) = data.time_clock.iter().position(|e| e.id == entry.id) {
+        data.time_clock[index] = entry;
+        save_app_data(&data)?;
+        Ok(())
+    } else {
+        Err("Time clock entry not found".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn get_time_clock_entries(start_date: String, end_date: String) -> Result<Vec<TimeClockEntry>, String> {
+    let data = load_app_data()?;
+    let (start, end) = parse_period(&start_date, &end_date)?;
+    
+    let mut entries: Vec<TimeClockEntry> = data.time_clock.into_iter()
+        .filter(|e| {
+            let day = e.clock_in.with_timezone(&Local).date_naive();
+            day >= start && day <= end
+        })
+        .collect();
+    
+    entries.sort_by_key(|e| e.clock_in);
+    Ok(entries)
+}
+
+pub fn parse_period(start_date: &str, end_date: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid start date format".to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d")
+        .map_err(|_| "Invalid end date format".to_string())?;
+    
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    Ok((start, end))
+}
+
+/// Total completed-shift hours per staff id for shifts starting within the period.
+pub fn hours_worked_by_staff(entries: &[TimeClockEntry], start: NaiveDate, end: NaiveDate) -> HashMap<String, f64> {
+    let mut hours_by_staff = HashMap::new();
+    
+    for entry in entries {
+        if let Some(clock_out) = entry.clock_out {
+            let day = entry.clock_in.with_timezone(&Local).date_naive();
+            if day >= start && day <= end {
+                let hours = (clock_out - entry.clock_in).num_minutes() as f64 / 60.0;
+                *hours_by_staff.entry(entry.staff_id.clone()).or_insert(0.0) += hours;
+            }
+        }
+    }
+    
+    hours_by_staff
+}
+
+/// Export worked hours per staff member for a pay period. Overtime is anything
+/// above the staff member's contracted weekly hours, evaluated per Monday-Sunday
+/// week so a long week isn't averaged away by a short one.
+///
+/// Weeks are paid whole in the period their Sunday falls in, the week-ending
+/// rule the payroll bureau settles overtime by: a week that began before the
+/// period is counted in full, and one still running when the period ends is
+/// left for the next export. Consecutive periods therefore add up to exactly
+/// the hours worked, and no week's overtime is split across two exports.
+#[tauri::command]
+pub fn export_payroll_csv(period_start: String, period_end: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    require_permission(&data, Permission::ManageStaff)?;
+    let (start, end) = parse_period(&period_start, &period_end)?;
+    
+    // Hours per (staff, week start) for completed shifts in weeks ending inside the period
+    let mut weekly_hours: HashMap<(String, NaiveDate), f64> = HashMap::new();
+    for entry in &data.time_clock {
+        let clock_out = match entry.clock_out {
+            Some(clock_out) => clock_out,
+            None => continue,
+        };
+        
+        let day = entry.clock_in.with_timezone(&Local).date_naive();
+        let week_start = day - chrono::Duration::days(day.weekday().num_days_from_monday() as i64);
+        let week_end = week_start + chrono::Duration::days(6);
+        if week_end < start || week_end > end {
+            continue;
+        }
+        
+        let hours = (clock_out - entry.clock_in).num_minutes() as f64 / 60.0;
+        *weekly_hours.entry((entry.staff_id.clone(), week_start)).or_insert(0.0) += hours;
+    }
+    
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["Employee ID", "Employee Name", "Period Start", "Period End", "Regular Hours", "Overtime Hours", "Total Hours"])
+        .map_err(|e| format!("Failed to write payroll CSV: {}", e))?;
+    
+    let mut staff: Vec<_> = data.staff.iter().collect();
+    staff.sort_by(|a, b| a.name.cmp(&b.name));
+    
+    for member in staff {
+        let mut regular = 0.0;
+        let mut overtime = 0.0;
+        
+        for ((staff_id, _), hours) in &weekly_hours {
+            if *staff_id != member.id {
+                continue;
+            }
+            
+            if member.contracted_hours_per_week > 0.0 && *hours > member.contracted_hours_per_week {
+                regular += member.contracted_hours_per_week;
+                overtime += hours - member.contracted_hours_per_week;
+            } else {
+                regular += hours;
+            }
+        }
+        
+        if regular == 0.0 && overtime == 0.0 {
+            continue;
+        }
+        
+        writer.write_record([
+            member.id.clone(),
+            member.name.clone(),
+            period_start.clone(),
+            period_end.clone(),
+            format!("{:.2}", regular),
+            format!("{:.2}", overtime),
+            format!("{:.2}", regular + overtime),
+        ]).map_err(|e| format!("Failed to write payroll CSV: {}", e))?;
+    }
+    
+    let bytes = writer.into_inner()
+        .map_err(|e| format!("Failed to write payroll CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write payroll CSV: {}", e))
+}
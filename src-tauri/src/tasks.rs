@@ -0,0 +1,151 @@
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum TaskRecurrence {
+    #[serde(rename = "daily")]
+    Daily,
+    #[serde(rename = "weekly")]
+    Weekly,
+    #[serde(rename = "monthly")]
+    Monthly,
+}
+
+/// A chore that isn't about any one dog, e.g. "order poo bags".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub due_date: Option<String>, // YYYY-MM-DD
+    pub assignee_id: Option<String>, // Staff id
+    pub recurrence: Option<TaskRecurrence>, // Recurring tasks move to their next due date when done
+    pub done: bool,
+    pub done_by: Option<String>,
+    pub done_at: Option<DateTime<Utc>>,
+}
+
+/// Open tasks due on or before `date`, overdue first.
+pub fn due_tasks(data: &AppData, date: &str) -> Vec<Task> {
+    let mut tasks: Vec<Task> = data.tasks.iter()
+        .filter(|t| !t.done && t.due_date.as_deref().is_some_and(|d| d <= date))
+        .cloned()
+        .collect();
+    tasks.sort_by(|a, b| a.due_date.cmp(&b.due_date).then(a.title.cmp(&b.title)));
+    tasks
+}
+
+fn next_due(due: NaiveDate, recurrence: TaskRecurrence) -> NaiveDate {
+    match recurrence {
+        TaskRecurrence::Daily => due + Duration::days(1),
+        TaskRecurrence::Weekly => due + Duration::days(7),
+        TaskRecurrence::Monthly => due.checked_add_months(Months::new(1)).unwrap_or(due + Duration::days(30)),
+    }
+}
+
+#[tauri::command]
+pub fn get_tasks(include_done: Option<bool>) -> Result<Vec<Task>, String> {
+    let data = load_app_data()?;
+    let include_done = include_done.unwrap_or(false);
+    
+    let mut tasks: Vec<Task> = data.tasks.into_iter()
+        .filter(|t| include_done || !t.done)
+        .collect();
+    
+    // Undated tasks after dated ones
+    tasks.sort_by(|a, b| {
+        (a.done, a.due_date.is_none(), &a.due_date, &a.title).cmp(&(b.done, b.due_date.is_none(), &b.due_date, &b.title))
+    });
+    Ok(tasks)
+}
+
+/// Add a task, or update it when the id matches an existing one.
+#[tauri::command]
+pub fn save_task(task: Task) -> Result<Task, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    if task.title.trim().is_empty() {
+        return Err("Task title is required".to_string());
+    }
+    if let Some(ref due_date) = task.due_date {
+        NaiveDate::parse_from_str(due_date, "%Y-%m-%d")
+            .map_err(|_| "Invalid due date format".to_string())?;
+    }
+    if task.recurrence.is_some() && task.due_date.is_none() {
+        return Err("Recurring tasks need a due date".to_string());
+    }
+    if let Some(ref assignee_id) = task.assignee_id {
+        if !data.staff.iter().any(|s| s.id == *assignee_id) {
+            return Err("Staff member not found".to_string());
+        }
+    }
+    let task = Task { title: task.title.trim().to_string(), ..task };
+    
+    let task = match data.tasks.iter_mut().find(|t| t.id == task.id) {
+        Some(existing) => {
+            *existing = task.clone();
+            task
+        }
+        None => {
+            let task = Task { id: Uuid::new_v4().to_string(), ..task };
+            data.tasks.push(task.clone());
+            task
+        }
+    };
+    
+    save_app_data(&data)?;
+    Ok(task)
+}
+
+/// Tick a task off. A recurring task stays open with its due date moved on.
+#[tauri::command]
+pub fn complete_task(task_id: String) -> Result<Task, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let task = data.tasks.iter_mut()
+        .find(|t| t.id == task_id)
+        .ok_or("Task not found")?;
+    if task.done {
+        return Err(format!("\"{}\" is already done", task.title));
+    }
+    task.done_by = current_staff_id();
+    task.done_at = Some(Utc::now());
+    
+    let due = task.due_date.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    match (task.recurrence, due) {
+        (Some(recurrence), Some(due)) => {
+            // Catch up past today so a long-overdue task isn't immediately due again
+            let today = Utc::now().date_naive();
+            let mut next = next_due(due, recurrence);
+            while next <= today {
+                next = next_due(next, recurrence);
+            }
+            task.due_date = Some(next.format("%Y-%m-%d").to_string());
+        }
+        _ => task.done = true,
+    }
+    let task = task.clone();
+    
+    save_app_data(&data)?;
+    Ok(task)
+}
+
+#[tauri::command]
+pub fn delete_task(task_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let before = data.tasks.len();
+    data.tasks.retain(|t| t.id != task_id);
+    if data.tasks.len() == before {
+        return Err("Task not found".to_string());
+    }
+    
+    save_app_data(&data)?;
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+
+use crate::audit::record_audit;
+use crate::permissions::{require_permission, Permission};
+use crate::{current_time_string, lock_app_data, save_app_data_past_day_locks, AppData, Dog, DogSchedule, ServiceType};
+
+/// Read a time the way staff type it ("8", "8am", "8.30", "0830", "5:15 pm",
+/// "noon") and return it as HH:MM, or None when it can't be read.
+pub fn normalize_time(value: &str) -> Option<String> {
+    let text: String = value.to_lowercase()
+        .replace("a.m.", "am")
+        .replace("p.m.", "pm")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if text == "noon" || text == "midday" {
+        return Some("12:00".to_string());
+    }
+    
+    let (body, meridiem) = match (text.strip_suffix("am"), text.strip_suffix("pm")) {
+        (Some(body), _) => (body, Some(false)),
+        (_, Some(body)) => (body, Some(true)),
+        _ => (text.as_str(), None),
+    };
+    
+    // Byte-based splitting below needs ASCII; anything else isn't a time anyway
+    if !body.is_ascii() {
+        return None;
+    }
+    let (hour, minute) = match body.find([':', '.', 'h']) {
+        Some(index) => (&body[..index], &body[index + 1..]),
+        None if body.len() <= 2 => (body, "00"),
+        None if body.len() <= 4 => body.split_at(body.len() - 2),
+        None => return None,
+    };
+    let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if hour.is_empty() || hour.len() > 2 || minute.len() != 2 || !digits(hour) || !digits(minute) {
+        return None;
+    }
+    let mut hour: u32 = hour.parse().ok()?;
+    let minute: u32 = minute.parse().ok()?;
+    
+    match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            hour = match (hour, pm) {
+                (12, false) => 0,
+                (12, true) => 12,
+                (hour, true) => hour + 12,
+                (hour, false) => hour,
+            };
+        }
+        None if hour > 23 => return None,
+        None => {}
+    }
+    if minute > 59 {
+        return None;
+    }
+    Some(format!("{:02}:{:02}", hour, minute))
+}
+
+/// Normalize an optional time from a command. Blank counts as no time; a time
+/// that can't be read is an error naming the field.
+pub fn normalize_optional(value: Option<String>, label: &str) -> Result<Option<String>, String> {
+    match value {
+        Some(value) if !value.trim().is_empty() => normalize_time(&value)
+            .map(Some)
+            .ok_or_else(|| format!("Couldn't read {} \"{}\"; use a time like 08:30", label, value)),
+        _ => Ok(None),
+    }
+}
+
+/// The given time normalized, or the current time when none was given.
+pub fn time_or_now(value: Option<String>, label: &str) -> Result<String, String> {
+    Ok(normalize_optional(value, label)?.unwrap_or_else(current_time_string))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ServiceTimes {
+    pub drop_off: Option<String>, // HH:MM
+    pub pick_up: Option<String>,
+}
+
+/// Times assumed for bookings and schedules that don't give their own, so
+/// rosters and reports have something to go on. Explicit times always win.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefaultTimes {
+    pub daycare: ServiceTimes,
+    pub training: ServiceTimes,
+    pub boarding: ServiceTimes,
+}
+
+impl Default for DefaultTimes {
+    fn default() -> Self {
+        Self {
+            daycare: ServiceTimes {
+                drop_off: Some("08:00".to_string()),
+                pick_up: Some("17:00".to_string()),
+            },
+            training: ServiceTimes::default(),
+            boarding: ServiceTimes::default(),
+        }
+    }
+}
+
+impl DefaultTimes {
+    pub fn for_service(&self, service_type: &ServiceType) -> &ServiceTimes {
+        match service_type {
+            ServiceType::Daycare => &self.daycare,
+            ServiceType::Training => &self.training,
+            ServiceType::Boarding => &self.boarding,
+        }
+    }
+    
+    /// The given drop-off time, or the service's default when there isn't one.
+    pub fn drop_off(&self, service_type: &ServiceType, time: &Option<String>) -> Option<String> {
+        time.clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| self.for_service(service_type).drop_off.clone())
+    }
+    
+    /// The given pick-up time, or the service's default when there isn't one.
+    pub fn pick_up(&self, service_type: &ServiceType, time: &Option<String>) -> Option<String> {
+        time.clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| self.for_service(service_type).pick_up.clone())
+    }
+    
+    pub fn normalized(self) -> Result<Self, String> {
+        let normalize = |times: ServiceTimes, service: &str| -> Result<ServiceTimes, String> {
+            Ok(ServiceTimes {
+                drop_off: normalize_optional(times.drop_off, &format!("default {} drop-off time", service))?,
+                pick_up: normalize_optional(times.pick_up, &format!("default {} pick-up time", service))?,
+            })
+        };
+        Ok(Self {
+            daycare: normalize(self.daycare, "daycare")?,
+            training: normalize(self.training, "training")?,
+            boarding: normalize(self.boarding, "boarding")?,
+        })
+    }
+}
+
+pub fn normalize_dog_schedule(schedule: &mut DogSchedule) -> Result<(), String> {
+    schedule.daycare_drop_off = normalize_optional(schedule.daycare_drop_off.take(), "daycare drop-off time")?;
+    schedule.daycare_pick_up = normalize_optional(schedule.daycare_pick_up.take(), "daycare pick-up time")?;
+    schedule.training_drop_off = normalize_optional(schedule.training_drop_off.take(), "training drop-off time")?;
+    schedule.training_pick_up = normalize_optional(schedule.training_pick_up.take(), "training pick-up time")?;
+    Ok(())
+}
+
+/// Normalize every time a dog record carries: its weekly schedule and van
+/// pickup window.
+pub fn normalize_dog_times(dog: &mut Dog) -> Result<(), String> {
+    normalize_dog_schedule(&mut dog.schedule)?;
+    if let Some(ref mut pickup) = dog.van_pickup {
+        pickup.window_start = normalize_optional(pickup.window_start.take(), "pickup window start")?;
+        pickup.window_end = normalize_optional(pickup.window_end.take(), "pickup window end")?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UnparseableTime {
+    pub location: String, // e.g. "2024-03-01 attendance for Buddy (Daycare), drop-off"
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct TimeNormalizationReport {
+    pub updated: usize,
+    pub unparseable: Vec<UnparseableTime>,
+}
+
+impl TimeNormalizationReport {
+    /// Rewrite one stored time as HH:MM, noting it if it can't be read.
+    fn normalize(&mut self, value: &mut Option<String>, location: impl FnOnce() -> String) {
+        let text = match value {
+            Some(text) if !text.trim().is_empty() => text,
+            _ => return,
+        };
+        match normalize_time(text) {
+            Some(normalized) if normalized != *text => {
+                *text = normalized;
+                self.updated += 1;
+            }
+            Some(_) => {}
+            None => self.unparseable.push(UnparseableTime { location: location(), value: text.clone() }),
+        }
+    }
+}
+
+/// Bring every stored drop-off, pick-up and pickup-window time into HH:MM.
+/// Values that can't be read are left as they are and listed in the report.
+fn normalize_stored_times_in(data: &mut AppData) -> TimeNormalizationReport {
+    let mut report = TimeNormalizationReport::default();
+    let dog_names: std::collections::HashMap<String, String> = data.dogs.iter()
+        .map(|d| (d.id.clone(), d.name.clone()))
+        .collect();
+    let name = |id: &str| dog_names.get(id).cloned().unwrap_or_else(|| id.to_string());
+    
+    for dog in data.dogs.iter_mut() {
+        let dog_name = dog.name.clone();
+        let schedule = &mut dog.schedule;
+        report.normalize(&mut schedule.daycare_drop_off, || format!("{}'s schedule, daycare drop-off", dog_name));
+        report.normalize(&mut schedule.daycare_pick_up, || format!("{}'s schedule, daycare pick-up", dog_name));
+        report.normalize(&mut schedule.training_drop_off, || format!("{}'s schedule, training drop-off", dog_name));
+        report.normalize(&mut schedule.training_pick_up, || format!("{}'s schedule, training pick-up", dog_name));
+        if let Some(ref mut pickup) = dog.van_pickup {
+            report.normalize(&mut pickup.window_start, || format!("{}'s van pickup window start", dog_name));
+            report.normalize(&mut pickup.window_end, || format!("{}'s van pickup window end", dog_name));
+        }
+    }
+    
+    for schedule in data.recurring_schedules.iter_mut() {
+        let label = format!("{}'s {:?} recurring schedule from {}", name(&schedule.dog_id), schedule.service_type, schedule.start_date);
+        report.normalize(&mut schedule.drop_off_time, || format!("{}, drop-off", label));
+        report.normalize(&mut schedule.pick_up_time, || format!("{}, pick-up", label));
+    }
+    
+    let mut dates: Vec<&String> = data.daily_data.keys().collect();
+    dates.sort();
+    let dates: Vec<String> = dates.into_iter().cloned().collect();
+    for date in dates {
+        let day_data = match data.daily_data.get_mut(&date) {
+            Some(day_data) => day_data,
+            None => continue,
+        };
+        for entry in day_data.attendance.entries.values_mut() {
+            let label = format!("{} attendance for {} ({:?})", date, name(&entry.dog_id), entry.service_type);
+            report.normalize(&mut entry.drop_off_time, || format!("{}, drop-off", label));
+            report.normalize(&mut entry.pick_up_time, || format!("{}, pick-up", label));
+        }
+        for (dog_id, record) in day_data.records.iter_mut() {
+            let label = format!("{} daily record for {}", date, name(dog_id));
+            report.normalize(&mut record.drop_off_time, || format!("{}, drop-off", label));
+            report.normalize(&mut record.pick_up_time, || format!("{}, pick-up", label));
+        }
+    }
+    
+    for entry in data.waitlist.iter_mut() {
+        let label = format!("{} waitlist entry for {}", entry.date, name(&entry.dog_id));
+        report.normalize(&mut entry.drop_off_time, || format!("{}, drop-off", label));
+        report.normalize(&mut entry.pick_up_time, || format!("{}, pick-up", label));
+    }
+    
+    report
+}
+
+/// Run at startup so times saved before validation existed sort and compare
+/// properly. Only saves when something changed.
+pub fn migrate_stored_times() {
+    let mut data = match lock_app_data() {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Time normalization skipped: {}", e);
+            return;
+        }
+    };
+    let report = normalize_stored_times_in(&mut data);
+    if report.updated > 0 {
+        match save_app_data_past_day_locks(&mut data, "Normalizing stored times") {
+            Ok(()) => println!("Normalized {} stored times", report.updated),
+            Err(e) => println!("Failed to save normalized times: {}", e),
+        }
+    }
+    for time in &report.unparseable {
+        println!("Unreadable time \"{}\" in {}", time.value, time.location);
+    }
+}
+
+/// Normalize stored times now and list the ones that need fixing by hand.
+#[tauri::command]
+pub fn normalize_stored_times() -> Result<TimeNormalizationReport, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditSettings)?;
+    
+    let report = normalize_stored_times_in(&mut data);
+    if report.updated > 0 {
+        record_audit(&mut data, "times_normalized", &format!("Normalized {} stored times", report.updated));
+        save_app_data_past_day_locks(&mut data, "Normalizing stored times")?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn reads_the_ways_staff_type_times() {
+        assert_eq!(normalize_time("8").as_deref(), Some("08:00"));
+        assert_eq!(normalize_time("8.30").as_deref(), Some("08:30"));
+        assert_eq!(normalize_time("0830").as_deref(), Some("08:30"));
+        assert_eq!(normalize_time("5:15 pm").as_deref(), Some("17:15"));
+        assert_eq!(normalize_time("12am").as_deref(), Some("00:00"));
+        assert_eq!(normalize_time("noon").as_deref(), Some("12:00"));
+    }
+    
+    #[test]
+    fn rejects_times_out_of_range() {
+        assert_eq!(normalize_time("24:00"), None);
+        assert_eq!(normalize_time("13pm"), None);
+        assert_eq!(normalize_time("8:60"), None);
+        assert_eq!(normalize_time("08300"), None);
+    }
+    
+    #[test]
+    fn non_ascii_input_is_rejected_without_panicking() {
+        for value in ["éa", "８", "à9", "8:3０", "８:30", "1é30", "ü", "9h½"] {
+            assert_eq!(normalize_time(value), None, "{}", value);
+        }
+        assert!(normalize_optional(Some("à9".to_string()), "drop-off time").is_err());
+    }
+}
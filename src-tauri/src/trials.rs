@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::attendance_keys::entry_key;
+use crate::audit::record_audit;
+use crate::opening_hours::{validate_attendance_times, WithWarnings};
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::times::normalize_optional;
+use crate::{
+    clear_future_attendance_for_dog, generate_recurring_attendance_internal, generate_schedules_for_dog,
+    load_app_data, lock_app_data, save_app_data, schedule_generation_window, AttendanceEntry, DogStatus, EntrySource, ServiceType,
+};
+
+/// How the dog came across on its trial day, each scored 1 (poor) to 5 (great).
+/// 0 means not scored.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TemperamentScores {
+    pub with_dogs: u8,
+    pub with_people: u8,
+    pub handling: u8, // Lets staff check paws, ears and collar
+    pub settling: u8, // Calms down in the group and at rest times
+}
+
+impl TemperamentScores {
+    fn validate(&self) -> Result<(), String> {
+        let scores = [self.with_dogs, self.with_people, self.handling, self.settling];
+        if scores.iter().any(|&s| s > 5) {
+            return Err("Temperament scores go from 1 to 5".to_string());
+        }
+        Ok(())
+    }
+    
+    fn all_scored(&self) -> bool {
+        [self.with_dogs, self.with_people, self.handling, self.settling].iter().all(|&s| (1..=5).contains(&s))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrialAssessment {
+    pub notes: String,
+    #[serde(default)]
+    pub scores: TemperamentScores,
+    #[serde(default)]
+    pub sociability_notes: String, // How the dog played and who it got on with
+    #[serde(default)]
+    pub passed: Option<bool>, // None until the assessor decides
+    pub recorded_by: Option<String>, // Staff id of the assessor
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "result")]
+pub enum TrialOutcome {
+    #[serde(rename = "accepted")]
+    Accepted,
+    #[serde(rename = "declined")]
+    Declined { reason: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Trial {
+    pub id: String,
+    pub dog_id: String,
+    pub date: String,
+    pub service_type: ServiceType,
+    pub assessment: Option<TrialAssessment>,
+    pub outcome: Option<TrialOutcome>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[tauri::command]
+pub fn get_trials() -> Result<Vec<Trial>, String> {
+    let data = load_app_data()?;
+    let mut trials = data.trials;
+    trials.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(trials)
+}
+
+/// Book a trial day for a new dog. The dog is put on trial, which holds back its
+/// recurring schedules until the trial is completed.
+#[tauri::command]
+pub fn book_trial(
+    dog_id: String,
+    date: String,
+    service_type: ServiceType,
+    drop_off_time: Option<String>,
+    pick_up_time: Option<String>,
+) -> Result<WithWarnings<Trial>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    let drop_off_time = normalize_optional(drop_off_time, "drop-off time")?;
+    let pick_up_time = normalize_optional(pick_up_time, "pick-up time")?;
+    
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .cloned()
+        .ok_or("Dog not found")?;
+    
+    if data.trials.iter().any(|t| t.dog_id == dog_id && t.outcome.is_none()) {
+        return Err(format!("{} already has a trial booked", dog.name));
+    }
+    
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let has_attended = data.daily_data.iter()
+        .filter(|(d, _)| **d < today)
+        .any(|(_, day)| day.attendance.entries.values().any(|e| e.dog_id == dog_id && e.attending));
+    if dog.status == DogStatus::Active && has_attended {
+        return Err(format!("{} has already attended; trials are for new dogs", dog.name));
+    }
+    
+    let warnings = validate_attendance_times(
+        &data,
+        &date,
+        &service_type,
+        drop_off_time.as_deref(),
+        pick_up_time.as_deref(),
+    )?;
+    
+    // Hold back any schedule created when the dog was added until the trial passes
+    data.recurring_schedules.retain(|s| s.dog_id != dog_id);
+    clear_future_attendance_for_dog(&mut data, &dog_id)?;
+    
+    if let Some(dog) = data.dogs.iter_mut().find(|d| d.id == dog_id) {
+        dog.status = DogStatus::Trial;
+    }
+    
+    let day_data = data.daily_data.entry(date.clone()).or_default();
+    if service_type == ServiceType::Daycare {
+        day_data.attendance.dogs.insert(dog_id.clone(), true);
+    }
+    day_data.attendance.entries.insert(entry_key(&dog_id, &service_type), AttendanceEntry {
+        dog_id: dog_id.clone(),
+        service_type: service_type.clone(),
+        attending: true,
+        drop_off_time,
+        pick_up_time,
+        notes: Some("Trial day".to_string()),
+        handler_id: None,
+        updated_by: current_staff_id(),
+        trial: true,
+        awaiting_confirmation: false,
+        excused: false,
+        generated_from: None,
+        source: EntrySource::Manual,
+        allergies: Vec::new(),
+    });
+    
+    let trial = Trial {
+        id: Uuid::new_v4().to_string(),
+        dog_id,
+        date,
+        service_type,
+        assessment: None,
+        outcome: None,
+        completed_at: None,
+        created_at: Utc::now(),
+    };
+    
+    data.trials.push(trial.clone());
+    save_app_data(&data)?;
+    
+    Ok(WithWarnings { record: trial, warnings })
+}
+
+/// Record how the dog got on during their trial day: temperament scores, how
+/// it mixed with the group and whether it passed. Can be updated until the
+/// trial is completed.
+#[tauri::command]
+pub fn record_trial_assessment(
+    trial_id: String,
+    notes: String,
+    scores: Option<TemperamentScores>,
+    sociability_notes: Option<String>,
+    passed: Option<bool>,
+) -> Result<Trial, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    let scores = scores.unwrap_or_default();
+    scores.validate()?;
+    if passed == Some(true) && !scores.all_scored() {
+        return Err("Score each temperament area from 1 to 5 before passing the dog".to_string());
+    }
+    
+    let trial = data.trials.iter_mut()
+        .find(|t| t.id == trial_id)
+        .ok_or("Trial not found")?;
+    
+    if trial.outcome.is_some() {
+        return Err("Trial has already been completed".to_string());
+    }
+    
+    trial.assessment = Some(TrialAssessment {
+        notes,
+        scores,
+        sociability_notes: sociability_notes.unwrap_or_default(),
+        passed,
+        recorded_by: current_staff_id(),
+        recorded_at: Utc::now(),
+    });
+    let trial = trial.clone();
+    
+    save_app_data(&data)?;
+    Ok(trial)
+}
+
+/// Finish a dog's open trial. Accepting approves the dog for daycare and
+/// generates its recurring schedules, and needs a passed assessment; declining
+/// records the reason and leaves it inactive.
+#[tauri::command]
+pub fn complete_trial(dog_id: String, outcome: TrialOutcome) -> Result<Trial, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    
+    if let TrialOutcome::Declined { ref reason } = outcome {
+        if reason.trim().is_empty() {
+            return Err("A reason is required when declining".to_string());
+        }
+    }
+    
+    let trial = data.trials.iter_mut()
+        .find(|t| t.dog_id == dog_id && t.outcome.is_none())
+        .ok_or("No open trial for this dog")?;
+    
+    let passed = match &trial.assessment {
+        Some(assessment) => assessment.passed,
+        None => return Err("Record the trial assessment before completing the trial".to_string()),
+    };
+    if outcome == TrialOutcome::Accepted {
+        match passed {
+            Some(true) => {}
+            Some(false) => return Err("Only dogs that passed their assessment can be accepted".to_string()),
+            // Also assessments recorded before pass/fail was asked for
+            None => return Err("Record a pass/fail decision on the assessment before accepting".to_string()),
+        }
+    }
+    
+    trial.outcome = Some(outcome.clone());
+    trial.completed_at = Some(Utc::now());
+    let trial = trial.clone();
+    
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    dog.status = match outcome {
+        TrialOutcome::Accepted => DogStatus::Active,
+        TrialOutcome::Declined { .. } => DogStatus::Declined,
+    };
+    let dog = dog.clone();
+    
+    match outcome {
+        TrialOutcome::Accepted => {
+            generate_schedules_for_dog(&mut data, &dog)?;
+            let (start_str, end_str) = schedule_generation_window(&dog);
+            generate_recurring_attendance_internal(&mut data, &start_str, &end_str)?;
+            record_audit(&mut data, "trial_accepted", &format!("{} passed their trial", dog.name));
+        }
+        TrialOutcome::Declined { ref reason } => {
+            record_audit(&mut data, "trial_declined", &format!("{} declined after trial: {}", dog.name, reason));
+        }
+    }
+    
+    save_app_data(&data)?;
+    Ok(trial)
+}
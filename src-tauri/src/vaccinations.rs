@@ -0,0 +1,195 @@
+use chrono::{Duration, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::record_audit;
+use crate::permissions::{require_permission, Permission};
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, Dog, DogStatus};
+
+/// One vaccine given to a dog. Each type (rabies, DHPP, kennel cough...) runs out
+/// on its own date, so a dog can be covered for one and lapsed on another.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Vaccination {
+    pub id: String,
+    pub vaccine_type: String,
+    pub administered: String, // YYYY-MM-DD
+    pub expires: String, // YYYY-MM-DD
+    pub certificate_ref: Option<String>, // Vet certificate number or document reference
+}
+
+/// Vaccine type shown for dogs recorded before vaccinations were kept per type,
+/// whose single date given lasts a year, and for imported expiry dates that
+/// cover all of a dog's vaccines.
+pub const ANNUAL_VACCINATION: &str = "annual vaccination";
+
+/// How far ahead the reminders screen looks when no window is given.
+const DEFAULT_WINDOW_DAYS: u32 = 30;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExpiringVaccine {
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub vaccine_type: String,
+    pub expiration_date: String, // YYYY-MM-DD
+    pub days_remaining: i64, // Negative once expired
+    pub vaccination_id: Option<String>, // None for a dog's single pre-per-type date
+}
+
+/// The latest vaccination of each type; earlier ones have been superseded.
+pub fn current_vaccinations(dog: &Dog) -> Vec<&Vaccination> {
+    let mut current: Vec<&Vaccination> = Vec::new();
+    for vaccination in &dog.vaccinations {
+        match current.iter_mut().find(|v| v.vaccine_type.eq_ignore_ascii_case(&vaccination.vaccine_type)) {
+            Some(existing) if existing.expires < vaccination.expires => *existing = vaccination,
+            Some(_) => {}
+            None => current.push(vaccination),
+        }
+    }
+    current
+}
+
+/// The first date one of the dog's vaccinations runs out, if any are recorded.
+pub fn next_expiry(dog: &Dog) -> Option<String> {
+    current_vaccinations(dog).into_iter().map(|v| v.expires.clone()).min()
+}
+
+fn validate(vaccine_type: &str, administered: &str, expires: &str) -> Result<(), String> {
+    if vaccine_type.trim().is_empty() {
+        return Err("A vaccine type is required".to_string());
+    }
+    let administered = NaiveDate::parse_from_str(administered, "%Y-%m-%d")
+        .map_err(|_| "Invalid administered date format".to_string())?;
+    let expires = NaiveDate::parse_from_str(expires, "%Y-%m-%d")
+        .map_err(|_| "Invalid expiry date format".to_string())?;
+    if expires <= administered {
+        return Err("The expiry date must be after the date the vaccine was given".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_vaccinations(dog_id: String) -> Result<Vec<Vaccination>, String> {
+    let data = load_app_data()?;
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    let mut vaccinations = dog.vaccinations.clone();
+    vaccinations.sort_by(|a, b| b.administered.cmp(&a.administered).then(a.vaccine_type.cmp(&b.vaccine_type)));
+    Ok(vaccinations)
+}
+
+#[tauri::command]
+pub fn add_vaccination(
+    dog_id: String,
+    vaccine_type: String,
+    administered: String,
+    expires: String,
+    certificate_ref: Option<String>,
+) -> Result<Vaccination, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    validate(&vaccine_type, &administered, &expires)?;
+    
+    let vaccination = Vaccination {
+        id: Uuid::new_v4().to_string(),
+        vaccine_type: vaccine_type.trim().to_string(),
+        administered,
+        expires,
+        certificate_ref: certificate_ref.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+    };
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    dog.vaccinations.push(vaccination.clone());
+    let details = format!(
+        "{}: {} given {}, expires {}",
+        dog.name, vaccination.vaccine_type, vaccination.administered, vaccination.expires
+    );
+    
+    record_audit(&mut data, "vaccination_added", &details);
+    save_app_data(&data)?;
+    Ok(vaccination)
+}
+
+#[tauri::command]
+pub fn update_vaccination(dog_id: String, vaccination: Vaccination) -> Result<Vaccination, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::ManageDogs)?;
+    validate(&vaccination.vaccine_type, &vaccination.administered, &vaccination.expires)?;
+    
+    let dog = data.dogs.iter_mut()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    let existing = dog.vaccinations.iter_mut()
+        .find(|v| v.id == vaccination.id)
+        .ok_or("Vaccination not found")?;
+    *existing = Vaccination {
+        vaccine_type: vaccination.vaccine_type.trim().to_string(),
+        certificate_ref: vaccination.certificate_ref.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+        ..vaccination
+    };
+    let updated = existing.clone();
+    let details = format!("{}: {} updated, expires {}", dog.name, updated.vaccine_type, updated.expires);
+    
+    record_audit(&mut data, "vaccination_updated", &details);
+    save_app_data(&data)?;
+    Ok(updated)
+}
+
+/// When each of a dog's current vaccines runs out, by type.
+pub fn expiries(dog: &Dog) -> Vec<(String, NaiveDate, Option<String>)> {
+    if dog.vaccinations.is_empty() {
+        return dog.vaccine_date.as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok())
+            .and_then(|given| given.checked_add_months(Months::new(12)))
+            .map(|expires| vec![(ANNUAL_VACCINATION.to_string(), expires, None)])
+            .unwrap_or_default();
+    }
+    current_vaccinations(dog).into_iter()
+        .filter_map(|v| {
+            let expires = NaiveDate::parse_from_str(&v.expires, "%Y-%m-%d").ok()?;
+            Some((v.vaccine_type.clone(), expires, Some(v.id.clone())))
+        })
+        .collect()
+}
+
+/// The dog's vaccine that runs out first, with its expiry date.
+pub fn soonest_expiry(dog: &Dog) -> Option<(String, NaiveDate)> {
+    expiries(dog).into_iter()
+        .min_by_key(|(_, expires, _)| *expires)
+        .map(|(vaccine_type, expires, _)| (vaccine_type, expires))
+}
+
+/// Vaccines of active dogs that run out within `days` days, including any
+/// already expired, soonest first.
+pub fn expiring_vaccines(data: &AppData, days: u32) -> Vec<ExpiringVaccine> {
+    let today = Utc::now().date_naive();
+    let cutoff = today + Duration::days(days as i64);
+    
+    let mut expiring: Vec<ExpiringVaccine> = data.dogs.iter()
+        .filter(|d| d.status == DogStatus::Active)
+        .flat_map(|dog| expiries(dog).into_iter()
+            .filter(|(_, expires, _)| *expires <= cutoff)
+            .map(move |(vaccine_type, expires, vaccination_id)| ExpiringVaccine {
+                dog_id: dog.id.clone(),
+                dog_name: dog.name.clone(),
+                owner: dog.owner.clone(),
+                vaccine_type,
+                expiration_date: expires.format("%Y-%m-%d").to_string(),
+                days_remaining: (expires - today).num_days(),
+                vaccination_id,
+            }))
+        .collect();
+    
+    expiring.sort_by(|a, b| a.days_remaining.cmp(&b.days_remaining).then(a.dog_name.cmp(&b.dog_name)));
+    expiring
+}
+
+/// Vaccines running out within `days` days (30 by default) or already expired.
+#[tauri::command]
+pub fn get_expiring_vaccines(days: Option<u32>) -> Result<Vec<ExpiringVaccine>, String> {
+    let data = load_app_data()?;
+    Ok(expiring_vaccines(&data, days.unwrap_or(DEFAULT_WINDOW_DAYS)))
+}
@@ -0,0 +1,249 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use uuid::Uuid;
+
+use crate::attendance_keys::entry_key;
+use crate::booking_notices::{notify_owner, BookingNotice};
+use crate::capacity::booked_count;
+use crate::opening_hours::{validate_attendance_times, WithWarnings};
+use crate::permissions::{current_staff_id, require_permission, Permission};
+use crate::times::normalize_optional;
+use crate::{load_app_data, lock_app_data, save_app_data, AppData, AttendanceEntry, DogStatus, EntrySource, ServiceType};
+
+/// Event emitted when a waitlisted dog is booked into a freed-up place, with a
+/// `WaitlistPromotion` payload so staff can contact the owner.
+pub const WAITLIST_PROMOTED_EVENT: &str = "waitlist:promoted";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum WaitlistStatus {
+    #[serde(rename = "waiting")]
+    Waiting,
+    #[serde(rename = "promoted")]
+    Promoted,
+    #[serde(rename = "removed")]
+    Removed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WaitlistEntry {
+    pub id: String,
+    pub dog_id: String,
+    pub date: String,
+    pub service_type: ServiceType,
+    pub drop_off_time: Option<String>,
+    pub pick_up_time: Option<String>,
+    pub notes: Option<String>,
+    pub status: WaitlistStatus,
+    pub created_at: DateTime<Utc>,
+    pub promoted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WaitlistPromotion {
+    pub waitlist_id: String,
+    pub date: String,
+    pub dog_id: String,
+    pub dog_name: String,
+    pub owner: String,
+    pub phone: String,
+    pub service_type: ServiceType,
+}
+
+#[tauri::command]
+pub fn get_waitlist(date: Option<String>) -> Result<Vec<WaitlistEntry>, String> {
+    let data = load_app_data()?;
+    
+    let mut entries: Vec<WaitlistEntry> = data.waitlist.into_iter()
+        .filter(|w| w.status == WaitlistStatus::Waiting)
+        .filter(|w| date.as_ref().is_none_or(|d| w.date == *d))
+        .collect();
+    
+    entries.sort_by(|a, b| a.date.cmp(&b.date).then(a.created_at.cmp(&b.created_at)));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn add_to_waitlist(
+    dog_id: String,
+    date: String,
+    service_type: ServiceType,
+    drop_off_time: Option<String>,
+    pick_up_time: Option<String>,
+    notes: Option<String>,
+) -> Result<WithWarnings<WaitlistEntry>, String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    let drop_off_time = normalize_optional(drop_off_time, "drop-off time")?;
+    let pick_up_time = normalize_optional(pick_up_time, "pick-up time")?;
+    
+    let dog = data.dogs.iter()
+        .find(|d| d.id == dog_id)
+        .ok_or("Dog not found")?;
+    
+    let already_booked = data.daily_data.get(&date)
+        .and_then(|d| d.attendance.entries.get(&entry_key(&dog_id, &service_type)))
+        .map(|e| e.attending)
+        .unwrap_or(false);
+    if already_booked {
+        return Err(format!("{} is already booked in on {}", dog.name, date));
+    }
+    
+    if data.waitlist.iter().any(|w| {
+        w.dog_id == dog_id && w.date == date && w.service_type == service_type && w.status == WaitlistStatus::Waiting
+    }) {
+        return Err(format!("{} is already on the waitlist for {}", dog.name, date));
+    }
+    
+    let warnings = validate_attendance_times(&data, &date, &service_type, drop_off_time.as_deref(), pick_up_time.as_deref())?;
+    
+    let entry = WaitlistEntry {
+        id: Uuid::new_v4().to_string(),
+        dog_id,
+        date,
+        service_type,
+        drop_off_time,
+        pick_up_time,
+        notes,
+        status: WaitlistStatus::Waiting,
+        created_at: Utc::now(),
+        promoted_at: None,
+    };
+    
+    data.waitlist.push(entry.clone());
+    save_app_data(&data)?;
+    
+    Ok(WithWarnings { record: entry, warnings })
+}
+
+#[tauri::command]
+pub fn remove_from_waitlist(waitlist_id: String) -> Result<(), String> {
+    let mut data = lock_app_data()?;
+    require_permission(&data, Permission::EditAttendance)?;
+    
+    let entry = data.waitlist.iter_mut()
+        .find(|w| w.id == waitlist_id)
+        .ok_or("Waitlist entry not found")?;
+    entry.status = WaitlistStatus::Removed;
+    
+    save_app_data(&data)?;
+    Ok(())
+}
+
+/// Whether a waitlisted dog can take a place on the day: still an active
+/// customer, not already booked, and not incompatible with anyone attending.
+fn is_eligible(data: &AppData, entry: &WaitlistEntry) -> bool {
+    let dog = match data.dogs.iter().find(|d| d.id == entry.dog_id) {
+        Some(dog) if dog.status == DogStatus::Active => dog,
+        _ => return false,
+    };
+    
+    let day_data = match data.daily_data.get(&entry.date) {
+        Some(day_data) => day_data,
+        None => return true,
+    };
+    
+    let already_booked = day_data.attendance.entries
+        .get(&entry_key(&dog.id, &entry.service_type))
+        .is_some_and(|e| e.attending);
+    if already_booked {
+        return false;
+    }
+    
+    let attending: Vec<&String> = day_data.attendance.entries.values()
+        .filter(|e| e.attending)
+        .map(|e| &e.dog_id)
+        .collect();
+    
+    let incompatible = attending.iter().any(|other| {
+        dog.incompatible_dog_ids.contains(other)
+            || data.dogs.iter().any(|d| d.id == **other && d.incompatible_dog_ids.contains(&dog.id))
+    });
+    if incompatible {
+        return false;
+    }
+    
+    // Nobody's there to agree times outside opening hours, so those wait for staff
+    validate_attendance_times(
+        data,
+        &entry.date,
+        &entry.service_type,
+        entry.drop_off_time.as_deref(),
+        entry.pick_up_time.as_deref(),
+    ).is_ok_and(|warnings| warnings.is_empty())
+}
+
+/// Book the first eligible waitlisted dog into a freed-up place. The new entry
+/// is flagged for staff to confirm with the owner. The caller saves, then
+/// announces the promotion with `emit_waitlist_promotion`.
+pub fn promote_from_waitlist(
+    data: &mut AppData,
+    date: &str,
+    service_type: &ServiceType,
+) -> Option<WaitlistPromotion> {
+    let capacity = &data.settings.capacity;
+    let limit = capacity.for_service(service_type);
+    let booked = data.daily_data.get(date)
+        .map(|d| booked_count(capacity, &data.dogs, d, service_type))
+        .unwrap_or(0);
+    if limit > 0 && booked >= limit {
+        return None;
+    }
+    // A solo-care dog only moves up if there's room for all the places they take
+    let fits = |w: &WaitlistEntry| limit == 0
+        || booked + capacity.places_for(data.dogs.iter().find(|d| d.id == w.dog_id)) <= limit;
+    
+    let mut candidates: Vec<&WaitlistEntry> = data.waitlist.iter()
+        .filter(|w| w.status == WaitlistStatus::Waiting && w.date == date && w.service_type == *service_type)
+        .collect();
+    candidates.sort_by_key(|w| w.created_at);
+    
+    let entry = candidates.into_iter().find(|w| fits(w) && is_eligible(data, w))?.clone();
+    let dog = data.dogs.iter().find(|d| d.id == entry.dog_id)?.clone();
+    
+    let day_data = data.daily_data.entry(date.to_string()).or_default();
+    if *service_type == ServiceType::Daycare {
+        day_data.attendance.dogs.insert(dog.id.clone(), true);
+    }
+    day_data.attendance.entries.insert(entry_key(&dog.id, service_type), AttendanceEntry {
+        dog_id: dog.id.clone(),
+        service_type: service_type.clone(),
+        attending: true,
+        drop_off_time: entry.drop_off_time.clone(),
+        pick_up_time: entry.pick_up_time.clone(),
+        notes: Some("Promoted from waitlist — confirm with owner".to_string()),
+        handler_id: None,
+        updated_by: current_staff_id(),
+        trial: false,
+        awaiting_confirmation: true,
+        excused: false,
+        generated_from: None,
+        source: EntrySource::WaitlistPromotion,
+        allergies: Vec::new(),
+    });
+    
+    if let Some(w) = data.waitlist.iter_mut().find(|w| w.id == entry.id) {
+        w.status = WaitlistStatus::Promoted;
+        w.promoted_at = Some(Utc::now());
+    }
+    
+    let promotion = WaitlistPromotion {
+        waitlist_id: entry.id,
+        date: date.to_string(),
+        dog_id: dog.id,
+        dog_name: dog.name,
+        owner: dog.owner,
+        phone: dog.phone,
+        service_type: service_type.clone(),
+    };
+    
+    notify_owner(data, BookingNotice::WaitlistPromotion, &promotion.dog_id, date, service_type);
+    println!("Promoted {} from the waitlist for {}", promotion.dog_name, date);
+    Some(promotion)
+}
+
+pub fn emit_waitlist_promotion(app: &tauri::AppHandle, promotion: &WaitlistPromotion) {
+    if let Err(e) = app.emit(WAITLIST_PROMOTED_EVENT, promotion) {
+        println!("Failed to emit waitlist promotion: {}", e);
+    }
+}
@@ -1,6 +1,7 @@
-import { useState, useEffect } from 'react';
+import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Heart } from 'lucide-react';
+import { listen } from '@tauri-apps/api/event';
+import { AlertTriangle, Heart } from 'lucide-react';
 
 import DailyChecklist from './components/DailyChecklist';
 import DogManagement from './components/DogManagement';
@@ -24,6 +25,21 @@ export interface DogSchedule {
   active: boolean;
 }
 
+export interface VetDetails {
+  name: string;
+  clinic: string;
+  phone: string;
+  address: string;
+  email?: string;
+}
+
+export interface Allergy {
+  allergen: string;
+  severity: 'mild' | 'moderate' | 'severe';
+  reaction?: string;
+  treatment?: string;
+}
+
 export interface Dog {
   id: string;
   name: string;
@@ -33,10 +49,25 @@ export interface Dog {
   breed: string;
   date_of_birth?: string;
   vaccine_date?: string;
+  vaccinations?: { id: string; vaccine_type: string; administered: string; expires: string; certificate_ref?: string }[];
   consent_last_signed?: string;
   created_at: string;
   schedule: DogSchedule;
   household_id?: string;
+  owner_id?: string;
+  solo_care?: boolean;
+  size?: 'small' | 'medium' | 'large';
+  neuter_status?: 'unknown' | 'neutered' | 'intact';
+  photo_path?: string;
+  vet?: VetDetails;
+  microchip_number?: string;
+  allergies?: Allergy[];
+  insurance?: { provider: string; policy_number: string; claims_phone?: string };
+  van_pickup?: { address: string; window_start?: string; window_end?: string };
+  quarantine?: { until: string; reason: string; set_by?: string; set_at: string };
+  intake?: { answers: Record<string, string>; completed_date?: string; updated_by?: string; updated_at: string };
+  age_class?: 'puppy' | 'adult' | 'senior';
+  breed_group?: string;
 }
 
 export enum ServiceType {
@@ -67,6 +98,7 @@ export interface AttendanceEntry {
   drop_off_time?: string;
   pick_up_time?: string;
   notes?: string;
+  allergies?: Allergy[];
 }
 
 export interface RecurringSchedule {
@@ -88,6 +120,9 @@ export interface DailyRecord {
   drop_off_time?: string;
   pick_up_time?: string;
   notes?: string;
+  belongings?: { item: string; returned: boolean }[];
+  photos?: string[];
+  allergies?: Allergy[];
 }
 
 export interface DayData {
@@ -107,15 +142,39 @@ export interface BackupFileInfo {
   size_bytes: number;
 }
 
+export interface BackupDestination {
+  id: string;
+  name: string;
+  enabled: boolean;
+  type: 'folder' | 's3';
+  directory?: string;
+  bucket?: string;
+  region?: string;
+  endpoint?: string;
+  prefix?: string;
+  access_key_id?: string;
+  secret_access_key?: string;
+}
+
+export interface DestinationStatus {
+  destination_id: string;
+  name: string;
+  last_attempt_at: string;
+  last_success_at?: string;
+  last_error?: string;
+}
+
 export interface Settings {
   business_name: string;
   business_phone: string;
+  business_address: string;
   auto_backup: boolean;
   cloud_backup?: {
     enabled: boolean;
     cloud_directory: string;
     max_backups: number;
     sync_interval_minutes: number;
+    destinations?: BackupDestination[];
   };
   email_templates: {
     consent_form: string;
@@ -129,6 +188,85 @@ export interface Settings {
     consent_form: string;
     vaccine_reminder: string;
   };
+  branding: Branding;
+  notifications: NotificationSettings;
+  booking_notices?: BookingNoticeSettings;
+  default_times?: Record<'daycare' | 'training' | 'boarding', { drop_off?: string; pick_up?: string }>;
+  fiscal?: FiscalSettings;
+  accounting?: AccountingSettings;
+}
+
+export interface FiscalSettings {
+  year_start_month: number;
+  week_starts_on: 'monday' | 'sunday';
+  week_numbering: 'iso' | 'fiscal_year';
+}
+
+export interface AccountingSettings {
+  daycare_account: string;
+  training_account: string;
+  boarding_account: string;
+  other_income_account: string;
+  discounts_account: string;
+  receivables_account: string;
+  deposit_account: string;
+  tax_type: string;
+  payment_terms_days: number;
+}
+
+export interface BookingNoticeSettings {
+  enabled: boolean;
+  channel: 'email' | 'whatsapp' | 'sms';
+  waitlist_promotion_subject: string;
+  waitlist_promotion: string;
+  booking_confirmed_subject: string;
+  booking_confirmed: string;
+}
+
+export interface NotificationSettings {
+  email_enabled: boolean;
+  whatsapp_enabled: boolean;
+  desktop_enabled: boolean;
+  reminder_advance_days: number;
+  quiet_hours_start?: string;
+  quiet_hours_end?: string;
+}
+
+export interface DesktopNotification {
+  id: string;
+  title: string;
+  body: string;
+}
+
+export interface InstanceMode {
+  read_only: boolean;
+  reason?: string;
+}
+
+export interface HealthCheck {
+  name: string;
+  status: 'ok' | 'warning' | 'failed';
+  message: string;
+}
+
+export interface RecoveryOption {
+  kind: 'latest_snapshot' | 'latest_cloud_backup' | 'start_fresh';
+  description: string;
+  source_path?: string;
+  saved_at?: string;
+}
+
+export interface StartupHealth {
+  checked_at: string;
+  data_readable: boolean;
+  checks: HealthCheck[];
+  recovery_options: RecoveryOption[];
+}
+
+export interface Branding {
+  logo_path?: string;
+  primary_color: string;
+  footer_text: string;
 }
 
 type Tab = 'daily' | 'management' | 'calendar' | 'compliance' | 'settings';
@@ -137,14 +275,47 @@ function App() {
   const [currentTab, setCurrentTab] = useState<Tab>('daily');
   const [dogs, setDogs] = useState<Dog[]>([]);
   const [settings, setSettings] = useState<Settings | null>(null);
+  const [instanceMode, setInstanceMode] = useState<InstanceMode | null>(null);
+  const [health, setHealth] = useState<StartupHealth | null>(null);
+
+  const [locked, setLocked] = useState(false);
+  const [passphrase, setPassphrase] = useState('');
+  const [rememberPassphrase, setRememberPassphrase] = useState(false);
+  const [unlockError, setUnlockError] = useState('');
+
+  const checkHealth = () => {
+    invoke<StartupHealth>('get_startup_health').then(result => {
+      setHealth(result);
+      if (result.data_readable) {
+        loadDogs();
+        loadSettings();
+      }
+    });
+  };
 
   useEffect(() => {
-    loadDogs();
-    loadSettings();
+    invoke<{ enabled: boolean; unlocked: boolean }>('get_encryption_status').then(status => {
+      if (status.enabled && !status.unlocked) {
+        setLocked(true);
+      } else {
+        checkHealth();
+      }
+    });
+    invoke<InstanceMode>('get_instance_mode').then(setInstanceMode);
     
+    // Due reminders raised by the backend are shown as OS notifications
+    const unlistenNotifications = listen<DesktopNotification[]>('desktop-notifications', async (event) => {
+      if (!('Notification' in window)) return;
+      if (Notification.permission === 'default') {
+        await Notification.requestPermission();
+      }
+      if (Notification.permission !== 'granted') return;
+      event.payload.forEach(n => new Notification(n.title, { body: n.body, tag: n.id }));
+    });
     
     return () => {
       cloudBackupService.stopConnectivityMonitoring();
+      unlistenNotifications.then(unlisten => unlisten());
     };
   }, []);
 
@@ -185,7 +356,10 @@ function App() {
         householdId: dogData.household_id || '',
       };
       console.log('Exact invoke params:', JSON.stringify(invokeParams, null, 2));
-      await invoke('add_dog', invokeParams);
+      const added = await invoke<{ warnings: string[] }>('add_dog', invokeParams);
+      if (added.warnings.length > 0) {
+        alert(added.warnings.join('\n'));
+      }
       loadDogs();
     } catch (error) {
       console.error('Failed to add dog:', error);
@@ -209,7 +383,10 @@ function App() {
         }
       };
 
-      await invoke('update_dog', { dog: cleanDog });
+      const warnings = await invoke<string[]>('update_dog', { dog: cleanDog });
+      if (warnings.length > 0) {
+        alert(warnings.join('\n'));
+      }
       loadDogs();
     } catch (error) {
       console.error('Failed to update dog:', error);
@@ -241,14 +418,14 @@ function App() {
     }
   };
 
-  const exportData = async () => {
+  const exportData = async (anonymized = false) => {
     try {
-      const data = await invoke<string>('export_data');
+      const data = await invoke<string>(anonymized ? 'export_anonymized' : 'export_data');
       const blob = new Blob([data], { type: 'application/json' });
       const url = URL.createObjectURL(blob);
       const a = document.createElement('a');
       a.href = url;
-      a.download = `doggy-daycare-backup-${new Date().toISOString().split('T')[0]}.json`;
+      a.download = `doggy-daycare-${anonymized ? 'anonymized' : 'backup'}-${new Date().toISOString().split('T')[0]}.json`;
       document.body.appendChild(a);
       a.click();
       document.body.removeChild(a);
@@ -270,6 +447,35 @@ function App() {
     }
   };
 
+  const unlockData = async (e: React.FormEvent) => {
+    e.preventDefault();
+    try {
+      await invoke('unlock_data', { passphrase, remember: rememberPassphrase });
+      setPassphrase('');
+      setUnlockError('');
+      setLocked(false);
+      checkHealth();
+    } catch (error) {
+      setUnlockError(String(error));
+    }
+  };
+
+  const recoverData = async (option: RecoveryOption) => {
+    const message = option.kind === 'start_fresh'
+      ? 'Start with empty data? The damaged file is kept aside but nothing from it will be shown.'
+      : `${option.description}? Changes made after it was saved will be missing.`;
+    if (!confirm(message)) return;
+    try {
+      const result = await invoke<StartupHealth>('recover_data', { kind: option.kind });
+      setHealth(result);
+      loadDogs();
+      loadSettings();
+    } catch (error) {
+      console.error('Failed to recover data:', error);
+      alert(`Recovery failed: ${error}`);
+    }
+  };
+
   const renderTab = () => {
     switch (currentTab) {
       case 'daily':
@@ -316,6 +522,64 @@ function App() {
           </div>
         </header>
 
+        {instanceMode?.read_only && (
+          <div className="warning-box">
+            <AlertTriangle size={16} />
+            <span>Read-only: you can look at everything but changes won't be saved. {instanceMode.reason}</span>
+          </div>
+        )}
+
+        {health && health.checks.filter(c => c.status === 'failed' && c.name !== 'Data').map(c => (
+          <div key={c.name} className="warning-box">
+            <AlertTriangle size={16} />
+            <span>{c.name}: {c.message}</span>
+          </div>
+        ))}
+
+        {locked ? (
+          <main className="tab-content">
+            <h2>The data is encrypted</h2>
+            <form onSubmit={unlockData}>
+              <div className="form-group">
+                <label htmlFor="unlock-passphrase">Passphrase</label>
+                <input
+                  id="unlock-passphrase"
+                  type="password"
+                  value={passphrase}
+                  onChange={(e) => setPassphrase(e.target.value)}
+                  autoFocus
+                />
+              </div>
+              <div className="form-group">
+                <label className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    checked={rememberPassphrase}
+                    onChange={(e) => setRememberPassphrase(e.target.checked)}
+                  />
+                  Remember on this computer
+                </label>
+              </div>
+              {unlockError && <p className="error-message">{unlockError}</p>}
+              <button type="submit" className="btn btn-primary">Unlock</button>
+            </form>
+          </main>
+        ) : health && !health.data_readable ? (
+          <main className="tab-content">
+            <h2>The data file can't be read</h2>
+            <p>{health.checks.find(c => c.name === 'Data')?.message}</p>
+            <p>Choose how to recover:</p>
+            {health.recovery_options.map(option => (
+              <div key={option.kind} className="form-group">
+                <button className="btn btn-primary" onClick={() => recoverData(option)}>
+                  {option.description}
+                </button>
+                {option.saved_at && <small> Saved {new Date(option.saved_at).toLocaleString()}</small>}
+              </div>
+            ))}
+          </main>
+        ) : (
+        <>
         <nav className="tabs">
           <button 
             className={`tab ${currentTab === 'daily' ? 'active' : ''}`}
@@ -352,6 +616,8 @@ function App() {
         <main className="tab-content">
           {renderTab()}
         </main>
+        </>
+        )}
       </div>
     </div>
   );
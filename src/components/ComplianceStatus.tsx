@@ -1,13 +1,81 @@
+import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Mail, FileText, Syringe, AlertTriangle, CheckCircle, MessageCircle } from 'lucide-react';
+import { Mail, FileText, Syringe, AlertTriangle, CheckCircle, MessageCircle, Clock, X } from 'lucide-react';
 import { Dog, Settings } from '../App';
 
+interface ExpiringVaccine {
+  dog_id: string;
+  dog_name: string;
+  owner: string;
+  vaccine_type: string;
+  expiration_date: string;
+  days_remaining: number;
+  vaccination_id?: string;
+}
+
+interface OverdueConsent {
+  dog_id: string;
+  dog_name: string;
+  owner: string;
+  last_signed?: string;
+  expired_on?: string;
+  days_overdue?: number;
+}
+
 interface ComplianceStatusProps {
   dogs: Dog[];
   settings: Settings | null;
 }
 
 export default function ComplianceStatus({ dogs, settings }: ComplianceStatusProps) {
+  const emailEnabled = settings?.notifications?.email_enabled ?? true;
+  const whatsappEnabled = settings?.notifications?.whatsapp_enabled ?? true;
+  const [activeReminders, setActiveReminders] = useState<Set<string> | null>(null);
+  const [expiringVaccines, setExpiringVaccines] = useState<ExpiringVaccine[]>([]);
+  const [overdueConsents, setOverdueConsents] = useState<OverdueConsent[]>([]);
+
+  const loadReminders = async () => {
+    try {
+      const reminders = await invoke<{ id: string }[]>('get_reminders');
+      setActiveReminders(new Set(reminders.map(r => r.id)));
+      setExpiringVaccines(await invoke<ExpiringVaccine[]>('get_expiring_vaccines', { days: null }));
+      setOverdueConsents(await invoke<OverdueConsent[]>('get_overdue_consents'));
+    } catch (error) {
+      console.error('Failed to load reminders:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadReminders();
+  }, [dogs]);
+
+  // Snoozed and dismissed reminders are left out of the issue lists
+  const isShown = (kind: 'vaccine' | 'consent', dog: Dog) =>
+    !activeReminders || activeReminders.has(`${kind}:${dog.id}`);
+
+  const snoozeReminder = async (kind: 'vaccine' | 'consent', dog: Dog) => {
+    const until = new Date();
+    until.setDate(until.getDate() + 7);
+    const untilDate = prompt(`Hide this reminder for ${dog.name} until (YYYY-MM-DD):`, until.toISOString().slice(0, 10));
+    if (!untilDate) return;
+    try {
+      await invoke('snooze_reminder', { reminderId: `${kind}:${dog.id}`, untilDate });
+      await loadReminders();
+    } catch (error) {
+      alert(`Failed to snooze reminder: ${error}`);
+    }
+  };
+
+  const dismissReminder = async (kind: 'vaccine' | 'consent', dog: Dog) => {
+    const reason = prompt(`Why dismiss this reminder for ${dog.name}? (e.g. vet appointment booked)`);
+    if (!reason) return;
+    try {
+      await invoke('dismiss_reminder', { reminderId: `${kind}:${dog.id}`, reason });
+      await loadReminders();
+    } catch (error) {
+      alert(`Failed to dismiss reminder: ${error}`);
+    }
+  };
   const isValidEmail = (email: string | undefined): boolean => {
     if (!email) return false;
     const emailRegex = /^[^\s@]+@[^\s@]+\.[^\s@]+$/;
@@ -20,24 +88,42 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
     const digitsOnly = phone.replace(/[^0-9]/g, '');
     return digitsOnly.length >= 10;
   };
+  // Soonest first, as the backend sorts them
+  const vaccinesFor = (dog: Dog) => expiringVaccines.filter(v => v.dog_id === dog.id);
+
   const getVaccineStatus = (dog: Dog) => {
-    if (!dog.vaccine_date) return 'missing';
-    
-    const vaccineDate = new Date(dog.vaccine_date);
-    const oneYearAgo = new Date();
-    oneYearAgo.setFullYear(oneYearAgo.getFullYear() - 1);
-    
-    return vaccineDate < oneYearAgo ? 'expired' : 'current';
+    if (!dog.vaccine_date && !dog.vaccinations?.length) return 'missing';
+    const expiring = vaccinesFor(dog);
+    if (expiring.some(v => v.days_remaining < 0)) return 'expired';
+    return expiring.length > 0 ? 'expiring' : 'current';
   };
 
+  const fillVaccineTemplate = (template: string, dog: Dog) => {
+    const soonest = vaccinesFor(dog)[0];
+    return template
+      .replace(/{dogName}/g, dog.name)
+      .replace(/{ownerName}/g, dog.owner)
+      .replace(/{ownerEmail}/g, dog.email)
+      .replace(/{vaccineType}/g, soonest?.vaccine_type ?? 'annual vaccination')
+      .replace(/{expirationDate}/g, soonest ? new Date(soonest.expiration_date).toLocaleDateString() : 'Not available');
+  };
+
+  const overdueConsentFor = (dog: Dog) => overdueConsents.find(c => c.dog_id === dog.id);
+
   const getConsentStatus = (dog: Dog) => {
-    if (!dog.consent_last_signed) return 'missing';
-    
-    const consentDate = new Date(dog.consent_last_signed);
-    const oneMonthAgo = new Date();
-    oneMonthAgo.setMonth(oneMonthAgo.getMonth() - 1);
-    
-    return consentDate < oneMonthAgo ? 'expired' : 'current';
+    const overdue = overdueConsentFor(dog);
+    if (!overdue) return 'current';
+    return overdue.last_signed ? 'expired' : 'missing';
+  };
+
+  const markConsentSigned = async (dog: Dog) => {
+    try {
+      const today = new Date().toISOString().split('T')[0];
+      await invoke('record_consent_signed', { dogId: dog.id, date: today });
+      loadReminders();
+    } catch (error) {
+      alert(`Failed to record consent: ${error}`);
+    }
   };
 
   const sendConsentEmail = async (dog: Dog) => {
@@ -46,6 +132,8 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
       return;
     }
 
+    if (!(await confirmChannel(dog, 'email'))) return;
+
     try {
       const template = settings.email_templates.consent_form
         .replace(/{dogName}/g, dog.name)
@@ -63,7 +151,9 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
       await invoke('open_email', {
         to: dog.email,
         subject: subject,
-        body: template
+        body: template,
+        dogId: dog.id,
+        template: 'consent_form'
       });
     } catch (error) {
       console.error('Failed to open email:', error);
@@ -77,30 +167,21 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
       return;
     }
 
-    try {
-      const expirationDate = dog.vaccine_date 
-        ? new Date(new Date(dog.vaccine_date).getFullYear() + 1, new Date(dog.vaccine_date).getMonth(), new Date(dog.vaccine_date).getDate())
-        : null;
+    if (!(await confirmChannel(dog, 'email'))) return;
 
-      const template = settings.email_templates.vaccine_reminder
-        .replace(/{dogName}/g, dog.name)
-        .replace(/{ownerName}/g, dog.owner)
-        .replace(/{ownerEmail}/g, dog.email)
-        .replace(/{vaccineType}/g, 'annual vaccination')
-        .replace(/{expirationDate}/g, expirationDate ? expirationDate.toLocaleDateString() : 'Not available');
+    try {
+      const template = fillVaccineTemplate(settings.email_templates.vaccine_reminder, dog);
 
       const subject = settings.email_subjects?.vaccine_reminder
-        ?.replace(/{dogName}/g, dog.name)
-        ?.replace(/{ownerName}/g, dog.owner)
-        ?.replace(/{ownerEmail}/g, dog.email)
-        ?.replace(/{vaccineType}/g, 'annual vaccination')
-        ?.replace(/{expirationDate}/g, expirationDate ? expirationDate.toLocaleDateString() : 'Not available')
-        || `Vaccine Record Update Required - ${dog.name}`;
+        ? fillVaccineTemplate(settings.email_subjects.vaccine_reminder, dog)
+        : `Vaccine Record Update Required - ${dog.name}`;
 
       await invoke('open_email', {
         to: dog.email,
         subject: subject,
-        body: template
+        body: template,
+        dogId: dog.id,
+        template: 'vaccine_reminder'
       });
     } catch (error) {
       console.error('Failed to open email:', error);
@@ -108,47 +189,40 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
     }
   };
 
-  const sendConsentWhatsApp = (dog: Dog) => {
-    if (!dog.phone || !settings || !settings.whatsapp_templates) {
-      alert('No phone number available for this dog owner or WhatsApp templates not configured.');
-      return;
+  // Owners may have opted out of a channel or prefer certain times; let staff decide
+  const confirmChannel = async (dog: Dog, channel: 'email' | 'whatsapp') => {
+    try {
+      const warning = await invoke<string | null>('check_contact_channel', { dogId: dog.id, channel });
+      return !warning || window.confirm(`${warning}.\n\nSend anyway?`);
+    } catch (error) {
+      console.error('Failed to check contact preferences:', error);
+      return true;
     }
-
-    const template = settings.whatsapp_templates.consent_form
-      .replace(/{dogName}/g, dog.name)
-      .replace(/{ownerName}/g, dog.owner)
-      .replace(/{ownerEmail}/g, dog.email)
-      .replace(/{currentDate}/g, new Date().toLocaleDateString());
-
-    const phoneNumber = dog.phone.replace(/[^0-9]/g, '');
-    const whatsappUrl = `https://api.whatsapp.com/send/?phone=${phoneNumber}&text=${encodeURIComponent(template)}`;
-    window.open(whatsappUrl, '_blank');
   };
 
-  const sendVaccineWhatsApp = (dog: Dog) => {
-    if (!dog.phone || !settings || !settings.whatsapp_templates) {
-      alert('No phone number available for this dog owner or WhatsApp templates not configured.');
+  const openWhatsApp = async (dog: Dog, templateKey: 'consent_form' | 'vaccine_reminder') => {
+    if (!dog.phone) {
+      alert('No phone number available for this dog owner.');
       return;
     }
+    if (!(await confirmChannel(dog, 'whatsapp'))) return;
 
-    const expirationDate = dog.vaccine_date 
-      ? new Date(new Date(dog.vaccine_date).getFullYear() + 1, new Date(dog.vaccine_date).getMonth(), new Date(dog.vaccine_date).getDate())
-      : null;
+    try {
+      await invoke('open_whatsapp', { phone: dog.phone, templateKey, dogId: dog.id });
+    } catch (error) {
+      console.error('Failed to open WhatsApp:', error);
+      alert(`Failed to open WhatsApp: ${error}`);
+    }
+  };
 
-    const template = settings.whatsapp_templates.vaccine_reminder
-      .replace(/{dogName}/g, dog.name)
-      .replace(/{ownerName}/g, dog.owner)
-      .replace(/{ownerEmail}/g, dog.email)
-      .replace(/{vaccineType}/g, 'annual vaccination')
-      .replace(/{expirationDate}/g, expirationDate ? expirationDate.toLocaleDateString() : 'Not available');
+  const sendConsentWhatsApp = (dog: Dog) => openWhatsApp(dog, 'consent_form');
 
-    const phoneNumber = dog.phone.replace(/[^0-9]/g, '');
-    const whatsappUrl = `https://api.whatsapp.com/send/?phone=${phoneNumber}&text=${encodeURIComponent(template)}`;
-    window.open(whatsappUrl, '_blank');
-  };
+  const sendVaccineWhatsApp = (dog: Dog) => openWhatsApp(dog, 'vaccine_reminder');
 
   const vaccineIssues = dogs.filter(dog => getVaccineStatus(dog) !== 'current');
   const consentIssues = dogs.filter(dog => getConsentStatus(dog) !== 'current');
+  const shownVaccineIssues = vaccineIssues.filter(dog => isShown('vaccine', dog));
+  const shownConsentIssues = consentIssues.filter(dog => isShown('consent', dog));
 
   return (
     <div className="compliance-status">
@@ -180,14 +254,14 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
         </div>
       </div>
 
-      {consentIssues.length > 0 && (
+      {shownConsentIssues.length > 0 && (
         <div className="compliance-section">
           <h3 className="section-title">
             <AlertTriangle size={20} />
-            Consent Form Issues ({consentIssues.length})
+            Consent Form Issues ({shownConsentIssues.length})
           </h3>
           <div className="compliance-grid">
-            {consentIssues.map(dog => (
+            {shownConsentIssues.map(dog => (
               <div key={dog.id} className="compliance-card consent-issue">
                 <div className="compliance-header">
                   <h4>{dog.name}</h4>
@@ -197,13 +271,16 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
                 </div>
                 <div className="compliance-info">
                   <p><strong>Owner:</strong> {dog.owner}</p>
-                  {dog.consent_last_signed && (
-                    <p><strong>Last Signed:</strong> {new Date(dog.consent_last_signed).toLocaleDateString()}</p>
+                  {overdueConsentFor(dog)?.last_signed && (
+                    <p><strong>Last Signed:</strong> {new Date(overdueConsentFor(dog)!.last_signed!).toLocaleDateString()}</p>
+                  )}
+                  {overdueConsentFor(dog)?.expired_on && (
+                    <p><strong>Expired:</strong> {new Date(overdueConsentFor(dog)!.expired_on!).toLocaleDateString()}</p>
                   )}
                   {dog.email && <p><strong>Email:</strong> {dog.email}</p>}
                 </div>
                 <div className="compliance-actions">
-                  {isValidEmail(dog.email) && (
+                  {emailEnabled && isValidEmail(dog.email) && (
                     <button 
                       className="btn btn-primary"
                       onClick={() => sendConsentEmail(dog)}
@@ -212,7 +289,7 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
                       Email
                     </button>
                   )}
-                  {isValidPhone(dog.phone) && (
+                  {whatsappEnabled && isValidPhone(dog.phone) && (
                     <button 
                       className="btn btn-secondary"
                       onClick={() => sendConsentWhatsApp(dog)}
@@ -221,6 +298,27 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
                       WhatsApp
                     </button>
                   )}
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => markConsentSigned(dog)}
+                  >
+                    <CheckCircle size={16} />
+                    Signed Today
+                  </button>
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => snoozeReminder('consent', dog)}
+                  >
+                    <Clock size={16} />
+                    Snooze
+                  </button>
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => dismissReminder('consent', dog)}
+                  >
+                    <X size={16} />
+                    Dismiss
+                  </button>
                 </div>
               </div>
             ))}
@@ -228,30 +326,32 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
         </div>
       )}
 
-      {vaccineIssues.length > 0 && (
+      {shownVaccineIssues.length > 0 && (
         <div className="compliance-section">
           <h3 className="section-title">
             <AlertTriangle size={20} />
-            Vaccine Record Issues ({vaccineIssues.length})
+            Vaccine Record Issues ({shownVaccineIssues.length})
           </h3>
           <div className="compliance-grid">
-            {vaccineIssues.map(dog => (
+            {shownVaccineIssues.map(dog => (
               <div key={dog.id} className="compliance-card vaccine-issue">
                 <div className="compliance-header">
                   <h4>{dog.name}</h4>
                   <span className={`status-badge ${getVaccineStatus(dog)}`}>
-                    {getVaccineStatus(dog) === 'missing' ? 'Missing' : 'Expired'}
+                    {{ missing: 'Missing', expired: 'Expired', expiring: 'Expires soon', current: 'Current' }[getVaccineStatus(dog)]}
                   </span>
                 </div>
                 <div className="compliance-info">
                   <p><strong>Owner:</strong> {dog.owner}</p>
-                  {dog.vaccine_date && (
-                    <p><strong>Last Vaccine:</strong> {new Date(dog.vaccine_date).toLocaleDateString()}</p>
-                  )}
+                  {vaccinesFor(dog).map(v => (
+                    <p key={v.vaccine_type}>
+                      <strong>{v.vaccine_type}:</strong> {v.days_remaining < 0 ? 'expired' : 'expires'} {new Date(v.expiration_date).toLocaleDateString()}
+                    </p>
+                  ))}
                   {dog.email && <p><strong>Email:</strong> {dog.email}</p>}
                 </div>
                 <div className="compliance-actions">
-                  {isValidEmail(dog.email) && (
+                  {emailEnabled && isValidEmail(dog.email) && (
                     <button 
                       className="btn btn-primary"
                       onClick={() => sendVaccineEmail(dog)}
@@ -260,7 +360,7 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
                       Email
                     </button>
                   )}
-                  {isValidPhone(dog.phone) && (
+                  {whatsappEnabled && isValidPhone(dog.phone) && (
                     <button 
                       className="btn btn-secondary"
                       onClick={() => sendVaccineWhatsApp(dog)}
@@ -269,6 +369,20 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
                       WhatsApp
                     </button>
                   )}
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => snoozeReminder('vaccine', dog)}
+                  >
+                    <Clock size={16} />
+                    Snooze
+                  </button>
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => dismissReminder('vaccine', dog)}
+                  >
+                    <X size={16} />
+                    Dismiss
+                  </button>
                 </div>
               </div>
             ))}
@@ -276,7 +390,7 @@ export default function ComplianceStatus({ dogs, settings }: ComplianceStatusPro
         </div>
       )}
 
-      {consentIssues.length === 0 && vaccineIssues.length === 0 && (
+      {shownConsentIssues.length === 0 && shownVaccineIssues.length === 0 && (
         <div className="compliance-section">
           <div className="compliance-success">
             <CheckCircle size={48} className="success-icon" />
@@ -475,6 +475,11 @@ export default function DailyChecklist({ dogs }: DailyChecklistProps) {
                         🏠
                       </span>
                     )}
+                    {(attendanceEntry?.allergies || record.allergies || []).map(allergy => (
+                      <span key={allergy.allergen} className={`allergy-badge allergy-${allergy.severity}`} title={[allergy.reaction, allergy.treatment].filter(Boolean).join(' - ')}>
+                        Allergy: {allergy.allergen}
+                      </span>
+                    ))}
                   </h3>
                 </div>
                 <div className="dog-content">
@@ -1,14 +1,16 @@
 import React, { useState, useEffect } from 'react';
-import { Plus, Edit, Trash2, Download, Upload, Search } from 'lucide-react';
+import { Plus, Edit, Trash2, Download, Upload, Search, Phone } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { Dog, DogSchedule } from '../App';
+import { Allergy, Dog, DogSchedule, VetDetails } from '../App';
+
+const emptyVet: VetDetails = { name: '', clinic: '', phone: '', address: '', email: '' };
 
 interface DogManagementProps {
   dogs: Dog[];
   onAddDog: (dog: Omit<Dog, 'id' | 'created_at'>) => Promise<void>;
   onUpdateDog: (dog: Dog) => Promise<void>;
   onDeleteDog: (dogId: string) => Promise<void>;
-  onExportData: () => Promise<void>;
+  onExportData: (anonymized?: boolean) => Promise<void>;
   onImportData: (file: File) => Promise<void>;
 }
 
@@ -55,6 +57,7 @@ export default function DogManagement({
   const [showForm, setShowForm] = useState(false);
   const [editingDog, setEditingDog] = useState<Dog | null>(null);
   const [searchTerm, setSearchTerm] = useState('');
+  const [breedOptions, setBreedOptions] = useState<string[]>([]);
   const [formData, setFormData] = useState<DogFormData>({
     name: '',
     owner: '',
@@ -79,6 +82,12 @@ export default function DogManagement({
     create_household: false,
   });
 
+  useEffect(() => {
+    invoke<{ name: string }[]>('get_breeds')
+      .then((breeds) => setBreedOptions(breeds.map((b) => b.name)))
+      .catch((error) => console.error('Failed to load breeds:', error));
+  }, []);
+
   const filteredDogs = dogs.filter(dog =>
     dog.name.toLowerCase().includes(searchTerm.toLowerCase()) ||
     dog.owner.toLowerCase().includes(searchTerm.toLowerCase()) ||
@@ -205,6 +214,25 @@ export default function DogManagement({
     setShowForm(true);
   };
 
+  const setAllergy = (index: number, allergy: Allergy | null) => {
+    if (!editingDog) return;
+    const allergies = [...(editingDog.allergies || [])];
+    if (allergy) {
+      allergies[index] = allergy;
+    } else {
+      allergies.splice(index, 1);
+    }
+    setEditingDog({ ...editingDog, allergies });
+  };
+
+  const handleContactVet = async (dog: Dog) => {
+    try {
+      await invoke('contact_vet', { dogId: dog.id });
+    } catch (error) {
+      alert(`Could not contact the vet: ${error}`);
+    }
+  };
+
   const handleDelete = async (dog: Dog) => {
     if (window.confirm(`Are you sure you want to delete ${dog.name}? This action cannot be undone.`)) {
       try {
@@ -251,11 +279,19 @@ export default function DogManagement({
           </button>
           <button 
             className="btn btn-secondary"
-            onClick={onExportData}
+            onClick={() => onExportData()}
           >
             <Download size={16} />
             Export
           </button>
+          <button 
+            className="btn btn-secondary"
+            onClick={() => onExportData(true)}
+            title="Attendance, services and revenue without names, contact details or notes"
+          >
+            <Download size={16} />
+            Export Anonymized
+          </button>
           <label className="btn btn-secondary">
             <Upload size={16} />
             Import
@@ -320,7 +356,13 @@ export default function DogManagement({
                     value={formData.breed}
                     onChange={(e) => setFormData({ ...formData, breed: e.target.value })}
                     className="input"
+                    list="breed-options"
                   />
+                  <datalist id="breed-options">
+                    {breedOptions.map((breed) => (
+                      <option key={breed} value={breed} />
+                    ))}
+                  </datalist>
                 </div>
                 <div className="form-group">
                   <label>Date of Birth</label>
@@ -340,6 +382,156 @@ export default function DogManagement({
                     className="input"
                   />
                 </div>
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Vet Name</label>
+                    <input
+                      type="text"
+                      value={editingDog.vet?.name || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, vet: { ...emptyVet, ...editingDog.vet, name: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Vet Clinic</label>
+                    <input
+                      type="text"
+                      value={editingDog.vet?.clinic || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, vet: { ...emptyVet, ...editingDog.vet, clinic: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Vet Phone</label>
+                    <input
+                      type="tel"
+                      value={editingDog.vet?.phone || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, vet: { ...emptyVet, ...editingDog.vet, phone: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Vet Email</label>
+                    <input
+                      type="email"
+                      value={editingDog.vet?.email || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, vet: { ...emptyVet, ...editingDog.vet, email: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Vet Address</label>
+                    <input
+                      type="text"
+                      value={editingDog.vet?.address || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, vet: { ...emptyVet, ...editingDog.vet, address: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Size</label>
+                    <select
+                      value={editingDog.size || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, size: (e.target.value || undefined) as Dog['size'] })}
+                      className="input"
+                    >
+                      <option value="">Not recorded</option>
+                      <option value="small">Small</option>
+                      <option value="medium">Medium</option>
+                      <option value="large">Large</option>
+                    </select>
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Microchip Number</label>
+                    <input
+                      type="text"
+                      value={editingDog.microchip_number || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, microchip_number: e.target.value || undefined })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Insurance Provider</label>
+                    <input
+                      type="text"
+                      value={editingDog.insurance?.provider || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, insurance: { policy_number: '', ...editingDog.insurance, provider: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Insurance Policy Number</label>
+                    <input
+                      type="text"
+                      value={editingDog.insurance?.policy_number || ''}
+                      onChange={(e) => setEditingDog({ ...editingDog, insurance: { provider: '', ...editingDog.insurance, policy_number: e.target.value } })}
+                      className="input"
+                    />
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>Allergies</label>
+                    {(editingDog.allergies || []).map((allergy, index) => (
+                      <div key={index} className="allergy-row">
+                        <input
+                          type="text"
+                          value={allergy.allergen}
+                          placeholder="Allergen"
+                          onChange={(e) => setAllergy(index, { ...allergy, allergen: e.target.value })}
+                          className="input"
+                        />
+                        <select
+                          value={allergy.severity}
+                          onChange={(e) => setAllergy(index, { ...allergy, severity: e.target.value as Allergy['severity'] })}
+                          className="input"
+                        >
+                          <option value="mild">Mild</option>
+                          <option value="moderate">Moderate</option>
+                          <option value="severe">Severe</option>
+                        </select>
+                        <button type="button" className="btn-icon btn-danger" onClick={() => setAllergy(index, null)} title="Remove allergy">
+                          <Trash2 size={14} />
+                        </button>
+                      </div>
+                    ))}
+                    <button
+                      type="button"
+                      className="btn btn-secondary"
+                      onClick={() => setEditingDog({ ...editingDog, allergies: [...(editingDog.allergies || []), { allergen: '', severity: 'mild' }] })}
+                    >
+                      Add allergy
+                    </button>
+                  </div>
+                )}
+                {editingDog && (
+                  <div className="form-group">
+                    <label>
+                      <input
+                        type="checkbox"
+                        checked={!!editingDog.solo_care}
+                        onChange={(e) => setEditingDog({ ...editingDog, solo_care: e.target.checked })}
+                      />
+                      Solo care (own handler, no group play)
+                    </label>
+                  </div>
+                )}
               </div>
               
               {/* Schedule Section */}
@@ -577,6 +769,16 @@ export default function DogManagement({
                     {dog.vaccine_date && (
                       <p><strong>Last Vaccine:</strong> {new Date(dog.vaccine_date).toLocaleDateString()}</p>
                     )}
+                    {dog.vet && (dog.vet.clinic || dog.vet.name) && (
+                      <p>
+                        <strong>Vet:</strong> {dog.vet.clinic || dog.vet.name}{' '}
+                        {(dog.vet.phone || dog.vet.email) && (
+                          <button className="btn-icon" onClick={() => handleContactVet(dog)} title="Contact vet">
+                            <Phone size={14} />
+                          </button>
+                        )}
+                      </p>
+                    )}
                   </div>
                 </div>
               ))}
@@ -621,6 +823,16 @@ export default function DogManagement({
                     {dog.vaccine_date && (
                       <p><strong>Last Vaccine:</strong> {new Date(dog.vaccine_date).toLocaleDateString()}</p>
                     )}
+                    {dog.vet && (dog.vet.clinic || dog.vet.name) && (
+                      <p>
+                        <strong>Vet:</strong> {dog.vet.clinic || dog.vet.name}{' '}
+                        {(dog.vet.phone || dog.vet.email) && (
+                          <button className="btn-icon" onClick={() => handleContactVet(dog)} title="Contact vet">
+                            <Phone size={14} />
+                          </button>
+                        )}
+                      </p>
+                    )}
                   </div>
                 </div>
               ))}
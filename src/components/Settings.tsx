@@ -1,7 +1,7 @@
-import React, { useState } from 'react';
-import { Save, RotateCcw, Mail, Settings as SettingsIcon, MessageCircle, Cloud, FolderOpen, Download, RefreshCw, AlertTriangle } from 'lucide-react';
+import React, { useState, useEffect } from 'react';
+import { Save, RotateCcw, Mail, Settings as SettingsIcon, MessageCircle, Bell, Cloud, FolderOpen, Download, RefreshCw, AlertTriangle } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { Settings as SettingsType, BackupFileInfo } from '../App';
+import { Settings as SettingsType, BackupFileInfo, BackupDestination, Branding, NotificationSettings, BookingNoticeSettings, FiscalSettings, AccountingSettings } from '../App';
 
 interface SettingsProps {
   settings: SettingsType;
@@ -15,13 +15,86 @@ export default function Settings({ settings, onUpdateSettings }: SettingsProps)
       enabled: false,
       cloud_directory: '',
       max_backups: 100,
-      sync_interval_minutes: 30
+      sync_interval_minutes: 30,
+      destinations: []
     }
   });
   const [isSaving, setIsSaving] = useState(false);
   const [backupFiles, setBackupFiles] = useState<BackupFileInfo[]>([]);
   const [isLoadingBackups, setIsLoadingBackups] = useState(false);
   const [showBackupRecovery, setShowBackupRecovery] = useState(false);
+  const [logoPath, setLogoPath] = useState('');
+  const [dataEncrypted, setDataEncrypted] = useState(false);
+  const [passphraseRemembered, setPassphraseRemembered] = useState(false);
+
+  useEffect(() => {
+    invoke<{ enabled: boolean; unlocked: boolean; remembered: boolean }>('get_encryption_status')
+      .then(status => {
+        setDataEncrypted(status.enabled);
+        setPassphraseRemembered(status.remembered);
+      })
+      .catch(error => console.error('Failed to load encryption status:', error));
+  }, []);
+
+  const updateNotifications = (changes: Partial<NotificationSettings>) => {
+    setFormData({ ...formData, notifications: { ...formData.notifications, ...changes } });
+  };
+
+  const updateDefaultTimes = (service: 'daycare' | 'training' | 'boarding', changes: { drop_off?: string; pick_up?: string }) => {
+    if (!formData.default_times) return;
+    setFormData({
+      ...formData,
+      default_times: { ...formData.default_times, [service]: { ...formData.default_times[service], ...changes } },
+    });
+  };
+
+  const updateFiscal = (changes: Partial<FiscalSettings>) => {
+    if (!formData.fiscal) return;
+    setFormData({ ...formData, fiscal: { ...formData.fiscal, ...changes } });
+  };
+
+  const updateAccounting = (changes: Partial<AccountingSettings>) => {
+    if (!formData.accounting) return;
+    setFormData({ ...formData, accounting: { ...formData.accounting, ...changes } });
+  };
+
+  const setDestinations = (destinations: BackupDestination[]) => {
+    setFormData({ ...formData, cloud_backup: { ...formData.cloud_backup!, destinations } });
+  };
+
+  const updateDestination = (index: number, changes: Partial<BackupDestination>) => {
+    const destinations = [...(formData.cloud_backup.destinations || [])];
+    destinations[index] = { ...destinations[index], ...changes };
+    setDestinations(destinations);
+  };
+
+  const addDestination = (type: 'folder' | 's3') => {
+    const destination: BackupDestination = type === 'folder'
+      ? { id: crypto.randomUUID(), name: 'Backup folder', enabled: true, type, directory: '' }
+      : { id: crypto.randomUUID(), name: 'S3 bucket', enabled: true, type, bucket: '', region: 'us-east-1', prefix: '', access_key_id: '', secret_access_key: '' };
+    setDestinations([...(formData.cloud_backup.destinations || []), destination]);
+  };
+
+  const removeDestination = (index: number) => {
+    setDestinations((formData.cloud_backup.destinations || []).filter((_, i) => i !== index));
+  };
+
+  const updateBookingNotices = (changes: Partial<BookingNoticeSettings>) => {
+    if (!formData.booking_notices) return;
+    setFormData({ ...formData, booking_notices: { ...formData.booking_notices, ...changes } });
+  };
+
+  const updateLogo = async (path: string | null) => {
+    try {
+      const branding = path
+        ? await invoke<Branding>('set_branding_logo', { sourcePath: path })
+        : await invoke<Branding>('clear_branding_logo');
+      setFormData({ ...formData, branding: { ...formData.branding, logo_path: branding.logo_path } });
+      setLogoPath('');
+    } catch (error) {
+      alert(`Failed to update logo: ${error}`);
+    }
+  };
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -100,6 +173,77 @@ export default function Settings({ settings, onUpdateSettings }: SettingsProps)
     }
   };
 
+  const exportSettings = async () => {
+    try {
+      const json = await invoke<string>('export_settings');
+      const blob = new Blob([json], { type: 'application/json' });
+      const url = URL.createObjectURL(blob);
+      const a = document.createElement('a');
+      a.href = url;
+      a.download = `doggy-daycare-settings-${new Date().toISOString().split('T')[0]}.json`;
+      document.body.appendChild(a);
+      a.click();
+      document.body.removeChild(a);
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      alert(`Failed to export settings: ${error}`);
+    }
+  };
+
+  const toggleEncryption = async () => {
+    const passphrase = window.prompt(dataEncrypted
+      ? 'Enter the passphrase to stop encrypting the data file:'
+      : 'Choose a passphrase of at least 10 characters. It is needed every time the app starts and cannot be recovered if lost:');
+    if (!passphrase) return;
+
+    try {
+      if (dataEncrypted) {
+        await invoke('disable_data_encryption', { passphrase });
+        setPassphraseRemembered(false);
+      } else {
+        if (window.prompt('Enter the passphrase again to confirm:') !== passphrase) {
+          alert('The passphrases did not match.');
+          return;
+        }
+        const remember = window.confirm('Remember the passphrase in this computer\'s keychain so the app unlocks by itself? Anyone who can sign in to this computer could then open the data.');
+        await invoke('enable_data_encryption', { passphrase, remember });
+        setPassphraseRemembered(remember);
+      }
+      setDataEncrypted(!dataEncrypted);
+    } catch (error) {
+      alert(`Failed to change encryption: ${error}`);
+    }
+  };
+
+  const toggleRememberedPassphrase = async () => {
+    try {
+      if (passphraseRemembered) {
+        await invoke('forget_data_passphrase');
+      } else {
+        const passphrase = window.prompt('Enter the passphrase to remember it in this computer\'s keychain:');
+        if (!passphrase) return;
+        await invoke('remember_data_passphrase', { passphrase });
+      }
+      setPassphraseRemembered(!passphraseRemembered);
+    } catch (error) {
+      alert(`Failed to change the remembered passphrase: ${error}`);
+    }
+  };
+
+  const importSettings = async (file: File) => {
+    if (!window.confirm('Replace these settings with the imported ones? Mail accounts, backup folder and logo are kept.')) {
+      return;
+    }
+
+    try {
+      await invoke('import_settings', { json: await file.text() });
+      alert('Settings imported! The application will reload to reflect the changes.');
+      window.location.reload();
+    } catch (error) {
+      alert(`Failed to import settings: ${error}`);
+    }
+  };
+
   const formatFileSize = (bytes: number) => {
     if (bytes === 0) return '0 Bytes';
     const k = 1024;
@@ -270,6 +414,100 @@ export default function Settings({ settings, onUpdateSettings }: SettingsProps)
           </div>
         </div>
 
+        <div className="card">
+          <div className="card-header">
+            <Bell size={20} />
+            <h3>Notifications</h3>
+          </div>
+
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={formData.notifications.email_enabled}
+                onChange={(e) => updateNotifications({ email_enabled: e.target.checked })}
+              />
+              <span>Email owners</span>
+            </label>
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={formData.notifications.whatsapp_enabled}
+                onChange={(e) => updateNotifications({ whatsapp_enabled: e.target.checked })}
+              />
+              <span>WhatsApp owners</span>
+            </label>
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={formData.notifications.desktop_enabled}
+                onChange={(e) => updateNotifications({ desktop_enabled: e.target.checked })}
+              />
+              <span>Desktop notifications for staff</span>
+            </label>
+          </div>
+
+          {formData.booking_notices && (
+            <div className="form-group">
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={formData.booking_notices.enabled}
+                  onChange={(e) => updateBookingNotices({ enabled: e.target.checked })}
+                />
+                <span>Tell owners about waitlist places and confirmed bookings</span>
+              </label>
+              <select
+                className="input"
+                value={formData.booking_notices.channel}
+                onChange={(e) => updateBookingNotices({ channel: e.target.value as BookingNoticeSettings['channel'] })}
+              >
+                <option value="email">By email</option>
+                <option value="whatsapp">By WhatsApp (queued for staff to send)</option>
+                <option value="sms">By SMS (queued for staff to send)</option>
+              </select>
+            </div>
+          )}
+
+          <div className="form-group">
+            <label htmlFor="reminder-advance-days">
+              Remind Owners (days before consent lapses)
+            </label>
+            <input
+              id="reminder-advance-days"
+              type="number"
+              min={0}
+              max={28}
+              className="input"
+              value={formData.notifications.reminder_advance_days}
+              onChange={(e) => updateNotifications({ reminder_advance_days: parseInt(e.target.value) || 0 })}
+            />
+          </div>
+
+          <div className="form-group">
+            <label htmlFor="quiet-hours-start">
+              Quiet Hours
+            </label>
+            <input
+              id="quiet-hours-start"
+              type="time"
+              className="input"
+              value={formData.notifications.quiet_hours_start || ''}
+              onChange={(e) => updateNotifications({ quiet_hours_start: e.target.value || undefined })}
+            />
+            <input
+              id="quiet-hours-end"
+              type="time"
+              className="input"
+              value={formData.notifications.quiet_hours_end || ''}
+              onChange={(e) => updateNotifications({ quiet_hours_end: e.target.value || undefined })}
+            />
+            <div className="template-help">
+              Automatic reminders wait until quiet hours are over
+            </div>
+          </div>
+        </div>
+
         <div className="card">
           <div className="card-header">
             <Cloud size={20} />
@@ -370,6 +608,95 @@ export default function Settings({ settings, onUpdateSettings }: SettingsProps)
                 </div>
               </div>
 
+              <div className="form-group">
+                <label>Additional Backup Destinations</label>
+                {(formData.cloud_backup.destinations || []).map((destination, index) => (
+                  <div key={destination.id} className="form-group">
+                    <label className="checkbox-label">
+                      <input
+                        type="checkbox"
+                        checked={destination.enabled}
+                        onChange={(e) => updateDestination(index, { enabled: e.target.checked })}
+                      />
+                      <input
+                        type="text"
+                        className="input"
+                        value={destination.name}
+                        onChange={(e) => updateDestination(index, { name: e.target.value })}
+                      />
+                    </label>
+                    {destination.type === 'folder' ? (
+                      <input
+                        type="text"
+                        className="input"
+                        value={destination.directory || ''}
+                        onChange={(e) => updateDestination(index, { directory: e.target.value })}
+                        placeholder="/path/to/another/folder"
+                      />
+                    ) : (
+                      <>
+                        <input
+                          type="text"
+                          className="input"
+                          value={destination.bucket || ''}
+                          onChange={(e) => updateDestination(index, { bucket: e.target.value })}
+                          placeholder="Bucket"
+                        />
+                        <input
+                          type="text"
+                          className="input"
+                          value={destination.region || ''}
+                          onChange={(e) => updateDestination(index, { region: e.target.value })}
+                          placeholder="Region, e.g. eu-west-2"
+                        />
+                        <input
+                          type="text"
+                          className="input"
+                          value={destination.endpoint || ''}
+                          onChange={(e) => updateDestination(index, { endpoint: e.target.value || undefined })}
+                          placeholder="Endpoint for S3-compatible storage (leave blank for Amazon S3)"
+                        />
+                        <input
+                          type="text"
+                          className="input"
+                          value={destination.prefix || ''}
+                          onChange={(e) => updateDestination(index, { prefix: e.target.value })}
+                          placeholder="Key prefix, e.g. daycare/"
+                        />
+                        <input
+                          type="text"
+                          className="input"
+                          value={destination.access_key_id || ''}
+                          onChange={(e) => updateDestination(index, { access_key_id: e.target.value })}
+                          placeholder="Access key ID"
+                        />
+                        <input
+                          type="password"
+                          className="input"
+                          value={destination.secret_access_key || ''}
+                          onChange={(e) => updateDestination(index, { secret_access_key: e.target.value })}
+                          placeholder="Secret access key"
+                        />
+                      </>
+                    )}
+                    <button type="button" className="btn btn-secondary btn-sm" onClick={() => removeDestination(index)}>
+                      Remove
+                    </button>
+                  </div>
+                ))}
+                <div className="form-actions">
+                  <button type="button" className="btn btn-secondary" onClick={() => addDestination('folder')}>
+                    Add Folder
+                  </button>
+                  <button type="button" className="btn btn-secondary" onClick={() => addDestination('s3')}>
+                    Add S3 Bucket
+                  </button>
+                </div>
+                <div className="template-help">
+                  Each backup is also written here, so one failing sync client doesn't stop backups. Old files are only pruned in folders; use a lifecycle rule to expire old S3 backups
+                </div>
+              </div>
+
               <div className="form-group">
                 <div className="form-actions">
                   <button
@@ -507,6 +834,218 @@ export default function Settings({ settings, onUpdateSettings }: SettingsProps)
             </div>
           </div>
 
+          <div className="form-group">
+            <label htmlFor="business-address">
+              Business Address
+            </label>
+            <textarea
+              id="business-address"
+              className="input"
+              rows={3}
+              value={formData.business_address || ''}
+              onChange={(e) => setFormData({ ...formData, business_address: e.target.value })}
+              placeholder="Street, town and postcode"
+            />
+            <div className="template-help">
+              Printed on invoices, one line per address line
+            </div>
+          </div>
+
+          {formData.default_times && (
+            <div className="form-group">
+              <label>Default Drop-off and Pick-up Times</label>
+              {(['daycare', 'training', 'boarding'] as const).map(service => (
+                <div key={service}>
+                  <span>{service.charAt(0).toUpperCase() + service.slice(1)}</span>
+                  <input
+                    type="time"
+                    className="input"
+                    value={formData.default_times![service].drop_off || ''}
+                    onChange={(e) => updateDefaultTimes(service, { drop_off: e.target.value || undefined })}
+                  />
+                  <input
+                    type="time"
+                    className="input"
+                    value={formData.default_times![service].pick_up || ''}
+                    onChange={(e) => updateDefaultTimes(service, { pick_up: e.target.value || undefined })}
+                  />
+                </div>
+              ))}
+              <div className="template-help">
+                Used on rosters and reports for bookings that don't give their own times
+              </div>
+            </div>
+          )}
+
+          {formData.fiscal && (
+            <div className="form-group">
+              <label htmlFor="fiscal-start">Fiscal Year Starts In</label>
+              <select
+                id="fiscal-start"
+                className="input"
+                value={formData.fiscal.year_start_month}
+                onChange={(e) => updateFiscal({ year_start_month: Number(e.target.value) })}
+              >
+                {Array.from({ length: 12 }, (_, i) => (
+                  <option key={i + 1} value={i + 1}>
+                    {new Date(2000, i, 1).toLocaleString(undefined, { month: 'long' })}
+                  </option>
+                ))}
+              </select>
+              <label htmlFor="week-numbering">Week Numbers</label>
+              <select
+                id="week-numbering"
+                className="input"
+                value={formData.fiscal.week_numbering === 'iso' ? 'iso' : `fiscal_${formData.fiscal.week_starts_on}`}
+                onChange={(e) => updateFiscal(e.target.value === 'iso'
+                  ? { week_numbering: 'iso', week_starts_on: 'monday' }
+                  : { week_numbering: 'fiscal_year', week_starts_on: e.target.value === 'fiscal_sunday' ? 'sunday' : 'monday' })}
+              >
+                <option value="iso">ISO weeks (Monday start)</option>
+                <option value="fiscal_monday">From the fiscal year start, weeks starting Monday</option>
+                <option value="fiscal_sunday">From the fiscal year start, weeks starting Sunday</option>
+              </select>
+              <div className="template-help">
+                Reports, the annual review and utilization are split into these periods to match your accountant's
+              </div>
+            </div>
+          )}
+
+          {formData.accounting && (
+            <div className="form-group">
+              <label>Accounting Export Accounts</label>
+              {([
+                ['daycare_account', 'Daycare income'],
+                ['training_account', 'Training income'],
+                ['boarding_account', 'Boarding income'],
+                ['other_income_account', 'Other income'],
+                ['discounts_account', 'Discounts'],
+                ['receivables_account', 'Accounts receivable'],
+                ['deposit_account', 'Payments deposited to'],
+                ['tax_type', 'Xero tax type'],
+              ] as const).map(([field, label]) => (
+                <div key={field}>
+                  <span>{label}</span>
+                  <input
+                    type="text"
+                    className="input"
+                    value={formData.accounting![field]}
+                    onChange={(e) => updateAccounting({ [field]: e.target.value })}
+                  />
+                </div>
+              ))}
+              <div>
+                <span>Payment terms (days)</span>
+                <input
+                  type="number"
+                  className="input"
+                  min={0}
+                  value={formData.accounting.payment_terms_days}
+                  onChange={(e) => updateAccounting({ payment_terms_days: Math.max(0, Number(e.target.value)) })}
+                />
+              </div>
+              <div className="template-help">
+                Account names for QuickBooks, or account codes for Xero
+              </div>
+            </div>
+          )}
+
+          <div className="form-group">
+            <label htmlFor="brand-logo">
+              Document Logo
+            </label>
+            <div className="template-help">
+              {formData.branding.logo_path ? `Current logo: ${formData.branding.logo_path}` : 'No logo set'}
+            </div>
+            <input
+              id="brand-logo"
+              type="text"
+              className="input"
+              value={logoPath}
+              onChange={(e) => setLogoPath(e.target.value)}
+              placeholder="Path to a PNG or JPEG file"
+            />
+            <button type="button" className="btn btn-secondary" onClick={() => updateLogo(logoPath)} disabled={!logoPath}>
+              Use Logo
+            </button>
+            {formData.branding.logo_path && (
+              <button type="button" className="btn btn-secondary" onClick={() => updateLogo(null)}>
+                Remove Logo
+              </button>
+            )}
+          </div>
+
+          <div className="form-group">
+            <label htmlFor="brand-color">
+              Brand Colour
+            </label>
+            <input
+              id="brand-color"
+              type="color"
+              value={formData.branding.primary_color}
+              onChange={(e) => setFormData({ ...formData, branding: { ...formData.branding, primary_color: e.target.value } })}
+            />
+          </div>
+
+          <div className="form-group">
+            <label htmlFor="brand-footer">
+              Document Footer
+            </label>
+            <input
+              id="brand-footer"
+              type="text"
+              className="input"
+              value={formData.branding.footer_text}
+              onChange={(e) => setFormData({ ...formData, branding: { ...formData.branding, footer_text: e.target.value } })}
+              placeholder="e.g. Company address, registration number, licence number"
+            />
+            <div className="template-help">
+              Printed along the bottom of invoices, forms and registers
+            </div>
+          </div>
+
+          <div className="form-group">
+            <label htmlFor="settings-import">
+              Share Configuration
+            </label>
+            <button type="button" className="btn btn-secondary" onClick={exportSettings}>
+              <Download size={16} />
+              Export Settings
+            </button>
+            <input
+              id="settings-import"
+              type="file"
+              accept=".json,application/json"
+              onChange={(e) => {
+                const file = e.target.files?.[0];
+                if (file) importSettings(file);
+                e.target.value = '';
+              }}
+            />
+            <div className="template-help">
+              Templates, rates, opening hours and other settings only; no dog or customer data
+            </div>
+          </div>
+
+          <div className="form-group">
+            <label>Data Encryption</label>
+            <button type="button" className="btn btn-secondary" onClick={toggleEncryption}>
+              {dataEncrypted ? 'Stop Encrypting Data' : 'Encrypt Data With a Passphrase'}
+            </button>
+            {dataEncrypted && (
+              <button type="button" className="btn btn-secondary" onClick={toggleRememberedPassphrase}>
+                {passphraseRemembered ? 'Forget Remembered Passphrase' : 'Remember Passphrase on This Computer'}
+              </button>
+            )}
+            <div className="template-help">
+              {!dataEncrypted
+                ? 'Protects the data file if this computer is lost or used by others. Backups are not encrypted'
+                : passphraseRemembered
+                  ? 'The data file is encrypted; the passphrase is kept in this computer\'s keychain and the app unlocks by itself'
+                  : 'The data file is encrypted; the passphrase is asked for when the app starts'}
+            </div>
+          </div>
+
           <div className="form-group">
             <label className="checkbox-label">
               <input
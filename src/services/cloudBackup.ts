@@ -1,10 +1,12 @@
 import { invoke } from '@tauri-apps/api/core';
+import { BackupDestination, DestinationStatus } from '../App';
 
 export interface CloudBackupConfig {
   enabled: boolean;
   cloud_directory: string;
   max_backups: number;
   sync_interval_minutes: number;
+  destinations?: BackupDestination[];
 }
 
 export interface ConnectionStatus {
@@ -83,8 +85,8 @@ class CloudBackupService {
         }, config.sync_interval_minutes * 60 * 1000);
       }
       
-      // If we're online and have a cloud directory, perform initial sync
-      if (this.connectionStatus.online && config.cloud_directory) {
+      // If we're online and have somewhere to back up to, perform initial sync
+      if (this.connectionStatus.online && this.hasDestinations(config)) {
         setTimeout(() => {
           this.performAutoSync();
         }, 2000); // Wait 2 seconds after startup to let the app fully load
@@ -130,52 +132,32 @@ class CloudBackupService {
 
     try {
       const config = await this.getCloudBackupConfig();
-      if (!config.enabled || !config.cloud_directory) {
+      if (!config.enabled || !this.hasDestinations(config)) {
         this.connectionStatus.syncStatus = 'idle';
         this.notifyListeners();
         return;
       }
 
-      const backupData = await invoke<string>('export_data');
-      const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
-      const filename = `doggy-daycare-backup-${timestamp}.json`;
-      
-      await this.saveToCloudDirectory(config.cloud_directory, filename, backupData);
-      await this.cleanupOldBackups(config.cloud_directory, config.max_backups);
+      // Fails only when every destination fails; partial failures are reported alongside
+      const statuses = await invoke<DestinationStatus[]>('run_backup');
+      const failed = statuses.filter(status => status.last_error);
 
       this.connectionStatus.syncStatus = 'success';
       this.connectionStatus.lastSync = new Date();
-      this.connectionStatus.errorMessage = undefined;
+      this.connectionStatus.errorMessage = failed.length > 0
+        ? `Backup failed for ${failed.map(status => `${status.name} (${status.last_error})`).join(', ')}`
+        : undefined;
     } catch (error) {
       console.error('Auto sync failed:', error);
       this.connectionStatus.syncStatus = 'error';
-      this.connectionStatus.errorMessage = error instanceof Error ? error.message : 'Unknown error';
+      this.connectionStatus.errorMessage = error instanceof Error ? error.message : String(error);
     }
 
     this.notifyListeners();
   }
 
-  private async saveToCloudDirectory(cloudDir: string, filename: string, data: string): Promise<void> {
-    try {
-      await invoke('save_cloud_backup', {
-        cloudDirectory: cloudDir,
-        filename: filename,
-        data: data
-      });
-    } catch (error) {
-      throw new Error(`Failed to save backup to cloud directory: ${error}`);
-    }
-  }
-
-  private async cleanupOldBackups(cloudDir: string, maxBackups: number): Promise<void> {
-    try {
-      await invoke('cleanup_old_backups', {
-        cloudDirectory: cloudDir,
-        maxBackups: maxBackups
-      });
-    } catch (error) {
-      console.warn('Failed to cleanup old backups:', error);
-    }
+  private hasDestinations(config: CloudBackupConfig): boolean {
+    return !!config.cloud_directory || !!config.destinations?.some(destination => destination.enabled);
   }
 
   public async getCloudBackupConfig(): Promise<CloudBackupConfig> {
@@ -187,7 +169,8 @@ class CloudBackupService {
         enabled: false,
         cloud_directory: '',
         max_backups: 100,
-        sync_interval_minutes: 30
+        sync_interval_minutes: 30,
+        destinations: []
       };
     }
   }
@@ -0,0 +1,54 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type JobKind = 'generation' | 'import' | 'export' | 'report';
+export type JobStatus = 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface Job<T = unknown> {
+  id: string;
+  kind: JobKind;
+  status: JobStatus;
+  done: number;
+  total: number;
+  error?: string;
+  result?: T;
+  started_at: string;
+  finished_at?: string;
+}
+
+// Start a background job with one of the start_* commands and wait for it to
+// finish, passing each progress update to onProgress. Resolves with the job's
+// result; rejects with its error if it fails or is cancelled.
+export async function runJob<T>(
+  kind: JobKind,
+  command: string,
+  args: Record<string, unknown>,
+  onProgress?: (job: Job<T>) => void,
+): Promise<T> {
+  let jobId: string | null = null;
+  let settle: ((job: Job<T>) => void) | null = null;
+  const finished = new Promise<Job<T>>(resolve => { settle = resolve; });
+
+  const unlisten = await listen<Job<T>>(`${kind}:progress`, (event) => {
+    if (event.payload.id !== jobId) return;
+    onProgress?.(event.payload);
+    if (event.payload.status !== 'running') settle?.(event.payload);
+  });
+
+  try {
+    jobId = await invoke<string>(command, args);
+    // The job may have finished before we knew its id
+    const current = await invoke<Job<T>>('get_job_status', { jobId });
+    const job = current.status === 'running' ? await finished : current;
+    if (job.status !== 'completed') {
+      throw new Error(job.error || `Job ${job.status}`);
+    }
+    return job.result as T;
+  } finally {
+    unlisten();
+  }
+}
+
+export function cancelJob(jobId: string): Promise<void> {
+  return invoke('cancel_job', { jobId });
+}